@@ -0,0 +1,35 @@
+#!/bin/bash
+
+set -e           # exit if any command has a non-zero exit code
+set -u           # exit if any referenced variable has not been previously defined
+unset CDPATH
+IFS=$' \n\t'
+: "${BASH_SOURCE?'BASH_SOURCE variable not defined, not running in bash'}"
+
+# change into the directory this script resides in
+cd "$(dirname "${BASH_SOURCE[0]}")"
+
+# Each optional feature must build alone, on top of the core wrapper.
+FEATURES=(std sampler daemon thermal ipmi wmi smc gpu-vendor smart remote mdns simulation arbitrary compact otel snmp serde tracing testing)
+
+# The data model must build without `std`, and its tests run without
+# the LM sensors library. Run `miri.sh` to check them under Miri.
+cargo clippy --no-default-features -- -D warnings
+cargo clippy --lib --tests --no-default-features --features arbitrary -- -D warnings
+cargo test --lib --no-default-features --features arbitrary
+cargo test --lib --no-default-features --features compact
+cargo clippy --lib --tests --no-default-features --features serde -- -D warnings
+cargo test --lib --no-default-features --features serde
+
+for feature in "${FEATURES[@]}"; do
+    cargo clippy --all-targets --no-default-features --features "$feature" -- -D warnings
+done
+
+cargo clippy --all-targets -- -D warnings
+cargo clippy --all-targets --all-features -- -D warnings
+
+cargo test --all-features
+
+# Other platforms build a stub of the LM sensors library.
+# rustup target add x86_64-unknown-freebsd
+cargo clippy --all-targets --all-features --target x86_64-unknown-freebsd -- -D warnings
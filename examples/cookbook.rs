@@ -0,0 +1,185 @@
+//! Recipes exercising the main parts of this crate, as subcommands.
+//!
+//! ```text
+//! cargo run --example cookbook --features simulation -- <command> [arguments]
+//! ```
+//!
+//! Commands:
+//! - `list`: print all readings, grouped by chip and feature.
+//! - `watch [interval_ms] [count]`: print the health and temperatures
+//!   periodically.
+//! - `export`: print all readings as tab-separated values.
+//! - `set <chip> <sub-feature> <value>`: set the raw value of a sub-feature.
+//! - `fan-curve <chip> <sub-feature> <temperature:pwm>...`: record the
+//!   temperature reported by a sub-feature for some seconds, and report the
+//!   PWM values that the given fan curve would have driven.
+
+use core::time::Duration;
+use std::process::ExitCode;
+use std::{env, thread};
+
+use lm_sensors::backend::{self, Backend};
+use lm_sensors::errors::Result;
+use lm_sensors::health::{self, Policy};
+use lm_sensors::simulation::{FanCurve, OpenLoop, Replay, Simulation};
+use lm_sensors::snapshot::Snapshot;
+use lm_sensors::value::Value;
+
+const USAGE: &str = "usage: cookbook list
+       cookbook watch [interval_ms] [count]
+       cookbook export
+       cookbook set <chip> <sub-feature> <value>
+       cookbook fan-curve <chip> <sub-feature> <temperature:pwm>...";
+
+/// Number of temperatures recorded by `fan-curve`, one per second.
+const FAN_CURVE_SAMPLES: usize = 10;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args.as_slice() {
+        ["list"] => list(),
+        ["watch", rest @ ..] if rest.len() <= 2 => {
+            let interval = rest.first().map_or(Some(1000), |s| s.parse().ok());
+            let count = rest.get(1).map_or(Some(u64::MAX), |s| s.parse().ok());
+            match (interval, count) {
+                (Some(interval), Some(count)) => watch(Duration::from_millis(interval), count),
+                _ => return usage(),
+            }
+        }
+        ["export"] => export(),
+        ["set", chip, sub_feature, value] => match value.parse() {
+            Ok(value) => set(chip, sub_feature, value),
+            Err(_) => return usage(),
+        },
+        ["fan-curve", chip, sub_feature, points @ ..] if !points.is_empty() => {
+            match points.iter().map(|p| parse_point(p)).collect() {
+                Some(points) => fan_curve(chip, sub_feature, points),
+                None => return usage(),
+            }
+        }
+        _ => return usage(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            let mut source = std::error::Error::source(&err);
+            while let Some(err) = source {
+                eprintln!("caused by: {err}");
+                source = err.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+fn parse_point(point: &str) -> Option<(f64, f64)> {
+    let (temperature, pwm) = point.split_once(':')?;
+    Some((temperature.parse().ok()?, pwm.parse().ok()?))
+}
+
+fn list() -> Result<()> {
+    let snapshot = Snapshot::read(&mut backend::native()?)?;
+
+    let mut chip = None;
+    for readings in snapshot.features() {
+        if chip != Some(&readings[0].chip) {
+            chip = Some(&readings[0].chip);
+            println!("chip: {}", readings[0].chip);
+        }
+
+        println!("    {}", readings[0].feature);
+        for reading in readings {
+            match reading.value() {
+                Some(value) => println!("        {}: {value}", reading.sub_feature),
+                None => println!("        {}: N/A", reading.sub_feature),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn watch(interval: Duration, count: u64) -> Result<()> {
+    let mut backend = backend::native()?;
+    let policy = Policy::default();
+
+    for round in 0..count {
+        if round != 0 {
+            thread::sleep(interval);
+        }
+
+        let snapshot = Snapshot::read(&mut backend)?;
+        let report = health::score(&snapshot, &policy);
+        println!("health: {}", report.health);
+        for cause in &report.causes {
+            println!("    {cause}");
+        }
+
+        for reading in &snapshot.readings {
+            if let Some(value @ Value::TemperatureInput(_)) = reading.value() {
+                println!("    {}: {}: {value}", reading.chip, reading.sub_feature);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn export() -> Result<()> {
+    for reading in backend::native()?.read_all()? {
+        let value = reading
+            .raw_value
+            .map_or(String::from("-"), |v| v.to_string());
+        println!(
+            "{}\t{}\t{}\t{}",
+            reading.chip, reading.feature, reading.sub_feature, value
+        );
+    }
+    Ok(())
+}
+
+fn set(chip: &str, sub_feature: &str, value: f64) -> Result<()> {
+    backend::native()?.write(chip, sub_feature, value)?;
+    println!("{chip}: {sub_feature}: set to {value}");
+    Ok(())
+}
+
+fn fan_curve(chip: &str, sub_feature: &str, points: Vec<(f64, f64)>) -> Result<()> {
+    let mut curve = FanCurve::new(points)?;
+    let mut backend = backend::native()?;
+
+    let interval = Duration::from_secs(1);
+    let mut frames = Vec::with_capacity(FAN_CURVE_SAMPLES);
+    for sample in 0..FAN_CURVE_SAMPLES {
+        if sample != 0 {
+            thread::sleep(interval);
+        }
+        frames.push(backend.read_all()?);
+    }
+
+    let mut simulation = Simulation::new(chip, sub_feature, interval, OpenLoop);
+    let report = simulation.run(&mut Replay::new(frames), &mut curve);
+
+    for step in &report.steps {
+        let time = step.time.as_secs();
+        println!(
+            "{time:>4} s: {:6.2} C -> PWM {:5.1}",
+            step.temperature, step.pwm
+        );
+    }
+    if report.missing != 0 {
+        println!("{} samples lacked {chip}: {sub_feature}", report.missing);
+    }
+    match (report.mean_pwm(), report.max_pwm()) {
+        (Some(mean), Some(max)) => println!("PWM: mean {mean:.1}, maximum {max:.1}"),
+        _ => println!("{chip}: {sub_feature}: no temperature was read"),
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+#!/bin/bash
+
+set -e           # exit if any command has a non-zero exit code
+set -u           # exit if any referenced variable has not been previously defined
+unset CDPATH
+IFS=$' \n\t'
+: "${BASH_SOURCE?'BASH_SOURCE variable not defined, not running in bash'}"
+
+# change into the directory this script resides in
+cd "$(dirname "${BASH_SOURCE[0]}")"
+
+# Miri cannot call into the LM sensors library, so only the data model is
+# checked, built without `std`, with the raw constants of this crate.
+# rustup +nightly component add miri
+cargo +nightly miri test --lib --no-default-features --features arbitrary
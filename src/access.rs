@@ -0,0 +1,201 @@
+//! Access control of servers sharing readings with other processes,
+//! *e.g.,* `daemon::Server` and `remote::Agent`.
+//!
+//! Exposing writes of actuators through a socket is a safety hazard, so
+//! servers are read-only unless writes are explicitly allowed, and clients
+//! authenticate with a shared token. Servers can also require that token
+//! before accepting any request, while still denying writes.
+//!
+//! Servers also bound the number of clients they serve concurrently, and
+//! close connections of clients beyond that bound.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::io;
+
+#[cfg(any(all(feature = "daemon", target_os = "linux"), feature = "remote"))]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::errors::{Error, Result};
+
+/// Stable identifier of errors answered to clients whose requests are denied.
+pub const PERMISSION_DENIED: &str = "permission_denied";
+
+/// Default maximum number of clients served concurrently by a server.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// Access policy of a server.
+///
+/// The default policy requires no authentication, and denies writes.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct AccessPolicy {
+    token: Option<String>,
+    allow_writes: bool,
+}
+
+impl AccessPolicy {
+    /// Return a policy requiring no authentication, and denying writes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require clients to authenticate with the given token.
+    pub fn auth_token(self, token: impl Into<String>) -> Self {
+        Self {
+            token: Some(token.into()),
+            ..self
+        }
+    }
+
+    /// Set whether authenticated clients may write sub-features.
+    ///
+    /// Writes are denied to everyone unless an authentication token is
+    /// also required. See: [`AccessPolicy::auth_token`].
+    pub fn allow_writes(self, allowed: bool) -> Self {
+        Self {
+            allow_writes: allowed,
+            ..self
+        }
+    }
+
+    /// Return `true` if clients need to authenticate.
+    #[must_use]
+    pub fn requires_authentication(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Return `true` if authenticated clients may write sub-features.
+    ///
+    /// This is `false` unless clients need to authenticate.
+    #[must_use]
+    pub fn writes_allowed(&self) -> bool {
+        self.allow_writes && self.requires_authentication()
+    }
+
+    /// Return `true` if the given token authenticates a client.
+    ///
+    /// Tokens are compared in constant time.
+    #[must_use]
+    pub fn accepts(&self, token: &str) -> bool {
+        self.token.as_deref().is_none_or(|expected| {
+            let (expected, token) = (expected.as_bytes(), token.as_bytes());
+            let diff = expected
+                .iter()
+                .zip(token)
+                .fold(0_u8, |diff, (a, b)| diff | (a ^ b));
+            expected.len() == token.len() && diff == 0
+        })
+    }
+
+    /// Return the state of a new client connection.
+    pub fn session(&self) -> Session<'_> {
+        Session {
+            policy: self,
+            authenticated: !self.requires_authentication(),
+        }
+    }
+}
+
+impl fmt::Debug for AccessPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessPolicy")
+            .field("token", &self.token.as_ref().map(|_token| "<redacted>"))
+            .field("allow_writes", &self.allow_writes)
+            .finish()
+    }
+}
+
+/// Access state of a client connection.
+#[derive(Debug, Clone)]
+pub struct Session<'a> {
+    policy: &'a AccessPolicy,
+    authenticated: bool,
+}
+
+impl Session<'_> {
+    /// Authenticate this client with the given token.
+    ///
+    /// A wrong token revokes any previous authentication.
+    pub fn authenticate(&mut self, token: &str) -> Result<()> {
+        self.authenticated = self.policy.accepts(token);
+        self.check_read()
+    }
+
+    /// Fail with a permission denied error if this client may not
+    /// read sub-features.
+    pub fn check_read(&self) -> Result<()> {
+        if self.authenticated {
+            Ok(())
+        } else {
+            Err(denied("Session::check_read()", "authentication required"))
+        }
+    }
+
+    /// Fail with a permission denied error if this client may not
+    /// write sub-features.
+    pub fn check_write(&self) -> Result<()> {
+        self.check_read()?;
+        if self.policy.writes_allowed() {
+            Ok(())
+        } else {
+            Err(denied("Session::check_write()", "server is read-only"))
+        }
+    }
+}
+
+fn denied(operation: &'static str, message: &str) -> Error {
+    let err = io::Error::new(io::ErrorKind::PermissionDenied, message);
+    Error::from_io(operation, err)
+}
+
+/// Return `true` if the error was caused by a denied request.
+#[must_use]
+pub fn is_denied(err: &Error) -> bool {
+    std::error::Error::source(err)
+        .and_then(|source| source.downcast_ref::<io::Error>())
+        .is_some_and(|err| err.kind() == io::ErrorKind::PermissionDenied)
+}
+
+#[cfg(any(all(feature = "daemon", target_os = "linux"), feature = "remote"))]
+/// Number of connections served concurrently, bounded by a maximum.
+#[derive(Debug)]
+pub(crate) struct Connections {
+    count: AtomicUsize,
+    max: usize,
+}
+
+#[cfg(any(all(feature = "daemon", target_os = "linux"), feature = "remote"))]
+impl Connections {
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            max,
+        }
+    }
+
+    /// Return a slot for a new connection, released when dropped, or `None`
+    /// if the maximum number of connections are already served.
+    pub(crate) fn acquire(&self) -> Option<ConnectionSlot<'_>> {
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < self.max).then_some(count + 1)
+            })
+            .ok()
+            .map(|_count| ConnectionSlot(&self.count))
+    }
+}
+
+#[cfg(any(all(feature = "daemon", target_os = "linux"), feature = "remote"))]
+/// Slot of a connection served concurrently. See: [`Connections::acquire`].
+#[derive(Debug)]
+pub(crate) struct ConnectionSlot<'a>(&'a AtomicUsize);
+
+#[cfg(any(all(feature = "daemon", target_os = "linux"), feature = "remote"))]
+impl Drop for ConnectionSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
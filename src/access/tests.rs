@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+use super::AccessPolicy;
+
+#[test]
+fn default_policy() {
+    let policy = AccessPolicy::new();
+    assert!(!policy.requires_authentication());
+    assert!(!policy.writes_allowed());
+    assert!(policy.accepts(""));
+
+    let session = policy.session();
+    session.check_read().unwrap();
+    let err = session.check_write().unwrap_err();
+    assert!(super::is_denied(&err));
+}
+
+#[test]
+fn token() {
+    let policy = AccessPolicy::new().auth_token("s3cret").allow_writes(true);
+    assert!(policy.requires_authentication());
+    assert!(policy.writes_allowed());
+    assert!(policy.accepts("s3cret"));
+    assert!(!policy.accepts("s3cre"));
+    assert!(!policy.accepts("s3cret!"));
+    assert!(!policy.accepts("S3cret"));
+    assert!(!format!("{policy:?}").contains("s3cret"));
+
+    let mut session = policy.session();
+    assert!(super::is_denied(&session.check_read().unwrap_err()));
+    assert!(super::is_denied(&session.check_write().unwrap_err()));
+
+    session.authenticate("s3cret").unwrap();
+    session.check_read().unwrap();
+    session.check_write().unwrap();
+
+    // A wrong token revokes authentication.
+    session.authenticate("wrong").unwrap_err();
+    session.check_read().unwrap_err();
+}
+
+#[test]
+fn writes_require_token() {
+    let policy = AccessPolicy::new().allow_writes(true);
+    assert!(!policy.writes_allowed());
+    assert!(super::is_denied(
+        &policy.session().check_write().unwrap_err()
+    ));
+}
+
+#[test]
+fn is_denied() {
+    let err = crate::errors::Error::UnsupportedPlatform;
+    assert!(!super::is_denied(&err));
+}
+
+#[cfg(any(all(feature = "daemon", target_os = "linux"), feature = "remote"))]
+#[test]
+fn connections() {
+    let connections = super::Connections::new(2);
+    let first = connections.acquire().unwrap();
+    let second = connections.acquire().unwrap();
+    assert!(connections.acquire().is_none());
+
+    drop(first);
+    let _third = connections.acquire().unwrap();
+    assert!(connections.acquire().is_none());
+    drop(second);
+}
@@ -0,0 +1,165 @@
+//! Audit trail of writes to chips, *e.g.,* setting actuator values.
+//!
+//! An [`AuditSink`] set by [`Initializer::audit_sink`] is notified of every
+//! write performed through the [`Capabilities`] token of an instance, after
+//! the write, whether it succeeded or not. Before setting the value of
+//! a sub-feature, its current value is read, so that the record holds both
+//! the old and new values.
+//!
+//! [`Initializer::audit_sink`]: crate::Initializer::audit_sink
+//! [`Capabilities`]: crate::Capabilities
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::errors::{self, Error, Result, Warning};
+
+/// Record of one write.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    /// Time at which the write was attempted.
+    pub time: SystemTime,
+    /// Written operation, *e.g.,* `sensors_set_value()`.
+    pub operation: &'static str,
+    /// Name of the chip, *e.g.,* `nct6775-isa-0290`.
+    pub chip: String,
+    /// Name of the written sub-feature, *e.g.,* `pwm1`, if any.
+    pub sub_feature: Option<String>,
+    /// Path of the attribute of the written sub-feature, *e.g.,*
+    /// `/sys/class/hwmon/hwmon2/pwm1`, if available.
+    pub path: Option<PathBuf>,
+    /// Raw value of the sub-feature before the write, if it could be read.
+    pub old_value: Option<f64>,
+    /// Written raw value, if any.
+    pub new_value: Option<f64>,
+    /// Description of the error that failed the write, if any.
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    /// Return `true` if the write succeeded.
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl fmt::Display for AuditRecord {
+    /// Format this record as tab-separated fields: seconds since the Unix
+    /// epoch, operation, chip, sub-feature, path, old value, new value,
+    /// and `ok` or the error. Missing fields are formatted as `-`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn or_dash(f: &mut fmt::Formatter<'_>, field: Option<impl fmt::Display>) -> fmt::Result {
+            match field {
+                Some(field) => write!(f, "\t{field}"),
+                None => f.write_str("\t-"),
+            }
+        }
+
+        let time = self.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        write!(
+            f,
+            "{}.{:06}\t{}\t{}",
+            time.as_secs(),
+            time.subsec_micros(),
+            self.operation,
+            self.chip
+        )?;
+        or_dash(f, self.sub_feature.as_deref())?;
+        or_dash(f, self.path.as_deref().map(Path::display))?;
+        or_dash(f, self.old_value)?;
+        or_dash(f, self.new_value)?;
+        match &self.error {
+            Some(error) => write!(f, "\t{}", error.replace(['\t', '\n'], " ")),
+            None => f.write_str("\tok"),
+        }
+    }
+}
+
+/// Return a description of an error, including its source.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn describe(err: &Error) -> String {
+    match std::error::Error::source(err) {
+        Some(source) => format!("{err}: {source}"),
+        None => err.to_string(),
+    }
+}
+
+/// Receiver of records of writes.
+///
+/// Sinks are called after each write, from the writing thread.
+/// Calling any function of this crate that writes to chips from within
+/// a sink leads to an infinite recursion.
+pub trait AuditSink: fmt::Debug {
+    /// This function is called after each write.
+    fn record(&self, record: &AuditRecord);
+}
+
+/// Sink calling a function for each record.
+pub struct Callback<F>(pub F);
+
+impl<F: Fn(&AuditRecord)> AuditSink for Callback<F> {
+    fn record(&self, record: &AuditRecord) {
+        (self.0)(record);
+    }
+}
+
+impl<F> fmt::Debug for Callback<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Callback").finish_non_exhaustive()
+    }
+}
+
+/// Sink appending one line per record to a file.
+///
+/// See: [`AuditRecord`]'s implementation of [`fmt::Display`].
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+    path: PathBuf,
+}
+
+impl FileSink {
+    /// Return a sink appending records to the file at the given path,
+    /// creating it if needed.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&path)
+            .map_err(|r| Error::from_io_path("OpenOptions::open()", &path, r))?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+            path,
+        })
+    }
+
+    /// Return the path of the file of this sink.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AuditSink for FileSink {
+    /// Failures to write records are reported as [`Warning::AuditRecordLost`]
+    /// to the error listener.
+    fn record(&self, record: &AuditRecord) {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(err) = writeln!(file, "{record}").and_then(|()| file.flush()) {
+            errors::report_warning(&Warning::AuditRecordLost {
+                path: self.path.clone(),
+                message: err.to_string(),
+            });
+        }
+    }
+}
@@ -0,0 +1,91 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use crate::audit::{AuditRecord, AuditSink, FileSink};
+
+fn record() -> AuditRecord {
+    AuditRecord {
+        time: UNIX_EPOCH + Duration::from_micros(1_700_000_000_000_042),
+        operation: "sensors_set_value()",
+        chip: "nct6775-isa-0290".into(),
+        sub_feature: Some("pwm1".into()),
+        path: Some(PathBuf::from("/sys/class/hwmon/hwmon2/pwm1")),
+        old_value: Some(128.0),
+        new_value: Some(255.0),
+        error: None,
+    }
+}
+
+#[test]
+fn display() {
+    let mut record = record();
+    assert!(record.succeeded());
+    assert_eq!(
+        record.to_string(),
+        "1700000000.000042\tsensors_set_value()\tnct6775-isa-0290\tpwm1\t\
+         /sys/class/hwmon/hwmon2/pwm1\t128\t255\tok"
+    );
+
+    record.sub_feature = None;
+    record.path = None;
+    record.old_value = None;
+    record.new_value = None;
+    record.error = Some("sensors_set_value() failed:\taccess denied\n".into());
+    assert!(!record.succeeded());
+    assert_eq!(
+        record.to_string(),
+        "1700000000.000042\tsensors_set_value()\tnct6775-isa-0290\t-\t-\t-\t-\t\
+         sensors_set_value() failed: access denied "
+    );
+}
+
+#[test]
+fn file_sink() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.log");
+
+    let sink = FileSink::open(&path).unwrap();
+    assert_eq!(sink.path(), path);
+    sink.record(&record());
+    sink.record(&record());
+    drop(sink);
+
+    // Records are appended to existing contents.
+    FileSink::open(&path).unwrap().record(&record());
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let line = format!("{}\n", record());
+    assert_eq!(contents, line.repeat(3));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[serial_test::serial]
+fn chip_sets() {
+    use std::sync::{Arc, Mutex};
+
+    use crate::audit::Callback;
+
+    let records = Arc::new(Mutex::new(Vec::default()));
+    let sink_records = Arc::clone(&records);
+    let s = crate::Initializer::isolated()
+        .audit_sink(Box::new(Callback(move |record: &AuditRecord| {
+            sink_records.lock().unwrap().push(record.clone());
+        })))
+        .initialize()
+        .unwrap();
+
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    let result = chip.do_chip_sets(s.capabilities());
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].operation, "sensors_do_chip_sets()");
+    assert_eq!(records[0].chip, "lm75-i2c-0-48");
+    assert_eq!(records[0].sub_feature, None);
+    assert_eq!(records[0].succeeded(), result.is_ok());
+}
@@ -0,0 +1,347 @@
+//! Sources of sensor readings other than, or besides, the LM sensors library.
+//!
+//! A [`Backend`] produces owned [`Reading`]s following the chip, feature and
+//! sub-feature model of the LM sensors library, so that the same consumer
+//! code works with every backend. [`native`] selects the backend suitable
+//! for the target platform, and [`MultiSensors`] merges many backends.
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests;
+
+#[cfg(all(feature = "ipmi", unix))]
+pub mod ipmi;
+#[cfg(all(feature = "gpu-vendor", target_os = "linux"))]
+pub mod nvml;
+#[cfg(all(feature = "smart", target_os = "linux"))]
+pub mod smart;
+#[cfg(all(feature = "smc", target_os = "macos"))]
+pub mod smc;
+#[cfg(target_os = "freebsd")]
+pub mod sysctl;
+#[cfg(all(feature = "thermal", target_os = "linux"))]
+pub mod thermal;
+#[cfg(all(feature = "wmi", windows))]
+pub mod wmi;
+
+use core::ffi::c_uint;
+use core::fmt;
+
+use crate::errors::{Capability, Error, Result};
+use crate::Value;
+
+/// Separator between the prefix of a backend and a chip name,
+/// in readings of [`MultiSensors`].
+pub const PREFIX_SEPARATOR: char = '/';
+
+/// Owned reading of a sub-feature, produced by a [`Backend`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    any(feature = "remote", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Reading {
+    /// Name of the chip, *e.g.,* `coretemp-isa-0000`.
+    pub chip: String,
+    /// Name of the feature, *e.g.,* `temp1`.
+    pub feature: String,
+    /// Name of the sub-feature, *e.g.,* `temp1_input`.
+    pub sub_feature: String,
+    /// Raw type of the sub-feature, one of `SENSORS_SUBFEATURE_*`.
+    pub raw_kind: c_uint,
+    /// Raw value of the sub-feature, if it could be read.
+    pub raw_value: Option<f64>,
+}
+
+impl Reading {
+    /// Return the value of the sub-feature, if it could be read,
+    /// and its type is known.
+    #[must_use]
+    pub fn value(&self) -> Option<Value> {
+        self.raw_value
+            .and_then(|value| Value::from_raw(self.raw_kind, value))
+    }
+
+    /// Return a reading of the given sub-feature, *e.g.,* `temp1_input`,
+    /// whose feature name is the sub-feature name up to the first
+    /// underscore, *e.g.,* `temp1`.
+    #[cfg(test)]
+    pub(crate) fn for_test(
+        chip: &str,
+        sub_feature: &str,
+        kind: crate::value::Kind,
+        raw_value: Option<f64>,
+    ) -> Self {
+        let feature = sub_feature.split('_').next().unwrap_or(sub_feature);
+        Self {
+            chip: chip.into(),
+            feature: feature.into(),
+            sub_feature: sub_feature.into(),
+            raw_kind: kind.as_raw(),
+            raw_value,
+        }
+    }
+}
+
+/// Source of sensor readings.
+pub trait Backend: fmt::Debug {
+    /// Return a short name of this backend, *e.g.,* `lm-sensors`.
+    fn name(&self) -> &'static str;
+
+    /// Return readings of all sub-features of all chips.
+    fn read_all(&mut self) -> Result<Vec<Reading>>;
+
+    /// Set the raw value of a sub-feature of a chip, *e.g.,* an actuator.
+    ///
+    /// By default, this fails with [`Error::Unsupported`].
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        let _unused = (chip, sub_feature, raw_value);
+        let needed = Capability::SubFeatureWrite;
+        Err(Error::unsupported("Backend::write()", needed))
+    }
+}
+
+impl<T: Backend + ?Sized> Backend for Box<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        (**self).read_all()
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        (**self).write(chip, sub_feature, raw_value)
+    }
+}
+
+/// Aggregate of many backends, presenting a single chip namespace.
+///
+/// Chip names of readings are prefixed by the prefix of their backend,
+/// followed by [`PREFIX_SEPARATOR`], *e.g.,* `thermal/cpu_thermal-thermal-0`.
+/// Backends with an empty prefix report chip names unchanged.
+/// Readings are reported in the order in which backends were added.
+///
+/// # Example
+///
+/// ```no_run
+/// use lm_sensors::backend::{self, Backend, MultiSensors};
+///
+/// let mut sensors = MultiSensors::new().with("", backend::native()?);
+/// for reading in sensors.read_all()? {
+///     println!("{}: {}: {:?}", reading.chip, reading.sub_feature, reading.value());
+/// }
+/// # Ok::<(), lm_sensors::errors::Error>(())
+/// ```
+#[derive(Debug, Default)]
+#[must_use]
+pub struct MultiSensors {
+    backends: Vec<(String, Box<dyn Backend>)>,
+}
+
+impl MultiSensors {
+    /// Return an aggregate of no backends.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a backend whose chip names are prefixed by the given prefix.
+    pub fn with(mut self, prefix: impl Into<String>, backend: impl Backend + 'static) -> Self {
+        self.push(prefix, backend);
+        self
+    }
+
+    /// Add a backend whose chip names are prefixed by the given prefix.
+    pub fn push(&mut self, prefix: impl Into<String>, backend: impl Backend + 'static) {
+        self.backends.push((prefix.into(), Box::new(backend)));
+    }
+
+    /// Return an iterator over the prefixes and names of the backends,
+    /// in the order in which they were added.
+    pub fn backends(&self) -> impl Iterator<Item = (&str, &'static str)> {
+        self.backends
+            .iter()
+            .map(|(prefix, backend)| (prefix.as_str(), backend.name()))
+    }
+
+    /// Return readings of all backends that could be read, and the errors
+    /// of the other backends, along with their prefixes.
+    pub fn read_available(&mut self) -> (Vec<Reading>, Vec<(String, Error)>) {
+        let mut readings = Vec::default();
+        let mut errors = Vec::default();
+        for (prefix, backend) in &mut self.backends {
+            match backend.read_all() {
+                Ok(r) => readings.extend(r.into_iter().map(|r| prefixed(prefix, r))),
+                Err(err) => errors.push((prefix.clone(), err)),
+            }
+        }
+        (readings, errors)
+    }
+}
+
+impl Backend for MultiSensors {
+    fn name(&self) -> &'static str {
+        "multi"
+    }
+
+    /// Return readings of all backends, or the first error.
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let mut readings = Vec::default();
+        for (prefix, backend) in &mut self.backends {
+            readings.extend(backend.read_all()?.into_iter().map(|r| prefixed(prefix, r)));
+        }
+        Ok(readings)
+    }
+
+    /// Write through the backend whose prefix starts the chip name,
+    /// or else through the first backend with an empty prefix.
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        for (prefix, backend) in &mut self.backends {
+            let name = chip
+                .strip_prefix(prefix.as_str())
+                .and_then(|name| name.strip_prefix(PREFIX_SEPARATOR));
+            if let (false, Some(name)) = (prefix.is_empty(), name) {
+                return backend.write(name, sub_feature, raw_value);
+            }
+        }
+
+        let unprefixed = self
+            .backends
+            .iter_mut()
+            .find(|(prefix, _)| prefix.is_empty());
+        if let Some((_prefix, backend)) = unprefixed {
+            return backend.write(chip, sub_feature, raw_value);
+        }
+
+        let err = std::io::ErrorKind::NotFound.into();
+        Err(Error::from_io("MultiSensors::write()", err))
+    }
+}
+
+fn prefixed(prefix: &str, mut reading: Reading) -> Reading {
+    if !prefix.is_empty() {
+        reading.chip = format!("{prefix}{PREFIX_SEPARATOR}{}", reading.chip);
+    }
+    reading
+}
+
+#[cfg(target_os = "linux")]
+impl Backend for crate::LMSensors {
+    fn name(&self) -> &'static str {
+        "lm-sensors"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Ok(read_lm_sensors(self))
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        write_lm_sensors(self, chip, sub_feature, raw_value)
+    }
+}
+
+/// Return readings of all sub-features of all chips known to
+/// the LM sensors library.
+#[cfg(target_os = "linux")]
+pub(crate) fn read_lm_sensors(sensors: &crate::LMSensors) -> Vec<Reading> {
+    read_lm_sensors_where(sensors, &crate::selector::Selector::all())
+}
+
+/// Return readings of the sub-features selected by the given selector,
+/// of the chips known to the LM sensors library.
+///
+/// Sub-features that are not selected are not read.
+#[cfg(target_os = "linux")]
+pub(crate) fn read_lm_sensors_where(
+    sensors: &crate::LMSensors,
+    selector: &crate::selector::Selector,
+) -> Vec<Reading> {
+    let mut result = Vec::default();
+    for chip in sensors.chip_iter(None) {
+        let chip_name = chip.to_string();
+        if !selector.matches_chip(&chip_name) {
+            continue;
+        }
+
+        for feature in chip.feature_iter() {
+            let feature_name = feature.raw_name().map(|name| name.to_string_lossy());
+            for sub_feature in feature.sub_feature_iter() {
+                let sub_feature_name = sub_feature.raw_name().map(|name| name.to_string_lossy());
+                let sub_feature_name = sub_feature_name.as_deref().unwrap_or_default();
+                if !selector.matches(&chip_name, sub_feature_name) {
+                    continue;
+                }
+
+                result.push(Reading {
+                    chip: chip_name.clone(),
+                    feature: feature_name.as_deref().unwrap_or_default().into(),
+                    sub_feature: sub_feature_name.into(),
+                    raw_kind: sub_feature.raw_kind(),
+                    raw_value: sub_feature.raw_value().ok(),
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Set the raw value of a sub-feature of a chip known to
+/// the LM sensors library.
+#[cfg(target_os = "linux")]
+pub(crate) fn write_lm_sensors(
+    sensors: &crate::LMSensors,
+    chip: &str,
+    sub_feature: &str,
+    raw_value: f64,
+) -> Result<()> {
+    let found = sensors
+        .chip_iter(None)
+        .filter(|c| c.to_string() == chip)
+        .flat_map(|c| c.feature_iter())
+        .flat_map(|feature| feature.sub_feature_iter())
+        .find(|s| {
+            s.raw_name()
+                .is_some_and(|name| name.to_bytes() == sub_feature.as_bytes())
+        });
+
+    if let Some(found) = found {
+        found.set_raw_value(sensors.capabilities(), raw_value)
+    } else {
+        let err = std::io::ErrorKind::NotFound.into();
+        Err(Error::from_io("Backend::write()", err))
+    }
+}
+
+/// Return the backend suitable for the target platform:
+/// - on Linux, the LM sensors library, loading the default configuration,
+/// - on FreeBSD, `sysctl::Sysctl`,
+/// - on Windows, with the `wmi` feature, `wmi::Wmi`, reading
+///   LibreHardwareMonitor,
+/// - on macOS, with the `smc` feature, `smc::Smc`, reading the System
+///   Management Controller,
+/// - elsewhere, this fails with [`Error::UnsupportedPlatform`].
+///
+/// [`Error::UnsupportedPlatform`]: crate::errors::Error::UnsupportedPlatform
+pub fn native() -> Result<Box<dyn Backend>> {
+    #[cfg(target_os = "linux")]
+    return crate::Initializer::default()
+        .initialize()
+        .map(|sensors| Box::new(sensors) as Box<dyn Backend>);
+
+    #[cfg(target_os = "freebsd")]
+    return Ok(Box::new(sysctl::Sysctl::new()));
+
+    #[cfg(all(feature = "wmi", windows))]
+    return Ok(Box::new(wmi::Wmi::new()));
+
+    #[cfg(all(feature = "smc", target_os = "macos"))]
+    return Ok(Box::new(smc::Smc::new()));
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        all(feature = "wmi", windows),
+        all(feature = "smc", target_os = "macos")
+    )))]
+    return Err(crate::errors::Error::UnsupportedPlatform);
+}
@@ -0,0 +1,144 @@
+//! Backend reading sensors of a baseboard management controller (BMC),
+//! through `ipmi-sensors` of FreeIPMI.
+//!
+//! Servers often report their most interesting temperatures and fans through
+//! the BMC rather than through hardware monitoring drivers.
+//! All sensors are reported on the chip [`CHIP_NAME`]. The feature name of a
+//! reading is the name of the IPMI sensor, *e.g.,* `CPU1 Temp`, and its
+//! sub-feature name follows the LM sensors library, *e.g.,* `temp1_input`.
+//! IPMI sensors whose units are not temperatures, fan speeds, voltages,
+//! currents or powers are ignored.
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::c_uint;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::{Backend, Reading};
+use crate::errors::{Capability, Error, Result};
+use crate::model::raw::sensors_subfeature_type::*;
+
+/// Name of the chip holding all IPMI sensors.
+pub const CHIP_NAME: &str = "ipmi-bmc-0";
+
+/// Default program reading IPMI sensors.
+pub const DEFAULT_PROGRAM: &str = "ipmi-sensors";
+
+/// Backend reading IPMI sensors.
+#[derive(Debug, Clone)]
+pub struct Ipmi {
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+impl Default for Ipmi {
+    fn default() -> Self {
+        Self {
+            program: DEFAULT_PROGRAM.into(),
+            args: Vec::default(),
+        }
+    }
+}
+
+impl Ipmi {
+    /// Return a backend running [`DEFAULT_PROGRAM`] on the local BMC.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the program to run instead of [`DEFAULT_PROGRAM`].
+    #[must_use]
+    pub fn program(self, program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            ..self
+        }
+    }
+
+    /// Add an argument to pass to the program, *e.g.,*
+    /// `--hostname=bmc.example.com` to read a remote BMC.
+    #[must_use]
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+}
+
+impl Backend for Ipmi {
+    fn name(&self) -> &'static str {
+        "ipmi"
+    }
+
+    /// This fails with [`Error::Unsupported`] if the program is not installed.
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .args(["--comma-separated-output", "--no-header-output"])
+            .output()
+            .map_err(|err| {
+                if err.kind() == io::ErrorKind::NotFound {
+                    Error::unsupported("ipmi-sensors", Capability::Ipmi)
+                } else {
+                    Error::from_io_path("Command::output()", &self.program, err)
+                }
+            })?;
+
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            let err = io::Error::other(message);
+            return Err(Error::from_io_path("ipmi-sensors", &self.program, err));
+        }
+
+        Ok(parse_sensors(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Return readings from the comma-separated output of `ipmi-sensors`, made
+/// of the fields: record ID, name, type, reading, units and event.
+fn parse_sensors(output: &str) -> Vec<Reading> {
+    // Number of readings of each kind, *e.g.,* temperatures.
+    let mut counts = [0_u32; 5];
+
+    let mut result = Vec::default();
+    for line in output.lines() {
+        let mut fields = line.splitn(6, ',');
+        let (Some(_id), Some(name), Some(_type), Some(value), Some(units)) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            continue;
+        };
+
+        let Some((index, prefix, raw_kind)) = kind_of_units(units.trim()) else {
+            continue;
+        };
+        counts[index] += 1;
+
+        result.push(Reading {
+            chip: CHIP_NAME.into(),
+            feature: name.trim().replace(['\t', '\n'], " "),
+            sub_feature: format!("{prefix}{}_input", counts[index]),
+            raw_kind,
+            raw_value: value.trim().parse().ok(),
+        });
+    }
+    result
+}
+
+fn kind_of_units(units: &str) -> Option<(usize, &'static str, c_uint)> {
+    match units {
+        "C" => Some((0, "temp", SENSORS_SUBFEATURE_TEMP_INPUT)),
+        "RPM" => Some((1, "fan", SENSORS_SUBFEATURE_FAN_INPUT)),
+        "V" => Some((2, "in", SENSORS_SUBFEATURE_IN_INPUT)),
+        "A" => Some((3, "curr", SENSORS_SUBFEATURE_CURR_INPUT)),
+        "W" => Some((4, "power", SENSORS_SUBFEATURE_POWER_INPUT)),
+        _ => None,
+    }
+}
@@ -0,0 +1,59 @@
+#![cfg(test)]
+
+use assert_matches::assert_matches;
+
+use crate::backend::Backend;
+use crate::errors::{Capability, Error};
+use crate::Value;
+
+const OUTPUT: &str = "\
+4,CPU1 Temp,Temperature,45.00,C,'OK'
+5,CPU2 Temp,Temperature,N/A,C,N/A
+10,FAN1,Fan,3100.00,RPM,'OK'
+20,12V,Voltage,12.19,V,'OK'
+30,PS1 Status,Power Supply,N/A,N/A,'Presence detected'
+31,PS1 Power,Power Supply,180.00,W,'OK'
+malformed line
+";
+
+#[test]
+fn parse_sensors() {
+    let readings = super::parse_sensors(OUTPUT);
+    let summary: Vec<_> = readings
+        .iter()
+        .map(|r| (r.feature.as_str(), r.sub_feature.as_str(), r.value()))
+        .collect();
+
+    assert_eq!(
+        summary,
+        [
+            (
+                "CPU1 Temp",
+                "temp1_input",
+                Some(Value::TemperatureInput(45.0))
+            ),
+            ("CPU2 Temp", "temp2_input", None),
+            ("FAN1", "fan1_input", Some(Value::FanInput(3100.0))),
+            ("12V", "in1_input", Some(Value::VoltageInput(12.19))),
+            ("PS1 Power", "power1_input", Some(Value::PowerInput(180.0))),
+        ]
+    );
+    assert!(readings.iter().all(|r| r.chip == super::CHIP_NAME));
+}
+
+#[test]
+fn read_all() {
+    let mut backend = super::Ipmi::new().program("/nonexistent/ipmi-sensors");
+    assert_eq!(backend.name(), "ipmi");
+    let err = backend.read_all().unwrap_err();
+    assert_matches!(
+        err,
+        Error::Unsupported {
+            needed: Capability::Ipmi,
+            ..
+        }
+    );
+
+    let mut backend = super::Ipmi::new().program("false");
+    assert_matches!(backend.read_all().unwrap_err(), Error::IO1Path { .. });
+}
@@ -0,0 +1,241 @@
+//! Backend reading sensors of NVIDIA GPUs, through the NVIDIA management
+//! library (NVML).
+//!
+//! NVIDIA GPUs are not exposed through hardware monitoring drivers, unlike
+//! AMD GPUs which are handled by the `amdgpu` driver.
+//! The library is loaded at run time from `libnvidia-ml.so.1`, which is
+//! installed with the proprietary NVIDIA driver.
+//! Each GPU is reported as the chip `nvidia-nvml-N`, with the sub-features
+//! `temp1_input`, `temp1_crit`, `fan1_percent` and `power1_input`.
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::{c_int, c_uint, c_void, CStr};
+use core::{mem, ptr};
+
+use super::{Backend, Reading};
+use crate::errors::{Capability, Error, Result};
+use crate::model::raw::sensors_subfeature_type::*;
+
+/// File name of the NVIDIA management library.
+pub const LIBRARY_NAME: &CStr = c"libnvidia-ml.so.1";
+
+type NvmlReturn = c_int;
+type NvmlDevice = *mut c_void;
+
+const NVML_SUCCESS: NvmlReturn = 0;
+const NVML_TEMPERATURE_GPU: c_int = 0;
+const NVML_TEMPERATURE_THRESHOLD_SHUTDOWN: c_int = 0;
+
+/// Functions of the NVIDIA management library.
+#[derive(Debug)]
+struct Functions {
+    init: unsafe extern "C" fn() -> NvmlReturn,
+    shutdown: unsafe extern "C" fn() -> NvmlReturn,
+    device_count: unsafe extern "C" fn(*mut c_uint) -> NvmlReturn,
+    device_by_index: unsafe extern "C" fn(c_uint, *mut NvmlDevice) -> NvmlReturn,
+    temperature: unsafe extern "C" fn(NvmlDevice, c_int, *mut c_uint) -> NvmlReturn,
+    temperature_threshold: unsafe extern "C" fn(NvmlDevice, c_int, *mut c_uint) -> NvmlReturn,
+    fan_speed: unsafe extern "C" fn(NvmlDevice, *mut c_uint) -> NvmlReturn,
+    power_usage: unsafe extern "C" fn(NvmlDevice, *mut c_uint) -> NvmlReturn,
+}
+
+/// Backend reading sensors of NVIDIA GPUs.
+///
+/// The NVIDIA management library is initialized by [`Nvml::new`], and shut
+/// down when this backend is dropped.
+#[derive(Debug)]
+pub struct Nvml {
+    handle: *mut c_void,
+    functions: Functions,
+}
+
+impl Nvml {
+    /// Load and initialize the NVIDIA management library.
+    ///
+    /// This fails with [`Error::Unsupported`] if the library is not
+    /// installed.
+    pub fn new() -> Result<Self> {
+        // Safety: LIBRARY_NAME is null-terminated.
+        let handle = unsafe { libc::dlopen(LIBRARY_NAME.as_ptr(), libc::RTLD_NOW) };
+        if handle.is_null() {
+            return Err(Error::unsupported("Nvml::new()", Capability::Nvml));
+        }
+
+        // Safety: symbols are resolved from the library we just loaded,
+        // and have the signatures declared in `nvml.h`.
+        let functions = unsafe {
+            (|| {
+                Some(Functions {
+                    init: symbol(handle, c"nvmlInit_v2")?,
+                    shutdown: symbol(handle, c"nvmlShutdown")?,
+                    device_count: symbol(handle, c"nvmlDeviceGetCount_v2")?,
+                    device_by_index: symbol(handle, c"nvmlDeviceGetHandleByIndex_v2")?,
+                    temperature: symbol(handle, c"nvmlDeviceGetTemperature")?,
+                    temperature_threshold: symbol(handle, c"nvmlDeviceGetTemperatureThreshold")?,
+                    fan_speed: symbol(handle, c"nvmlDeviceGetFanSpeed")?,
+                    power_usage: symbol(handle, c"nvmlDeviceGetPowerUsage")?,
+                })
+            })()
+        };
+
+        let Some(functions) = functions else {
+            // Safety: handle was returned by dlopen().
+            unsafe { libc::dlclose(handle) };
+            return Err(Error::unsupported("Nvml::new()", Capability::Nvml));
+        };
+
+        // Safety: nvmlInit_v2() takes no arguments.
+        let r = unsafe { (functions.init)() };
+        if r != NVML_SUCCESS {
+            // Safety: handle was returned by dlopen().
+            unsafe { libc::dlclose(handle) };
+            return Err(Error::from_io("nvmlInit_v2()", nvml_error(r)));
+        }
+
+        Ok(Self { handle, functions })
+    }
+
+    /// Return the number of GPUs.
+    pub fn device_count(&self) -> Result<u32> {
+        let mut count: c_uint = 0;
+        // Safety: count is a valid output location.
+        let r = unsafe { (self.functions.device_count)(&mut count) };
+        if r != NVML_SUCCESS {
+            return Err(Error::from_io("nvmlDeviceGetCount_v2()", nvml_error(r)));
+        }
+        Ok(count)
+    }
+
+    fn sample(&self, index: u32) -> Result<Sample> {
+        let mut device: NvmlDevice = ptr::null_mut();
+        // Safety: device is a valid output location.
+        let r = unsafe { (self.functions.device_by_index)(index, &mut device) };
+        if r != NVML_SUCCESS {
+            let err = nvml_error(r);
+            return Err(Error::from_io("nvmlDeviceGetHandleByIndex_v2()", err));
+        }
+
+        let query = |f: &dyn Fn(*mut c_uint) -> NvmlReturn| {
+            let mut value: c_uint = 0;
+            (f(&mut value) == NVML_SUCCESS).then_some(value)
+        };
+
+        // Safety: device is a valid handle, and outputs are valid locations.
+        unsafe {
+            Ok(Sample {
+                index,
+                temperature: query(&|v| {
+                    (self.functions.temperature)(device, NVML_TEMPERATURE_GPU, v)
+                }),
+                temperature_shutdown: query(&|v| {
+                    let threshold = NVML_TEMPERATURE_THRESHOLD_SHUTDOWN;
+                    (self.functions.temperature_threshold)(device, threshold, v)
+                }),
+                fan_percent: query(&|v| (self.functions.fan_speed)(device, v)),
+                power_milliwatts: query(&|v| (self.functions.power_usage)(device, v)),
+            })
+        }
+    }
+}
+
+impl Drop for Nvml {
+    fn drop(&mut self) {
+        // Safety: the library was initialized by Nvml::new().
+        unsafe {
+            (self.functions.shutdown)();
+            libc::dlclose(self.handle);
+        }
+    }
+}
+
+impl Backend for Nvml {
+    fn name(&self) -> &'static str {
+        "nvml"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let mut result = Vec::default();
+        for index in 0..self.device_count()? {
+            result.extend(self.sample(index)?.readings());
+        }
+        Ok(result)
+    }
+}
+
+/// Values read from one GPU.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Sample {
+    index: u32,
+    /// Degrees Celsius.
+    temperature: Option<u32>,
+    /// Degrees Celsius.
+    temperature_shutdown: Option<u32>,
+    /// Percentage of the maximum fan speed.
+    fan_percent: Option<u32>,
+    /// Milliwatts.
+    power_milliwatts: Option<u32>,
+}
+
+impl Sample {
+    fn readings(&self) -> Vec<Reading> {
+        let chip = format!("nvidia-nvml-{}", self.index);
+        let reading = |feature: &str, sub_feature: &str, raw_kind, raw_value: Option<f64>| {
+            raw_value.map(|raw_value| Reading {
+                chip: chip.clone(),
+                feature: feature.into(),
+                sub_feature: sub_feature.into(),
+                raw_kind,
+                raw_value: Some(raw_value),
+            })
+        };
+
+        [
+            reading(
+                "temp1",
+                "temp1_input",
+                SENSORS_SUBFEATURE_TEMP_INPUT,
+                self.temperature.map(f64::from),
+            ),
+            reading(
+                "temp1",
+                "temp1_crit",
+                SENSORS_SUBFEATURE_TEMP_CRIT,
+                self.temperature_shutdown.map(f64::from),
+            ),
+            reading(
+                "fan1",
+                "fan1_percent",
+                SENSORS_SUBFEATURE_UNKNOWN,
+                self.fan_percent.map(f64::from),
+            ),
+            reading(
+                "power1",
+                "power1_input",
+                SENSORS_SUBFEATURE_POWER_INPUT,
+                self.power_milliwatts.map(|mw| f64::from(mw) / 1000.0),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Return the function of the given name in a loaded library.
+///
+/// # Safety
+///
+/// `handle` must have been returned by `dlopen()`, and `F` must be
+/// the function pointer type of the symbol.
+unsafe fn symbol<F: Copy>(handle: *mut c_void, name: &CStr) -> Option<F> {
+    debug_assert_eq!(mem::size_of::<F>(), mem::size_of::<*mut c_void>());
+
+    let symbol = unsafe { libc::dlsym(handle, name.as_ptr()) };
+    (!symbol.is_null()).then(|| unsafe { mem::transmute_copy::<*mut c_void, F>(&symbol) })
+}
+
+fn nvml_error(code: NvmlReturn) -> std::io::Error {
+    std::io::Error::other(format!("NVML error {code}"))
+}
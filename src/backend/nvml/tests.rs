@@ -0,0 +1,63 @@
+#![cfg(test)]
+
+use super::Sample;
+use crate::Value;
+
+#[test]
+fn sample_readings() {
+    let sample = Sample {
+        index: 1,
+        temperature: Some(64),
+        temperature_shutdown: Some(98),
+        fan_percent: Some(45),
+        power_milliwatts: Some(123_500),
+    };
+
+    let readings = sample.readings();
+    let summary: Vec<_> = readings
+        .iter()
+        .map(|r| (r.chip.as_str(), r.sub_feature.as_str(), r.value()))
+        .collect();
+
+    assert_eq!(summary.len(), 4);
+    assert_eq!(
+        summary[0],
+        (
+            "nvidia-nvml-1",
+            "temp1_input",
+            Some(Value::TemperatureInput(64.0))
+        )
+    );
+    assert_eq!(
+        summary[1],
+        (
+            "nvidia-nvml-1",
+            "temp1_crit",
+            Some(Value::TemperatureCritical(98.0))
+        )
+    );
+    assert_eq!(summary[2].1, "fan1_percent");
+    assert_eq!(summary[2].2.map(|v| v.raw_value()), Some(45.0));
+    assert_eq!(
+        summary[3],
+        (
+            "nvidia-nvml-1",
+            "power1_input",
+            Some(Value::PowerInput(123.5))
+        )
+    );
+}
+
+#[test]
+fn sample_missing_values() {
+    let sample = Sample {
+        index: 0,
+        temperature: Some(40),
+        ..Sample::default()
+    };
+
+    let readings = sample.readings();
+    assert_eq!(readings.len(), 1);
+    assert_eq!(readings[0].chip, "nvidia-nvml-0");
+    assert_eq!(readings[0].raw_value, Some(40.0));
+}
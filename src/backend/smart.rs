@@ -0,0 +1,316 @@
+//! Backend reading drive temperatures through SMART, for drives not covered
+//! by the `drivetemp` hardware monitoring driver.
+//!
+//! ATA drives are queried with `SMART READ DATA` through SCSI generic
+//! ATA pass-through, and NVMe drives with the SMART / health log page.
+//! Both usually require elevated privileges.
+//! Each drive is reported as the chip `{device}-smart-0`, *e.g.,*
+//! `sda-smart-0`, with the sub-feature `temp1_input`, whose value is missing
+//! if the drive could not be queried.
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::{c_int, c_uint, c_ulong, c_void};
+use core::{mem, ptr};
+use std::fs::{self, File};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use super::{Backend, Reading};
+use crate::errors::{Error, Result};
+use crate::model::raw::sensors_subfeature_type::SENSORS_SUBFEATURE_TEMP_INPUT;
+
+/// Default directory listing block devices.
+pub const DEFAULT_SYS_BLOCK: &str = "/sys/block";
+
+/// Drive queried through SMART.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Drive {
+    /// ATA drive, *e.g.,* `/dev/sda`.
+    Ata(PathBuf),
+    /// NVMe namespace, *e.g.,* `/dev/nvme0n1`.
+    Nvme(PathBuf),
+}
+
+impl Drive {
+    /// Return the path of the device node of this drive.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Ata(path) | Self::Nvme(path) => path,
+        }
+    }
+
+    /// Return the current temperature of this drive, in degrees Celsius.
+    pub fn temperature(&self) -> Result<f64> {
+        let file = File::open(self.path())
+            .map_err(|r| Error::from_io_path("File::open()", self.path(), r))?;
+
+        let temperature = match self {
+            Self::Ata(_) => read_ata_smart_data(&file).map(|data| ata_temperature(&data)),
+            Self::Nvme(_) => read_nvme_smart_log(&file).map(|log| nvme_temperature(&log)),
+        };
+
+        temperature
+            .map_err(|r| Error::from_io_path("ioctl()", self.path(), r))?
+            .ok_or_else(|| {
+                let err = io::ErrorKind::NotFound.into();
+                Error::from_io_path("Drive::temperature()", self.path(), err)
+            })
+    }
+}
+
+/// Backend reading drive temperatures through SMART.
+#[derive(Debug, Clone)]
+pub struct Smart {
+    drives: Vec<Drive>,
+}
+
+impl Smart {
+    /// Return a backend reading drives listed in [`DEFAULT_SYS_BLOCK`],
+    /// excluding those handled by the `drivetemp` driver.
+    pub fn new() -> Result<Self> {
+        discover(Path::new(DEFAULT_SYS_BLOCK), Path::new("/dev")).map(Self::with_drives)
+    }
+
+    /// Return a backend reading the given drives.
+    #[must_use]
+    pub fn with_drives(drives: impl IntoIterator<Item = Drive>) -> Self {
+        Self {
+            drives: drives.into_iter().collect(),
+        }
+    }
+
+    /// Return the drives read by this backend.
+    #[must_use]
+    pub fn drives(&self) -> &[Drive] {
+        &self.drives
+    }
+}
+
+impl Backend for Smart {
+    fn name(&self) -> &'static str {
+        "smart"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let readings = self.drives.iter().map(|drive| {
+            let device = drive.path().file_name().unwrap_or_default();
+            Reading {
+                chip: format!("{}-smart-0", device.to_string_lossy()),
+                feature: "temp1".into(),
+                sub_feature: "temp1_input".into(),
+                raw_kind: SENSORS_SUBFEATURE_TEMP_INPUT,
+                raw_value: drive.temperature().ok(),
+            }
+        });
+        Ok(readings.collect())
+    }
+}
+
+/// Return drives listed in `sys_block`, with device nodes in `dev`,
+/// excluding drives handled by the `drivetemp` driver.
+fn discover(sys_block: &Path, dev: &Path) -> Result<Vec<Drive>> {
+    let entries =
+        fs::read_dir(sys_block).map_err(|r| Error::from_io_path("fs::read_dir()", sys_block, r))?;
+
+    let mut result = Vec::default();
+    for entry in entries {
+        let entry = entry.map_err(|r| Error::from_io_path("fs::read_dir()", sys_block, r))?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+
+        if let Some(suffix) = name.strip_prefix("sd") {
+            let is_disk = !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_lowercase());
+            // `drivetemp` registers a hardware monitoring device.
+            let has_drivetemp = entry.path().join("device/hwmon").exists();
+            if is_disk && !has_drivetemp {
+                result.push(Drive::Ata(dev.join(name)));
+            }
+        } else if name.starts_with("nvme") && name.contains('n') && !name.contains('p') {
+            result.push(Drive::Nvme(dev.join(name)));
+        }
+    }
+    result.sort_unstable_by(|a, b| a.path().cmp(b.path()));
+    Ok(result)
+}
+
+const SMART_DATA_LEN: usize = 512;
+
+/// Return the temperature reported by the attributes of SMART data of
+/// an ATA drive, *i.e.,* attribute 194, or attribute 190.
+fn ata_temperature(data: &[u8; SMART_DATA_LEN]) -> Option<f64> {
+    const TEMPERATURE_CELSIUS: u8 = 194;
+    const AIRFLOW_TEMPERATURE_CELSIUS: u8 = 190;
+
+    // 30 attributes of 12 bytes, after a 2 bytes version.
+    let attributes = data[2..2 + 30 * 12].chunks_exact(12);
+    let raw_of = |id: u8| {
+        attributes
+            .clone()
+            .find(|attribute| attribute[0] == id)
+            // The lowest byte of the raw value is the current temperature.
+            .map(|attribute| f64::from(attribute[5]))
+    };
+
+    raw_of(TEMPERATURE_CELSIUS).or_else(|| raw_of(AIRFLOW_TEMPERATURE_CELSIUS))
+}
+
+/// Return the composite temperature reported by the SMART / health
+/// log page of an NVMe drive.
+fn nvme_temperature(log: &[u8; SMART_DATA_LEN]) -> Option<f64> {
+    let kelvin = u16::from_le_bytes([log[1], log[2]]);
+    (kelvin != 0).then(|| f64::from(kelvin) - 273.15)
+}
+
+/// See: `struct sg_io_hdr` in `<scsi/sg.h>`.
+#[repr(C)]
+struct SgIoHdr {
+    interface_id: c_int,
+    dxfer_direction: c_int,
+    cmd_len: u8,
+    mx_sb_len: u8,
+    iovec_count: u16,
+    dxfer_len: c_uint,
+    dxferp: *mut c_void,
+    cmdp: *mut u8,
+    sbp: *mut u8,
+    timeout: c_uint,
+    flags: c_uint,
+    pack_id: c_int,
+    usr_ptr: *mut c_void,
+    status: u8,
+    masked_status: u8,
+    msg_status: u8,
+    sb_len_wr: u8,
+    host_status: u16,
+    driver_status: u16,
+    resid: c_int,
+    duration: c_uint,
+    info: c_uint,
+}
+
+const SG_IO: c_ulong = 0x2285;
+const SG_DXFER_FROM_DEV: c_int = -3;
+const SG_INFO_OK_MASK: c_uint = 0x1;
+
+fn read_ata_smart_data(file: &File) -> io::Result<[u8; SMART_DATA_LEN]> {
+    // ATA PASS-THROUGH (16): PIO data-in of one sector,
+    // SMART READ DATA (feature 0xd0, command 0xb0).
+    let mut cdb: [u8; 16] = [
+        0x85, 0x08, 0x0e, 0x00, 0xd0, 0x00, 0x01, 0x00, 0x00, 0x00, 0x4f, 0x00, 0xc2, 0x00, 0xb0,
+        0x00,
+    ];
+    let mut sense = [0_u8; 32];
+    let mut data = [0_u8; SMART_DATA_LEN];
+
+    let mut header = SgIoHdr {
+        interface_id: c_int::from(b'S'),
+        dxfer_direction: SG_DXFER_FROM_DEV,
+        cmd_len: cdb.len() as u8,
+        mx_sb_len: sense.len() as u8,
+        iovec_count: 0,
+        dxfer_len: data.len() as c_uint,
+        dxferp: data.as_mut_ptr().cast(),
+        cmdp: cdb.as_mut_ptr(),
+        sbp: sense.as_mut_ptr(),
+        timeout: 5000,
+        flags: 0,
+        pack_id: 0,
+        usr_ptr: ptr::null_mut(),
+        status: 0,
+        masked_status: 0,
+        msg_status: 0,
+        sb_len_wr: 0,
+        host_status: 0,
+        driver_status: 0,
+        resid: 0,
+        duration: 0,
+        info: 0,
+    };
+
+    // Safety: `header` points to buffers that outlive this call.
+    if unsafe { libc::ioctl(file.as_raw_fd(), SG_IO as _, ptr::from_mut(&mut header)) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    if header.info & SG_INFO_OK_MASK != 0 || header.host_status != 0 {
+        return Err(io::ErrorKind::InvalidData.into());
+    }
+    Ok(data)
+}
+
+/// See: `struct nvme_admin_cmd` in `<linux/nvme_ioctl.h>`.
+#[repr(C)]
+struct NvmeAdminCmd {
+    opcode: u8,
+    flags: u8,
+    rsvd1: u16,
+    nsid: u32,
+    cdw2: u32,
+    cdw3: u32,
+    metadata: u64,
+    addr: u64,
+    metadata_len: u32,
+    data_len: u32,
+    cdw10: u32,
+    cdw11: u32,
+    cdw12: u32,
+    cdw13: u32,
+    cdw14: u32,
+    cdw15: u32,
+    timeout_ms: u32,
+    result: u32,
+}
+
+/// `_IOWR('N', 0x41, struct nvme_admin_cmd)`.
+const NVME_IOCTL_ADMIN_CMD: c_ulong =
+    (3 << 30) | ((mem::size_of::<NvmeAdminCmd>() as c_ulong) << 16) | (0x4e << 8) | 0x41;
+const NVME_ADMIN_GET_LOG_PAGE: u8 = 0x02;
+const NVME_LOG_SMART: u32 = 0x02;
+
+fn read_nvme_smart_log(file: &File) -> io::Result<[u8; SMART_DATA_LEN]> {
+    let mut log = [0_u8; SMART_DATA_LEN];
+    let dwords = (SMART_DATA_LEN / 4) as u32;
+
+    let mut command = NvmeAdminCmd {
+        opcode: NVME_ADMIN_GET_LOG_PAGE,
+        flags: 0,
+        rsvd1: 0,
+        nsid: u32::MAX,
+        cdw2: 0,
+        cdw3: 0,
+        metadata: 0,
+        addr: log.as_mut_ptr() as u64,
+        metadata_len: 0,
+        data_len: SMART_DATA_LEN as u32,
+        cdw10: ((dwords - 1) << 16) | NVME_LOG_SMART,
+        cdw11: 0,
+        cdw12: 0,
+        cdw13: 0,
+        cdw14: 0,
+        cdw15: 0,
+        timeout_ms: 5000,
+        result: 0,
+    };
+
+    // Safety: `command` points to a buffer that outlives this call.
+    let r = unsafe {
+        libc::ioctl(
+            file.as_raw_fd(),
+            NVME_IOCTL_ADMIN_CMD as _,
+            ptr::from_mut(&mut command),
+        )
+    };
+    match r {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(log),
+        // NVMe status code.
+        _ => Err(io::ErrorKind::InvalidData.into()),
+    }
+}
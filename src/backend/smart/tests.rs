@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use std::fs;
+use std::path::Path;
+
+use super::{Drive, SMART_DATA_LEN};
+use crate::backend::Backend;
+
+#[test]
+fn ata_temperature() {
+    let mut data = [0_u8; SMART_DATA_LEN];
+    assert_eq!(super::ata_temperature(&data), None);
+
+    // Attribute 190 in the first slot.
+    data[2] = 190;
+    data[2 + 5] = 38;
+    assert_eq!(super::ata_temperature(&data), Some(38.0));
+
+    // Attribute 194 in the third slot takes precedence.
+    data[2 + 2 * 12] = 194;
+    data[2 + 2 * 12 + 5] = 41;
+    data[2 + 2 * 12 + 6] = 20;
+    assert_eq!(super::ata_temperature(&data), Some(41.0));
+}
+
+#[test]
+fn nvme_temperature() {
+    let mut log = [0_u8; SMART_DATA_LEN];
+    assert_eq!(super::nvme_temperature(&log), None);
+
+    log[1..3].copy_from_slice(&318_u16.to_le_bytes());
+    let celsius = super::nvme_temperature(&log).unwrap();
+    assert!((celsius - 44.85).abs() < 1e-9);
+}
+
+#[test]
+fn discover() {
+    let sys_block = tempfile::tempdir().unwrap();
+    for name in ["sda", "sdb", "sdb1", "nvme0n1", "nvme0n1p1", "loop0"] {
+        fs::create_dir(sys_block.path().join(name)).unwrap();
+    }
+    fs::create_dir_all(sys_block.path().join("sdb/device/hwmon")).unwrap();
+
+    let dev = Path::new("/dev");
+    let drives = super::discover(sys_block.path(), dev).unwrap();
+    assert_eq!(
+        drives,
+        [
+            Drive::Nvme(dev.join("nvme0n1")),
+            Drive::Ata(dev.join("sda")),
+        ]
+    );
+
+    super::discover(&sys_block.path().join("inexistent"), dev).unwrap_err();
+}
+
+#[test]
+fn read_all() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut backend = super::Smart::with_drives([Drive::Ata(dir.path().join("sdz"))]);
+    assert_eq!(backend.name(), "smart");
+    assert_eq!(backend.drives().len(), 1);
+
+    let readings = backend.read_all().unwrap();
+    assert_eq!(readings.len(), 1);
+    assert_eq!(readings[0].chip, "sdz-smart-0");
+    assert_eq!(readings[0].raw_value, None);
+}
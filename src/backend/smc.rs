@@ -0,0 +1,177 @@
+//! Backend reading temperatures and fan speeds from the System Management
+//! Controller (SMC) of Apple computers, on macOS.
+//!
+//! macOS offers no hardware monitoring interface comparable to the LM
+//! sensors library, but the SMC exposes sensors as four-character keys,
+//! *e.g.,* `TC0P` for a CPU proximity temperature, or `F0Ac` for the actual
+//! speed of the first fan. Keys are listed through the `smc` tool of
+//! smcFanControl, so that no IOKit bindings are needed.
+//!
+//! Readings follow the `applesmc` driver of Linux, so that the same
+//! consumer code works on both platforms: their chip name is
+//! [`CHIP_NAME`], their feature name is the SMC key, and their sub-feature
+//! name follows the LM sensors library, *e.g.,* `temp1_input`. Keys of
+//! other sensors are ignored, and readings of keys whose data types are not
+//! supported have no values.
+
+#[cfg(test)]
+mod tests;
+
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::{Backend, Reading};
+use crate::errors::{Capability, Error, Result};
+use crate::model::raw::sensors_subfeature_type::*;
+
+/// Default program listing SMC keys.
+pub const DEFAULT_PROGRAM: &str = "smc";
+
+/// Chip name of readings, which is the chip name of the `applesmc` driver
+/// of Linux.
+pub const CHIP_NAME: &str = "applesmc-isa-0300";
+
+/// Backend reading temperatures and fan speeds from the SMC.
+#[derive(Debug, Clone)]
+pub struct Smc {
+    program: PathBuf,
+}
+
+impl Default for Smc {
+    fn default() -> Self {
+        Self {
+            program: DEFAULT_PROGRAM.into(),
+        }
+    }
+}
+
+impl Smc {
+    /// Return a backend listing SMC keys through [`DEFAULT_PROGRAM`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the program to run instead of [`DEFAULT_PROGRAM`],
+    /// *e.g.,* `/Applications/smcFanControl.app/Contents/Resources/smc`.
+    #[must_use]
+    pub fn program(self, program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+}
+
+impl Backend for Smc {
+    fn name(&self) -> &'static str {
+        "smc"
+    }
+
+    /// This fails with [`Error::Unsupported`] if the `smc` tool
+    /// is not installed.
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let output = Command::new(&self.program)
+            .arg("-l")
+            .output()
+            .map_err(|err| {
+                if err.kind() == io::ErrorKind::NotFound {
+                    Error::unsupported("Smc::read_all()", Capability::Smc)
+                } else {
+                    Error::from_io_path("Command::output()", &self.program, err)
+                }
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let err = io::Error::other(stderr.trim().to_owned());
+            return Err(Error::from_io_path("Smc::read_all()", &self.program, err));
+        }
+
+        Ok(parse_keys(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Return readings from the output of `smc -l`, made of lines of the key,
+/// the data type in brackets, the decoded value if any, then the bytes
+/// of the value, *e.g.,* `  TC0P  [sp78]  47.25 (bytes 2f 40)`.
+fn parse_keys(output: &str) -> Vec<Reading> {
+    let mut temperatures = 0_u32;
+
+    let mut result = Vec::default();
+    for line in output.lines() {
+        let Some((key, data_type, bytes)) = parse_line(line) else {
+            continue;
+        };
+
+        let (sub_feature, raw_kind) = if let Some(fan) = fan_index(key) {
+            (
+                format!("fan{}_input", fan + 1),
+                SENSORS_SUBFEATURE_FAN_INPUT,
+            )
+        } else if key.starts_with('T') {
+            temperatures += 1;
+            let sub_feature = format!("temp{temperatures}_input");
+            (sub_feature, SENSORS_SUBFEATURE_TEMP_INPUT)
+        } else {
+            continue;
+        };
+
+        result.push(Reading {
+            chip: CHIP_NAME.into(),
+            feature: key.into(),
+            sub_feature,
+            raw_kind,
+            raw_value: decode(data_type, &bytes),
+        });
+    }
+    result
+}
+
+/// Return the key, the data type and the bytes of a line of `smc -l`.
+fn parse_line(line: &str) -> Option<(&str, &str, Vec<u8>)> {
+    let (key, rest) = line.trim_start().split_once(char::is_whitespace)?;
+    if key.len() != 4 {
+        return None;
+    }
+
+    let rest = rest.trim_start().strip_prefix('[')?;
+    let (data_type, rest) = rest.split_once(']')?;
+
+    let (_, bytes) = rest.split_once("(bytes")?;
+    let bytes = bytes.trim_end().strip_suffix(')')?;
+    let bytes = bytes
+        .split_whitespace()
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect::<Option<_>>()?;
+
+    Some((key, data_type.trim(), bytes))
+}
+
+/// Return the zero-based index of the fan whose actual speed is given by
+/// the given key, *e.g.,* `0` for `F0Ac`.
+fn fan_index(key: &str) -> Option<u32> {
+    let index = key.strip_prefix('F')?.strip_suffix("Ac")?;
+    let [digit] = index.as_bytes() else {
+        return None;
+    };
+    char::from(*digit).to_digit(10)
+}
+
+/// Return the value of the given bytes, according to the given data type
+/// of the SMC, if it is supported.
+fn decode(data_type: &str, bytes: &[u8]) -> Option<f64> {
+    match (data_type, bytes) {
+        // Signed fixed point, with 8 fraction bits, big endian.
+        ("sp78", &[b0, b1]) => Some(f64::from(i16::from_be_bytes([b0, b1])) / 256.0),
+        // Unsigned fixed point, with 2 fraction bits, big endian.
+        ("fpe2", &[b0, b1]) => Some(f64::from(u16::from_be_bytes([b0, b1])) / 4.0),
+        // Floating point, little endian, on Apple silicon.
+        ("flt", &[b0, b1, b2, b3]) => Some(f64::from(f32::from_le_bytes([b0, b1, b2, b3]))),
+        ("ui8", &[b0]) => Some(f64::from(b0)),
+        ("ui16", &[b0, b1]) => Some(f64::from(u16::from_be_bytes([b0, b1]))),
+        ("ui32", &[b0, b1, b2, b3]) => Some(f64::from(u32::from_be_bytes([b0, b1, b2, b3]))),
+        _ => None,
+    }
+    .filter(|value| value.is_finite())
+}
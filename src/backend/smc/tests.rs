@@ -0,0 +1,70 @@
+#![cfg(test)]
+
+use crate::Value;
+
+const OUTPUT: &str = "\
+  F0Ac  [fpe2]  1796 (bytes 1c 10)
+  F0Mn  [fpe2]  1200 (bytes 12 c0)
+  F1Ac  [flt ]  2100 (bytes 00 40 03 45)
+  TC0P  [sp78]  47.25 (bytes 2f 40)
+  TG0D  [sp78]  -1 (bytes ff 00)
+  Tp09  [flt ]  38.5 (bytes 00 00 1a 42)
+  TB0T  [ch8*]  (bytes 41 42)
+  VC0C  [sp5a]  (bytes 1c 00)
+  #KEY  [ui32]  1234 (bytes 00 00 04 d2)
+malformed line
+";
+
+#[test]
+fn parse_keys() {
+    let readings = super::parse_keys(OUTPUT);
+    let summary: Vec<_> = readings
+        .iter()
+        .map(|r| {
+            assert_eq!(r.chip, super::CHIP_NAME);
+            ((r.feature.as_str(), r.sub_feature.as_str()), r.value())
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        [
+            (("F0Ac", "fan1_input"), Some(Value::FanInput(1796.0))),
+            (("F1Ac", "fan2_input"), Some(Value::FanInput(2100.0))),
+            (
+                ("TC0P", "temp1_input"),
+                Some(Value::TemperatureInput(47.25))
+            ),
+            (("TG0D", "temp2_input"), Some(Value::TemperatureInput(-1.0))),
+            (("Tp09", "temp3_input"), Some(Value::TemperatureInput(38.5))),
+            (("TB0T", "temp4_input"), None),
+        ]
+    );
+}
+
+#[test]
+fn parse_line() {
+    assert_eq!(
+        super::parse_line("  TC0P  [sp78]  47.25 (bytes 2f 40)"),
+        Some(("TC0P", "sp78", vec![0x2f, 0x40]))
+    );
+    assert_eq!(super::parse_line("  TC0P  [sp78]  no data"), None);
+    assert_eq!(super::parse_line("  TC0P  [sp78]  (bytes 2g)"), None);
+}
+
+#[test]
+fn fan_index() {
+    assert_eq!(super::fan_index("F0Ac"), Some(0));
+    assert_eq!(super::fan_index("F3Ac"), Some(3));
+    assert_eq!(super::fan_index("F0Mn"), None);
+    assert_eq!(super::fan_index("FNum"), None);
+}
+
+#[test]
+fn decode() {
+    assert_eq!(super::decode("sp78", &[0xff, 0x80]), Some(-0.5));
+    assert_eq!(super::decode("fpe2", &[0x1c, 0x10]), Some(1796.0));
+    assert_eq!(super::decode("ui16", &[0x01, 0x00]), Some(256.0));
+    assert_eq!(super::decode("sp78", &[0x2f]), None);
+    assert_eq!(super::decode("flt", &[0x00, 0x00, 0xc0, 0x7f]), None);
+}
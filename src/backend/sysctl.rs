@@ -0,0 +1,114 @@
+//! Backend reading temperatures through `sysctl` on FreeBSD.
+//!
+//! Readings follow the naming of the LM sensors library:
+//! - `dev.cpu.N.temperature` is reported as the feature `temp{N+1}` of
+//!   the chip `cpu-sysctl-0`,
+//! - `hw.acpi.thermal.tzN.temperature` is reported as the feature
+//!   `temp{N+1}` of the chip `acpitz-sysctl-0`.
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::c_int;
+use core::{mem, ptr};
+use std::ffi::CString;
+use std::io;
+
+use super::{Backend, Reading};
+use crate::errors::{Error, Result};
+use crate::model::raw::sensors_subfeature_type::SENSORS_SUBFEATURE_TEMP_INPUT;
+
+/// Name of the chip holding temperatures of processors.
+pub const CPU_CHIP_NAME: &str = "cpu-sysctl-0";
+
+/// Name of the chip holding temperatures of ACPI thermal zones.
+pub const ACPI_CHIP_NAME: &str = "acpitz-sysctl-0";
+
+/// Backend reading temperatures through `sysctl`.
+#[derive(Debug, Default)]
+pub struct Sysctl {
+    _private: (),
+}
+
+impl Sysctl {
+    /// Return a new backend.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Backend for Sysctl {
+    fn name(&self) -> &'static str {
+        "sysctl"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let mut result = Vec::default();
+
+        let cpu_count = sysctl_int("hw.ncpu")?.unwrap_or(0);
+        for index in 0..cpu_count {
+            let name = format!("dev.cpu.{index}.temperature");
+            if let Some(value) = sysctl_int(&name)? {
+                result.push(temperature_reading(CPU_CHIP_NAME, index, value));
+            }
+        }
+
+        for index in 0.. {
+            let name = format!("hw.acpi.thermal.tz{index}.temperature");
+            let Some(value) = sysctl_int(&name)? else {
+                break;
+            };
+            result.push(temperature_reading(ACPI_CHIP_NAME, index, value));
+        }
+
+        Ok(result)
+    }
+}
+
+/// Return the reading of an input temperature, given its raw value
+/// in tenths of a kelvin.
+fn temperature_reading(chip: &str, index: c_int, deci_kelvin: c_int) -> Reading {
+    let feature = format!("temp{}", index + 1);
+    Reading {
+        chip: chip.into(),
+        sub_feature: format!("{feature}_input"),
+        feature,
+        raw_kind: SENSORS_SUBFEATURE_TEMP_INPUT,
+        raw_value: Some(celsius_from_deci_kelvin(deci_kelvin)),
+    }
+}
+
+/// Convert a temperature expressed in tenths of a kelvin, as reported by
+/// `sysctl`, into degrees Celsius.
+fn celsius_from_deci_kelvin(deci_kelvin: c_int) -> f64 {
+    f64::from(deci_kelvin) / 10.0 - 273.15
+}
+
+/// Return the value of an integer `sysctl` variable, or `None` if it
+/// does not exist.
+fn sysctl_int(name: &str) -> Result<Option<c_int>> {
+    let c_name = CString::new(name)?;
+
+    let mut value: c_int = 0;
+    let mut len = mem::size_of::<c_int>();
+    // Safety: `c_name` is null-terminated, and `value` is `len` bytes long.
+    let r = unsafe {
+        libc::sysctlbyname(
+            c_name.as_ptr(),
+            ptr::from_mut(&mut value).cast(),
+            &mut len,
+            ptr::null(),
+            0,
+        )
+    };
+
+    if r == -1 {
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::ENOENT) {
+            return Ok(None);
+        }
+        return Err(Error::from_io("sysctlbyname()", err));
+    }
+    Ok(Some(value))
+}
@@ -0,0 +1,24 @@
+#![cfg(test)]
+
+use crate::Value;
+
+#[test]
+fn temperature_reading() {
+    assert!((super::celsius_from_deci_kelvin(2731) - (-0.05)).abs() < 1e-9);
+    assert!((super::celsius_from_deci_kelvin(3232) - 50.05).abs() < 1e-9);
+
+    let reading = super::temperature_reading(super::CPU_CHIP_NAME, 0, 3232);
+    assert_eq!(reading.chip, "cpu-sysctl-0");
+    assert_eq!(reading.feature, "temp1");
+    assert_eq!(reading.sub_feature, "temp1_input");
+    assert!(matches!(reading.value(), Some(Value::TemperatureInput(_))));
+}
+
+#[test]
+fn read_all() {
+    use crate::backend::Backend;
+
+    let mut backend = super::Sysctl::new();
+    assert_eq!(backend.name(), "sysctl");
+    let _readings = backend.read_all().unwrap();
+}
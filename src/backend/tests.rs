@@ -0,0 +1,159 @@
+#![cfg(test)]
+
+use serial_test::serial;
+
+use super::{Backend, MultiSensors, Reading};
+use crate::errors::{Error, Result};
+use crate::Value;
+
+#[derive(Debug)]
+struct Fixed(Option<Vec<Reading>>);
+
+impl Backend for Fixed {
+    fn name(&self) -> &'static str {
+        "fixed"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        self.0.clone().ok_or(Error::UnsupportedPlatform)
+    }
+}
+
+/// Backend recording writes.
+#[derive(Debug, Default)]
+struct Recorder(Vec<(String, String, f64)>);
+
+impl Backend for Recorder {
+    fn name(&self) -> &'static str {
+        "recorder"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Ok(Vec::default())
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        self.0.push((chip.into(), sub_feature.into(), raw_value));
+        Ok(())
+    }
+}
+
+fn temperature(chip: &str, value: f64) -> Reading {
+    Reading {
+        chip: chip.into(),
+        feature: "temp1".into(),
+        sub_feature: "temp1_input".into(),
+        raw_kind: sensors_sys::sensors_subfeature_type::SENSORS_SUBFEATURE_TEMP_INPUT,
+        raw_value: Some(value),
+    }
+}
+
+#[test]
+fn reading_value() {
+    let mut reading = temperature("coretemp-isa-0000", 47.5);
+    assert_eq!(reading.value(), Some(Value::TemperatureInput(47.5)));
+
+    reading.raw_value = None;
+    assert_eq!(reading.value(), None);
+
+    reading.raw_value = Some(1.0);
+    reading.raw_kind = 0x7fff_fff0;
+    assert_eq!(reading.value(), None);
+}
+
+#[test]
+#[serial]
+fn native() {
+    let mut backend = super::native().unwrap();
+    assert_eq!(backend.name(), "lm-sensors");
+
+    let sensors = crate::Initializer::default().initialize();
+    assert!(sensors.is_err(), "only one instance may exist");
+    drop(sensors);
+
+    let readings = backend.read_all().unwrap();
+    assert_eq!(backend.read_all().unwrap(), readings);
+
+    backend
+        .write("inexistent-isa-0000", "pwm1", 128.0)
+        .unwrap_err();
+}
+
+#[test]
+fn write() {
+    use assert_matches::assert_matches;
+
+    let err = Fixed(None)
+        .write("coretemp-isa-0000", "pwm1", 1.0)
+        .unwrap_err();
+    assert_matches!(
+        err,
+        Error::Unsupported {
+            needed: crate::errors::Capability::SubFeatureWrite,
+            ..
+        }
+    );
+
+    let mut sensors = MultiSensors::new().with("thermal", Recorder::default());
+    sensors.write("nct6775-isa-0290", "pwm1", 1.0).unwrap_err();
+    sensors
+        .write("thermal/cooling-0", "cur_state", 2.0)
+        .unwrap();
+    sensors.push("", Recorder::default());
+    sensors.write("nct6775-isa-0290", "pwm1", 128.0).unwrap();
+    sensors
+        .write("thermalx/cooling-0", "cur_state", 3.0)
+        .unwrap();
+
+    let written = format!("{sensors:?}");
+    assert!(
+        written.contains(r#"("cooling-0", "cur_state", 2.0)"#),
+        "{written}"
+    );
+    assert!(
+        written.contains(r#"("nct6775-isa-0290", "pwm1", 128.0)"#),
+        "{written}"
+    );
+    assert!(
+        written.contains(r#"("thermalx/cooling-0", "cur_state", 3.0)"#),
+        "{written}"
+    );
+}
+
+#[test]
+fn multi_sensors() {
+    let mut sensors = MultiSensors::new()
+        .with(
+            "",
+            Fixed(Some(vec![temperature("coretemp-isa-0000", 40.0)])),
+        )
+        .with(
+            "thermal",
+            Fixed(Some(vec![temperature("cpu-thermal-0", 41.0)])),
+        );
+    sensors.push("remote", Fixed(None));
+
+    let backends: Vec<_> = sensors.backends().collect();
+    assert_eq!(
+        backends,
+        [("", "fixed"), ("thermal", "fixed"), ("remote", "fixed")]
+    );
+    assert_eq!(sensors.name(), "multi");
+
+    sensors.read_all().unwrap_err();
+
+    let (readings, errors) = sensors.read_available();
+    let chips: Vec<_> = readings.iter().map(|r| r.chip.as_str()).collect();
+    assert_eq!(chips, ["coretemp-isa-0000", "thermal/cpu-thermal-0"]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "remote");
+
+    let mut sensors = MultiSensors::new().with("a", Fixed(Some(readings)));
+    let chips: Vec<_> = sensors
+        .read_all()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.chip)
+        .collect();
+    assert_eq!(chips, ["a/coretemp-isa-0000", "a/thermal/cpu-thermal-0"]);
+}
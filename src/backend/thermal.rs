@@ -0,0 +1,191 @@
+//! Backend reading thermal zones and cooling devices of the Linux thermal
+//! framework, *i.e.,* `/sys/class/thermal`.
+//!
+//! Many boards, *e.g.,* Raspberry Pi, report temperatures only there.
+//! Readings follow the naming of the LM sensors library:
+//! - `thermal_zoneN` is reported as the chip `{type}-thermal-N`, with the
+//!   sub-feature `temp1_input`, and `temp1_crit` for its critical trip point,
+//! - `cooling_deviceN` is reported as the chip `{type}-cooling-N`, with the
+//!   sub-features `cur_state` and `max_state`, of unknown type.
+//!
+//! Characters of `{type}` that are not allowed in chip names are replaced
+//! by `_`.
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::c_uint;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use super::{Backend, Reading};
+use crate::errors::{Error, Result};
+use crate::model::raw::sensors_subfeature_type::*;
+
+/// Default root directory of the thermal framework.
+pub const DEFAULT_ROOT: &str = "/sys/class/thermal";
+
+/// Backend reading thermal zones and cooling devices.
+#[derive(Debug, Clone)]
+pub struct Thermal {
+    root: PathBuf,
+}
+
+impl Default for Thermal {
+    fn default() -> Self {
+        Self::with_root(DEFAULT_ROOT)
+    }
+}
+
+impl Thermal {
+    /// Return a backend reading [`DEFAULT_ROOT`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a backend reading the given root directory instead of
+    /// [`DEFAULT_ROOT`].
+    #[must_use]
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Return the root directory read by this backend.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl Backend for Thermal {
+    fn name(&self) -> &'static str {
+        "thermal"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let mut zones = Vec::default();
+        let mut devices = Vec::default();
+
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            // No thermal framework.
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::default()),
+            Err(err) => return Err(Error::from_io_path("fs::read_dir()", &self.root, err)),
+        };
+
+        for entry in entries {
+            let entry = entry.map_err(|r| Error::from_io_path("fs::read_dir()", &self.root, r))?;
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+
+            if let Some(index) = parse_index(name, "thermal_zone") {
+                zones.push((index, entry.path()));
+            } else if let Some(index) = parse_index(name, "cooling_device") {
+                devices.push((index, entry.path()));
+            }
+        }
+        zones.sort_unstable();
+        devices.sort_unstable();
+
+        let mut result = Vec::default();
+        for (index, path) in zones {
+            read_zone(&mut result, index, &path);
+        }
+        for (index, path) in devices {
+            read_cooling_device(&mut result, index, &path);
+        }
+        Ok(result)
+    }
+}
+
+fn read_zone(result: &mut Vec<Reading>, index: u32, path: &Path) {
+    let chip = chip_name(&read_type(path), "thermal", index);
+    let millidegrees = read_number(&path.join("temp"));
+    result.push(reading(
+        &chip,
+        "temp1",
+        "temp1_input",
+        SENSORS_SUBFEATURE_TEMP_INPUT,
+        millidegrees.map(|value| value / 1000.0),
+    ));
+
+    for trip in 0.. {
+        let Ok(kind) = fs::read_to_string(path.join(format!("trip_point_{trip}_type"))) else {
+            break;
+        };
+        if kind.trim() == "critical" {
+            let millidegrees = read_number(&path.join(format!("trip_point_{trip}_temp")));
+            result.push(reading(
+                &chip,
+                "temp1",
+                "temp1_crit",
+                SENSORS_SUBFEATURE_TEMP_CRIT,
+                millidegrees.map(|value| value / 1000.0),
+            ));
+            break;
+        }
+    }
+}
+
+fn read_cooling_device(result: &mut Vec<Reading>, index: u32, path: &Path) {
+    let chip = chip_name(&read_type(path), "cooling", index);
+    for state in ["cur_state", "max_state"] {
+        let value = read_number(&path.join(state));
+        result.push(reading(
+            &chip,
+            state,
+            state,
+            SENSORS_SUBFEATURE_UNKNOWN,
+            value,
+        ));
+    }
+}
+
+fn reading(
+    chip: &str,
+    feature: &str,
+    sub_feature: &str,
+    raw_kind: c_uint,
+    raw_value: Option<f64>,
+) -> Reading {
+    Reading {
+        chip: chip.into(),
+        feature: feature.into(),
+        sub_feature: sub_feature.into(),
+        raw_kind,
+        raw_value,
+    }
+}
+
+/// Return `N` given a name made of a prefix followed by `N`.
+fn parse_index(name: &str, prefix: &str) -> Option<u32> {
+    let digits = name.strip_prefix(prefix)?;
+    // `u32::from_str()` also accepts a leading `+`.
+    if digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn read_type(path: &Path) -> String {
+    fs::read_to_string(path.join("type")).unwrap_or_default()
+}
+
+fn read_number(path: &Path) -> Option<f64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Return a chip name made of a sanitized type, a bus name and an index.
+fn chip_name(kind: &str, bus: &str, index: u32) -> String {
+    let kind: String = kind
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let kind = if kind.is_empty() { "unknown" } else { &kind };
+    format!("{kind}-{bus}-{index}")
+}
@@ -0,0 +1,76 @@
+#![cfg(test)]
+
+use std::fs;
+use std::path::Path;
+
+use crate::backend::Backend;
+use crate::Value;
+
+fn write(dir: &Path, name: &str, contents: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join(name), contents).unwrap();
+}
+
+#[test]
+fn read_all() {
+    let root = tempfile::tempdir().unwrap();
+
+    let zone = root.path().join("thermal_zone0");
+    write(&zone, "type", "cpu-thermal\n");
+    write(&zone, "temp", "47236\n");
+    write(&zone, "trip_point_0_type", "passive\n");
+    write(&zone, "trip_point_0_temp", "70000\n");
+    write(&zone, "trip_point_1_type", "critical\n");
+    write(&zone, "trip_point_1_temp", "90000\n");
+
+    // Zones are reported in numeric order.
+    let zone = root.path().join("thermal_zone10");
+    write(&zone, "type", "\n");
+    write(&zone, "temp", "invalid\n");
+
+    let device = root.path().join("cooling_device0");
+    write(&device, "type", "pwm-fan\n");
+    write(&device, "cur_state", "2\n");
+    write(&device, "max_state", "4\n");
+
+    fs::create_dir(root.path().join("thermal_zones")).unwrap();
+
+    let mut backend = super::Thermal::with_root(root.path());
+    assert_eq!(backend.name(), "thermal");
+    let readings = backend.read_all().unwrap();
+
+    let summary: Vec<_> = readings
+        .iter()
+        .map(|r| (r.chip.as_str(), r.sub_feature.as_str(), r.raw_value))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("cpu_thermal-thermal-0", "temp1_input", Some(47.236)),
+            ("cpu_thermal-thermal-0", "temp1_crit", Some(90.0)),
+            ("unknown-thermal-10", "temp1_input", None),
+            ("pwm_fan-cooling-0", "cur_state", Some(2.0)),
+            ("pwm_fan-cooling-0", "max_state", Some(4.0)),
+        ]
+    );
+    assert_eq!(readings[0].value(), Some(Value::TemperatureInput(47.236)));
+    assert_eq!(readings[1].value(), Some(Value::TemperatureCritical(90.0)));
+}
+
+#[test]
+fn read_all_without_thermal_framework() {
+    let root = tempfile::tempdir().unwrap();
+    let mut backend = super::Thermal::with_root(root.path().join("inexistent"));
+    assert!(backend.read_all().unwrap().is_empty());
+}
+
+#[test]
+fn parse_index() {
+    assert_eq!(
+        super::parse_index("thermal_zone12", "thermal_zone"),
+        Some(12)
+    );
+    assert_eq!(super::parse_index("thermal_zone", "thermal_zone"), None);
+    assert_eq!(super::parse_index("thermal_zone+1", "thermal_zone"), None);
+    assert_eq!(super::parse_index("cooling_device1", "thermal_zone"), None);
+}
@@ -0,0 +1,206 @@
+//! Backend reading sensors of LibreHardwareMonitor or OpenHardwareMonitor,
+//! through their WMI namespace on Windows.
+//!
+//! Windows offers no hardware monitoring interface comparable to the LM
+//! sensors library, but these hardware monitors publish their sensors in
+//! a WMI namespace while they run. Sensors are queried through PowerShell,
+//! so that no COM bindings are needed.
+//!
+//! The chip name of a reading is derived from the identifier of the parent
+//! hardware of the sensor, *e.g.,* `/lpc/nct6798d/0` becomes
+//! `lpc-nct6798d-0`. Its feature name is the name of the sensor,
+//! *e.g.,* `CPU Package`, and its sub-feature name follows the LM sensors
+//! library, *e.g.,* `temp1_input`. Sensors whose types are not temperatures,
+//! fan speeds, voltages, currents, powers or humidities are ignored.
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::c_uint;
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::{Backend, Reading};
+use crate::errors::{Capability, Error, Result};
+use crate::model::raw::sensors_subfeature_type::*;
+
+/// Default program querying WMI.
+pub const DEFAULT_PROGRAM: &str = "powershell.exe";
+
+/// WMI namespace of a hardware monitor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    /// Namespace of LibreHardwareMonitor, *i.e.,* `root/LibreHardwareMonitor`.
+    #[default]
+    LibreHardwareMonitor,
+    /// Namespace of OpenHardwareMonitor, *i.e.,* `root/OpenHardwareMonitor`.
+    OpenHardwareMonitor,
+}
+
+impl Namespace {
+    /// Return the path of this namespace.
+    #[must_use]
+    pub fn path(self) -> &'static str {
+        match self {
+            Self::LibreHardwareMonitor => "root/LibreHardwareMonitor",
+            Self::OpenHardwareMonitor => "root/OpenHardwareMonitor",
+        }
+    }
+}
+
+/// Backend reading sensors of a hardware monitor through WMI.
+#[derive(Debug, Clone)]
+pub struct Wmi {
+    program: PathBuf,
+    namespace: Namespace,
+}
+
+impl Default for Wmi {
+    fn default() -> Self {
+        Self {
+            program: DEFAULT_PROGRAM.into(),
+            namespace: Namespace::default(),
+        }
+    }
+}
+
+impl Wmi {
+    /// Return a backend querying the namespace of LibreHardwareMonitor
+    /// through [`DEFAULT_PROGRAM`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the namespace to query.
+    #[must_use]
+    pub fn namespace(self, namespace: Namespace) -> Self {
+        Self { namespace, ..self }
+    }
+
+    /// Set the program to run instead of [`DEFAULT_PROGRAM`],
+    /// *e.g.,* `pwsh.exe`.
+    #[must_use]
+    pub fn program(self, program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            ..self
+        }
+    }
+
+    fn query(&self) -> String {
+        format!(
+            "Get-CimInstance -Namespace {} -ClassName Sensor \
+             | Select-Object Parent,Name,SensorType,Value \
+             | ConvertTo-Csv -NoTypeInformation",
+            self.namespace.path()
+        )
+    }
+}
+
+impl Backend for Wmi {
+    fn name(&self) -> &'static str {
+        "wmi"
+    }
+
+    /// This fails with [`Error::Unsupported`] if PowerShell is not installed,
+    /// or if the hardware monitor is not running.
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let output = Command::new(&self.program)
+            .args(["-NoProfile", "-NonInteractive", "-Command", &self.query()])
+            .output()
+            .map_err(|err| {
+                if err.kind() == io::ErrorKind::NotFound {
+                    Error::unsupported("Wmi::read_all()", Capability::Wmi)
+                } else {
+                    Error::from_io_path("Command::output()", &self.program, err)
+                }
+            })?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("Invalid namespace") {
+            return Err(Error::unsupported("Wmi::read_all()", Capability::Wmi));
+        }
+        if !output.status.success() {
+            let err = io::Error::other(stderr.trim().to_owned());
+            return Err(Error::from_io_path("Wmi::read_all()", &self.program, err));
+        }
+
+        Ok(parse_sensors(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Return readings from the CSV output of the query, made of a header line,
+/// then of lines of the fields: parent, name, sensor type and value.
+fn parse_sensors(output: &str) -> Vec<Reading> {
+    // Number of readings of each kind, *e.g.,* temperatures, of each chip.
+    let mut counts: HashMap<String, [u32; 6]> = HashMap::default();
+
+    let mut result = Vec::default();
+    for line in output.lines().skip(1) {
+        let fields = csv_fields(line);
+        let [parent, name, sensor_type, value] = fields.as_slice() else {
+            continue;
+        };
+
+        let Some((index, prefix, raw_kind)) = kind_of_sensor_type(sensor_type) else {
+            continue;
+        };
+        let chip = chip_name(parent);
+        let count = &mut counts.entry(chip.clone()).or_default()[index];
+        *count += 1;
+
+        result.push(Reading {
+            chip,
+            feature: name.trim().replace(['\t', '\n'], " "),
+            sub_feature: format!("{prefix}{count}_input"),
+            raw_kind,
+            // Values might be formatted with a decimal comma.
+            raw_value: value.trim().replace(',', ".").parse().ok(),
+        });
+    }
+    result
+}
+
+/// Return the fields of a line of CSV, unquoting quoted fields.
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::default();
+    let mut field = String::default();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.next_if_eq(&'"').is_some() => field.push('"'),
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(core::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Return the chip name of the given hardware identifier,
+/// *e.g.,* `lpc-nct6798d-0` for `/lpc/nct6798d/0`.
+fn chip_name(parent: &str) -> String {
+    let name = parent.trim().trim_matches('/').replace(['/', ' '], "-");
+    if name.is_empty() {
+        "wmi-0".into()
+    } else {
+        name
+    }
+}
+
+fn kind_of_sensor_type(sensor_type: &str) -> Option<(usize, &'static str, c_uint)> {
+    match sensor_type.trim() {
+        "Temperature" => Some((0, "temp", SENSORS_SUBFEATURE_TEMP_INPUT)),
+        "Fan" => Some((1, "fan", SENSORS_SUBFEATURE_FAN_INPUT)),
+        "Voltage" => Some((2, "in", SENSORS_SUBFEATURE_IN_INPUT)),
+        "Current" => Some((3, "curr", SENSORS_SUBFEATURE_CURR_INPUT)),
+        "Power" => Some((4, "power", SENSORS_SUBFEATURE_POWER_INPUT)),
+        "Humidity" => Some((5, "humidity", SENSORS_SUBFEATURE_HUMIDITY_INPUT)),
+        _ => None,
+    }
+}
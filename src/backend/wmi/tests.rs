@@ -0,0 +1,79 @@
+#![cfg(test)]
+
+use crate::Value;
+
+const OUTPUT: &str = "\
+\"Parent\",\"Name\",\"SensorType\",\"Value\"\r
+\"/intelcpu/0\",\"CPU Package\",\"Temperature\",\"52\"\r
+\"/intelcpu/0\",\"CPU Core #1\",\"Temperature\",\"48,5\"\r
+\"/intelcpu/0\",\"CPU Package\",\"Power\",\"21.25\"\r
+\"/lpc/nct6798d/0\",\"Fan #1\",\"Fan\",\"1150\"\r
+\"/lpc/nct6798d/0\",\"Vcore\",\"Voltage\",\"1.216\"\r
+\"/lpc/nct6798d/0\",\"Temperature #1\",\"Temperature\",\"\"\r
+\"/ram\",\"Memory\",\"Load\",\"41.2\"\r
+malformed line\r
+";
+
+#[test]
+fn parse_sensors() {
+    let readings = super::parse_sensors(OUTPUT);
+    let summary: Vec<_> = readings
+        .iter()
+        .map(|r| {
+            let name = (r.chip.as_str(), r.feature.as_str(), r.sub_feature.as_str());
+            (name, r.value())
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        [
+            (
+                ("intelcpu-0", "CPU Package", "temp1_input"),
+                Some(Value::TemperatureInput(52.0))
+            ),
+            (
+                ("intelcpu-0", "CPU Core #1", "temp2_input"),
+                Some(Value::TemperatureInput(48.5))
+            ),
+            (
+                ("intelcpu-0", "CPU Package", "power1_input"),
+                Some(Value::PowerInput(21.25))
+            ),
+            (
+                ("lpc-nct6798d-0", "Fan #1", "fan1_input"),
+                Some(Value::FanInput(1150.0))
+            ),
+            (
+                ("lpc-nct6798d-0", "Vcore", "in1_input"),
+                Some(Value::VoltageInput(1.216))
+            ),
+            (("lpc-nct6798d-0", "Temperature #1", "temp1_input"), None),
+        ]
+    );
+}
+
+#[test]
+fn csv_fields() {
+    assert_eq!(
+        super::csv_fields(r#""a","b,c","d""e""#),
+        ["a", "b,c", "d\"e"]
+    );
+    assert_eq!(super::csv_fields("a,,b"), ["a", "", "b"]);
+}
+
+#[test]
+fn chip_name() {
+    assert_eq!(super::chip_name("/gpu-nvidia/0"), "gpu-nvidia-0");
+    assert_eq!(super::chip_name("/nvme/Samsung SSD"), "nvme-Samsung-SSD");
+    assert_eq!(super::chip_name(""), "wmi-0");
+}
+
+#[test]
+fn namespace() {
+    use super::{Namespace, Wmi};
+
+    assert_eq!(Namespace::default().path(), "root/LibreHardwareMonitor");
+    let wmi = Wmi::new().namespace(Namespace::OpenHardwareMonitor);
+    assert!(wmi.query().contains("-Namespace root/OpenHardwareMonitor "));
+}
@@ -3,6 +3,11 @@
 #[cfg(test)]
 mod tests;
 
+mod sealed {
+    pub trait Sealed {}
+}
+
+use core::borrow::Borrow;
 use core::ffi::CStr;
 use core::fmt;
 use std::io;
@@ -12,14 +17,30 @@ use sensors_sys::*;
 
 use crate::errors::{Error, Result};
 use crate::utils::api_access_lock;
+use crate::LMSensors;
 
 /// Bus connection of some [`Kind`], *e.g.,* PCI.
 ///
 /// [`Kind`]: crate::bus::Kind
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Bus(pub(crate) sensors_bus_id);
 
 impl Bus {
+    /// Returns a shared reference to the raw data structure [`sensors_bus_id`].
+    #[must_use]
+    pub fn raw_ref(&self) -> &sensors_bus_id {
+        &self.0
+    }
+
+    /// Returns a pointer to the raw data structure [`sensors_bus_id`].
+    ///
+    /// The pointer is valid while this bus is alive and not moved, and must
+    /// not be used to change the raw data structure.
+    #[must_use]
+    pub fn as_raw_ptr(&self) -> *const sensors_bus_id {
+        &self.0
+    }
+
     /// Return the adapter name of this bus.
     ///
     /// If it could not be found, it returns an error.
@@ -94,6 +115,38 @@ impl Bus {
     pub fn set_raw_number(&mut self, number: c_short) {
         self.0.nr = number;
     }
+
+    /// Return an owned snapshot of this bus, including its adapter name,
+    /// if any.
+    ///
+    /// The result does not refer to the LM sensors library, so it can be
+    /// stored and sent across threads freely.
+    #[must_use]
+    pub fn resolve(&self, _sensors: &LMSensors) -> ResolvedBus {
+        ResolvedBus {
+            kind: self.kind(),
+            number: self.number(),
+            adapter_name: self.name().ok().map(ToOwned::to_owned),
+        }
+    }
+}
+
+impl Borrow<sensors_bus_id> for Bus {
+    fn borrow(&self) -> &sensors_bus_id {
+        &self.0
+    }
+}
+
+impl From<sensors_bus_id> for Bus {
+    fn from(raw: sensors_bus_id) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<Bus> for sensors_bus_id {
+    fn from(bus: Bus) -> Self {
+        bus.0
+    }
 }
 
 impl AsMut<sensors_bus_id> for Bus {
@@ -108,6 +161,16 @@ impl AsRef<sensors_bus_id> for Bus {
     }
 }
 
+/// Formats the bus type and number, without looking up the adapter name.
+impl fmt::Debug for Bus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bus")
+            .field("kind", &self.kind())
+            .field("number", &self.number())
+            .finish()
+    }
+}
+
 impl fmt::Display for Bus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Ok(name) = self.raw_name() {
@@ -118,6 +181,105 @@ impl fmt::Display for Bus {
     }
 }
 
+/**
+Shared access to a bus connection, for generic code accepting owned
+buses as well as references to them.
+
+This trait is sealed. It is implemented for [`Bus`], and for all shared
+references to its implementors.
+
+# Example
+
+```rust
+use lm_sensors::prelude::*;
+
+fn print_bus(bus: impl SharedBus) {
+    println!("{:?} {:?}", bus.kind(), bus.number());
+}
+
+let sensors = lm_sensors::Initializer::default().initialize()?;
+let bus = sensors.default_bus();
+print_bus(&bus);
+print_bus(bus);
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub trait SharedBus: sealed::Sealed {
+    /// Return a shared reference to the bus.
+    fn as_bus(&self) -> &Bus;
+
+    /// Return the adapter name of this bus.
+    ///
+    /// See: [`Bus::name`].
+    fn name(&self) -> Result<&str> {
+        self.as_bus().name()
+    }
+
+    /// Return the bus type.
+    fn kind(&self) -> Option<Kind> {
+        self.as_bus().kind()
+    }
+
+    /// Return the bus number.
+    fn number(&self) -> Number {
+        self.as_bus().number()
+    }
+
+    /// Return the raw adapter name of this bus.
+    ///
+    /// See: [`Bus::raw_name`].
+    fn raw_name(&self) -> Result<&CStr> {
+        self.as_bus().raw_name()
+    }
+
+    /// Return one of `SENSORS_BUS_TYPE_*` values.
+    fn raw_kind(&self) -> c_short {
+        self.as_bus().raw_kind()
+    }
+
+    /// Return a number, or one of `SENSORS_BUS_NR_*` values.
+    fn raw_number(&self) -> c_short {
+        self.as_bus().raw_number()
+    }
+}
+
+impl sealed::Sealed for Bus {}
+
+impl SharedBus for Bus {
+    fn as_bus(&self) -> &Bus {
+        self
+    }
+}
+
+impl<T: SharedBus + ?Sized> sealed::Sealed for &T {}
+
+impl<T: SharedBus + ?Sized> SharedBus for &T {
+    fn as_bus(&self) -> &Bus {
+        (**self).as_bus()
+    }
+}
+
+/// Owned snapshot of a [`Bus`], produced by [`Bus::resolve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedBus {
+    /// Bus type, if it is a valid [`Kind`].
+    pub kind: Option<Kind>,
+    /// Bus number.
+    pub number: Number,
+    /// Adapter name of the bus, if it could be found and is valid UTF-8.
+    pub adapter_name: Option<String>,
+}
+
+impl fmt::Display for ResolvedBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.adapter_name {
+            write!(f, "{name}")
+        } else {
+            write!(f, "\u{fffd}")
+        }
+    }
+}
+
 /// Type of a [`Bus`].
 #[repr(i16)]
 #[non_exhaustive]
@@ -132,6 +294,7 @@ impl fmt::Display for Bus {
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
     /// Any type of bus.
     Any = SENSORS_BUS_TYPE_ANY as c_short,
@@ -197,6 +360,7 @@ impl fmt::Display for Kind {
 /// Number of a [`Bus`].
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Number {
     /// A bus of any number.
     Any,
@@ -148,3 +148,90 @@ fn kind() {
         assert_eq!(k.to_string(), s);
     }
 }
+
+#[test]
+#[serial]
+fn resolve() {
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    let b0 = s.new_bus(super::Kind::PCI, super::Number::Number(42));
+    let r0 = b0.resolve(&s);
+    assert_eq!(r0.kind, Some(super::Kind::PCI));
+    assert_eq!(r0.number, super::Number::Number(42));
+    assert_eq!(r0.adapter_name.as_deref(), Some(b0.name().unwrap()));
+    assert_eq!(r0.to_string(), b0.to_string());
+
+    let b1 = s.default_bus();
+    let r1 = b1.resolve(&s);
+    assert_eq!(r1.kind, Some(super::Kind::Any));
+    assert_eq!(r1.number, super::Number::Any);
+    assert!(r1.adapter_name.is_none());
+    assert_eq!(r1.to_string(), "�");
+
+    drop(s);
+
+    // Resolved buses outlive the library instance, and can cross threads.
+    std::thread::spawn(move || assert_ne!(r0, r1))
+        .join()
+        .unwrap();
+}
+
+#[test]
+#[serial]
+#[allow(clippy::needless_borrows_for_generic_args)] // Borrows are tested.
+fn shared_bus() {
+    use crate::prelude::*;
+
+    fn kind_and_number(bus: impl SharedBus) -> (Option<super::Kind>, super::Number) {
+        (bus.kind(), bus.number())
+    }
+
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let bus = s.new_bus(super::Kind::PCI, super::Number::Number(42));
+    let expected = (Some(super::Kind::PCI), super::Number::Number(42));
+
+    assert_eq!(kind_and_number(&bus), expected);
+    assert_eq!(kind_and_number(&&bus), expected);
+    assert_eq!(kind_and_number(bus), expected);
+}
+
+#[test]
+#[serial]
+fn conversions() {
+    use core::borrow::Borrow;
+
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let b0 = s.new_bus(super::Kind::PCI, super::Number::Number(42));
+
+    let raw = sensors_bus_id::from(b0);
+    assert_eq!(raw.type_, SENSORS_BUS_TYPE_PCI as c_short);
+    assert_eq!(raw.nr, 42);
+    assert_eq!(super::Bus::from(raw), b0);
+
+    let raw: &sensors_bus_id = b0.borrow();
+    assert_eq!(raw, b0.as_ref());
+    assert_eq!(raw, b0.raw_ref());
+
+    let name = unsafe { sensors_get_adapter_name(b0.as_raw_ptr()) };
+    assert!(!name.is_null());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    use super::{Kind, Number};
+
+    for k in [Kind::Any, Kind::I2C, Kind::PCI, Kind::SCSI] {
+        let json = serde_json::to_string(&k).unwrap();
+        assert_eq!(serde_json::from_str::<Kind>(&json).unwrap(), k);
+    }
+
+    for n in [Number::Any, Number::Ignore, Number::Number(3)] {
+        let json = serde_json::to_string(&n).unwrap();
+        assert_eq!(serde_json::from_str::<Number>(&json).unwrap(), n);
+    }
+    assert_eq!(
+        serde_json::to_string(&Number::Number(3)).unwrap(),
+        r#"{"Number":3}"#
+    );
+}
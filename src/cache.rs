@@ -0,0 +1,473 @@
+//! Caches of values that are expensive to compute repeatedly.
+//!
+//! [`DisplayCache`] holds formatted names and labels, and [`FeatureCache`]
+//! holds the features of chips and the sub-features of features.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::hash::Hash;
+use std::os::raw::{c_int, c_short};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use sensors_sys::{sensors_feature, sensors_subfeature};
+
+use crate::{Bus, ChipRef, FeatureRef, SubFeatureRef};
+
+/**
+Cache of formatted chip names, feature labels and adapter names.
+
+Each value is formatted on first use, then returned from the cache
+without allocating memory, until the cache is invalidated. Values are
+returned without locking once they were looked up twice.
+
+Chips are identified by their prefix, bus and address, as the LM sensors
+library does, so that handles of the same chip share entries, *e.g.,*
+a [`Chip`] and a chip returned by [`LMSensors::chip_iter`]. Features are
+identified by the address of their raw data structure, which is owned by
+the LM sensors library.
+
+See: [`LMSensors::display_cache`].
+
+# Example
+
+```rust
+let sensors = lm_sensors::Initializer::default().initialize()?;
+let cache = sensors.display_cache();
+
+for chip in sensors.chip_iter(None) {
+    println!("chip: {} at {}", cache.chip_name(chip), cache.adapter_name(chip.bus()));
+    for feature in chip.feature_iter() {
+        println!("    {}", cache.feature_label(feature));
+    }
+}
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+
+[`LMSensors::chip_iter`]: crate::LMSensors::chip_iter
+[`LMSensors::display_cache`]: crate::LMSensors::display_cache
+[`Chip`]: crate::Chip
+*/
+#[derive(Debug, Default)]
+pub struct DisplayCache {
+    chip_names: Map<ChipId, Arc<str>>,
+    feature_labels: Map<(ChipId, *const sensors_feature), Arc<str>>,
+    adapter_names: Map<(c_short, c_short), Arc<str>>,
+}
+
+impl DisplayCache {
+    /// Return the formatted name of the given chip.
+    ///
+    /// See: [`ChipRef`]'s implementation of [`Display`](core::fmt::Display).
+    #[must_use]
+    pub fn chip_name(&self, chip: ChipRef<'_>) -> Arc<str> {
+        let (id, prefix) = chip_key(chip);
+        self.chip_names
+            .get_or_insert_with(id, prefix, || chip.to_string().into())
+    }
+
+    /// Return the formatted label of the given feature.
+    ///
+    /// See: [`FeatureRef`]'s implementation of [`Display`](core::fmt::Display).
+    #[must_use]
+    pub fn feature_label(&self, feature: FeatureRef<'_>) -> Arc<str> {
+        let (id, prefix) = chip_key(feature.chip());
+        self.feature_labels
+            .get_or_insert_with((id, feature.as_raw_ptr()), prefix, || {
+                feature.to_string().into()
+            })
+    }
+
+    /// Return the formatted adapter name of the given bus.
+    ///
+    /// See: [`Bus`]'s implementation of [`Display`](core::fmt::Display).
+    #[must_use]
+    pub fn adapter_name(&self, bus: Bus) -> Arc<str> {
+        let key = (bus.raw_kind(), bus.raw_number());
+        self.adapter_names
+            .get_or_insert_with(key, &[], || bus.to_string().into())
+    }
+
+    /// Forget the cached name of the given chip, and the cached labels
+    /// of its features.
+    pub fn invalidate_chip(&self, chip: ChipRef<'_>) {
+        let chip = chip_key(chip);
+        self.chip_names.retain(|&id, prefix| (id, prefix) != chip);
+        self.feature_labels
+            .retain(|&(id, _), prefix| (id, prefix) != chip);
+    }
+
+    /// Forget all cached values.
+    pub fn invalidate(&self) {
+        self.chip_names.clear();
+        self.feature_labels.clear();
+        self.adapter_names.clear();
+    }
+}
+
+// Safety: keys are only compared, never dereferenced.
+unsafe impl Send for DisplayCache {}
+// Safety: keys are only compared, never dereferenced.
+unsafe impl Sync for DisplayCache {}
+
+/**
+Cache of the features of chips, and of the sub-features of features.
+
+Features and sub-features are enumerated on first use, then iterated from
+the cache without calling into the LM sensors library, until the cache is
+invalidated. They do not change until the LM sensors library is cleaned up,
+so each [`LMSensors`] instance has its own cache.
+
+Chips are identified by their prefix, bus and address, as the LM sensors
+library does, so that handles of the same chip share entries, *e.g.,*
+a [`Chip`] and a chip returned by [`LMSensors::chip_iter`]. Features are
+identified by the address of their raw data structure, which is owned by
+the LM sensors library.
+
+See: [`LMSensors::feature_cache`].
+
+# Example
+
+```rust
+let sensors = lm_sensors::Initializer::default().initialize()?;
+let cache = sensors.feature_cache();
+
+for chip in sensors.chip_iter(None) {
+    for feature in cache.features(chip) {
+        for sub_feature in cache.sub_features(feature) {
+            println!("{sub_feature}: {:?}", sub_feature.raw_value());
+        }
+    }
+}
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+
+[`LMSensors`]: crate::LMSensors
+[`LMSensors::chip_iter`]: crate::LMSensors::chip_iter
+[`LMSensors::feature_cache`]: crate::LMSensors::feature_cache
+[`Chip`]: crate::Chip
+*/
+#[derive(Debug, Default)]
+pub struct FeatureCache {
+    features: Map<ChipId, Arc<[*const sensors_feature]>>,
+    sub_features: Map<(ChipId, *const sensors_feature), Arc<[*const sensors_subfeature]>>,
+}
+
+impl FeatureCache {
+    /// Return an iterator which yields all features of the given chip.
+    ///
+    /// See: [`ChipRef::feature_iter`].
+    pub fn features<'a>(&'a self, chip: ChipRef<'a>) -> Features<'a> {
+        let (id, prefix) = chip_key(chip);
+        let raw = self.features.get_or_insert_with(id, prefix, || {
+            chip.feature_iter().map(FeatureRef::as_raw_ptr).collect()
+        });
+        Features {
+            chip,
+            raw,
+            index: 0,
+        }
+    }
+
+    /// Return an iterator which yields all sub-features of the given feature.
+    ///
+    /// See: [`FeatureRef::sub_feature_iter`].
+    pub fn sub_features<'a>(&'a self, feature: FeatureRef<'a>) -> SubFeatures<'a> {
+        let (id, prefix) = chip_key(feature.chip());
+        let key = (id, feature.as_raw_ptr());
+        let raw = self.sub_features.get_or_insert_with(key, prefix, || {
+            let sub_features = feature.sub_feature_iter();
+            sub_features.map(SubFeatureRef::as_raw_ptr).collect()
+        });
+        SubFeatures {
+            feature,
+            raw,
+            index: 0,
+        }
+    }
+
+    /// Forget the cached features of the given chip, and the cached
+    /// sub-features of its features.
+    pub fn invalidate_chip(&self, chip: ChipRef<'_>) {
+        let chip = chip_key(chip);
+        self.features.retain(|&id, prefix| (id, prefix) != chip);
+        self.sub_features
+            .retain(|&(id, _), prefix| (id, prefix) != chip);
+    }
+
+    /// Forget all cached features and sub-features.
+    pub fn invalidate(&self) {
+        self.features.clear();
+        self.sub_features.clear();
+    }
+}
+
+// Safety: cached structures are owned by the LM sensors library, and do not
+// change until it is cleaned up. They are only dereferenced by iterators
+// borrowing the cache, hence the LM sensors library, with chips of the same
+// identity as the chips they were enumerated for.
+unsafe impl Send for FeatureCache {}
+// Safety: see above.
+unsafe impl Sync for FeatureCache {}
+
+/// Iterator over the cached features of a chip. Yields [`FeatureRef`]s.
+///
+/// See: [`FeatureCache::features`].
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Features<'a> {
+    chip: ChipRef<'a>,
+    raw: Arc<[*const sensors_feature]>,
+    index: usize,
+}
+
+impl<'a> Iterator for Features<'a> {
+    type Item = FeatureRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let raw = *self.raw.get(self.index)?;
+        self.index += 1;
+        // Safety: `raw` was returned by sensors_get_features() for this chip.
+        let raw = unsafe { &*raw };
+        Some(FeatureRef {
+            chip: self.chip,
+            raw,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.raw.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Features<'_> {}
+
+/// Iterator over the cached sub-features of a feature.
+/// Yields [`SubFeatureRef`]s.
+///
+/// See: [`FeatureCache::sub_features`].
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct SubFeatures<'a> {
+    feature: FeatureRef<'a>,
+    raw: Arc<[*const sensors_subfeature]>,
+    index: usize,
+}
+
+impl<'a> Iterator for SubFeatures<'a> {
+    type Item = SubFeatureRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let raw = *self.raw.get(self.index)?;
+        self.index += 1;
+        // Safety: `raw` was returned by sensors_get_all_subfeatures()
+        // for this feature.
+        let raw = unsafe { &*raw };
+        Some(SubFeatureRef {
+            feature: self.feature,
+            raw,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.raw.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for SubFeatures<'_> {}
+
+/// Identity of a chip, besides its prefix: bus type, bus number and address.
+type ChipId = (c_short, c_short, c_int);
+
+/// Return the identity of the given chip, and its prefix.
+fn chip_key(chip: ChipRef<'_>) -> (ChipId, &[u8]) {
+    let bus = chip.bus();
+    let prefix = chip.raw_prefix().map_or(&[][..], CStr::to_bytes);
+    (
+        (bus.raw_kind(), bus.raw_number(), chip.raw_address()),
+        prefix,
+    )
+}
+
+/// Entries of a [`Map`], identified by a key and a sub-key.
+type Table<K, V> = HashMap<K, Vec<(Box<[u8]>, V)>>;
+
+/**
+Map of entries identified by a key and a sub-key, *e.g.,* the identity
+of a chip and its prefix, so that entries are looked up without
+allocating memory.
+
+Published entries are held by an immutable table, and are looked up
+without locking. Inserted entries are held by a locked table, until they
+are looked up again, which publishes them all at once in a new table.
+Replaced tables are freed once no lookups are in progress.
+*/
+struct Map<K, V> {
+    published: AtomicPtr<Table<K, V>>,
+    lookups: AtomicUsize,
+    state: Mutex<State<K, V>>,
+}
+
+#[derive(Debug)]
+struct State<K, V> {
+    inserted: Table<K, V>,
+    /// Replaced tables, which might still be looked up.
+    retired: Vec<*mut Table<K, V>>,
+}
+
+impl<K, V> Default for Map<K, V> {
+    fn default() -> Self {
+        Self {
+            published: AtomicPtr::new(Box::into_raw(Box::default())),
+            lookups: AtomicUsize::new(0),
+            state: Mutex::new(State {
+                inserted: HashMap::default(),
+                retired: Vec::default(),
+            }),
+        }
+    }
+}
+
+impl<K, V> Drop for Map<K, V> {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+        free_tables(&mut state.retired);
+        // Safety: the published table was allocated by `Box::new()`,
+        // and this map is borrowed exclusively.
+        drop(unsafe { Box::from_raw(*self.published.get_mut()) });
+    }
+}
+
+// Safety: tables are owned by the map, and shared between threads only
+// through lookups.
+unsafe impl<K: Send + Sync, V: Send + Sync> Send for Map<K, V> {}
+// Safety: see above.
+unsafe impl<K: Send + Sync, V: Send + Sync> Sync for Map<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Map<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        self.with_published(|published| {
+            f.debug_struct("Map")
+                .field("published", published)
+                .field("inserted", &state.inserted)
+                .finish()
+        })
+    }
+}
+
+impl<K, V> Map<K, V> {
+    /// Call the given function with the published table.
+    fn with_published<R>(&self, f: impl FnOnce(&Table<K, V>) -> R) -> R {
+        // Tables are replaced, then freed only if no lookups are counted,
+        // so a table is never freed while it is being looked up.
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        // Safety: see above.
+        let result = f(unsafe { &*self.published.load(Ordering::SeqCst) });
+        self.lookups.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+
+    /// Publish the given table, replacing the published one.
+    fn publish(&self, state: &mut State<K, V>, table: Table<K, V>) {
+        let replaced = self
+            .published
+            .swap(Box::into_raw(Box::new(table)), Ordering::SeqCst);
+        state.retired.push(replaced);
+
+        // Lookups starting from now use the new table.
+        if self.lookups.load(Ordering::SeqCst) == 0 {
+            free_tables(&mut state.retired);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<K, V>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K: Eq + Hash + Copy, V: Clone> Map<K, V> {
+    fn get_or_insert_with(&self, key: K, sub_key: &[u8], f: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.with_published(|table| find(table, key, sub_key).cloned()) {
+            return value;
+        }
+
+        let mut state = self.lock();
+        if let Some(value) = find(&state.inserted, key, sub_key).cloned() {
+            // Entries looked up again are likely looked up repeatedly.
+            let table = self.merged(&mut state);
+            self.publish(&mut state, table);
+            return value;
+        }
+        drop(state);
+
+        // Compute outside of the lock, because computing calls into
+        // the LM sensors library.
+        let value = f();
+        let mut state = self.lock();
+        let entries = state.inserted.entry(key).or_default();
+        if let Some(value) = find_entry(entries, sub_key) {
+            return value.clone();
+        }
+        entries.push((sub_key.into(), value.clone()));
+        value
+    }
+
+    /// Return a copy of the published table, with the inserted entries
+    /// moved into it.
+    fn merged(&self, state: &mut State<K, V>) -> Table<K, V> {
+        // The published table is only replaced while the state is locked.
+        let mut table = self.with_published(Clone::clone);
+        for (key, entries) in state.inserted.drain() {
+            table.entry(key).or_default().extend(entries);
+        }
+        table
+    }
+
+    fn retain(&self, mut f: impl FnMut(&K, &[u8]) -> bool) {
+        let mut retain = |table: &mut Table<K, V>| {
+            table.retain(|key, entries| {
+                entries.retain(|(sub_key, _)| f(key, sub_key));
+                !entries.is_empty()
+            });
+        };
+
+        let mut state = self.lock();
+        let mut table = self.with_published(Clone::clone);
+        retain(&mut table);
+        retain(&mut state.inserted);
+        self.publish(&mut state, table);
+    }
+
+    fn clear(&self) {
+        let mut state = self.lock();
+        state.inserted.clear();
+        self.publish(&mut state, Table::default());
+    }
+}
+
+/// Free the given tables, which are not looked up anymore.
+fn free_tables<K, V>(tables: &mut Vec<*mut Table<K, V>>) {
+    for table in tables.drain(..) {
+        // Safety: tables were allocated by `Box::new()`, and are freed once.
+        drop(unsafe { Box::from_raw(table) });
+    }
+}
+
+fn find<'a, K: Eq + Hash, V>(map: &'a Table<K, V>, key: K, sub_key: &[u8]) -> Option<&'a V> {
+    map.get(&key)
+        .and_then(|entries| find_entry(entries, sub_key))
+}
+
+fn find_entry<'a, V>(entries: &'a [(Box<[u8]>, V)], sub_key: &[u8]) -> Option<&'a V> {
+    entries
+        .iter()
+        .find(|(key, _)| **key == *sub_key)
+        .map(|(_, value)| value)
+}
@@ -0,0 +1,183 @@
+#![cfg(test)]
+
+use std::sync::Arc;
+
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn display_cache() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let cache = s.display_cache();
+
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    let name0 = cache.chip_name(chip.as_ref());
+    assert_eq!(&*name0, "lm75-i2c-0-48");
+    let name1 = cache.chip_name(chip.as_ref());
+    assert!(Arc::ptr_eq(&name0, &name1));
+
+    let bus = s.new_bus(crate::bus::Kind::PCI, crate::bus::Number::Number(42));
+    let adapter0 = cache.adapter_name(bus);
+    assert_eq!(*adapter0, *bus.to_string());
+    assert!(Arc::ptr_eq(&adapter0, &cache.adapter_name(bus)));
+
+    cache.invalidate_chip(chip.as_ref());
+    let name2 = cache.chip_name(chip.as_ref());
+    assert_eq!(name0, name2);
+    assert!(!Arc::ptr_eq(&name0, &name2));
+    assert!(Arc::ptr_eq(&adapter0, &cache.adapter_name(bus)));
+
+    cache.invalidate();
+    assert!(!Arc::ptr_eq(&adapter0, &cache.adapter_name(bus)));
+    assert!(!Arc::ptr_eq(&name2, &cache.chip_name(chip.as_ref())));
+}
+
+#[test]
+#[serial]
+fn display_cache_features() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let cache = s.display_cache();
+
+    for chip in s.chip_iter(None) {
+        for feature in chip.feature_iter() {
+            let label = cache.feature_label(feature);
+            assert_eq!(*label, *feature.to_string());
+            assert!(Arc::ptr_eq(&label, &cache.feature_label(feature)));
+        }
+    }
+}
+
+#[test]
+#[serial]
+fn feature_cache() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let cache = s.feature_cache();
+
+    for chip in s.chip_iter(None) {
+        let expected: Vec<_> = chip.feature_iter().collect();
+        let features = cache.features(chip);
+        assert_eq!(features.len(), expected.len());
+        assert_eq!(features.collect::<Vec<_>>(), expected);
+
+        for feature in cache.features(chip) {
+            let expected: Vec<_> = feature.sub_feature_iter().collect();
+            let sub_features: Vec<_> = cache.sub_features(feature).collect();
+            assert_eq!(sub_features, expected);
+        }
+
+        cache.invalidate_chip(chip);
+        assert_eq!(cache.features(chip).len(), expected.len());
+    }
+
+    cache.invalidate();
+    let chip0 = s.chip_iter(None).next().unwrap();
+    assert_eq!(cache.features(chip0).count(), chip0.feature_iter().count());
+}
+
+#[test]
+#[serial]
+fn feature_cache_chip_identity() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let cache = s.feature_cache();
+
+    // Handles of the same chip share entries, and handles of other chips
+    // never do, even at the same address.
+    for chip in s.chip_iter(None) {
+        let expected: Vec<_> = chip.feature_iter().map(|f| f.as_raw_ptr()).collect();
+
+        let owned = s.new_chip(&chip.name().unwrap()).unwrap();
+        let features = cache.features(owned.as_ref());
+        assert_eq!(
+            features.map(|f| f.as_raw_ptr()).collect::<Vec<_>>(),
+            expected
+        );
+        drop(owned);
+
+        let other = s.new_chip("lm75-i2c-0-48").unwrap();
+        if other.as_ref() != chip {
+            let features: Vec<_> = cache.features(other.as_ref()).collect();
+            assert_eq!(features, other.as_ref().feature_iter().collect::<Vec<_>>());
+        }
+    }
+
+    let a = s.new_chip("lm75-i2c-0-48").unwrap();
+    let b = s.new_chip("lm75-i2c-0-49").unwrap();
+    let c = s.new_chip("lm78-i2c-0-48").unwrap();
+    assert_ne!(super::chip_key(a.as_ref()), super::chip_key(b.as_ref()));
+    assert_ne!(super::chip_key(a.as_ref()), super::chip_key(c.as_ref()));
+    let a2 = s.new_chip("lm75-i2c-0-48").unwrap();
+    assert_eq!(super::chip_key(a.as_ref()), super::chip_key(a2.as_ref()));
+}
+
+#[test]
+#[serial]
+fn display_cache_chip_identity() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let cache = s.display_cache();
+
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    let name0 = cache.chip_name(chip.as_ref());
+    drop(chip);
+
+    // Chips of other identities never share entries.
+    let chip = s.new_chip("lm75-i2c-0-49").unwrap();
+    assert_eq!(&*cache.chip_name(chip.as_ref()), "lm75-i2c-0-49");
+    drop(chip);
+
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    assert!(Arc::ptr_eq(&name0, &cache.chip_name(chip.as_ref())));
+}
+
+#[test]
+fn map() {
+    let map = super::Map::<u32, Arc<str>>::default();
+    let a = map.get_or_insert_with(1, b"a", || "1a".into());
+    let b = map.get_or_insert_with(1, b"b", || "1b".into());
+    assert_ne!(a, b);
+
+    // Entries looked up again are published.
+    assert!(Arc::ptr_eq(
+        &a,
+        &map.get_or_insert_with(1, b"a", || unreachable!())
+    ));
+    assert!(map.lock().inserted.is_empty());
+    assert!(Arc::ptr_eq(
+        &b,
+        &map.get_or_insert_with(1, b"b", || unreachable!())
+    ));
+
+    map.retain(|_, sub_key| sub_key != b"a");
+    assert!(!Arc::ptr_eq(
+        &a,
+        &map.get_or_insert_with(1, b"a", || "1a".into())
+    ));
+    assert!(Arc::ptr_eq(
+        &b,
+        &map.get_or_insert_with(1, b"b", || unreachable!())
+    ));
+
+    map.clear();
+    assert!(!Arc::ptr_eq(
+        &b,
+        &map.get_or_insert_with(1, b"b", || "1b".into())
+    ));
+
+    let map = Arc::new(super::Map::<u32, u32>::default());
+    let threads: Vec<_> = (0..4)
+        .map(|thread| {
+            let map = Arc::clone(&map);
+            std::thread::spawn(move || {
+                for i in 0..1000 {
+                    let key = i % 64;
+                    assert_eq!(map.get_or_insert_with(key, b"", || key * 2), key * 2);
+                    if thread == 0 && i % 100 == 0 {
+                        map.clear();
+                    }
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+//! Type-level separation of read access from write access.
+//!
+//! Mutating APIs, *e.g.,* [`SubFeatureRef::set_value`], require a
+//! [`Capabilities`] token, which can only be obtained from an [`LMSensors`]
+//! instance. [`LMSensors::read_only`] returns a [`ReadOnlySensors`] handle,
+//! which gives access to chips, features and sub-features, but never to
+//! a [`Capabilities`] token. Libraries can hand out such handles to code
+//! that must only read sensors.
+//!
+//! [`SubFeatureRef::set_value`]: crate::SubFeatureRef::set_value
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::{c_short, CStr};
+use core::fmt;
+
+use crate::audit::AuditRecord;
+use crate::bus::{self, Bus};
+use crate::cache::{DisplayCache, FeatureCache};
+use crate::chip::{Chip, ChipRef};
+use crate::errors::Result;
+use crate::events::{self, Event};
+use crate::LMSensors;
+
+/// Token granting write access to chips of an [`LMSensors`] instance,
+/// *e.g.,* setting values of sub-features.
+///
+/// See: [`LMSensors::capabilities`].
+#[derive(Clone, Copy)]
+pub struct Capabilities<'a> {
+    sensors: &'a LMSensors,
+}
+
+/**
+Handle giving read-only access to an [`LMSensors`] instance.
+
+See: [`LMSensors::read_only`].
+
+# Example
+
+A [`Capabilities`] token cannot be obtained from this handle:
+
+```compile_fail
+fn set_fan(sensors: lm_sensors::ReadOnlySensors, pwm: lm_sensors::SubFeatureRef) {
+    pwm.set_raw_value(sensors.capabilities(), 255.0).unwrap();
+}
+```
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct ReadOnlySensors<'a> {
+    sensors: &'a LMSensors,
+}
+
+impl LMSensors {
+    /// Return a token granting write access to chips of this instance.
+    #[must_use]
+    pub fn capabilities(&self) -> Capabilities<'_> {
+        Capabilities { sensors: self }
+    }
+
+    /// Return a handle giving read-only access to this instance.
+    #[must_use]
+    pub fn read_only(&self) -> ReadOnlySensors<'_> {
+        ReadOnlySensors { sensors: self }
+    }
+}
+
+impl Capabilities<'_> {
+    /// Return `true` if writes are recorded, by the audit sink or as events.
+    pub(crate) fn audited(self) -> bool {
+        self.sensors.audit_sink.is_some() || events::has_subscribers()
+    }
+
+    /// Record the given write in the audit sink, if any,
+    /// and publish it as an event.
+    pub(crate) fn audit(self, record: AuditRecord) {
+        if let Some(sink) = &self.sensors.audit_sink {
+            sink.record(&record);
+        }
+        events::publish(&Event::WriteAudited(record));
+    }
+}
+
+impl fmt::Debug for Capabilities<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Capabilities").finish_non_exhaustive()
+    }
+}
+
+impl<'a> ReadOnlySensors<'a> {
+    /// See: [`LMSensors::version`].
+    #[must_use]
+    pub fn version(self) -> Option<&'a str> {
+        self.sensors.version()
+    }
+
+    /// See: [`LMSensors::raw_version`].
+    #[must_use]
+    pub fn raw_version(self) -> Option<&'a CStr> {
+        self.sensors.raw_version()
+    }
+
+    /// See: [`LMSensors::display_cache`].
+    #[must_use]
+    pub fn display_cache(self) -> &'a DisplayCache {
+        self.sensors.display_cache()
+    }
+
+    /// See: [`LMSensors::feature_cache`].
+    #[must_use]
+    pub fn feature_cache(self) -> &'a FeatureCache {
+        self.sensors.feature_cache()
+    }
+
+    /// See: [`LMSensors::new_chip`].
+    pub fn new_chip(self, name: &str) -> Result<Chip<'a>> {
+        self.sensors.new_chip(name)
+    }
+
+    /// See: [`LMSensors::new_raw_bus`].
+    #[must_use]
+    pub fn new_raw_bus(self, kind: c_short, number: c_short) -> Bus {
+        self.sensors.new_raw_bus(kind, number)
+    }
+
+    /// See: [`LMSensors::new_bus`].
+    #[must_use]
+    pub fn new_bus(self, kind: bus::Kind, number: bus::Number) -> Bus {
+        self.sensors.new_bus(kind, number)
+    }
+
+    /// See: [`LMSensors::default_bus`].
+    #[must_use]
+    pub fn default_bus(self) -> Bus {
+        self.sensors.default_bus()
+    }
+
+    /// See: [`LMSensors::chip_iter`].
+    pub fn chip_iter(self, match_pattern: Option<ChipRef<'a>>) -> crate::chip::Iter<'a> {
+        self.sensors.chip_iter(match_pattern)
+    }
+}
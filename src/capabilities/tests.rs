@@ -0,0 +1,26 @@
+#![cfg(test)]
+
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn read_only() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let read_only = s.read_only();
+
+    assert_eq!(read_only.version(), s.version());
+    assert_eq!(read_only.raw_version(), s.raw_version());
+    assert_eq!(read_only.chip_iter(None).count(), s.chip_iter(None).count());
+    assert_eq!(read_only.default_bus(), s.default_bus());
+    assert_eq!(
+        read_only.new_bus(crate::bus::Kind::ISA, crate::bus::Number::Any),
+        s.new_bus(crate::bus::Kind::ISA, crate::bus::Number::Any)
+    );
+    assert_eq!(read_only.new_raw_bus(1, 2), s.new_raw_bus(1, 2));
+
+    let chip = read_only.new_chip("lm75-i2c-0-48").unwrap();
+    assert_eq!(chip.name().unwrap(), "lm75-i2c-0-48");
+
+    // Writes need a token from the library instance.
+    chip.do_chip_sets(s.capabilities()).unwrap();
+}
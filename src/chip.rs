@@ -3,23 +3,35 @@
 #[cfg(test)]
 mod tests;
 
+mod sealed {
+    pub trait Sealed {}
+}
+
+use core::borrow::Borrow;
 use core::ffi::CStr;
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
+use core::sync::atomic;
+use core::time::Duration;
 use core::{fmt, mem, ptr};
+use std::collections::BTreeSet;
 use std::ffi::{CString, OsStr};
-use std::io;
 use std::os::raw::c_int;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::{fs, io};
 
 use sensors_sys::*;
 
-use crate::errors::{Error, Result};
-use crate::utils::api_access_lock;
+use crate::audit::{self, AuditRecord};
+use crate::capabilities::Capabilities;
+use crate::errors::{Capability, Error, Result};
+use crate::redact::Redacted;
+use crate::utils::{api_access_lock, c_str_dup};
 use crate::Bus;
 
 /// Chip connected to sensors or actuators.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
 pub struct Chip<'a> {
     pub(crate) raw: sensors_chip_name,
     pub(crate) _phantom: &'a PhantomData<crate::LMSensors>,
@@ -28,6 +40,8 @@ pub struct Chip<'a> {
 impl<'a> Chip<'a> {
     /// See: [`sensors_parse_chip_name`].
     pub(crate) fn new(name: &str) -> Result<Self> {
+        crate::chip_name::validate(name)?;
+
         // Though undocumented, sensors_parse_chip_name() assumes its output
         // parameter to be zero-initialized.
         let c_name = CString::new(name)?;
@@ -53,8 +67,10 @@ impl<'a> Chip<'a> {
 
     /// # Safety
     /// It is the responsibility of the caller to call
-    /// [`sensors_free_chip_name`] on the result.
+    /// [`sensors_free_chip_name`] on the result, then to `free()` its `path`.
     /// Failing to do so leaks memory.
+    ///
+    /// See: [`Chip::from_raw_parts`], [`RawChipGuard`].
     #[must_use]
     pub unsafe fn into_raw_parts(self) -> sensors_chip_name {
         let raw = self.raw;
@@ -62,12 +78,48 @@ impl<'a> Chip<'a> {
         raw
     }
 
+    /// Return a chip taking ownership of the given raw chip,
+    /// *e.g.,* returned by [`Chip::into_raw_parts`].
+    ///
+    /// # Safety
+    ///
+    /// - `prefix` and `path` of the given [`sensors_chip_name`] must be null,
+    ///   or allocated by `malloc()` and not owned by anything else,
+    ///   *e.g.,* initialized by calling [`sensors_parse_chip_name`].
+    /// - The result must not outlive the instance of [`LMSensors`] it is
+    ///   used with.
+    ///
+    /// [`LMSensors`]: crate::LMSensors
+    #[must_use]
+    pub unsafe fn from_raw_parts(raw: sensors_chip_name) -> Self {
+        Self {
+            raw,
+            _phantom: &PhantomData,
+        }
+    }
+
+    /// Return a guard owning the raw data structure of this chip.
+    #[must_use]
+    pub fn into_raw_guard(self) -> RawChipGuard {
+        // Safety: `raw` is owned by this chip, and ownership is moved to the guard.
+        unsafe { RawChipGuard::new(self.into_raw_parts()) }
+    }
+
     /// Returns a shared reference to the raw data structure [`sensors_chip_name`].
     #[must_use]
     pub fn raw_ref(&self) -> &sensors_chip_name {
         &self.raw
     }
 
+    /// Returns a pointer to the raw data structure [`sensors_chip_name`].
+    ///
+    /// The pointer is valid while this chip is alive and not moved, and must
+    /// not be used to change the raw data structure.
+    #[must_use]
+    pub fn as_raw_ptr(&self) -> *const sensors_chip_name {
+        &self.raw
+    }
+
     /// Returns an exclusive reference to the raw data structure [`sensors_chip_name`].
     ///
     /// # Safety
@@ -90,6 +142,14 @@ impl<'a> Chip<'a> {
         self.raw.bus = new_bus.0;
     }
 
+    /// Return this chip, formatted with serial-number-like strings redacted,
+    /// *e.g.,* for public bug reports.
+    ///
+    /// See: [`Redacted`].
+    pub fn redact(&self) -> Redacted<&Self> {
+        Redacted::new(self)
+    }
+
     /// Return an iterator which yields all sensors and actuators
     /// (*a.k.a.,* features) controlled by this chip.
     pub fn feature_iter(&'a self) -> crate::feature::Iter<'a> {
@@ -99,6 +159,14 @@ impl<'a> Chip<'a> {
         }
     }
 
+    /// Return an iterator which yields all features controlled by this chip,
+    /// with the values of their canonical input sub-features.
+    ///
+    /// See: [`ChipRef::readings`].
+    pub fn readings(&'a self) -> crate::feature::Readings<'a> {
+        self.as_ref().readings()
+    }
+
     /// Return name of this chip, if it is valid UTF-8.
     pub fn name(&self) -> Result<String> {
         self.as_ref().name()
@@ -117,17 +185,47 @@ impl<'a> Chip<'a> {
         self.as_ref().path()
     }
 
+    /// Return the interval at which the driver of this chip updates
+    /// its readings.
+    ///
+    /// See: [`ChipRef::update_interval`].
+    #[cfg(unix)]
+    pub fn update_interval(&self) -> Result<Duration> {
+        self.as_ref().update_interval()
+    }
+
+    /// Ask the driver of this chip to update its readings at
+    /// the given interval.
+    ///
+    /// See: [`ChipRef::set_update_interval`].
+    #[cfg(unix)]
+    pub fn set_update_interval(
+        &self,
+        capabilities: Capabilities<'_>,
+        interval: Duration,
+    ) -> Result<()> {
+        self.as_ref().set_update_interval(capabilities, interval)
+    }
+
     /// Return the address of this chip, if available.
     #[must_use]
     pub fn address(&self) -> Option<c_int> {
         self.as_ref().address()
     }
 
+    /// Return a hash identifying the hardware of this chip.
+    ///
+    /// See: [`ChipRef::fingerprint`].
+    #[must_use]
+    pub fn fingerprint(&'a self) -> u64 {
+        self.as_ref().fingerprint()
+    }
+
     /// Execute all set statements for this chip.
     ///
     /// See: [`sensors_do_chip_sets`].
-    pub fn do_chip_sets(&self) -> Result<()> {
-        self.as_ref().do_chip_sets()
+    pub fn do_chip_sets(&self, capabilities: Capabilities<'_>) -> Result<()> {
+        self.as_ref().do_chip_sets(capabilities)
     }
 
     /// Return a copy of the bus connected to this chip.
@@ -143,6 +241,22 @@ impl<'a> Chip<'a> {
         self.as_ref().raw_name()
     }
 
+    /// Write the name of this chip into the given buffer, without allocating
+    /// memory, and return it, if it is valid UTF-8.
+    ///
+    /// See: [`ChipRef::name_into`].
+    pub fn name_into<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b str> {
+        self.as_ref().name_into(buffer)
+    }
+
+    /// Write the raw name of this chip into the given buffer,
+    /// without allocating memory, and return it.
+    ///
+    /// See: [`ChipRef::raw_name_into`].
+    pub fn raw_name_into<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b CStr> {
+        self.as_ref().raw_name_into(buffer)
+    }
+
     /// Return the raw prefix of this chip, if available.
     #[must_use]
     pub fn raw_prefix(&self) -> Option<&CStr> {
@@ -166,10 +280,46 @@ impl<'a> Chip<'a> {
 impl<'a> Drop for Chip<'a> {
     /// See: [`sensors_free_chip_name`].
     fn drop(&mut self) {
-        let _ignored = api_access_lock()
-            .lock()
-            // Safety: sensors_free_chip_name() is assumed to be safe.
-            .map(|_guard| unsafe { sensors_free_chip_name(&mut self.raw) });
+        // Safety: `raw` is owned by this chip.
+        unsafe { free_raw(&mut self.raw) }
+    }
+}
+
+impl<'a> Clone for Chip<'a> {
+    /// Return a deep copy of this chip.
+    ///
+    /// # Panics
+    ///
+    /// This panics if memory allocation fails.
+    fn clone(&self) -> Self {
+        match deep_copy("Chip::clone()", &self.raw) {
+            Ok(raw) => Self {
+                raw,
+                _phantom: self._phantom,
+            },
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
+
+impl<'a> Borrow<sensors_chip_name> for Chip<'a> {
+    fn borrow(&self) -> &sensors_chip_name {
+        &self.raw
+    }
+}
+
+impl<'a> TryFrom<ChipRef<'a>> for Chip<'a> {
+    type Error = Error;
+
+    /// See: [`ChipRef::to_chip`].
+    fn try_from(chip: ChipRef<'a>) -> Result<Self> {
+        chip.to_chip()
+    }
+}
+
+impl<'a> From<&'a Chip<'_>> for ChipRef<'a> {
+    fn from(chip: &'a Chip<'_>) -> Self {
+        ChipRef(&chip.raw)
     }
 }
 
@@ -185,6 +335,12 @@ impl<'a> PartialEq<Chip<'_>> for ChipRef<'a> {
     }
 }
 
+impl<'a> fmt::Debug for Chip<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_chip(self.as_ref(), "Chip", f)
+    }
+}
+
 impl<'a> fmt::Display for Chip<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Ok(name) = self.raw_name() {
@@ -196,7 +352,7 @@ impl<'a> fmt::Display for Chip<'a> {
 }
 
 /// Shared reference to a chip connected to sensors or actuators.
-#[derive(Debug, Clone, Copy, Eq)]
+#[derive(Clone, Copy, Eq)]
 pub struct ChipRef<'a>(pub(crate) &'a sensors_chip_name);
 
 impl<'a> ChipRef<'a> {
@@ -206,6 +362,36 @@ impl<'a> ChipRef<'a> {
         self.0
     }
 
+    /// Returns a pointer to the raw data structure [`sensors_chip_name`].
+    ///
+    /// The pointer is valid for the lifetime `'a`, and must not be used
+    /// to change the raw data structure.
+    #[must_use]
+    pub fn as_raw_ptr(self) -> *const sensors_chip_name {
+        self.0
+    }
+
+    /// Return an owned deep copy of this chip.
+    ///
+    /// The copy can be used as a match pattern, *e.g.,* with
+    /// [`LMSensors::chip_iter`], after changing it.
+    ///
+    /// [`LMSensors::chip_iter`]: crate::LMSensors::chip_iter
+    pub fn to_chip(self) -> Result<Chip<'a>> {
+        Ok(Chip {
+            raw: deep_copy("ChipRef::to_chip()", self.0)?,
+            _phantom: &PhantomData,
+        })
+    }
+
+    /// Return this chip, formatted with serial-number-like strings redacted,
+    /// *e.g.,* for public bug reports.
+    ///
+    /// See: [`Redacted`].
+    pub fn redact(self) -> Redacted<Self> {
+        Redacted::new(self)
+    }
+
     /// Return an iterator which yields all sensors and actuators
     /// (*a.k.a.,* features) controlled by this chip.
     pub fn feature_iter(self) -> crate::feature::Iter<'a> {
@@ -215,6 +401,29 @@ impl<'a> ChipRef<'a> {
         }
     }
 
+    /**
+    Return an iterator which yields all features controlled by this chip,
+    with the values of their canonical input sub-features, *e.g.,*
+    `temp1_input` for temperature features.
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::default().initialize()?;
+    for chip in sensors.chip_iter(None) {
+        for (feature, value) in chip.readings() {
+            println!("{chip}: {feature}: {value:?}");
+        }
+    }
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    pub fn readings(self) -> crate::feature::Readings<'a> {
+        crate::feature::Readings {
+            iter: self.feature_iter(),
+        }
+    }
+
     /// Return name of this chip, if it is valid UTF-8.
     pub fn name(self) -> Result<String> {
         self.raw_name()?.into_string().map_err(Into::into)
@@ -239,6 +448,37 @@ impl<'a> ChipRef<'a> {
             .map(Path::new)
     }
 
+    /// Return the interval at which the driver of this chip updates
+    /// its readings.
+    ///
+    /// This fails with [`Error::Unsupported`] if the driver does not expose
+    /// `update_interval`.
+    #[cfg(unix)]
+    pub fn update_interval(self) -> Result<Duration> {
+        read_update_interval(&self.update_interval_path()?)
+    }
+
+    /// Ask the driver of this chip to update its readings at
+    /// the given interval.
+    ///
+    /// Drivers round the interval to what the hardware supports.
+    /// This usually requires elevated privileges.
+    #[cfg(unix)]
+    pub fn set_update_interval(
+        self,
+        _capabilities: Capabilities<'_>,
+        interval: Duration,
+    ) -> Result<()> {
+        write_update_interval(&self.update_interval_path()?, interval)
+    }
+
+    #[cfg(unix)]
+    fn update_interval_path(self) -> Result<PathBuf> {
+        self.path()
+            .map(|path| path.join("update_interval"))
+            .ok_or_else(|| Error::unsupported("ChipRef::path()", Capability::SysfsPath))
+    }
+
     /// Return the address of this chip, if available.
     #[must_use]
     pub fn address(self) -> Option<c_int> {
@@ -246,14 +486,90 @@ impl<'a> ChipRef<'a> {
         (addr != SENSORS_CHIP_NAME_ADDR_ANY).then_some(addr)
     }
 
+    /**
+    Return a hash identifying the hardware of this chip, *e.g.,* to cache
+    per-chip configuration across runs.
+
+    The hash covers the prefix, bus and address of this chip, and the set of
+    types of its features and sub-features. It does not depend on the order
+    of features, nor on values, labels or paths, and it is stable across
+    runs, versions of this crate and platforms. Chips of the same model at
+    the same place have the same fingerprint.
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::default().initialize()?;
+    for chip in sensors.chip_iter(None) {
+        println!("{chip}: {:016x}", chip.fingerprint());
+    }
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn fingerprint(self) -> u64 {
+        let kinds: BTreeSet<_> = self
+            .feature_iter()
+            .flat_map(|feature| {
+                let sub_features = feature.sub_feature_iter();
+                sub_features.map(move |sub_feature| (feature.raw_kind(), sub_feature.raw_kind()))
+            })
+            .collect();
+
+        let prefix = self
+            .raw_prefix()
+            .map_or(b"".as_slice(), CStr::to_bytes_with_nul);
+        let mut hash = fnv1a(FNV_OFFSET_BASIS, prefix);
+        hash = fnv1a(hash, &self.bus().raw_kind().to_le_bytes());
+        hash = fnv1a(hash, &self.bus().raw_number().to_le_bytes());
+        hash = fnv1a(hash, &self.raw_address().to_le_bytes());
+        for (feature_kind, sub_feature_kind) in kinds {
+            hash = fnv1a(hash, &feature_kind.to_le_bytes());
+            hash = fnv1a(hash, &sub_feature_kind.to_le_bytes());
+        }
+        hash
+    }
+
     /// Execute all set statements for this chip.
     ///
-    /// See: [`sensors_do_chip_sets`].
-    pub fn do_chip_sets(self) -> Result<()> {
-        let r = api_access_lock()
-            .lock()
-            // Safety: sensors_do_chip_sets() is assumed to be safe.
-            .map(|_guard| unsafe { sensors_do_chip_sets(self.0) })?;
+    /// This fails if set statements were denied during initialization.
+    /// The execution is recorded by the audit sink of the library instance,
+    /// if any, and published as an [`Event::WriteAudited`].
+    ///
+    /// See: [`sensors_do_chip_sets`], [`Initializer::allow_chip_sets`].
+    ///
+    /// [`Initializer::allow_chip_sets`]: crate::Initializer::allow_chip_sets
+    /// [`Event::WriteAudited`]: crate::events::Event::WriteAudited
+    pub fn do_chip_sets(self, capabilities: Capabilities<'_>) -> Result<()> {
+        if !capabilities.audited() {
+            return self.execute_chip_sets();
+        }
+
+        let time = SystemTime::now();
+        let result = self.execute_chip_sets();
+        capabilities.audit(AuditRecord {
+            time,
+            operation: "sensors_do_chip_sets()",
+            chip: self.to_string(),
+            sub_feature: None,
+            path: self.path().map(Path::to_path_buf),
+            old_value: None,
+            new_value: None,
+            error: result.as_ref().err().map(audit::describe),
+        });
+        result
+    }
+
+    fn execute_chip_sets(self) -> Result<()> {
+        let _guard = api_access_lock().lock()?;
+
+        if crate::CHIP_SETS_DENIED.load(atomic::Ordering::Acquire) {
+            let err = io::ErrorKind::PermissionDenied.into();
+            return Err(Error::from_io("sensors_do_chip_sets()", err));
+        }
+
+        // Safety: sensors_do_chip_sets() is assumed to be safe.
+        let r = unsafe { sensors_do_chip_sets(self.0) };
         if r == 0 {
             Ok(())
         } else {
@@ -302,6 +618,51 @@ impl<'a> ChipRef<'a> {
         }
     }
 
+    /// Write the raw name of this chip into the given buffer,
+    /// without allocating memory, and return it.
+    ///
+    /// If the buffer is too small, this fails with `ERANGE`.
+    ///
+    /// See: [`sensors_snprintf_chip_name`].
+    pub fn raw_name_into(self, buffer: &mut [u8]) -> Result<&CStr> {
+        let r = api_access_lock().lock().map(|_guard| {
+            // Safety: `buffer` is valid for writing `buffer.len()` bytes.
+            unsafe { sensors_snprintf_chip_name(buffer.as_mut_ptr().cast(), buffer.len(), self.0) }
+        })?;
+
+        if r < 0 {
+            Err(Error::from_lm_sensors("sensors_snprintf_chip_name()", r))
+        } else if (r as usize) >= buffer.len() {
+            // The name was truncated.
+            let err = io::Error::from_raw_os_error(libc::ERANGE);
+            Err(Error::from_io("sensors_snprintf_chip_name()", err))
+        } else {
+            CStr::from_bytes_with_nul(&buffer[..=(r as usize)]).map_err(|_r| {
+                let err = io::ErrorKind::InvalidData.into();
+                Error::from_io("sensors_snprintf_chip_name()", err)
+            })
+        }
+    }
+
+    /// Write the name of this chip into the given buffer, without allocating
+    /// memory, and return it, if it is valid UTF-8.
+    ///
+    /// See: [`ChipRef::raw_name_into`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let sensors = lm_sensors::Initializer::default().initialize()?;
+    /// let chip = sensors.new_chip("lm75-i2c-0-48")?;
+    ///
+    /// let mut buffer = [0_u8; 64];
+    /// assert_eq!(chip.as_ref().name_into(&mut buffer)?, "lm75-i2c-0-48");
+    /// # Ok::<(), lm_sensors::errors::Error>(())
+    /// ```
+    pub fn name_into(self, buffer: &mut [u8]) -> Result<&str> {
+        self.raw_name_into(buffer)?.to_str().map_err(Into::into)
+    }
+
     /// Return the raw prefix of this chip, if available.
     #[must_use]
     pub fn raw_prefix(self) -> Option<&'a CStr> {
@@ -333,6 +694,32 @@ impl<'a> PartialEq<ChipRef<'_>> for ChipRef<'a> {
     }
 }
 
+impl<'a> fmt::Debug for ChipRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_chip(*self, "ChipRef", f)
+    }
+}
+
+/// Initial value of the 64-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Return the given 64-bit FNV-1a hash, extended with the given bytes.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Format the fields of the given chip that are readable without locking.
+fn debug_chip(chip: ChipRef<'_>, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct(name)
+        .field("prefix", &chip.raw_prefix())
+        .field("bus", &chip.bus())
+        .field("address", &chip.address())
+        .field("path", &chip.raw_path())
+        .finish()
+}
+
 impl<'a> fmt::Display for ChipRef<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Ok(name) = self.raw_name() {
@@ -343,6 +730,285 @@ impl<'a> fmt::Display for ChipRef<'a> {
     }
 }
 
+/// Read an `update_interval` attribute, expressed in milliseconds.
+fn read_update_interval(path: &Path) -> Result<Duration> {
+    let contents = fs::read_to_string(path).map_err(|r| {
+        if r.kind() == io::ErrorKind::NotFound {
+            Error::unsupported("fs::read_to_string()", Capability::UpdateInterval)
+        } else {
+            Error::from_io_path("fs::read_to_string()", path, r)
+        }
+    })?;
+    let millis: u64 = contents.trim().parse()?;
+    Ok(Duration::from_millis(millis))
+}
+
+/// Write an `update_interval` attribute, expressed in milliseconds.
+fn write_update_interval(path: &Path, interval: Duration) -> Result<()> {
+    fs::write(path, interval.as_millis().to_string())
+        .map_err(|r| Error::from_io_path("fs::write()", path, r))
+}
+
+/// Free the allocations owned by the given chip.
+///
+/// # Safety
+///
+/// - `prefix` and `path` are null, or were allocated by `malloc()`,
+///   and are unused after this call.
+unsafe fn free_raw(raw: &mut sensors_chip_name) {
+    let _ignored = api_access_lock()
+        .lock()
+        // Safety: sensors_free_chip_name() is assumed to be safe.
+        .map(|_guard| unsafe { sensors_free_chip_name(raw) });
+
+    // sensors_free_chip_name() does not free the path, which is null
+    // unless the chip was copied from a detected chip.
+    // Safety: `path` is null or was allocated by `malloc()`.
+    unsafe { libc::free(raw.path.cast()) };
+    raw.prefix = ptr::null_mut();
+    raw.path = ptr::null_mut();
+}
+
+/// Return a copy of the given chip, with its own allocations of
+/// `prefix` and `path`.
+fn deep_copy(operation: &'static str, raw: &sensors_chip_name) -> Result<sensors_chip_name> {
+    let prefix = c_str_dup(operation, raw.prefix)?;
+    let path = c_str_dup(operation, raw.path).inspect_err(|_err| {
+        // Safety: `prefix` was allocated by `malloc()`, and is now unused.
+        unsafe { libc::free(prefix.cast()) };
+    })?;
+
+    Ok(sensors_chip_name {
+        prefix,
+        bus: raw.bus,
+        addr: raw.addr,
+        path,
+    })
+}
+
+/**
+Raw chip, freed when dropped.
+
+This helps code passing [`sensors_chip_name`]s to and from other FFI code,
+without leaking or double-freeing them.
+
+# Example
+
+```rust
+let sensors = lm_sensors::Initializer::default().initialize()?;
+let chip = sensors.new_chip("lm75-i2c-0-48")?;
+
+let guard = chip.into_raw_guard();
+let raw_chip: *const sensors_sys::sensors_chip_name = guard.as_ptr();
+// Pass `raw_chip` to other FFI code...
+
+// Safety: `guard` owns a chip returned by `Chip::into_raw_guard`.
+let chip = unsafe { guard.into_chip() };
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Debug)]
+pub struct RawChipGuard(sensors_chip_name);
+
+impl RawChipGuard {
+    /// Return a guard taking ownership of the given raw chip.
+    ///
+    /// # Safety
+    ///
+    /// - `prefix` and `path` of the given [`sensors_chip_name`] must be null,
+    ///   or allocated by `malloc()` and not owned by anything else.
+    #[must_use]
+    pub unsafe fn new(raw: sensors_chip_name) -> Self {
+        Self(raw)
+    }
+
+    /// Returns a shared reference to the raw data structure [`sensors_chip_name`].
+    #[must_use]
+    pub fn raw_ref(&self) -> &sensors_chip_name {
+        &self.0
+    }
+
+    /// Returns a pointer to the raw data structure [`sensors_chip_name`],
+    /// valid while this guard is alive.
+    #[must_use]
+    pub fn as_ptr(&self) -> *const sensors_chip_name {
+        &self.0
+    }
+
+    /// Returns a mutable pointer to the raw data structure [`sensors_chip_name`],
+    /// valid while this guard is alive.
+    ///
+    /// Changes must keep `prefix` and `path` null, or allocated by `malloc()`.
+    #[must_use]
+    pub fn as_mut_ptr(&mut self) -> *mut sensors_chip_name {
+        &mut self.0
+    }
+
+    /// Return the raw chip, without freeing it.
+    ///
+    /// # Safety
+    ///
+    /// See: [`Chip::into_raw_parts`].
+    #[must_use]
+    pub unsafe fn into_raw_parts(self) -> sensors_chip_name {
+        let raw = self.0;
+        mem::forget(self);
+        raw
+    }
+
+    /// Return a chip taking ownership of the raw chip of this guard.
+    ///
+    /// # Safety
+    ///
+    /// See: [`Chip::from_raw_parts`].
+    #[must_use]
+    pub unsafe fn into_chip<'a>(self) -> Chip<'a> {
+        // Safety: ownership of the raw chip is moved.
+        unsafe { Chip::from_raw_parts(self.into_raw_parts()) }
+    }
+}
+
+impl Drop for RawChipGuard {
+    /// See: [`sensors_free_chip_name`].
+    fn drop(&mut self) {
+        // Safety: the raw chip is owned by this guard.
+        unsafe { free_raw(&mut self.0) }
+    }
+}
+
+/**
+Shared access to a chip, for generic code accepting owned chips
+as well as references to them.
+
+This trait is sealed. It is implemented for [`Chip`], [`ChipRef`],
+and for all shared references to its implementors.
+
+# Example
+
+```rust
+use lm_sensors::prelude::*;
+
+fn print_chip(chip: impl SharedChip) {
+    println!("{} has {} features", chip.as_chip_ref(), chip.feature_iter().count());
+}
+
+let sensors = lm_sensors::Initializer::default().initialize()?;
+for chip in sensors.chip_iter(None) {
+    print_chip(chip);
+}
+
+let chip = sensors.new_chip("lm75-i2c-0-48")?;
+print_chip(&chip);
+print_chip(chip);
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub trait SharedChip: sealed::Sealed {
+    /// Return a shared reference to the chip.
+    fn as_chip_ref(&self) -> ChipRef<'_>;
+
+    /// Return an iterator which yields all sensors and actuators
+    /// (*a.k.a.,* features) controlled by this chip.
+    fn feature_iter(&self) -> crate::feature::Iter<'_> {
+        self.as_chip_ref().feature_iter()
+    }
+
+    /// Return an iterator which yields all features controlled by this chip,
+    /// with the values of their canonical input sub-features.
+    ///
+    /// See: [`ChipRef::readings`].
+    fn readings(&self) -> crate::feature::Readings<'_> {
+        self.as_chip_ref().readings()
+    }
+
+    /// Return name of this chip, if it is valid UTF-8.
+    fn name(&self) -> Result<String> {
+        self.as_chip_ref().name()
+    }
+
+    /// Return the prefix of this chip, if it is valid UTF-8.
+    fn prefix(&self) -> Option<Result<&str>> {
+        self.as_chip_ref().prefix()
+    }
+
+    /// Return the path of the driver of this chip, if available.
+    #[cfg(unix)]
+    fn path(&self) -> Option<&Path> {
+        self.as_chip_ref().path()
+    }
+
+    /// Return the address of this chip, if available.
+    fn address(&self) -> Option<c_int> {
+        self.as_chip_ref().address()
+    }
+
+    /// Return a hash identifying the hardware of this chip.
+    ///
+    /// See: [`ChipRef::fingerprint`].
+    fn fingerprint(&self) -> u64 {
+        self.as_chip_ref().fingerprint()
+    }
+
+    /// Execute all set statements for this chip.
+    ///
+    /// See: [`ChipRef::do_chip_sets`].
+    fn do_chip_sets(&self, capabilities: Capabilities<'_>) -> Result<()> {
+        self.as_chip_ref().do_chip_sets(capabilities)
+    }
+
+    /// Return a copy of the bus connected to this chip.
+    fn bus(&self) -> Bus {
+        self.as_chip_ref().bus()
+    }
+
+    /// Return the raw name of this chip.
+    ///
+    /// See: [`sensors_snprintf_chip_name`].
+    fn raw_name(&self) -> Result<CString> {
+        self.as_chip_ref().raw_name()
+    }
+
+    /// Return the raw prefix of this chip, if available.
+    fn raw_prefix(&self) -> Option<&CStr> {
+        self.as_chip_ref().raw_prefix()
+    }
+
+    /// Return the raw path of the driver of this chip, if available.
+    fn raw_path(&self) -> Option<&CStr> {
+        self.as_chip_ref().raw_path()
+    }
+
+    /// Return the raw address of this chip, which is either a number,
+    /// or [`SENSORS_CHIP_NAME_ADDR_ANY`].
+    fn raw_address(&self) -> c_int {
+        self.as_chip_ref().raw_address()
+    }
+}
+
+impl sealed::Sealed for Chip<'_> {}
+
+impl SharedChip for Chip<'_> {
+    fn as_chip_ref(&self) -> ChipRef<'_> {
+        ChipRef(&self.raw)
+    }
+}
+
+impl sealed::Sealed for ChipRef<'_> {}
+
+impl SharedChip for ChipRef<'_> {
+    fn as_chip_ref(&self) -> ChipRef<'_> {
+        *self
+    }
+}
+
+impl<T: SharedChip + ?Sized> sealed::Sealed for &T {}
+
+impl<T: SharedChip + ?Sized> SharedChip for &T {
+    fn as_chip_ref(&self) -> ChipRef<'_> {
+        (**self).as_chip_ref()
+    }
+}
+
 /// Iterator over available chips. Yields [`ChipRef`]s.
 #[derive(Debug)]
 #[must_use]
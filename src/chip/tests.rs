@@ -1,14 +1,23 @@
 #![cfg(test)]
 
+use std::io;
 use std::mem::MaybeUninit;
+use std::os::raw::c_int;
 
+use assert_matches::assert_matches;
+use sensors_sys::sensors_chip_name;
 use serial_test::serial;
 
+use crate::errors::{Capability, Error};
+
 #[test]
 #[serial]
 fn new() {
     let s = crate::Initializer::default().initialize().unwrap();
     s.new_chip("").unwrap_err();
+    let err = s.new_chip("typo-isa-").unwrap_err();
+    assert_matches!(err, Error::InvalidChipName(ref e) if e.position == 9);
+    assert_eq!(err.code(), "invalid_chip_name");
     let chip0 = s.new_chip("*-isa-*").unwrap();
 
     let mut state = 0;
@@ -91,7 +100,23 @@ fn raw() {
 fn do_chip_sets() {
     let s = crate::Initializer::default().initialize().unwrap();
     let chip0 = s.chip_iter(None).next().unwrap();
-    chip0.do_chip_sets().unwrap();
+    chip0.do_chip_sets(s.capabilities()).unwrap();
+}
+
+#[test]
+#[serial]
+fn do_chip_sets_denied() {
+    let s = crate::Initializer::minimal().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    let err = chip.do_chip_sets(s.capabilities()).unwrap_err();
+    assert_matches!(err, Error::IO { source, .. } if source.kind() == io::ErrorKind::PermissionDenied);
+    drop(chip);
+    drop(s);
+
+    // Set statements are only denied for the library instance initialized with `minimal()`.
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    chip.do_chip_sets(s.capabilities()).unwrap();
 }
 
 #[test]
@@ -120,3 +145,246 @@ fn feature_iter() {
     let _count1 = chip0.feature_iter().count();
     let _count2 = chip1.feature_iter().count();
 }
+
+#[test]
+#[serial]
+fn readings() {
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    for chip in s.chip_iter(None) {
+        let features: Vec<_> = chip.feature_iter().collect();
+        let readings: Vec<_> = chip.readings().collect();
+        assert_eq!(readings.len(), features.len());
+
+        for ((feature, value), expected) in readings.into_iter().zip(features) {
+            assert_eq!(feature, expected);
+            let input = feature.input().and_then(crate::SubFeatureRef::value).ok();
+            assert_eq!(value.is_some(), input.is_some());
+        }
+    }
+}
+
+#[test]
+#[serial]
+#[allow(clippy::needless_borrows_for_generic_args)] // Borrows are tested.
+fn shared_chip() {
+    use crate::prelude::*;
+
+    fn name_and_address(chip: impl SharedChip) -> (String, Option<c_int>) {
+        (chip.name().unwrap(), chip.address())
+    }
+
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    let expected = ("lm75-i2c-0-48".to_string(), Some(0x48));
+
+    assert_eq!(name_and_address(chip.as_ref()), expected);
+    assert_eq!(name_and_address(&chip.as_ref()), expected);
+    assert_eq!(name_and_address(&chip), expected);
+    assert_eq!(SharedChip::as_chip_ref(&chip), chip);
+    assert_eq!(name_and_address(chip), expected);
+}
+
+#[test]
+#[serial]
+fn to_chip() {
+    use core::borrow::Borrow;
+
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip0 = s.new_chip("lm75-i2c-0-48").unwrap();
+
+    let chip1 = chip0.as_ref().to_chip().unwrap();
+    assert_eq!(chip1, chip0.as_ref());
+    assert_ne!(chip1.raw_ref().prefix, chip0.raw_ref().prefix);
+    assert!(chip1.raw_path().is_none());
+
+    let chip2 = crate::Chip::try_from(crate::ChipRef::from(&chip1)).unwrap();
+    assert_eq!(chip2, chip1.as_ref());
+    let raw: &sensors_chip_name = chip2.borrow();
+    assert_eq!(raw, chip2.raw_ref());
+
+    assert_eq!(chip2.name().unwrap(), "lm75-i2c-0-48");
+}
+
+#[test]
+#[serial]
+fn to_chip_detected() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let chips: Vec<_> = s.chip_iter(None).map(|c| c.to_chip().unwrap()).collect();
+
+    for (chip, detected) in chips.iter().zip(s.chip_iter(None)) {
+        assert_eq!(*chip, detected);
+        assert_eq!(chip.raw_path(), detected.raw_path());
+        assert_ne!(chip.raw_ref().path, detected.raw_ref().path);
+    }
+}
+
+#[test]
+#[serial]
+fn clone() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip0 = s.new_chip("lm75-i2c-0-48").unwrap();
+    let mut chip1 = chip0.clone();
+    assert_eq!(chip1, chip0.as_ref());
+
+    // The clone owns its allocations.
+    assert_ne!(chip1.raw_ref().prefix, chip0.raw_ref().prefix);
+    drop(chip0);
+    assert_eq!(chip1.name().unwrap(), "lm75-i2c-0-48");
+
+    // Clones can be changed independently, e.g., as match patterns.
+    let chip2 = chip1.clone();
+    chip1.set_bus(&s.new_bus(crate::bus::Kind::ISA, crate::bus::Number::Number(0)));
+    assert_eq!(chip1.name().unwrap(), "lm75-isa-0048");
+    assert_eq!(chip2.name().unwrap(), "lm75-i2c-0-48");
+    assert_eq!(s.chip_iter(Some(chip2.as_ref())).count(), 0);
+
+    let chips = vec![chip1, chip2];
+    let copies = chips.clone();
+    drop(chips);
+    assert_eq!(copies[1].name().unwrap(), "lm75-i2c-0-48");
+}
+
+#[test]
+#[serial]
+fn raw_parts() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip0 = s.new_chip("lm75-i2c-0-48").unwrap();
+
+    let raw = unsafe { chip0.into_raw_parts() };
+    let chip0 = unsafe { crate::Chip::from_raw_parts(raw) };
+    assert_eq!(chip0.name().unwrap(), "lm75-i2c-0-48");
+    assert_eq!(chip0.as_raw_ptr(), chip0.as_ref().as_raw_ptr());
+
+    let mut buffer = [0_u8; 32];
+    let len = unsafe {
+        sensors_sys::sensors_snprintf_chip_name(
+            buffer.as_mut_ptr().cast(),
+            buffer.len(),
+            chip0.as_raw_ptr(),
+        )
+    };
+    assert_eq!(&buffer[..len as usize], b"lm75-i2c-0-48");
+
+    let mut guard = chip0.clone().into_raw_guard();
+    assert_eq!(guard.raw_ref().addr, 0x48);
+    assert_eq!(guard.as_ptr(), guard.as_mut_ptr().cast_const());
+    drop(guard);
+
+    let guard = chip0.into_raw_guard();
+    let chip1 = unsafe { guard.into_chip() };
+    assert_eq!(chip1.name().unwrap(), "lm75-i2c-0-48");
+
+    let guard = unsafe { super::RawChipGuard::new(chip1.into_raw_parts()) };
+    let raw = unsafe { guard.into_raw_parts() };
+    drop(unsafe { super::RawChipGuard::new(raw) });
+}
+
+#[test]
+#[serial]
+fn name_into() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+
+    let mut buffer = [0xff_u8; 14];
+    assert_eq!(chip.name_into(&mut buffer).unwrap(), "lm75-i2c-0-48");
+    assert_eq!(
+        chip.raw_name_into(&mut buffer).unwrap().to_bytes(),
+        b"lm75-i2c-0-48"
+    );
+
+    let err = chip.name_into(&mut buffer[..13]).unwrap_err();
+    assert_matches!(err, Error::IO { source, .. } if source.raw_os_error() == Some(libc::ERANGE));
+    chip.name_into(&mut []).unwrap_err();
+
+    let pattern = s.new_chip("*-isa-*").unwrap();
+    let err = pattern.name_into(&mut buffer).unwrap_err();
+    assert_matches!(err, Error::LMSensors { .. });
+}
+
+#[test]
+fn update_interval() {
+    use core::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("update_interval");
+
+    std::fs::write(&path, "1500\n").unwrap();
+    assert_eq!(
+        super::read_update_interval(&path).unwrap(),
+        Duration::from_millis(1500)
+    );
+
+    super::write_update_interval(&path, Duration::from_micros(250_900)).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "250");
+
+    std::fs::write(&path, "fast").unwrap();
+    let err = super::read_update_interval(&path).unwrap_err();
+    assert_matches!(err, Error::NotInteger(_));
+
+    let err = super::read_update_interval(&dir.path().join("inexistent")).unwrap_err();
+    assert_matches!(
+        err,
+        Error::Unsupported {
+            needed: Capability::UpdateInterval,
+            ..
+        }
+    );
+
+    std::fs::create_dir(dir.path().join("directory")).unwrap();
+    let err = super::read_update_interval(&dir.path().join("directory")).unwrap_err();
+    assert_matches!(err, Error::IO1Path { .. });
+}
+
+#[test]
+#[serial]
+fn update_interval_without_path() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    let err = chip.update_interval().unwrap_err();
+    assert_matches!(
+        err,
+        Error::Unsupported {
+            needed: Capability::SysfsPath,
+            ..
+        }
+    );
+}
+
+#[test]
+#[serial]
+fn debug() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+
+    let debug = format!("{chip:?}");
+    assert!(debug.starts_with("Chip {"));
+    assert!(debug.contains("\"lm75\""));
+    assert!(debug.contains("address: Some(72)"));
+    assert!(format!("{:?}", chip.as_ref()).starts_with("ChipRef {"));
+
+    assert_eq!(format!("{:?}", chip.redact()), debug);
+    assert_eq!(chip.as_ref().redact().to_string(), "lm75-i2c-0-48");
+}
+
+#[test]
+#[serial]
+fn fingerprint() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let chips: Vec<_> = s.chip_iter(None).collect();
+
+    for chip in &chips {
+        let copy = chip.to_chip().unwrap();
+        assert_eq!(copy.fingerprint(), chip.fingerprint());
+    }
+
+    let lm75 = s.new_chip("lm75-i2c-0-48").unwrap();
+    let mut other = lm75.clone();
+    assert_eq!(other.fingerprint(), lm75.fingerprint());
+    other.set_bus(&s.new_chip("lm75-i2c-1-48").unwrap().bus());
+    assert_ne!(other.fingerprint(), lm75.fingerprint());
+    assert_ne!(
+        s.new_chip("lm75-i2c-0-49").unwrap().fingerprint(),
+        lm75.fingerprint()
+    );
+}
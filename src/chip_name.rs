@@ -0,0 +1,157 @@
+//! Validation of chip names and patterns, *e.g.,* `nct6775-isa-0290`.
+//!
+//! The LM sensors library reports malformed chip names with a bare error
+//! number. [`validate`] follows the same grammar, and reports which part of
+//! a name is malformed, and where:
+//!
+//! ```text
+//! prefix-bus_type-[bus_number-]address
+//! ```
+//!
+//! The prefix and the address can be `*`, and so can the whole part after
+//! the prefix. Bus numbers are only present on I2C, SPI, HID and SCSI buses,
+//! and can also be `*`. Addresses are hexadecimal.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+
+/// Bus types, in the order they are matched by the LM sensors library.
+const BUS_TYPES: &[&str] = &[
+    "i2c", "isa", "pci", "spi", "virtual", "acpi", "hid", "mdio", "scsi",
+];
+
+/// Bus types followed by a bus number.
+const NUMBERED_BUS_TYPES: &[&str] = &["i2c", "spi", "hid", "scsi"];
+
+/// Part of a chip name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChipNamePart {
+    /// Chip prefix, *e.g.,* `nct6775`.
+    Prefix,
+    /// Bus type, *e.g.,* `isa`.
+    BusType,
+    /// Bus number, *e.g.,* `0` in `lm75-i2c-0-48`.
+    BusNumber,
+    /// Chip address, *e.g.,* `0290`.
+    Address,
+}
+
+impl fmt::Display for ChipNamePart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Prefix => "prefix",
+            Self::BusType => "bus type",
+            Self::BusNumber => "bus number",
+            Self::Address => "address",
+        })
+    }
+}
+
+/**
+Malformed part of a chip name.
+
+# Example
+
+```rust
+use lm_sensors::chip_name::{validate, ChipNamePart};
+
+let err = validate("nct6775-isa-").unwrap_err();
+assert_eq!(err.part, ChipNamePart::Address);
+assert_eq!(err.position, 12);
+assert_eq!(err.annotate(), "nct6775-isa-\n            ^");
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipNameError {
+    /// Malformed chip name.
+    pub name: String,
+    /// Malformed part of the chip name.
+    pub part: ChipNamePart,
+    /// Offset of the malformed part in the chip name, in bytes.
+    pub position: usize,
+    /// Description of the expected part, *e.g.,* `a hexadecimal address`.
+    pub expected: &'static str,
+}
+
+impl ChipNameError {
+    /// Return the chip name, followed by a line pointing at the malformed part.
+    #[must_use]
+    pub fn annotate(&self) -> String {
+        let indent = self.name[..self.position].chars().count();
+        format!("{}\n{:indent$}^", self.name, "")
+    }
+}
+
+impl fmt::Display for ChipNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} at position {} of chip name '{}': expected {}",
+            self.part, self.position, self.name, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ChipNameError {}
+
+/// Return an error describing the malformed part of the given chip name,
+/// if any.
+///
+/// This accepts the same names as `sensors_parse_chip_name()`, including
+/// abbreviated bus types, *e.g.,* `i` for `i2c`.
+pub fn validate(name: &str) -> Result<(), ChipNameError> {
+    let error = |part, position, expected| ChipNameError {
+        name: name.into(),
+        part,
+        position,
+        expected,
+    };
+
+    // Prefix.
+    let Some((prefix, _)) = name.split_once('-') else {
+        return Err(error(ChipNamePart::Prefix, 0, "a prefix followed by '-'"));
+    };
+    let mut position = prefix.len() + 1;
+    let rest = &name[position..];
+    if rest == "*" {
+        return Ok(());
+    }
+
+    // Bus type.
+    let Some((bus_type, mut rest)) = rest.split_once('-') else {
+        let expected = "'*', or a bus type followed by '-'";
+        return Err(error(ChipNamePart::BusType, position, expected));
+    };
+    let Some(bus_type_name) = BUS_TYPES.iter().find(|t| t.starts_with(bus_type)) else {
+        let expected = "a bus type: i2c, isa, pci, spi, virtual, acpi, hid, mdio or scsi";
+        return Err(error(ChipNamePart::BusType, position, expected));
+    };
+    position += bus_type.len() + 1;
+
+    // Bus number.
+    if NUMBERED_BUS_TYPES.contains(bus_type_name) {
+        let number = rest.split_once('-').filter(|(number, _)| {
+            *number == "*" || (!number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+        });
+        let Some((number, after)) = number else {
+            let expected = "'*', or a decimal bus number, followed by '-'";
+            return Err(error(ChipNamePart::BusNumber, position, expected));
+        };
+        position += number.len() + 1;
+        rest = after;
+    }
+
+    // Address.
+    let digits = rest
+        .strip_prefix("0x")
+        .or_else(|| rest.strip_prefix("0X"))
+        .unwrap_or(rest);
+    if rest != "*" && (digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_hexdigit())) {
+        let expected = "'*', or a hexadecimal address";
+        return Err(error(ChipNamePart::Address, position, expected));
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+#![cfg(test)]
+
+use crate::chip_name::{validate, ChipNamePart};
+
+#[test]
+fn valid() {
+    let names = [
+        "*-*",
+        "nct6775-*",
+        "-isa-0290",
+        "nct6775-isa-0290",
+        "*-isa-*",
+        "lm75-i2c-0-48",
+        "lm75-i2c-*-0x48",
+        "lm75-i-12-4A",
+        "acpitz-acpi-0",
+        "nvme-pci-0100",
+        "dell_smm-virtual-0",
+        "hidpp_battery-hid-3-2",
+        "drivetemp-scsi-0-0",
+    ];
+    for name in names {
+        assert_eq!(validate(name), Ok(()), "{name}");
+    }
+}
+
+#[test]
+fn invalid() {
+    let names = [
+        ("", ChipNamePart::Prefix, 0),
+        ("nct6775", ChipNamePart::Prefix, 0),
+        ("nct6775-isa", ChipNamePart::BusType, 8),
+        ("nct6775-usb-0290", ChipNamePart::BusType, 8),
+        ("nct6775-isa-", ChipNamePart::Address, 12),
+        ("nct6775-isa-29g", ChipNamePart::Address, 12),
+        ("nct6775-isa-0x", ChipNamePart::Address, 12),
+        ("lm75-i2c-48", ChipNamePart::BusNumber, 9),
+        ("lm75-i2c-x-48", ChipNamePart::BusNumber, 9),
+        ("lm75-i2c--48", ChipNamePart::BusNumber, 9),
+        ("lm75-i2c-0-", ChipNamePart::Address, 11),
+    ];
+    for (name, part, position) in names {
+        let err = validate(name).unwrap_err();
+        assert_eq!((err.part, err.position), (part, position), "{name}");
+        assert_eq!(err.name, name);
+    }
+}
+
+#[test]
+fn display() {
+    let err = validate("typo-usb-0").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid bus type at position 5 of chip name 'typo-usb-0': expected a bus type: \
+         i2c, isa, pci, spi, virtual, acpi, hid, mdio or scsi"
+    );
+    assert_eq!(err.annotate(), "typo-usb-0\n     ^");
+}
@@ -0,0 +1,163 @@
+//! Sources of time of the time-bearing APIs.
+//!
+//! Samplers and snapshots read the time from a [`Clock`]. By default, they
+//! use a [`Monotonic`] clock, which is unaffected by changes of the system
+//! time, and which advances while the system is suspended on Linux. A [`Wall`] clock follows the system time instead, *e.g.,* to align
+//! sampling with wall-clock boundaries, and a [`Mock`] clock is advanced
+//! manually, so that replays and unit tests are deterministic.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::time::Duration;
+#[cfg(not(target_os = "linux"))]
+use std::sync::OnceLock;
+use std::sync::{Arc, Mutex, PoisonError};
+#[cfg(not(target_os = "linux"))]
+use std::time::Instant;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of time.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Return the time elapsed since the epoch of this clock.
+    ///
+    /// Differences between values returned by this method measure
+    /// elapsed time, *e.g.,* between readings.
+    fn now(&self) -> Duration;
+
+    /// Return the wall-clock time, used to time-stamp records,
+    /// *e.g.,* snapshots.
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Monotonic clock, whose epoch is its creation.
+///
+/// On Linux, this clock is based on `CLOCK_BOOTTIME`, so it advances while
+/// the system is suspended, which allows detecting suspensions.
+///
+/// This is the default clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Monotonic {
+    origin: Duration,
+}
+
+impl Monotonic {
+    /// Return a monotonic clock starting now.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            origin: monotonic_time(),
+        }
+    }
+}
+
+impl Default for Monotonic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for Monotonic {
+    fn now(&self) -> Duration {
+        monotonic_time().saturating_sub(self.origin)
+    }
+}
+
+/// Return the time elapsed since boot, including time spent suspended.
+///
+/// See: `clock_gettime()`.
+#[cfg(target_os = "linux")]
+fn monotonic_time() -> Duration {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // Safety: `time` is valid for writes.
+    let r = unsafe { libc::clock_gettime(libc::CLOCK_BOOTTIME, &mut time) };
+    // CLOCK_BOOTTIME is supported since Linux 2.6.39.
+    assert_eq!(r, 0, "clock_gettime(CLOCK_BOOTTIME) failed");
+
+    let secs = u64::try_from(time.tv_sec).unwrap_or_default();
+    let nanos = u32::try_from(time.tv_nsec).unwrap_or_default();
+    Duration::new(secs, nanos)
+}
+
+/// Return the time elapsed since the first call.
+#[cfg(not(target_os = "linux"))]
+fn monotonic_time() -> Duration {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    ORIGIN.get_or_init(Instant::now).elapsed()
+}
+
+/// Wall clock, whose epoch is the Unix epoch.
+///
+/// This clock can jump backward or forward when the system time is changed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wall;
+
+impl Clock for Wall {
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/**
+Clock advanced manually.
+
+Clones of a mock clock share the same time, so a clone can be given to
+a sampler while the original is advanced by the test.
+
+Its wall-clock time is the Unix epoch plus its current time.
+
+# Example
+
+```rust
+use core::time::Duration;
+use lm_sensors::clock::{Clock, Mock};
+
+let clock = Mock::new(Duration::from_secs(10));
+let shared = clock.clone();
+clock.advance(Duration::from_secs(5));
+assert_eq!(shared.now(), Duration::from_secs(15));
+```
+*/
+#[derive(Debug, Default, Clone)]
+pub struct Mock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl Mock {
+    /// Return a mock clock set to the given time.
+    #[must_use]
+    pub fn new(now: Duration) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Set the time of this clock, and of its clones.
+    pub fn set(&self, now: Duration) {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner) = now;
+    }
+
+    /// Advance the time of this clock, and of its clones.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(PoisonError::into_inner);
+        *now = now.saturating_add(duration);
+    }
+}
+
+impl Clock for Mock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn system_time(&self) -> SystemTime {
+        UNIX_EPOCH + self.now()
+    }
+}
@@ -0,0 +1,40 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::clock::{Clock, Mock, Monotonic, Wall};
+
+#[test]
+fn monotonic() {
+    let clock = Monotonic::new();
+    let first = clock.now();
+    std::thread::sleep(Duration::from_millis(2));
+    assert!(clock.now() >= first + Duration::from_millis(2));
+}
+
+#[test]
+fn wall() {
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let now = Wall.now();
+    assert!(now >= before);
+    assert!(Wall.system_time() >= UNIX_EPOCH + now);
+}
+
+#[test]
+fn mock() {
+    let clock = Mock::default();
+    assert_eq!(clock.now(), Duration::ZERO);
+    assert_eq!(clock.system_time(), UNIX_EPOCH);
+
+    let shared = clock.clone();
+    clock.advance(Duration::from_secs(3));
+    assert_eq!(shared.now(), Duration::from_secs(3));
+
+    shared.set(Duration::from_secs(1));
+    assert_eq!(clock.now(), Duration::from_secs(1));
+    assert_eq!(clock.system_time(), UNIX_EPOCH + Duration::from_secs(1));
+
+    clock.advance(Duration::MAX);
+    assert_eq!(shared.now(), Duration::MAX);
+}
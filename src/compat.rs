@@ -0,0 +1,502 @@
+//! Compatibility with the `sensors(1)` command line tool.
+//!
+//! [`parse_dump`] converts the output of `sensors -j` into a [`Snapshot`],
+//! so that recordings of machines that only ran the command line tool can be
+//! analyzed like snapshots taken by this crate.
+//!
+//! [`compare_with_cli`] reports discrepancies between the readings of this
+//! crate and those of `sensors(1)`, which helps validating this crate and
+//! alternative backends.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::time::Duration;
+use std::collections::BTreeMap;
+use std::io;
+#[cfg(target_os = "linux")]
+use std::process::Command;
+use std::time::SystemTime;
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::backend::Reading;
+use crate::errors::{Error, Result};
+use crate::model::raw::sensors_subfeature_type::*;
+use crate::snapshot::Snapshot;
+
+/// Name of the member holding the adapter of a chip, in `sensors -j` output.
+const ADAPTER_KEY: &str = "Adapter";
+
+/// Contents of one `sensors -j` output.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Dump {
+    /// Readings of all sub-features, sorted by chip, feature and sub-feature.
+    pub snapshot: Snapshot,
+    /// Adapter of each chip, *e.g.,* `ISA adapter`.
+    pub adapters: BTreeMap<String, String>,
+    /// Label of each feature, keyed by chip and feature names,
+    /// *e.g.,* `Package id 0`.
+    pub labels: BTreeMap<(String, String), String>,
+}
+
+#[cfg(target_os = "linux")]
+impl Dump {
+    /// Return the readings, adapters and labels of all chips known to
+    /// the given library instance, as `sensors -j` would report them.
+    #[must_use]
+    pub fn read(sensors: &crate::LMSensors) -> Self {
+        let mut readings = crate::backend::read_lm_sensors(sensors);
+        sort_readings(&mut readings);
+
+        let mut adapters = BTreeMap::default();
+        let mut labels = BTreeMap::default();
+        for chip in sensors.chip_iter(None) {
+            let chip_name = chip.to_string();
+            if let Ok(adapter) = chip.bus().name() {
+                adapters.insert(chip_name.clone(), adapter.into());
+            }
+
+            for feature in chip.feature_iter() {
+                if let (Some(Ok(name)), Ok(label)) = (feature.name(), feature.label()) {
+                    labels.insert((chip_name.clone(), name.into()), label);
+                }
+            }
+        }
+
+        Self {
+            snapshot: Snapshot::new(readings),
+            adapters,
+            labels,
+        }
+    }
+}
+
+/// Difference between the readings of this crate and of `sensors(1)`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Discrepancy {
+    /// Sub-feature read by this crate, but not reported by `sensors(1)`.
+    MissingFromCli {
+        /// Name of the chip, *e.g.,* `coretemp-isa-0000`.
+        chip: String,
+        /// Name of the sub-feature, *e.g.,* `temp1_input`.
+        sub_feature: String,
+    },
+    /// Sub-feature reported by `sensors(1)`, but unknown to this crate.
+    MissingFromCrate {
+        /// Name of the chip, *e.g.,* `coretemp-isa-0000`.
+        chip: String,
+        /// Name of the sub-feature, *e.g.,* `temp1_input`.
+        sub_feature: String,
+    },
+    /// Sub-feature whose values differ by more than the tolerance,
+    /// or which only one side could read.
+    Value {
+        /// Name of the chip, *e.g.,* `coretemp-isa-0000`.
+        chip: String,
+        /// Name of the sub-feature, *e.g.,* `temp1_input`.
+        sub_feature: String,
+        /// Raw value read by this crate.
+        crate_value: Option<f64>,
+        /// Raw value reported by `sensors(1)`.
+        cli_value: Option<f64>,
+    },
+    /// Feature whose labels differ.
+    Label {
+        /// Name of the chip, *e.g.,* `coretemp-isa-0000`.
+        chip: String,
+        /// Name of the feature, *e.g.,* `temp1`.
+        feature: String,
+        /// Label read by this crate.
+        crate_label: String,
+        /// Label reported by `sensors(1)`.
+        cli_label: String,
+    },
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingFromCli { chip, sub_feature } => {
+                write!(f, "{chip}/{sub_feature}: not reported by sensors(1)")
+            }
+
+            Self::MissingFromCrate { chip, sub_feature } => {
+                write!(f, "{chip}/{sub_feature}: not read by lm-sensors")
+            }
+
+            Self::Value {
+                chip,
+                sub_feature,
+                crate_value,
+                cli_value,
+            } => write!(
+                f,
+                "{chip}/{sub_feature}: value {crate_value:?} differs from {cli_value:?}"
+            ),
+
+            Self::Label {
+                chip,
+                feature,
+                crate_label,
+                cli_label,
+            } => write!(
+                f,
+                "{chip}/{feature}: label '{crate_label}' differs from '{cli_label}'"
+            ),
+        }
+    }
+}
+
+/**
+Run `sensors -j`, and return the discrepancies between its output and
+the current readings of the given library instance.
+
+Values differing by at most `tolerance` are considered equal. Since
+`sensors(1)` reports values with three decimals, and readings change
+between both reads, `tolerance` should account for the rounding and for
+the expected noise of readings.
+
+This returns `None` if `sensors(1)` is not installed.
+
+# Example
+
+```no_run
+let sensors = lm_sensors::Initializer::default().initialize()?;
+if let Some(discrepancies) = lm_sensors::compat::compare_with_cli(&sensors, 0.5)? {
+    for discrepancy in discrepancies {
+        println!("{discrepancy}");
+    }
+}
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[cfg(target_os = "linux")]
+pub fn compare_with_cli(
+    sensors: &crate::LMSensors,
+    tolerance: f64,
+) -> Result<Option<Vec<Discrepancy>>> {
+    let output = match Command::new("sensors").arg("-j").output() {
+        Ok(output) => output,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(Error::from_io("compat::compare_with_cli()", err)),
+    };
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+        let err = io::Error::other(message);
+        return Err(Error::from_io("compat::compare_with_cli()", err));
+    }
+
+    let json = String::from_utf8_lossy(&output.stdout);
+    let cli = parse_dump(&json, SystemTime::now())?;
+    Ok(Some(compare(&Dump::read(sensors), &cli, tolerance)))
+}
+
+/// Return the discrepancies between readings of this crate and readings
+/// of `sensors(1)`, ordered by chip and sub-feature, then by chip
+/// and feature.
+///
+/// Values differing by at most `tolerance` are considered equal.
+/// Sub-features that this crate could not read are not expected to be
+/// reported by `sensors(1)`.
+#[must_use]
+pub fn compare(crate_dump: &Dump, cli_dump: &Dump, tolerance: f64) -> Vec<Discrepancy> {
+    let by_name = |dump: &Dump| -> BTreeMap<(String, String), Option<f64>> {
+        dump.snapshot
+            .readings
+            .iter()
+            .map(|r| ((r.chip.clone(), r.sub_feature.clone()), r.raw_value))
+            .collect()
+    };
+    let crate_readings = by_name(crate_dump);
+    let mut cli_readings = by_name(cli_dump);
+
+    let mut result = Vec::default();
+    for ((chip, sub_feature), crate_value) in crate_readings {
+        match cli_readings.remove(&(chip.clone(), sub_feature.clone())) {
+            None if crate_value.is_none() => {}
+
+            None => result.push(Discrepancy::MissingFromCli { chip, sub_feature }),
+
+            Some(Some(cli_value))
+                if crate_value.is_some_and(|value| (value - cli_value).abs() <= tolerance) => {}
+
+            Some(cli_value) => result.push(Discrepancy::Value {
+                chip,
+                sub_feature,
+                crate_value,
+                cli_value,
+            }),
+        }
+    }
+
+    let missing = cli_readings.into_keys();
+    result.extend(
+        missing.map(|(chip, sub_feature)| Discrepancy::MissingFromCrate { chip, sub_feature }),
+    );
+    result.sort_by(|a, b| reading_key(a).cmp(&reading_key(b)));
+
+    for ((chip, feature), cli_label) in &cli_dump.labels {
+        let Some(crate_label) = crate_dump.labels.get(&(chip.clone(), feature.clone())) else {
+            continue;
+        };
+        if crate_label != cli_label {
+            result.push(Discrepancy::Label {
+                chip: chip.clone(),
+                feature: feature.clone(),
+                crate_label: crate_label.clone(),
+                cli_label: cli_label.clone(),
+            });
+        }
+    }
+    result
+}
+
+fn reading_key(discrepancy: &Discrepancy) -> (&str, &str) {
+    match discrepancy {
+        Discrepancy::MissingFromCli { chip, sub_feature }
+        | Discrepancy::MissingFromCrate { chip, sub_feature }
+        | Discrepancy::Value {
+            chip, sub_feature, ..
+        } => (chip, sub_feature),
+
+        Discrepancy::Label { chip, feature, .. } => (chip, feature),
+    }
+}
+
+/**
+Return the contents of the given `sensors -j` output, recorded at the given
+time.
+
+Feature names are deduced from sub-feature names, since `sensors -j` only
+reports feature labels.
+
+# Example
+
+```rust
+let json = r#"{"coretemp-isa-0000": {
+    "Adapter": "ISA adapter",
+    "Package id 0": {"temp1_input": 45.000, "temp1_max": 80.000}
+}}"#;
+
+let dump = lm_sensors::compat::parse_dump(json, std::time::SystemTime::now())?;
+assert_eq!(dump.snapshot.readings.len(), 2);
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub fn parse_dump(json: &str, time: SystemTime) -> Result<Dump> {
+    let chips: Map<String, JsonValue> = serde_json::from_str(json).map_err(json_error)?;
+    dump_from_json(chips, time)
+}
+
+/// Return the contents of the given concatenated `sensors -j` outputs,
+/// *e.g.,* as logged by running `sensors -j` periodically.
+///
+/// The first output is considered recorded at `start`, and each following
+/// output is considered recorded `interval` after the previous one.
+pub fn parse_dumps(json: &str, start: SystemTime, interval: Duration) -> Result<Vec<Dump>> {
+    let mut time = start;
+    serde_json::Deserializer::from_str(json)
+        .into_iter::<Map<String, JsonValue>>()
+        .map(|chips| {
+            let dump = dump_from_json(chips.map_err(json_error)?, time)?;
+            time += interval;
+            Ok(dump)
+        })
+        .collect()
+}
+
+fn dump_from_json(chips: Map<String, JsonValue>, time: SystemTime) -> Result<Dump> {
+    let mut readings = Vec::default();
+    let mut adapters = BTreeMap::default();
+    let mut labels = BTreeMap::default();
+
+    for (chip, members) in chips {
+        let JsonValue::Object(members) = members else {
+            return Err(invalid_data(format!("chip '{chip}' is not an object")));
+        };
+
+        for (label, member) in members {
+            match member {
+                JsonValue::String(adapter) if label == ADAPTER_KEY => {
+                    adapters.insert(chip.clone(), adapter);
+                }
+
+                JsonValue::Object(sub_features) => {
+                    for (sub_feature, value) in sub_features {
+                        let raw_value = value.as_f64().ok_or_else(|| {
+                            let message = format!("'{chip}/{sub_feature}' is not a number");
+                            invalid_data(message)
+                        })?;
+
+                        let feature = feature_name(&sub_feature).to_owned();
+                        labels.insert((chip.clone(), feature.clone()), label.clone());
+
+                        readings.push(Reading {
+                            chip: chip.clone(),
+                            raw_kind: sub_feature_kind(&sub_feature),
+                            feature,
+                            sub_feature,
+                            raw_value: Some(raw_value),
+                        });
+                    }
+                }
+
+                _ => {
+                    let message = format!("feature '{chip}/{label}' is not an object");
+                    return Err(invalid_data(message));
+                }
+            }
+        }
+    }
+
+    sort_readings(&mut readings);
+
+    Ok(Dump {
+        snapshot: Snapshot { time, readings },
+        adapters,
+        labels,
+    })
+}
+
+fn sort_readings(readings: &mut [Reading]) {
+    readings.sort_by(|a, b| {
+        (&a.chip, &a.feature, &a.sub_feature).cmp(&(&b.chip, &b.feature, &b.sub_feature))
+    });
+}
+
+/// Return the name of the feature of the given sub-feature,
+/// *e.g.,* `temp1` for `temp1_input`.
+fn feature_name(sub_feature: &str) -> &str {
+    if sub_feature.ends_with("_vid") || sub_feature == "beep_enable" {
+        return sub_feature;
+    }
+    sub_feature
+        .split_once('_')
+        .map_or(sub_feature, |(feature, _)| feature)
+}
+
+/// Return the raw type of the given sub-feature, one of
+/// `SENSORS_SUBFEATURE_*`, following the naming of `sysfs` attributes.
+fn sub_feature_kind(sub_feature: &str) -> core::ffi::c_uint {
+    if sub_feature.ends_with("_vid") {
+        return SENSORS_SUBFEATURE_VID;
+    }
+    if sub_feature == "beep_enable" {
+        return SENSORS_SUBFEATURE_BEEP_ENABLE;
+    }
+
+    let Some((feature, suffix)) = sub_feature.split_once('_') else {
+        return SENSORS_SUBFEATURE_UNKNOWN;
+    };
+    let prefix = feature.trim_end_matches(|c: char| c.is_ascii_digit());
+
+    match (prefix, suffix) {
+        ("in", "input") => SENSORS_SUBFEATURE_IN_INPUT,
+        ("in", "min") => SENSORS_SUBFEATURE_IN_MIN,
+        ("in", "max") => SENSORS_SUBFEATURE_IN_MAX,
+        ("in", "lcrit") => SENSORS_SUBFEATURE_IN_LCRIT,
+        ("in", "crit") => SENSORS_SUBFEATURE_IN_CRIT,
+        ("in", "average") => SENSORS_SUBFEATURE_IN_AVERAGE,
+        ("in", "lowest") => SENSORS_SUBFEATURE_IN_LOWEST,
+        ("in", "highest") => SENSORS_SUBFEATURE_IN_HIGHEST,
+        ("in", "alarm") => SENSORS_SUBFEATURE_IN_ALARM,
+        ("in", "min_alarm") => SENSORS_SUBFEATURE_IN_MIN_ALARM,
+        ("in", "max_alarm") => SENSORS_SUBFEATURE_IN_MAX_ALARM,
+        ("in", "beep") => SENSORS_SUBFEATURE_IN_BEEP,
+        ("in", "lcrit_alarm") => SENSORS_SUBFEATURE_IN_LCRIT_ALARM,
+        ("in", "crit_alarm") => SENSORS_SUBFEATURE_IN_CRIT_ALARM,
+
+        ("fan", "input") => SENSORS_SUBFEATURE_FAN_INPUT,
+        ("fan", "min") => SENSORS_SUBFEATURE_FAN_MIN,
+        ("fan", "max") => SENSORS_SUBFEATURE_FAN_MAX,
+        ("fan", "alarm") => SENSORS_SUBFEATURE_FAN_ALARM,
+        ("fan", "fault") => SENSORS_SUBFEATURE_FAN_FAULT,
+        ("fan", "div") => SENSORS_SUBFEATURE_FAN_DIV,
+        ("fan", "beep") => SENSORS_SUBFEATURE_FAN_BEEP,
+        ("fan", "pulses") => SENSORS_SUBFEATURE_FAN_PULSES,
+        ("fan", "min_alarm") => SENSORS_SUBFEATURE_FAN_MIN_ALARM,
+        ("fan", "max_alarm") => SENSORS_SUBFEATURE_FAN_MAX_ALARM,
+
+        ("temp", "input") => SENSORS_SUBFEATURE_TEMP_INPUT,
+        ("temp", "max") => SENSORS_SUBFEATURE_TEMP_MAX,
+        ("temp", "max_hyst") => SENSORS_SUBFEATURE_TEMP_MAX_HYST,
+        ("temp", "min") => SENSORS_SUBFEATURE_TEMP_MIN,
+        ("temp", "crit") => SENSORS_SUBFEATURE_TEMP_CRIT,
+        ("temp", "crit_hyst") => SENSORS_SUBFEATURE_TEMP_CRIT_HYST,
+        ("temp", "lcrit") => SENSORS_SUBFEATURE_TEMP_LCRIT,
+        ("temp", "emergency") => SENSORS_SUBFEATURE_TEMP_EMERGENCY,
+        ("temp", "emergency_hyst") => SENSORS_SUBFEATURE_TEMP_EMERGENCY_HYST,
+        ("temp", "lowest") => SENSORS_SUBFEATURE_TEMP_LOWEST,
+        ("temp", "highest") => SENSORS_SUBFEATURE_TEMP_HIGHEST,
+        ("temp", "min_hyst") => SENSORS_SUBFEATURE_TEMP_MIN_HYST,
+        ("temp", "lcrit_hyst") => SENSORS_SUBFEATURE_TEMP_LCRIT_HYST,
+        ("temp", "alarm") => SENSORS_SUBFEATURE_TEMP_ALARM,
+        ("temp", "max_alarm") => SENSORS_SUBFEATURE_TEMP_MAX_ALARM,
+        ("temp", "min_alarm") => SENSORS_SUBFEATURE_TEMP_MIN_ALARM,
+        ("temp", "crit_alarm") => SENSORS_SUBFEATURE_TEMP_CRIT_ALARM,
+        ("temp", "fault") => SENSORS_SUBFEATURE_TEMP_FAULT,
+        ("temp", "type") => SENSORS_SUBFEATURE_TEMP_TYPE,
+        ("temp", "offset") => SENSORS_SUBFEATURE_TEMP_OFFSET,
+        ("temp", "beep") => SENSORS_SUBFEATURE_TEMP_BEEP,
+        ("temp", "emergency_alarm") => SENSORS_SUBFEATURE_TEMP_EMERGENCY_ALARM,
+        ("temp", "lcrit_alarm") => SENSORS_SUBFEATURE_TEMP_LCRIT_ALARM,
+
+        ("power", "average") => SENSORS_SUBFEATURE_POWER_AVERAGE,
+        ("power", "average_highest") => SENSORS_SUBFEATURE_POWER_AVERAGE_HIGHEST,
+        ("power", "average_lowest") => SENSORS_SUBFEATURE_POWER_AVERAGE_LOWEST,
+        ("power", "input") => SENSORS_SUBFEATURE_POWER_INPUT,
+        ("power", "input_highest") => SENSORS_SUBFEATURE_POWER_INPUT_HIGHEST,
+        ("power", "input_lowest") => SENSORS_SUBFEATURE_POWER_INPUT_LOWEST,
+        ("power", "cap") => SENSORS_SUBFEATURE_POWER_CAP,
+        ("power", "cap_hyst") => SENSORS_SUBFEATURE_POWER_CAP_HYST,
+        ("power", "max") => SENSORS_SUBFEATURE_POWER_MAX,
+        ("power", "crit") => SENSORS_SUBFEATURE_POWER_CRIT,
+        ("power", "min") => SENSORS_SUBFEATURE_POWER_MIN,
+        ("power", "lcrit") => SENSORS_SUBFEATURE_POWER_LCRIT,
+        ("power", "average_interval") => SENSORS_SUBFEATURE_POWER_AVERAGE_INTERVAL,
+        ("power", "alarm") => SENSORS_SUBFEATURE_POWER_ALARM,
+        ("power", "cap_alarm") => SENSORS_SUBFEATURE_POWER_CAP_ALARM,
+        ("power", "max_alarm") => SENSORS_SUBFEATURE_POWER_MAX_ALARM,
+        ("power", "crit_alarm") => SENSORS_SUBFEATURE_POWER_CRIT_ALARM,
+        ("power", "min_alarm") => SENSORS_SUBFEATURE_POWER_MIN_ALARM,
+        ("power", "lcrit_alarm") => SENSORS_SUBFEATURE_POWER_LCRIT_ALARM,
+
+        ("energy", "input") => SENSORS_SUBFEATURE_ENERGY_INPUT,
+
+        ("curr", "input") => SENSORS_SUBFEATURE_CURR_INPUT,
+        ("curr", "min") => SENSORS_SUBFEATURE_CURR_MIN,
+        ("curr", "max") => SENSORS_SUBFEATURE_CURR_MAX,
+        ("curr", "lcrit") => SENSORS_SUBFEATURE_CURR_LCRIT,
+        ("curr", "crit") => SENSORS_SUBFEATURE_CURR_CRIT,
+        ("curr", "average") => SENSORS_SUBFEATURE_CURR_AVERAGE,
+        ("curr", "lowest") => SENSORS_SUBFEATURE_CURR_LOWEST,
+        ("curr", "highest") => SENSORS_SUBFEATURE_CURR_HIGHEST,
+        ("curr", "alarm") => SENSORS_SUBFEATURE_CURR_ALARM,
+        ("curr", "min_alarm") => SENSORS_SUBFEATURE_CURR_MIN_ALARM,
+        ("curr", "max_alarm") => SENSORS_SUBFEATURE_CURR_MAX_ALARM,
+        ("curr", "beep") => SENSORS_SUBFEATURE_CURR_BEEP,
+        ("curr", "lcrit_alarm") => SENSORS_SUBFEATURE_CURR_LCRIT_ALARM,
+        ("curr", "crit_alarm") => SENSORS_SUBFEATURE_CURR_CRIT_ALARM,
+
+        ("humidity", "input") => SENSORS_SUBFEATURE_HUMIDITY_INPUT,
+
+        ("intrusion", "alarm") => SENSORS_SUBFEATURE_INTRUSION_ALARM,
+        ("intrusion", "beep") => SENSORS_SUBFEATURE_INTRUSION_BEEP,
+
+        _ => SENSORS_SUBFEATURE_UNKNOWN,
+    }
+}
+
+fn json_error(err: serde_json::Error) -> Error {
+    Error::from_io("compat::parse_dump()", err.into())
+}
+
+fn invalid_data(message: String) -> Error {
+    let err = io::Error::new(io::ErrorKind::InvalidData, message);
+    Error::from_io("compat::parse_dump()", err)
+}
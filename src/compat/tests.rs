@@ -0,0 +1,197 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use assert_matches::assert_matches;
+
+use crate::compat::{parse_dump, parse_dumps};
+use crate::errors::Error;
+use crate::model::raw::sensors_subfeature_type::*;
+
+const DUMP: &str = r#"{
+   "coretemp-isa-0000":{
+      "Adapter": "ISA adapter",
+      "Package id 0":{
+         "temp1_input": 45.000,
+         "temp1_max": 80.000,
+         "temp1_crit_alarm": 0.000
+      },
+      "Core 0":{
+         "temp2_input": 43.000
+      }
+   },
+   "nct6775-isa-0290":{
+      "Adapter": "ISA adapter",
+      "Vcore":{
+         "in0_input": 0.880,
+         "in0_min_alarm": 0.000
+      },
+      "cpu0_vid":{
+         "cpu0_vid": 1.550
+      },
+      "beep_enable":{
+         "beep_enable": 0.000
+      },
+      "CPU Fan":{
+         "fan2_input": 1017.000,
+         "fan2_pulses": 2.000
+      }
+   }
+}"#;
+
+#[test]
+fn dump() {
+    let dump = parse_dump(DUMP, UNIX_EPOCH).unwrap();
+    assert_eq!(dump.snapshot.time, UNIX_EPOCH);
+
+    let features: Vec<_> = dump
+        .snapshot
+        .features()
+        .map(|readings| (readings[0].chip.as_str(), readings[0].feature.as_str()))
+        .collect();
+    assert_eq!(
+        features,
+        [
+            ("coretemp-isa-0000", "temp1"),
+            ("coretemp-isa-0000", "temp2"),
+            ("nct6775-isa-0290", "beep_enable"),
+            ("nct6775-isa-0290", "cpu0_vid"),
+            ("nct6775-isa-0290", "fan2"),
+            ("nct6775-isa-0290", "in0"),
+        ]
+    );
+
+    let kinds: Vec<_> = dump
+        .snapshot
+        .readings
+        .iter()
+        .map(|r| (r.sub_feature.as_str(), r.raw_kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("temp1_crit_alarm", SENSORS_SUBFEATURE_TEMP_CRIT_ALARM),
+            ("temp1_input", SENSORS_SUBFEATURE_TEMP_INPUT),
+            ("temp1_max", SENSORS_SUBFEATURE_TEMP_MAX),
+            ("temp2_input", SENSORS_SUBFEATURE_TEMP_INPUT),
+            ("beep_enable", SENSORS_SUBFEATURE_BEEP_ENABLE),
+            ("cpu0_vid", SENSORS_SUBFEATURE_VID),
+            ("fan2_input", SENSORS_SUBFEATURE_FAN_INPUT),
+            ("fan2_pulses", SENSORS_SUBFEATURE_FAN_PULSES),
+            ("in0_input", SENSORS_SUBFEATURE_IN_INPUT),
+            ("in0_min_alarm", SENSORS_SUBFEATURE_IN_MIN_ALARM),
+        ]
+    );
+    assert_eq!(dump.snapshot.readings[2].raw_value, Some(80.0));
+
+    assert_eq!(dump.adapters["coretemp-isa-0000"], "ISA adapter");
+    let key = ("coretemp-isa-0000".to_owned(), "temp2".to_owned());
+    assert_eq!(dump.labels[&key], "Core 0");
+    let key = ("nct6775-isa-0290".to_owned(), "fan2".to_owned());
+    assert_eq!(dump.labels[&key], "CPU Fan");
+}
+
+#[test]
+fn dumps() {
+    let interval = Duration::from_secs(10);
+    let json = format!("{DUMP}\n{DUMP}\n");
+    let dumps = parse_dumps(&json, UNIX_EPOCH, interval).unwrap();
+    assert_eq!(dumps.len(), 2);
+    assert_eq!(dumps[1].snapshot.time, UNIX_EPOCH + interval);
+    assert_eq!(dumps[0].snapshot.readings, dumps[1].snapshot.readings);
+
+    assert!(parse_dumps("", UNIX_EPOCH, interval).unwrap().is_empty());
+}
+
+#[test]
+fn unknown_sub_feature() {
+    let json = r#"{"foo-virtual-0": {"Bar": {"bar1_baz": 1.0}}}"#;
+    let dump = parse_dump(json, UNIX_EPOCH).unwrap();
+    assert_eq!(dump.snapshot.readings[0].feature, "bar1");
+    assert_eq!(
+        dump.snapshot.readings[0].raw_kind,
+        SENSORS_SUBFEATURE_UNKNOWN
+    );
+}
+
+#[test]
+fn invalid() {
+    for json in [
+        "",
+        "[]",
+        r#"{"coretemp-isa-0000": 1.0}"#,
+        r#"{"coretemp-isa-0000": {"Core 0": 1.0}}"#,
+        r#"{"coretemp-isa-0000": {"Core 0": {"temp2_input": "N/A"}}}"#,
+    ] {
+        assert_matches!(parse_dump(json, UNIX_EPOCH), Err(Error::IO { .. }));
+    }
+}
+
+#[test]
+fn compare() {
+    use crate::compat::{compare, Discrepancy};
+
+    let cli = parse_dump(DUMP, UNIX_EPOCH).unwrap();
+    assert!(compare(&cli, &cli, 0.0).is_empty());
+
+    let mut live = cli.clone();
+    let readings = &mut live.snapshot.readings;
+    readings[1].raw_value = Some(45.4); // temp1_input
+    readings[2].raw_value = Some(81.0); // temp1_max
+    readings[3].raw_value = None; // temp2_input
+    readings.remove(4); // beep_enable
+    readings.push(crate::backend::Reading {
+        chip: "nct6775-isa-0290".into(),
+        feature: "in0".into(),
+        sub_feature: "in0_max".into(),
+        raw_kind: SENSORS_SUBFEATURE_IN_MAX,
+        raw_value: Some(1.7),
+    });
+    readings.push(crate::backend::Reading {
+        chip: "nct6775-isa-0290".into(),
+        feature: "in0".into(),
+        sub_feature: "in0_beep".into(),
+        raw_kind: SENSORS_SUBFEATURE_IN_BEEP,
+        raw_value: None,
+    });
+    let key = ("nct6775-isa-0290".to_owned(), "fan2".to_owned());
+    live.labels.insert(key, "fan2".into());
+
+    let discrepancies = compare(&live, &cli, 0.5);
+    assert_eq!(
+        discrepancies,
+        [
+            Discrepancy::Value {
+                chip: "coretemp-isa-0000".into(),
+                sub_feature: "temp1_max".into(),
+                crate_value: Some(81.0),
+                cli_value: Some(80.0),
+            },
+            Discrepancy::Value {
+                chip: "coretemp-isa-0000".into(),
+                sub_feature: "temp2_input".into(),
+                crate_value: None,
+                cli_value: Some(43.0),
+            },
+            Discrepancy::MissingFromCrate {
+                chip: "nct6775-isa-0290".into(),
+                sub_feature: "beep_enable".into(),
+            },
+            Discrepancy::MissingFromCli {
+                chip: "nct6775-isa-0290".into(),
+                sub_feature: "in0_max".into(),
+            },
+            Discrepancy::Label {
+                chip: "nct6775-isa-0290".into(),
+                feature: "fan2".into(),
+                crate_label: "fan2".into(),
+                cli_label: "CPU Fan".into(),
+            },
+        ]
+    );
+    assert_eq!(
+        discrepancies[4].to_string(),
+        "nct6775-isa-0290/fan2: label 'fan2' differs from 'CPU Fan'"
+    );
+}
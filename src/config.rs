@@ -0,0 +1,383 @@
+//! Layered configuration of the LM sensors library.
+//!
+//! A [`LayeredConfig`] concatenates configuration sources in order, *e.g.,*
+//! the default configuration files, files shipped by a package, overrides
+//! of the user, and statements built in memory. The LM sensors library gives
+//! precedence to the statements that come last, so each layer overrides
+//! the layers before it.
+//!
+//! Every line of the concatenation remembers its [`Origin`], so that
+//! configuration errors and effective statements can be traced back to
+//! their source.
+//!
+//! See: `Initializer::config_layers`.
+
+#[cfg(test)]
+mod tests;
+
+use core::{fmt, mem};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::errors::{Error, Result};
+
+/// Default configuration files, of which only the first existing one is read.
+pub const DEFAULT_CONFIG_FILES: &[&str] = &["/etc/sensors3.conf", "/etc/sensors.conf"];
+
+/// Directory of default configuration files, all read after
+/// [`DEFAULT_CONFIG_FILES`].
+pub const DEFAULT_CONFIG_DIR: &str = "/etc/sensors.d";
+
+/// Layer of a [`LayeredConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigLayer {
+    /// Default configuration files: the first existing one of
+    /// [`DEFAULT_CONFIG_FILES`], then the files in [`DEFAULT_CONFIG_DIR`].
+    ///
+    /// Missing files are ignored.
+    Default,
+    /// Configuration file at the given path, or all files in the given
+    /// directory, in the order of their names.
+    ///
+    /// Hidden files, *i.e.,* whose names start with `.`, are ignored.
+    Path(PathBuf),
+    /// Configuration held in memory.
+    Text {
+        /// Name identifying the configuration, *e.g.,* `overrides`.
+        name: String,
+        /// Configuration statements.
+        contents: String,
+    },
+}
+
+impl ConfigLayer {
+    /// Return a layer of the configuration file, or directory, at the given
+    /// path.
+    #[must_use]
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::Path(path.into())
+    }
+
+    /// Return a layer of the given configuration statements, identified by
+    /// the given name.
+    #[must_use]
+    pub fn text(name: impl Into<String>, contents: impl Into<String>) -> Self {
+        Self::Text {
+            name: name.into(),
+            contents: contents.into(),
+        }
+    }
+}
+
+/// Configuration file, or configuration held in memory, included in
+/// a [`LayeredConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    /// Index of the layer including this source.
+    pub layer: usize,
+    /// Path of the configuration file, or `None` for configurations held
+    /// in memory.
+    pub path: Option<PathBuf>,
+    /// Name of the configuration held in memory, or path of the configuration
+    /// file.
+    pub name: String,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "file '{}'", path.display()),
+            None => write!(f, "configuration '{}'", self.name),
+        }
+    }
+}
+
+/// Location of a line of a [`LayeredConfig`] in its source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Origin<'a> {
+    /// Source of the line.
+    pub source: &'a Source,
+    /// Line number in the source, starting from 1.
+    pub line_number: usize,
+}
+
+impl fmt::Display for Origin<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} line {}", self.source, self.line_number)
+    }
+}
+
+/// Statement of a [`LayeredConfig`], *e.g.,* `label temp1 "CPU"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement<'a> {
+    /// Location of the first line of the statement.
+    pub origin: Origin<'a>,
+    /// Chip statement preceding this statement, if any,
+    /// *e.g.,* `chip "nct6775-*"`.
+    pub chip: Option<String>,
+    /// Text of the statement, without comments, continued lines being
+    /// joined.
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segment {
+    source: Source,
+    first_line: usize,
+    line_count: usize,
+}
+
+/**
+Concatenation of configuration layers, remembering the origin of each line.
+
+# Example
+
+```rust
+use lm_sensors::config::{ConfigLayer, LayeredConfig};
+
+let config = LayeredConfig::load([
+    ConfigLayer::text("package", "chip \"nct6775-*\"\n    label temp1 \"SYSTIN\"\n"),
+    ConfigLayer::text("user", "chip \"nct6775-*\"\n    label temp1 \"Case\"\n"),
+])?;
+
+let statements = config.effective_statements();
+assert_eq!(statements.len(), 1);
+assert_eq!(statements[0].text, "label temp1 \"Case\"");
+assert_eq!(statements[0].origin.source.name, "user");
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayeredConfig {
+    text: String,
+    segments: Vec<Segment>,
+}
+
+impl LayeredConfig {
+    /// Read and concatenate the given layers, in order.
+    ///
+    /// This fails if a configuration file cannot be read.
+    pub fn load(layers: impl IntoIterator<Item = ConfigLayer>) -> Result<Self> {
+        let mut result = Self::default();
+        for (layer, config_layer) in layers.into_iter().enumerate() {
+            match config_layer {
+                ConfigLayer::Default => {
+                    let file = DEFAULT_CONFIG_FILES
+                        .iter()
+                        .map(Path::new)
+                        .find(|p| p.is_file());
+                    if let Some(path) = file {
+                        result.push_file(layer, path)?;
+                    }
+
+                    match dir_entries(Path::new(DEFAULT_CONFIG_DIR)) {
+                        Ok(paths) => result.push_files(layer, &paths)?,
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                        Err(err) => {
+                            return Err(Error::from_io_path("read_dir()", DEFAULT_CONFIG_DIR, err))
+                        }
+                    }
+                }
+
+                ConfigLayer::Path(path) if path.is_dir() => {
+                    let paths = dir_entries(&path)
+                        .map_err(|err| Error::from_io_path("read_dir()", &path, err))?;
+                    result.push_files(layer, &paths)?;
+                }
+
+                ConfigLayer::Path(path) => result.push_file(layer, &path)?,
+
+                ConfigLayer::Text { name, contents } => {
+                    let source = Source {
+                        layer,
+                        path: None,
+                        name,
+                    };
+                    result.push(source, &contents);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Return the concatenated configuration.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Return an iterator over the sources of the configuration, in order.
+    pub fn sources(&self) -> impl ExactSizeIterator<Item = &Source> {
+        self.segments.iter().map(|segment| &segment.source)
+    }
+
+    /// Return the origin of the given line of the concatenated configuration,
+    /// starting from 1, *e.g.,* as reported in a configuration error.
+    #[must_use]
+    pub fn origin(&self, line_number: usize) -> Option<Origin<'_>> {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.first_line + segment.line_count <= line_number);
+        let segment = self.segments.get(index)?;
+
+        (line_number >= segment.first_line).then(|| Origin {
+            source: &segment.source,
+            line_number: line_number - segment.first_line + 1,
+        })
+    }
+
+    /// Return the statements of the configuration that are not overridden
+    /// by later statements, in order.
+    ///
+    /// A statement is overridden by a later statement of the same kind,
+    /// applying to the same feature (*e.g.,* `label temp1`) or bus, under a
+    /// chip statement of the same chip patterns. Chip statements themselves
+    /// are not returned.
+    #[must_use]
+    pub fn effective_statements(&self) -> Vec<Statement<'_>> {
+        let statements = self.statements();
+
+        let mut last_by_key = HashMap::new();
+        for (index, statement) in statements.iter().enumerate() {
+            if let Some(key) = statement_key(statement) {
+                last_by_key.insert(key, index);
+            }
+        }
+        let effective: HashSet<usize> = last_by_key.into_values().collect();
+
+        let statements = statements.into_iter().enumerate();
+        statements
+            .filter_map(|(index, statement)| effective.contains(&index).then_some(statement))
+            .collect()
+    }
+
+    /// Return all statements of the configuration, including chip statements.
+    fn statements(&self) -> Vec<Statement<'_>> {
+        let mut result = Vec::default();
+        let mut chip: Option<String> = None;
+        let mut text = String::default();
+        let mut first_line = 0;
+
+        for (index, line) in self.text.lines().enumerate() {
+            if text.is_empty() {
+                first_line = index + 1;
+            }
+
+            // Lines ending with a backslash continue on the next line.
+            let line = strip_comment(line).trim();
+            if let Some(line) = line.strip_suffix('\\') {
+                text.push_str(line);
+                text.push(' ');
+                continue;
+            }
+            text.push_str(line);
+
+            let statement = mem::take(&mut text);
+            let statement = statement.trim();
+            if statement.is_empty() {
+                continue;
+            }
+            let Some(origin) = self.origin(first_line) else {
+                continue;
+            };
+
+            let keyword = keyword(statement);
+            if keyword == "chip" {
+                chip = Some(statement.into());
+            }
+            result.push(Statement {
+                origin,
+                // Bus statements apply to all chips.
+                chip: if keyword == "bus" { None } else { chip.clone() },
+                text: statement.into(),
+            });
+        }
+        result
+    }
+
+    fn push_file(&mut self, layer: usize, path: &Path) -> Result<()> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| Error::from_io_path("std::fs::read_to_string()", path, err))?;
+
+        let source = Source {
+            layer,
+            path: Some(path.into()),
+            name: path.display().to_string(),
+        };
+        self.push(source, &contents);
+        Ok(())
+    }
+
+    fn push_files(&mut self, layer: usize, paths: &[PathBuf]) -> Result<()> {
+        paths
+            .iter()
+            .try_for_each(|path| self.push_file(layer, path))
+    }
+
+    fn push(&mut self, source: Source, contents: &str) {
+        let first_line = self.text.matches('\n').count() + 1;
+        self.text.push_str(contents);
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            self.text.push('\n');
+        }
+        let line_count = self.text.matches('\n').count() + 1 - first_line;
+
+        self.segments.push(Segment {
+            source,
+            first_line,
+            line_count,
+        });
+    }
+}
+
+/// Return the paths of the regular files in the given directory, that are
+/// not hidden, sorted by name.
+fn dir_entries(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut result = Vec::default();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.path().is_file() {
+            result.push(entry.path());
+        }
+    }
+    result.sort_unstable();
+    Ok(result)
+}
+
+/// Return the given line without its comment, if any.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Return the first word of the given statement.
+fn keyword(statement: &str) -> &str {
+    statement.split_whitespace().next().unwrap_or_default()
+}
+
+/// Return the key of statements overriding each other, or `None` for chip
+/// statements.
+fn statement_key<'s>(statement: &'s Statement<'_>) -> Option<(Option<&'s str>, &'s str, &'s str)> {
+    let mut words = statement.text.split_whitespace();
+    let keyword = words.next()?;
+    if keyword == "chip" {
+        return None;
+    }
+
+    let chip = if keyword == "bus" {
+        None
+    } else {
+        statement.chip.as_deref()
+    };
+    Some((chip, keyword, words.next().unwrap_or_default()))
+}
@@ -0,0 +1,132 @@
+#![cfg(test)]
+
+use std::fs;
+use std::path::Path;
+
+use crate::config::{ConfigLayer, LayeredConfig};
+
+#[test]
+fn load() {
+    let dir = tempfile::tempdir().unwrap();
+    let package = dir.path().join("package.conf");
+    fs::write(&package, "chip \"nct6775-*\"\n    label temp1 \"SYSTIN\"").unwrap();
+
+    let conf_d = dir.path().join("sensors.d");
+    fs::create_dir(&conf_d).unwrap();
+    fs::write(conf_d.join("b.conf"), "bus \"i2c-0\" \"SMBus\"\n").unwrap();
+    fs::write(conf_d.join("a.conf"), "# Empty.\n").unwrap();
+    fs::write(conf_d.join(".hidden.conf"), "invalid\n").unwrap();
+
+    let config = LayeredConfig::load([
+        ConfigLayer::path(&package),
+        ConfigLayer::path(&conf_d),
+        ConfigLayer::text("empty", ""),
+        ConfigLayer::text(
+            "overrides",
+            "chip \"nct6775-*\"\n    label temp1 \"Case\"\n",
+        ),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        config.text(),
+        "chip \"nct6775-*\"\n    label temp1 \"SYSTIN\"\n\
+         # Empty.\n\
+         bus \"i2c-0\" \"SMBus\"\n\
+         chip \"nct6775-*\"\n    label temp1 \"Case\"\n"
+    );
+
+    let sources: Vec<_> = config
+        .sources()
+        .map(|s| (s.layer, s.name.as_str()))
+        .collect();
+    let name = |path: &Path| path.display().to_string();
+    assert_eq!(
+        sources,
+        [
+            (0, name(&package).as_str()),
+            (1, name(&conf_d.join("a.conf")).as_str()),
+            (1, name(&conf_d.join("b.conf")).as_str()),
+            (2, "empty"),
+            (3, "overrides"),
+        ]
+    );
+
+    let origin = |line| config.origin(line).map(|o| o.to_string());
+    assert_eq!(origin(0), None);
+    assert_eq!(
+        origin(2),
+        Some(format!("file '{}' line 2", package.display()))
+    );
+    assert_eq!(
+        origin(4),
+        Some(format!("file '{}' line 1", conf_d.join("b.conf").display()))
+    );
+    assert_eq!(
+        origin(6).as_deref(),
+        Some("configuration 'overrides' line 2")
+    );
+    assert_eq!(origin(7), None);
+
+    let missing = LayeredConfig::load([ConfigLayer::path(dir.path().join("missing"))]);
+    assert_eq!(missing.unwrap_err().code(), "io_path");
+}
+
+#[test]
+fn effective_statements() {
+    let config = LayeredConfig::load([
+        ConfigLayer::text(
+            "package",
+            "chip \"nct6775-*\"\n\
+             \x20   label temp1 \"SYSTIN\" # System.\n\
+             \x20   label temp2 \"CPUTIN\"\n\
+             \x20   set pwm1_enable 1\n\
+             chip \"it8688-*\"\n\
+             \x20   label temp1 \"System\"\n\
+             bus \"i2c-0\" \"SMBus\"\n",
+        ),
+        ConfigLayer::text(
+            "user",
+            "bus \"i2c-0\" \\\n    \"SMBus I801\"\n\
+             chip \"nct6775-*\"\n\
+             \x20   label temp1 \"# Case\"\n",
+        ),
+    ])
+    .unwrap();
+
+    let statements = config.effective_statements();
+    let summary: Vec<_> = statements
+        .iter()
+        .map(|s| {
+            let origin = (s.origin.source.name.as_str(), s.origin.line_number);
+            (origin, s.chip.as_deref(), s.text.as_str())
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        [
+            (
+                ("package", 3),
+                Some("chip \"nct6775-*\""),
+                "label temp2 \"CPUTIN\""
+            ),
+            (
+                ("package", 4),
+                Some("chip \"nct6775-*\""),
+                "set pwm1_enable 1"
+            ),
+            (
+                ("package", 6),
+                Some("chip \"it8688-*\""),
+                "label temp1 \"System\""
+            ),
+            (("user", 1), None, "bus \"i2c-0\"  \"SMBus I801\""),
+            (
+                ("user", 4),
+                Some("chip \"nct6775-*\""),
+                "label temp1 \"# Case\""
+            ),
+        ]
+    );
+}
@@ -0,0 +1,580 @@
+//! Control of actuators, *e.g.,* fans.
+//!
+//! Leaving fans at a fixed low duty cycle when a fan controlling process
+//! dies is dangerous. [`RestoreGuard`]s record the values that actuator
+//! attributes need to be restored to, and [`install_emergency_restore`]
+//! makes sure these values are written back even if the process is killed
+//! by a signal.
+//!
+//! [`ActuatorState`]s snapshot the values of writable sub-features, so that
+//! they can be written back after trying other settings, *e.g.,* a fan
+//! profile.
+
+#[cfg(test)]
+mod tests;
+
+use core::cell::UnsafeCell;
+use core::ffi::c_void;
+use core::mem::MaybeUninit;
+use core::sync::atomic;
+use core::sync::atomic::AtomicU8;
+use std::fs;
+use std::io;
+use std::os::raw::c_int;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use crate::capabilities::Capabilities;
+use crate::chip::ChipRef;
+use crate::errors::{Capability, Error, Result};
+use crate::sub_feature::{Flags, SubFeatureRef};
+use crate::LMSensors;
+
+/// Value of a `pwmN_enable` attribute selecting automatic fan speed control,
+/// for most hardware monitoring drivers.
+pub const PWM_ENABLE_AUTOMATIC: &str = "2";
+
+/// Maximum number of simultaneously armed [`RestoreGuard`]s.
+pub const MAX_RESTORE_GUARDS: usize = 64;
+
+const MAX_PATH_LEN: usize = 256;
+const MAX_VALUE_LEN: usize = 32;
+
+const SLOT_FREE: u8 = 0;
+const SLOT_BUSY: u8 = 1;
+const SLOT_ARMED: u8 = 2;
+const SLOT_RESTORING: u8 = 3;
+
+/// Storage of one restore action.
+///
+/// This is only accessed with async-signal-safe operations, so that restore
+/// actions can be performed from signal handlers.
+/// The contents of `path` and `value` are owned by whoever moves `state`
+/// from `SLOT_FREE` to `SLOT_BUSY`, or from `SLOT_ARMED` to `SLOT_RESTORING`.
+struct Slot {
+    state: AtomicU8,
+    path: UnsafeCell<[u8; MAX_PATH_LEN]>,
+    value: UnsafeCell<[u8; MAX_VALUE_LEN]>,
+    value_len: UnsafeCell<usize>,
+}
+
+// Safety: contents are protected by `state`.
+unsafe impl Sync for Slot {}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(SLOT_FREE),
+            path: UnsafeCell::new([0_u8; MAX_PATH_LEN]),
+            value: UnsafeCell::new([0_u8; MAX_VALUE_LEN]),
+            value_len: UnsafeCell::new(0),
+        }
+    }
+
+    fn claim(&self) -> bool {
+        self.state
+            .compare_exchange(
+                SLOT_FREE,
+                SLOT_BUSY,
+                atomic::Ordering::Acquire,
+                atomic::Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    /// Move from `SLOT_ARMED` to `SLOT_BUSY`, waiting for any ongoing
+    /// restoration to finish.
+    fn disarm(&self) {
+        loop {
+            match self.state.compare_exchange_weak(
+                SLOT_ARMED,
+                SLOT_BUSY,
+                atomic::Ordering::Acquire,
+                atomic::Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(SLOT_RESTORING | SLOT_ARMED) => core::hint::spin_loop(),
+                Err(_) => unreachable!(),
+            }
+        }
+    }
+
+    /// Write the value to the path, if this slot is armed.
+    ///
+    /// This function is async-signal-safe.
+    fn restore(&self) {
+        if self
+            .state
+            .compare_exchange(
+                SLOT_ARMED,
+                SLOT_RESTORING,
+                atomic::Ordering::Acquire,
+                atomic::Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        // Safety: we own the slot contents while it is `SLOT_RESTORING`.
+        // `path` is null-terminated, and `value_len` is at most `MAX_VALUE_LEN`.
+        unsafe {
+            let path = (*self.path.get()).as_ptr();
+            let value = (*self.value.get()).as_ptr();
+            let value_len = *self.value_len.get();
+
+            let fd = libc::open(path.cast(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd != -1 {
+                let _ignored = libc::write(fd, value.cast(), value_len);
+                libc::close(fd);
+            }
+        }
+
+        self.state.store(SLOT_ARMED, atomic::Ordering::Release);
+    }
+}
+
+static SLOTS: [Slot; MAX_RESTORE_GUARDS] = [const { Slot::new() }; MAX_RESTORE_GUARDS];
+
+/// Guard restoring an actuator attribute to a recorded value when dropped.
+///
+/// While armed, the recorded value is also restored by the emergency
+/// handlers installed by [`install_emergency_restore`], and by
+/// [`emergency_restore`].
+#[derive(Debug)]
+#[must_use]
+pub struct RestoreGuard {
+    slot: usize,
+    path: PathBuf,
+    armed: bool,
+}
+
+impl RestoreGuard {
+    /// Return a guard writing back `value` to the attribute at the given path.
+    ///
+    /// The path is typically a sysfs attribute, *e.g.,*
+    /// `/sys/class/hwmon/hwmon2/pwm1_enable`.
+    pub fn new(path: impl Into<PathBuf>, value: impl AsRef<[u8]>) -> Result<Self> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = path.into();
+        let value = value.as_ref();
+
+        let path_bytes = path.as_os_str().as_bytes();
+        if path_bytes.len() >= MAX_PATH_LEN || path_bytes.contains(&0) {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io_path("RestoreGuard::new()", path, err));
+        }
+
+        if value.len() > MAX_VALUE_LEN {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io_path("RestoreGuard::new()", path, err));
+        }
+
+        let Some(slot) = SLOTS.iter().position(Slot::claim) else {
+            let err = io::ErrorKind::OutOfMemory.into();
+            return Err(Error::from_io_path("RestoreGuard::new()", path, err));
+        };
+
+        // Safety: we own the slot contents while it is `SLOT_BUSY`.
+        unsafe {
+            let slot_path = &mut *SLOTS[slot].path.get();
+            slot_path[..path_bytes.len()].copy_from_slice(path_bytes);
+            slot_path[path_bytes.len()] = 0;
+
+            let slot_value = &mut *SLOTS[slot].value.get();
+            slot_value[..value.len()].copy_from_slice(value);
+            *SLOTS[slot].value_len.get() = value.len();
+        }
+
+        SLOTS[slot]
+            .state
+            .store(SLOT_ARMED, atomic::Ordering::Release);
+
+        Ok(Self {
+            slot,
+            path,
+            armed: true,
+        })
+    }
+
+    /// Return a guard writing back the current value of the attribute
+    /// at the given path.
+    pub fn capture(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let value = fs::read(&path).map_err(|r| Error::from_io_path("fs::read()", &path, r))?;
+        Self::new(path, value)
+    }
+
+    /// Return a guard re-enabling automatic fan speed control
+    /// of the given PWM output of a chip.
+    ///
+    /// See: [`PWM_ENABLE_AUTOMATIC`].
+    pub fn pwm_automatic(
+        _capabilities: Capabilities<'_>,
+        chip: ChipRef<'_>,
+        pwm_number: u32,
+    ) -> Result<Self> {
+        let path = pwm_enable_path(chip, pwm_number).ok_or_else(|| {
+            Error::unsupported("RestoreGuard::pwm_automatic()", Capability::SysfsPath)
+        })?;
+        if !path.exists() {
+            let needed = Capability::PwmControl;
+            return Err(Error::unsupported("RestoreGuard::pwm_automatic()", needed));
+        }
+        Self::new(path, PWM_ENABLE_AUTOMATIC)
+    }
+
+    /// Return the path of the guarded attribute.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write back the recorded value now, and disarm this guard.
+    pub fn restore(mut self) -> Result<()> {
+        self.release(true)
+    }
+
+    /// Disarm this guard without writing back the recorded value.
+    pub fn disarm(mut self) {
+        let _ignored = self.release(false);
+    }
+
+    fn release(&mut self, write_back: bool) -> Result<()> {
+        if !self.armed {
+            return Ok(());
+        }
+        self.armed = false;
+
+        let slot = &SLOTS[self.slot];
+        slot.disarm();
+
+        let result = if write_back {
+            // Safety: we own the slot contents while it is `SLOT_BUSY`.
+            let value = unsafe { &(&*slot.value.get())[..*slot.value_len.get()] };
+            fs::write(&self.path, value)
+                .map_err(|r| Error::from_io_path("fs::write()", &self.path, r))
+        } else {
+            Ok(())
+        };
+
+        slot.state.store(SLOT_FREE, atomic::Ordering::Release);
+        result
+    }
+}
+
+impl Drop for RestoreGuard {
+    fn drop(&mut self) {
+        let _ignored = self.release(true);
+    }
+}
+
+/**
+Snapshot of the values of writable sub-features, *e.g.,* actuators.
+
+Only sub-features that are both readable and writable are captured.
+
+# Example
+
+```no_run
+# fn f(sensors: &lm_sensors::LMSensors) -> lm_sensors::errors::Result<()> {
+use lm_sensors::control::ActuatorState;
+use lm_sensors::value::Kind;
+
+let state = ActuatorState::capture(sensors, |sub_feature| {
+    sub_feature.kind() == Some(Kind::FanMinimum)
+})?;
+
+// Try other fan speed limits...
+
+state.restore(sensors.capabilities())?;
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ActuatorState<'a> {
+    values: Vec<(SubFeatureRef<'a>, f64)>,
+}
+
+impl<'a> ActuatorState<'a> {
+    /// Return a snapshot of the raw values of all readable and writable
+    /// sub-features of all chips, for which `selector` returns `true`.
+    ///
+    /// This fails if any selected value cannot be read.
+    pub fn capture(
+        sensors: &'a LMSensors,
+        mut selector: impl FnMut(SubFeatureRef<'a>) -> bool,
+    ) -> Result<Self> {
+        let sub_features = sensors
+            .chip_iter(None)
+            .flat_map(ChipRef::feature_iter)
+            .flat_map(|feature| feature.sub_feature_iter())
+            .filter(|sub_feature| {
+                sub_feature
+                    .flags()
+                    .is_some_and(|f| f.contains(Flags::READABLE | Flags::WRITABLE))
+            })
+            .filter(|&sub_feature| selector(sub_feature));
+
+        Self::from_sub_features(sub_features)
+    }
+
+    /// Return a snapshot of the raw values of the given sub-features.
+    ///
+    /// This fails if any value cannot be read.
+    pub fn from_sub_features(
+        sub_features: impl IntoIterator<Item = SubFeatureRef<'a>>,
+    ) -> Result<Self> {
+        let values = sub_features
+            .into_iter()
+            .map(|sub_feature| Ok((sub_feature, sub_feature.raw_value()?)))
+            .collect::<Result<_>>()?;
+        Ok(Self { values })
+    }
+
+    /// Return the captured sub-features and their raw values.
+    pub fn values(&self) -> impl ExactSizeIterator<Item = (SubFeatureRef<'a>, f64)> + '_ {
+        self.values.iter().copied()
+    }
+
+    /// Return `true` if no sub-feature was captured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Write back the captured raw values, in the order they were captured.
+    ///
+    /// All values are written even if some writes fail, then the first
+    /// failure is returned. The snapshot remains usable afterwards.
+    pub fn restore(&self, capabilities: Capabilities<'_>) -> Result<()> {
+        let mut result = Ok(());
+        for &(sub_feature, value) in &self.values {
+            let r = sub_feature.set_raw_value(capabilities, value);
+            if result.is_ok() {
+                result = r;
+            }
+        }
+        result
+    }
+}
+
+/// Return the path of the `pwmN_enable` attribute of the given PWM output
+/// of a chip, if the chip has a path.
+#[must_use]
+pub fn pwm_enable_path(chip: ChipRef<'_>, pwm_number: u32) -> Option<PathBuf> {
+    chip.path()
+        .map(|path| path.join(format!("pwm{pwm_number}_enable")))
+}
+
+/// Write back the recorded values of all armed [`RestoreGuard`]s.
+///
+/// Guards remain armed. This function is async-signal-safe.
+pub fn emergency_restore() {
+    for slot in &SLOTS {
+        slot.restore();
+    }
+}
+
+/// Signals handled by [`install_emergency_restore`].
+///
+/// `SIGSEGV` and `SIGBUS` are not included, because the Rust standard
+/// library handles them to report stack overflows. They can be handled by
+/// [`install_emergency_restore_on`].
+pub const DEFAULT_EMERGENCY_SIGNALS: [c_int; 7] = [
+    libc::SIGHUP,
+    libc::SIGINT,
+    libc::SIGQUIT,
+    libc::SIGTERM,
+    libc::SIGABRT,
+    libc::SIGILL,
+    libc::SIGFPE,
+];
+
+/// Number of signal numbers whose previous actions can be saved.
+const MAX_SIGNALS: usize = 65;
+
+/// Action of a signal before its emergency handler was installed.
+///
+/// `action` is written only while `saved` is `false`, and is read by signal
+/// handlers only after `saved` is `true`.
+struct SavedAction {
+    saved: atomic::AtomicBool,
+    action: UnsafeCell<MaybeUninit<libc::sigaction>>,
+}
+
+// Safety: contents are protected by `saved`.
+unsafe impl Sync for SavedAction {}
+
+impl SavedAction {
+    const fn new() -> Self {
+        Self {
+            saved: atomic::AtomicBool::new(false),
+            action: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Return the saved action, if any.
+    ///
+    /// This function is async-signal-safe.
+    fn get(&self) -> Option<&libc::sigaction> {
+        self.saved
+            .load(atomic::Ordering::Acquire)
+            // Safety: `action` was initialized before `saved` became `true`.
+            .then(|| unsafe { (*self.action.get()).assume_init_ref() })
+    }
+}
+
+static SAVED_ACTIONS: [SavedAction; MAX_SIGNALS] = [const { SavedAction::new() }; MAX_SIGNALS];
+
+/// Install process-wide handlers calling [`emergency_restore`] when
+/// the process exits, or is terminated by a signal.
+///
+/// Handlers are installed for [`DEFAULT_EMERGENCY_SIGNALS`].
+/// See: [`install_emergency_restore_on`].
+pub fn install_emergency_restore() -> Result<()> {
+    install_emergency_restore_on(&DEFAULT_EMERGENCY_SIGNALS)
+}
+
+/// Install process-wide handlers calling [`emergency_restore`] when
+/// the process exits, or receives one of the given signals.
+///
+/// Each handler restores armed guards, then calls the handler that was
+/// installed for the signal before, if any. If the signal had its default
+/// disposition, then the handler restores it, and raises the signal again.
+/// Signals that were ignored remain ignored. An `atexit()` handler covers
+/// normal process termination.
+///
+/// Installing a handler for a signal more than once has no further effect.
+pub fn install_emergency_restore_on(signals: &[c_int]) -> Result<()> {
+    static INSTALLED: Mutex<bool> = Mutex::new(false);
+
+    let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+    if !*installed {
+        install_exit_handler()?;
+        *installed = true;
+    }
+
+    for &signal in signals {
+        install_signal_handler(signal)?;
+    }
+    Ok(())
+}
+
+fn install_exit_handler() -> Result<()> {
+    extern "C" fn on_exit() {
+        emergency_restore();
+    }
+
+    // Safety: atexit() is assumed to be safe.
+    if unsafe { libc::atexit(on_exit) } != 0 {
+        let err = io::ErrorKind::Other.into();
+        return Err(Error::from_io("atexit()", err));
+    }
+    Ok(())
+}
+
+/// Install the emergency handler of the given signal, saving its previous
+/// action. This must be called while `INSTALLED` is locked.
+fn install_signal_handler(signal: c_int) -> Result<()> {
+    let Some(saved) = usize::try_from(signal)
+        .ok()
+        .and_then(|index| SAVED_ACTIONS.get(index))
+        .filter(|_| signal > 0)
+    else {
+        let err = io::ErrorKind::InvalidInput.into();
+        return Err(Error::from_io("install_emergency_restore_on()", err));
+    };
+
+    if saved.get().is_some() {
+        return Ok(());
+    }
+
+    // Safety: `previous` is filled by sigaction().
+    let previous = unsafe {
+        let mut previous = MaybeUninit::<libc::sigaction>::zeroed();
+        if libc::sigaction(signal, core::ptr::null(), previous.as_mut_ptr()) == -1 {
+            return Err(Error::from_io("sigaction()", io::Error::last_os_error()));
+        }
+        previous.assume_init()
+    };
+
+    if previous.sa_sigaction == libc::SIG_IGN {
+        return Ok(());
+    }
+
+    // Safety: `saved` is not saved yet, and signal handlers do not read it.
+    unsafe { (*saved.action.get()).write(previous) };
+    saved.saved.store(true, atomic::Ordering::Release);
+
+    // Safety: `action` is zero-initialized, then properly filled.
+    let r = unsafe {
+        let mut action: libc::sigaction = core::mem::zeroed();
+        action.sa_sigaction = on_signal as SigInfoHandler as libc::sighandler_t;
+        action.sa_flags =
+            libc::SA_SIGINFO | (previous.sa_flags & (libc::SA_ONSTACK | libc::SA_RESTART));
+        action.sa_mask = previous.sa_mask;
+        libc::sigaction(signal, &action, core::ptr::null_mut())
+    };
+
+    if r == -1 {
+        saved.saved.store(false, atomic::Ordering::Release);
+        return Err(Error::from_io("sigaction()", io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+type SigInfoHandler = extern "C" fn(c_int, *mut libc::siginfo_t, *mut c_void);
+
+extern "C" fn on_signal(signal: c_int, info: *mut libc::siginfo_t, context: *mut c_void) {
+    emergency_restore();
+
+    let Some(previous) = usize::try_from(signal)
+        .ok()
+        .and_then(|index| SAVED_ACTIONS.get(index))
+        .and_then(SavedAction::get)
+    else {
+        return;
+    };
+
+    match previous.sa_sigaction {
+        libc::SIG_DFL => {
+            // Safety: sigaction() and raise() are async-signal-safe.
+            // The signal is delivered once this handler returns.
+            unsafe {
+                libc::sigaction(signal, previous, core::ptr::null_mut());
+                libc::raise(signal);
+            }
+        }
+
+        libc::SIG_IGN => {}
+
+        handler if previous.sa_flags & libc::SA_SIGINFO != 0 => {
+            // Safety: the previous handler was installed with `SA_SIGINFO`.
+            let handler: SigInfoHandler = unsafe { core::mem::transmute(handler) };
+            handler(signal, info, context);
+        }
+
+        handler => {
+            // Safety: the previous handler was installed without `SA_SIGINFO`.
+            let handler: extern "C" fn(c_int) = unsafe { core::mem::transmute(handler) };
+            handler(signal);
+        }
+    }
+}
+
+/// Restore the actions of all signals saved by
+/// [`install_emergency_restore_on`].
+#[cfg(test)]
+fn uninstall_signal_handlers() {
+    for (signal, saved) in (0..).zip(&SAVED_ACTIONS) {
+        if let Some(previous) = saved.get() {
+            // Safety: `previous` was returned by sigaction().
+            unsafe { libc::sigaction(signal, previous, core::ptr::null_mut()) };
+            saved.saved.store(false, atomic::Ordering::Release);
+        }
+    }
+}
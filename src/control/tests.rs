@@ -0,0 +1,141 @@
+#![cfg(test)]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::fs;
+use std::os::raw::c_int;
+
+use serial_test::serial;
+
+use super::{ActuatorState, RestoreGuard};
+
+#[test]
+#[serial]
+fn restore_guard() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pwm1_enable");
+    fs::write(&path, "2\n").unwrap();
+
+    let guard = RestoreGuard::capture(&path).unwrap();
+    assert_eq!(guard.path(), path);
+    fs::write(&path, "1\n").unwrap();
+    drop(guard);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "2\n");
+
+    let guard = RestoreGuard::new(&path, "5").unwrap();
+    guard.restore().unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "5");
+
+    let guard = RestoreGuard::new(&path, "2").unwrap();
+    guard.disarm();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "5");
+
+    RestoreGuard::capture(dir.path().join("inexistent")).unwrap_err();
+    RestoreGuard::new(&path, [b'1'; 64]).unwrap_err();
+    RestoreGuard::new(dir.path().join("x".repeat(300)), "2").unwrap_err();
+}
+
+#[test]
+#[serial]
+fn emergency_restore() {
+    let dir = tempfile::tempdir().unwrap();
+    let path0 = dir.path().join("pwm1_enable");
+    let path1 = dir.path().join("pwm2_enable");
+
+    let guard0 = RestoreGuard::new(&path0, "2").unwrap();
+    let guard1 = RestoreGuard::new(&path1, "5").unwrap();
+    fs::write(&path0, "1").unwrap();
+    fs::write(&path1, "1").unwrap();
+
+    super::emergency_restore();
+    assert_eq!(fs::read_to_string(&path0).unwrap(), "2");
+    assert_eq!(fs::read_to_string(&path1).unwrap(), "5");
+
+    // Guards remain armed.
+    fs::write(&path0, "1").unwrap();
+    drop(guard0);
+    assert_eq!(fs::read_to_string(&path0).unwrap(), "2");
+
+    guard1.disarm();
+    fs::write(&path1, "1").unwrap();
+    super::emergency_restore();
+    assert_eq!(fs::read_to_string(&path1).unwrap(), "1");
+}
+
+#[test]
+#[serial]
+fn restore_guard_capacity() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pwm1_enable");
+
+    let guards: Vec<_> = (0..super::MAX_RESTORE_GUARDS)
+        .map(|_| RestoreGuard::new(&path, "2").unwrap())
+        .collect();
+    RestoreGuard::new(&path, "2").unwrap_err();
+
+    guards.into_iter().for_each(RestoreGuard::disarm);
+    let _guard = RestoreGuard::new(&path, "2").unwrap();
+}
+
+#[test]
+#[serial]
+fn install_emergency_restore() {
+    super::install_emergency_restore().unwrap();
+    super::install_emergency_restore().unwrap();
+    super::uninstall_signal_handlers();
+
+    super::install_emergency_restore_on(&[0]).unwrap_err();
+    super::install_emergency_restore_on(&[-1]).unwrap_err();
+}
+
+#[test]
+#[serial]
+fn install_emergency_restore_chaining() {
+    static HANDLED: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn on_signal(_signal: c_int) {
+        HANDLED.store(true, Ordering::SeqCst);
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pwm1_enable");
+    fs::write(&path, "2\n").unwrap();
+
+    // Safety: `on_signal` is async-signal-safe.
+    let previous = unsafe {
+        libc::signal(
+            libc::SIGUSR1,
+            on_signal as extern "C" fn(c_int) as libc::sighandler_t,
+        )
+    };
+    assert_ne!(previous, libc::SIG_ERR);
+
+    super::install_emergency_restore_on(&[libc::SIGUSR1]).unwrap();
+    let guard = RestoreGuard::capture(&path).unwrap();
+    fs::write(&path, "1\n").unwrap();
+
+    // Safety: the handler of the signal is installed above.
+    assert_eq!(unsafe { libc::raise(libc::SIGUSR1) }, 0);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "2\n");
+    assert!(HANDLED.load(Ordering::SeqCst));
+
+    guard.disarm();
+    super::uninstall_signal_handlers();
+
+    // Safety: this restores the action found before the test.
+    unsafe { libc::signal(libc::SIGUSR1, previous) };
+}
+
+#[test]
+#[serial]
+fn actuator_state() {
+    let s = crate::Initializer::isolated().initialize().unwrap();
+
+    let state = ActuatorState::capture(&s, |_| false).unwrap();
+    assert!(state.is_empty());
+    assert_eq!(state.values().len(), 0);
+    state.restore(s.capabilities()).unwrap();
+
+    let state = ActuatorState::from_sub_features([]).unwrap();
+    assert!(state.is_empty());
+    state.restore(s.capabilities()).unwrap();
+}
@@ -0,0 +1,667 @@
+//! Sharing one instance of the LM sensors library between processes.
+//!
+//! A [`Server`] reads all sensors on behalf of [`Client`]s connected to it
+//! through a Unix domain socket, so that many monitoring processes do not
+//! all poll hardware monitoring drivers. [`Source`] connects to a running
+//! server if there is one, and falls back to reading sensors directly
+//! otherwise.
+//!
+//! Servers are read-only by default, and may require clients to
+//! authenticate. See: [`AccessPolicy`].
+//!
+//! A server answers each client on its own thread, while sensors are read
+//! by the thread running [`Server::serve`], so that long-lived clients do
+//! not block others. Connections idle for longer than the timeout of the
+//! server are closed, and [`Client`]s reconnect transparently. Connections
+//! beyond the maximum number of clients of the server are closed as soon as
+//! they are accepted.
+//!
+//! # Protocol
+//!
+//! Lines are at most [`MAX_LINE_LEN`] bytes long, excluding line feeds.
+//!
+//! A client sends the line `READ`. The server answers with one line per
+//! sub-feature, made of tab-separated fields: chip name, feature name,
+//! sub-feature name, raw sub-feature type, and raw value or `-` if the value
+//! could not be read. An empty line ends the answer.
+//!
+//! A client authenticates by sending the line `AUTH` followed by a space
+//! and the token, and writes a sub-feature by sending the line `SET`
+//! followed by a space and tab-separated fields: chip name, sub-feature
+//! name, and raw value. A client checks that the server is responsive by
+//! sending the line `PING`. The server answers any of these by the line `OK`.
+//!
+//! A failed request is answered by the line `ERR` followed by a space and
+//! an error identifier, *e.g.,* `permission_denied`.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::{panic, thread};
+
+use crate::access::{self, AccessPolicy, Connections, Session, DEFAULT_MAX_CONNECTIONS};
+use crate::backend::Backend;
+use crate::errors::{Error, Result};
+use crate::{Initializer, LMSensors};
+
+pub use crate::backend::Reading;
+
+/// Default path of the socket of a [`Server`].
+pub const DEFAULT_SOCKET_PATH: &str = "/run/lm-sensors.sock";
+
+/// Default timeout of reads and writes of connections, after which
+/// connections are closed.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Maximum length of a line of the protocol, excluding its line feed.
+pub const MAX_LINE_LEN: usize = 4096;
+
+const READ_REQUEST: &str = "READ";
+const PING_REQUEST: &str = "PING";
+const AUTH_REQUEST: &str = "AUTH ";
+const SET_REQUEST: &str = "SET ";
+const OK_ANSWER: &str = "OK";
+const ERROR_ANSWER: &str = "ERR ";
+
+/// Return readings of all sub-features of all chips.
+#[must_use]
+pub fn read_all(sensors: &LMSensors) -> Vec<Reading> {
+    crate::backend::read_lm_sensors(sensors)
+}
+
+/// Server reading sensors on behalf of [`Client`]s.
+#[derive(Debug)]
+pub struct Server {
+    listener: UnixListener,
+    path: PathBuf,
+    policy: AccessPolicy,
+    timeout: Option<Duration>,
+    max_connections: usize,
+}
+
+impl Server {
+    /// Return a server listening on a Unix domain socket at the given path.
+    ///
+    /// A stale socket left at that path by a previous server is replaced.
+    /// Other files are never removed.
+    pub fn bind(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+
+        let is_socket =
+            std::fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.file_type().is_socket());
+        if is_socket && UnixStream::connect(&path).is_err() {
+            let _ignored = std::fs::remove_file(&path);
+        }
+
+        let listener =
+            UnixListener::bind(&path).map_err(|r| Error::from_io_path("bind()", &path, r))?;
+        Ok(Self {
+            listener,
+            path,
+            policy: AccessPolicy::default(),
+            timeout: Some(DEFAULT_TIMEOUT),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        })
+    }
+
+    /// Set the access policy enforced on clients.
+    ///
+    /// By default, clients need no authentication, and may not write.
+    #[must_use]
+    pub fn access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Set the timeout of reads and writes of connections, instead of
+    /// [`DEFAULT_TIMEOUT`]. A zero timeout disables timeouts.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout).filter(|timeout| !timeout.is_zero());
+        self
+    }
+
+    /// Set the maximum number of clients served concurrently by
+    /// [`Server::serve`], instead of [`DEFAULT_MAX_CONNECTIONS`].
+    #[must_use]
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Return the path of the socket of this server.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Accept one client connection, and answer all its requests.
+    pub fn serve_one(&self, sensors: &LMSensors) -> Result<()> {
+        let stream = self.accept()?;
+        let stop = AtomicBool::new(false);
+        let execute = |command| Some(execute(sensors, command));
+        serve_stream(self.policy.session(), &stream, &stop, execute)
+            .map_err(|r| Error::from_io_path("serve()", &self.path, r))
+    }
+
+    /// Accept client connections, and answer their requests concurrently,
+    /// until accepting a connection fails. Connections are then closed
+    /// after their current requests.
+    ///
+    /// Connections accepted while the maximum number of clients are served
+    /// are closed immediately. Failures of individual connections are ignored.
+    pub fn serve(&self, sensors: &LMSensors) -> Result<()> {
+        self.serve_streams(sensors, core::iter::repeat_with(|| self.accept_stream()))
+            .map_err(|r| Error::from_io_path("accept()", &self.path, r))
+    }
+
+    /// Answer requests received through the given streams concurrently,
+    /// until the streams end and their connections are closed, or until
+    /// one of them is an error.
+    fn serve_streams(
+        &self,
+        sensors: &LMSensors,
+        streams: impl Iterator<Item = io::Result<UnixStream>> + Send,
+    ) -> io::Result<()> {
+        let (sender, receiver) = mpsc::channel::<(Command, mpsc::Sender<Answer>)>();
+        let stop = &AtomicBool::new(false);
+        let connections = &Connections::new(self.max_connections);
+
+        thread::scope(|scope| {
+            let acceptor = scope.spawn(move || {
+                for stream in streams {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(err) => {
+                            stop.store(true, Ordering::Relaxed);
+                            return Err(err);
+                        }
+                    };
+
+                    // Dropping the stream closes the connection.
+                    let Some(slot) = connections.acquire() else {
+                        continue;
+                    };
+
+                    let sender = sender.clone();
+                    let session = self.policy.session();
+                    scope.spawn(move || {
+                        let _slot = slot;
+                        let execute = |command| {
+                            let (answer_sender, answer_receiver) = mpsc::channel();
+                            sender.send((command, answer_sender)).ok()?;
+                            answer_receiver.recv().ok()
+                        };
+                        let _ignored = serve_stream(session, &stream, stop, execute);
+                    });
+                }
+                Ok(())
+            });
+
+            // Sensors are read by this thread only, until all connections
+            // are closed.
+            for (command, answer_sender) in receiver {
+                let _ignored = answer_sender.send(execute(sensors, command));
+            }
+
+            acceptor
+                .join()
+                .unwrap_or_else(|payload| panic::resume_unwind(payload))
+        })
+    }
+
+    fn accept(&self) -> Result<UnixStream> {
+        self.accept_stream()
+            .map_err(|r| Error::from_io_path("accept()", &self.path, r))
+    }
+
+    fn accept_stream(&self) -> io::Result<UnixStream> {
+        let (stream, _address) = self.listener.accept()?;
+        set_timeouts(&stream, self.timeout)?;
+        Ok(stream)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ignored = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Request of a client, performed with the LM sensors library.
+enum Command {
+    Read,
+    Write {
+        chip: String,
+        sub_feature: String,
+        raw_value: f64,
+    },
+}
+
+/// Result of a [`Command`], with errors given by their identifiers.
+enum Answer {
+    Readings(Vec<Reading>),
+    Written(core::result::Result<(), &'static str>),
+}
+
+fn execute(sensors: &LMSensors, command: Command) -> Answer {
+    match command {
+        Command::Read => Answer::Readings(read_all(sensors)),
+        Command::Write {
+            chip,
+            sub_feature,
+            raw_value,
+        } => {
+            let result = crate::backend::write_lm_sensors(sensors, &chip, &sub_feature, raw_value);
+            Answer::Written(result.map_err(|err| error_code(&err)))
+        }
+    }
+}
+
+/// Answer requests received through the given stream, performing commands
+/// through the given function, until the client closes the stream, or
+/// until `stop` is set.
+fn serve_stream(
+    mut session: Session,
+    stream: &UnixStream,
+    stop: &AtomicBool,
+    mut execute: impl FnMut(Command) -> Option<Answer>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut writer = BufWriter::new(stream);
+
+    let mut request = String::default();
+    while !stop.load(Ordering::Relaxed) && read_line(&mut reader, &mut request)? != 0 {
+        let line = request.trim_end_matches('\n');
+
+        if line == PING_REQUEST {
+            write_answer(&mut writer, Ok(()))?;
+        } else if let Some(token) = line.strip_prefix(AUTH_REQUEST) {
+            let result = session.authenticate(token).map_err(|r| error_code(&r));
+            write_answer(&mut writer, result)?;
+        } else {
+            let command = if line == READ_REQUEST {
+                session.check_read().map(|()| Command::Read)
+            } else if let Some(fields) = line.strip_prefix(SET_REQUEST) {
+                let (chip, sub_feature, raw_value) =
+                    parse_set_request(fields).ok_or(io::ErrorKind::InvalidData)?;
+                session.check_write().map(|()| Command::Write {
+                    chip: chip.into(),
+                    sub_feature: sub_feature.into(),
+                    raw_value,
+                })
+            } else {
+                return Err(io::ErrorKind::InvalidData.into());
+            };
+
+            match command.map(&mut execute) {
+                Ok(Some(Answer::Readings(readings))) => write_readings(&mut writer, &readings)?,
+                Ok(Some(Answer::Written(result))) => write_answer(&mut writer, result)?,
+                // The thread reading sensors is gone.
+                Ok(None) => return Err(io::ErrorKind::BrokenPipe.into()),
+                Err(err) => write_answer(&mut writer, Err(error_code(&err)))?,
+            }
+        }
+
+        writer.flush()?;
+        request.clear();
+    }
+    Ok(())
+}
+
+/// Append a line of at most [`MAX_LINE_LEN`] bytes to the given buffer,
+/// and return its length, including its line feed, or zero at the end of
+/// the stream.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<usize> {
+    let len = reader.take(MAX_LINE_LEN as u64 + 1).read_line(line)?;
+    if len > MAX_LINE_LEN && !line.ends_with('\n') {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "line is too long",
+        ))
+    } else {
+        Ok(len)
+    }
+}
+
+fn parse_set_request(fields: &str) -> Option<(&str, &str, f64)> {
+    let mut fields = fields.split('\t');
+    let chip = fields.next()?;
+    let sub_feature = fields.next()?;
+    let value = fields.next()?.parse().ok()?;
+    fields
+        .next()
+        .is_none()
+        .then_some((chip, sub_feature, value))
+}
+
+fn write_answer(writer: &mut impl Write, result: core::result::Result<(), &str>) -> io::Result<()> {
+    match result {
+        Ok(()) => writeln!(writer, "{OK_ANSWER}"),
+        Err(code) => writeln!(writer, "{ERROR_ANSWER}{code}"),
+    }
+}
+
+fn error_code(err: &Error) -> &'static str {
+    if access::is_denied(err) {
+        access::PERMISSION_DENIED
+    } else {
+        err.code()
+    }
+}
+
+/// Return an error for an answer of the server starting with `ERR`.
+fn answer_error(code: &str) -> io::Error {
+    if code == access::PERMISSION_DENIED {
+        io::Error::new(io::ErrorKind::PermissionDenied, code)
+    } else {
+        io::Error::other(code.to_owned())
+    }
+}
+
+fn read_answer(reader: &mut impl BufRead) -> io::Result<()> {
+    let mut line = String::default();
+    if read_line(reader, &mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    let line = line.trim_end_matches('\n');
+    if line == OK_ANSWER {
+        Ok(())
+    } else if let Some(code) = line.strip_prefix(ERROR_ANSWER) {
+        Err(answer_error(code))
+    } else {
+        Err(io::ErrorKind::InvalidData.into())
+    }
+}
+
+fn write_readings(writer: &mut impl Write, readings: &[Reading]) -> io::Result<()> {
+    for reading in readings {
+        write!(
+            writer,
+            "{}\t{}\t{}\t{}\t",
+            reading.chip, reading.feature, reading.sub_feature, reading.raw_kind
+        )?;
+        if let Some(value) = reading.raw_value {
+            writeln!(writer, "{value}")?;
+        } else {
+            writeln!(writer, "-")?;
+        }
+    }
+    writeln!(writer)
+}
+
+fn read_readings(reader: &mut impl BufRead) -> io::Result<Vec<Reading>> {
+    let mut result = Vec::default();
+    let mut line = String::default();
+    loop {
+        line.clear();
+        if read_line(reader, &mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let line = line.trim_end_matches('\n');
+        if line.is_empty() {
+            return Ok(result);
+        }
+        if let Some(code) = line
+            .strip_prefix(ERROR_ANSWER)
+            .filter(|_| result.is_empty())
+        {
+            return Err(answer_error(code));
+        }
+
+        result.push(parse_reading(line).ok_or(io::ErrorKind::InvalidData)?);
+    }
+}
+
+fn parse_reading(line: &str) -> Option<Reading> {
+    let mut fields = line.split('\t');
+    let chip = fields.next()?.into();
+    let feature = fields.next()?.into();
+    let sub_feature = fields.next()?.into();
+    let raw_kind = fields.next()?.parse().ok()?;
+    let raw_value = match fields.next()? {
+        "-" => None,
+        value => Some(value.parse().ok()?),
+    };
+
+    fields.next().is_none().then_some(Reading {
+        chip,
+        feature,
+        sub_feature,
+        raw_kind,
+        raw_value,
+    })
+}
+
+/// Client of a [`Server`].
+///
+/// A client whose connection was closed by the server, *e.g.,* after being
+/// idle for longer than the timeout of the server, reconnects once per
+/// request, and authenticates again with the token it last authenticated
+/// with, if any.
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    path: PathBuf,
+    timeout: Option<Duration>,
+    token: Option<String>,
+}
+
+impl Client {
+    /// Connect to a server listening at the given path.
+    ///
+    /// Reads and writes of requests time out after [`DEFAULT_TIMEOUT`].
+    pub fn connect(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let timeout = Some(DEFAULT_TIMEOUT);
+        let (reader, writer) =
+            open(&path, timeout).map_err(|r| Error::from_io_path("connect()", &path, r))?;
+
+        Ok(Self {
+            reader,
+            writer,
+            path,
+            timeout,
+            token: None,
+        })
+    }
+
+    /// Set the timeout of reads and writes of requests, instead of
+    /// [`DEFAULT_TIMEOUT`]. A zero timeout disables timeouts.
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = Some(timeout).filter(|timeout| !timeout.is_zero());
+        set_timeouts(&self.writer, self.timeout)
+            .map_err(|r| Error::from_io_path("Client::set_timeout()", &self.path, r))
+    }
+
+    /// Check that the server answers requests.
+    pub fn ping(&mut self) -> Result<()> {
+        self.request("Client::ping()", |writer, reader| {
+            writeln!(writer, "{PING_REQUEST}")?;
+            read_answer(reader)
+        })
+    }
+
+    /// Return readings of all sub-features of all chips, read by the server.
+    pub fn read_all(&mut self) -> Result<Vec<Reading>> {
+        self.request("Client::read_all()", |writer, reader| {
+            writeln!(writer, "{READ_REQUEST}")?;
+            read_readings(reader)
+        })
+    }
+
+    /// Authenticate to the server with the given token.
+    ///
+    /// See: [`AccessPolicy::auth_token`].
+    pub fn authenticate(&mut self, token: &str) -> Result<()> {
+        if token.contains('\n') {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io_path(
+                "Client::authenticate()",
+                &self.path,
+                err,
+            ));
+        }
+
+        // A failed authentication revokes any previous one.
+        self.token = None;
+        self.request("Client::authenticate()", |writer, reader| {
+            writeln!(writer, "{AUTH_REQUEST}{token}")?;
+            read_answer(reader)
+        })?;
+        self.token = Some(token.into());
+        Ok(())
+    }
+
+    /// Set the raw value of a sub-feature of a chip, through the server.
+    ///
+    /// This fails unless the server allows writes.
+    /// See: [`AccessPolicy::allow_writes`].
+    pub fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        if [chip, sub_feature].iter().any(|s| s.contains(['\t', '\n'])) {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io_path("Client::write()", &self.path, err));
+        }
+
+        self.request("Client::write()", |writer, reader| {
+            writeln!(writer, "{SET_REQUEST}{chip}\t{sub_feature}\t{raw_value}")?;
+            read_answer(reader)
+        })
+    }
+
+    /// Perform a request, reconnecting once if the server closed
+    /// the connection.
+    fn request<T>(
+        &mut self,
+        operation: &'static str,
+        mut request: impl FnMut(&mut UnixStream, &mut BufReader<UnixStream>) -> io::Result<T>,
+    ) -> Result<T> {
+        let result = match request(&mut self.writer, &mut self.reader) {
+            Err(err) if is_disconnected(&err) => self
+                .reconnect()
+                .and_then(|()| request(&mut self.writer, &mut self.reader)),
+            result => result,
+        };
+        result.map_err(|r| Error::from_io_path(operation, &self.path, r))
+    }
+
+    fn reconnect(&mut self) -> io::Result<()> {
+        (self.reader, self.writer) = open(&self.path, self.timeout)?;
+
+        if let Some(token) = &self.token {
+            writeln!(self.writer, "{AUTH_REQUEST}{token}")?;
+            read_answer(&mut self.reader)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("path", &self.path)
+            .field("timeout", &self.timeout)
+            .field("token", &self.token.as_ref().map(|_token| "<redacted>"))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Connect to a server, and return the reader and the writer of the
+/// connection.
+fn open(path: &Path, timeout: Option<Duration>) -> io::Result<(BufReader<UnixStream>, UnixStream)> {
+    let stream = UnixStream::connect(path)?;
+    set_timeouts(&stream, timeout)?;
+    let writer = stream.try_clone()?;
+    Ok((BufReader::new(stream), writer))
+}
+
+fn set_timeouts(stream: &UnixStream, timeout: Option<Duration>) -> io::Result<()> {
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)
+}
+
+/// Return `true` if the error was caused by the peer closing the connection.
+fn is_disconnected(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::UnexpectedEof
+    )
+}
+
+/// Source of readings: either a running [`Server`], or the LM sensors library.
+#[derive(Debug)]
+pub enum Source {
+    /// Readings are proxied to a running server.
+    Daemon(Client),
+    /// Readings are performed by this process.
+    Direct(Box<LMSensors>),
+}
+
+impl Source {
+    /// Connect to a server listening at the given path, if it is running
+    /// and answers requests, or initialize the LM sensors library otherwise.
+    pub fn new(path: impl Into<PathBuf>, initializer: Initializer) -> Result<Self> {
+        let client = Client::connect(path).and_then(|mut client| client.ping().map(|()| client));
+        match client {
+            Ok(client) => Ok(Self::Daemon(client)),
+            Err(_err) => initializer
+                .initialize()
+                .map(|sensors| Self::Direct(Box::new(sensors))),
+        }
+    }
+
+    /// Return readings of all sub-features of all chips.
+    pub fn read_all(&mut self) -> Result<Vec<Reading>> {
+        match self {
+            Self::Daemon(client) => client.read_all(),
+            Self::Direct(sensors) => Ok(read_all(sensors)),
+        }
+    }
+}
+
+impl Backend for Client {
+    fn name(&self) -> &'static str {
+        "daemon"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Client::read_all(self)
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        Client::write(self, chip, sub_feature, raw_value)
+    }
+}
+
+impl Backend for Source {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Daemon(client) => client.name(),
+            Self::Direct(sensors) => sensors.name(),
+        }
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Source::read_all(self)
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        match self {
+            Self::Daemon(client) => client.write(chip, sub_feature, raw_value),
+            Self::Direct(sensors) => sensors.write(chip, sub_feature, raw_value),
+        }
+    }
+}
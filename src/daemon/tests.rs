@@ -0,0 +1,249 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::io::BufReader;
+use std::thread;
+
+use serial_test::serial;
+
+use super::{Client, Reading, Server, Source};
+
+#[test]
+fn protocol() {
+    let readings = [
+        Reading {
+            chip: "coretemp-isa-0000".into(),
+            feature: "temp1".into(),
+            sub_feature: "temp1_input".into(),
+            raw_kind: 0x200,
+            raw_value: Some(47.5),
+        },
+        Reading {
+            chip: "coretemp-isa-0000".into(),
+            feature: "temp1".into(),
+            sub_feature: "temp1_crit".into(),
+            raw_kind: 0x204,
+            raw_value: None,
+        },
+    ];
+
+    let mut buffer = Vec::default();
+    super::write_readings(&mut buffer, &readings).unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&buffer),
+        "coretemp-isa-0000\ttemp1\ttemp1_input\t512\t47.5\n\
+         coretemp-isa-0000\ttemp1\ttemp1_crit\t516\t-\n\n"
+    );
+
+    let parsed = super::read_readings(&mut BufReader::new(buffer.as_slice())).unwrap();
+    assert_eq!(parsed, readings);
+    assert_eq!(
+        parsed[0].value(),
+        Some(crate::Value::TemperatureInput(47.5))
+    );
+    assert_eq!(parsed[1].value(), None);
+
+    // Truncated or malformed answers.
+    super::read_readings(&mut BufReader::new(&buffer[..20])).unwrap_err();
+    super::read_readings(&mut BufReader::new(&b"a\tb\n\n"[..])).unwrap_err();
+}
+
+#[test]
+#[serial]
+fn client_server() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lm-sensors.sock");
+
+    let server = Server::bind(&path).unwrap();
+    assert_eq!(server.path(), path);
+
+    let server_thread = thread::spawn(move || {
+        let sensors = crate::Initializer::default().initialize().unwrap();
+        server.serve_one(&sensors).unwrap();
+        super::read_all(&sensors)
+    });
+
+    let mut source = Source::new(&path, crate::Initializer::isolated()).unwrap();
+    let remote = source.read_all().unwrap();
+    assert_eq!(source.read_all().unwrap().len(), remote.len());
+    assert!(matches!(source, Source::Daemon(_)));
+    drop(source);
+
+    let local = server_thread.join().unwrap();
+    assert_eq!(remote.len(), local.len());
+
+    // The server removed its socket.
+    Client::connect(&path).unwrap_err();
+
+    let mut source = Source::new(&path, crate::Initializer::isolated()).unwrap();
+    assert!(matches!(source, Source::Direct(_)));
+    assert!(source.read_all().unwrap().is_empty());
+}
+
+#[test]
+#[serial]
+fn access_policy() {
+    use crate::access::{self, AccessPolicy};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lm-sensors.sock");
+
+    let policy = AccessPolicy::new().auth_token("s3cret");
+    let server = Server::bind(&path).unwrap().access_policy(policy);
+
+    let server_thread = thread::spawn(move || {
+        let sensors = crate::Initializer::isolated().initialize().unwrap();
+        server.serve_one(&sensors).unwrap();
+    });
+
+    let mut client = Client::connect(&path).unwrap();
+    assert!(access::is_denied(&client.read_all().unwrap_err()));
+
+    assert!(access::is_denied(
+        &client.authenticate("wrong").unwrap_err()
+    ));
+    client.authenticate("bad\ntoken").unwrap_err();
+    client.read_all().unwrap_err();
+
+    client.authenticate("s3cret").unwrap();
+    assert!(client.read_all().unwrap().is_empty());
+
+    // The server is read-only.
+    let err = client.write("nct6775-isa-0290", "pwm1", 128.0).unwrap_err();
+    assert!(access::is_denied(&err));
+    client.write("bad\tchip", "pwm1", 128.0).unwrap_err();
+    drop(client);
+
+    server_thread.join().unwrap();
+}
+
+#[test]
+fn set_request() {
+    assert_eq!(
+        super::parse_set_request("nct6775-isa-0290\tpwm1\t128"),
+        Some(("nct6775-isa-0290", "pwm1", 128.0))
+    );
+    assert_eq!(super::parse_set_request("nct6775-isa-0290\tpwm1"), None);
+    assert_eq!(super::parse_set_request("a\tb\tc"), None);
+    assert_eq!(super::parse_set_request("a\tb\t1\td"), None);
+}
+
+#[test]
+fn line_length() {
+    let mut line = String::default();
+    let long = "a".repeat(super::MAX_LINE_LEN);
+
+    let input = format!("{long}\nb");
+    let mut reader = BufReader::new(input.as_bytes());
+    assert_eq!(
+        super::read_line(&mut reader, &mut line).unwrap(),
+        long.len() + 1
+    );
+    line.clear();
+    assert_eq!(super::read_line(&mut reader, &mut line).unwrap(), 1);
+
+    let input = format!("{long}a\n");
+    let mut reader = BufReader::new(input.as_bytes());
+    line.clear();
+    super::read_line(&mut reader, &mut line).unwrap_err();
+}
+
+#[test]
+fn bind_keeps_other_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lm-sensors.sock");
+    std::fs::write(&path, "data").unwrap();
+
+    Server::bind(&path).unwrap_err();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "data");
+}
+
+#[test]
+#[serial]
+fn concurrent_clients() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lm-sensors.sock");
+    let server = Server::bind(&path).unwrap();
+
+    let server_thread = thread::spawn(move || {
+        let sensors = crate::Initializer::default().initialize().unwrap();
+        let streams = core::iter::repeat_with(|| server.accept_stream()).take(2);
+        server.serve_streams(&sensors, streams).unwrap();
+        super::read_all(&sensors).len()
+    });
+
+    // The first client stays connected while the second one is served.
+    let mut first = Client::connect(&path).unwrap();
+    let mut second = Client::connect(&path).unwrap();
+    let readings = second.read_all().unwrap();
+    drop(second);
+    assert_eq!(first.read_all().unwrap(), readings);
+    drop(first);
+
+    assert_eq!(server_thread.join().unwrap(), readings.len());
+}
+
+#[test]
+#[serial]
+fn max_connections() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lm-sensors.sock");
+    let server = Server::bind(&path).unwrap().max_connections(1);
+
+    let server_thread = thread::spawn(move || {
+        let sensors = crate::Initializer::isolated().initialize().unwrap();
+        let streams = core::iter::repeat_with(|| server.accept_stream()).take(4);
+        server.serve_streams(&sensors, streams).unwrap();
+    });
+
+    let mut first = Client::connect(&path).unwrap();
+    first.ping().unwrap();
+
+    // Connections beyond the maximum are closed, including the one opened
+    // by the client to reconnect.
+    let mut refused = Client::connect(&path).unwrap();
+    refused.ping().unwrap_err();
+    drop(refused);
+    drop(first);
+
+    // The connection of the first client is eventually released.
+    thread::sleep(Duration::from_millis(100));
+    let mut client = Client::connect(&path).unwrap();
+    client.ping().unwrap();
+    drop(client);
+
+    server_thread.join().unwrap();
+}
+
+#[test]
+#[serial]
+fn reconnection() {
+    use crate::access::AccessPolicy;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lm-sensors.sock");
+    let server = Server::bind(&path)
+        .unwrap()
+        .access_policy(AccessPolicy::new().auth_token("s3cret"))
+        .timeout(Duration::from_millis(50));
+
+    let server_thread = thread::spawn(move || {
+        let sensors = crate::Initializer::isolated().initialize().unwrap();
+        let streams = core::iter::repeat_with(|| server.accept_stream()).take(2);
+        server.serve_streams(&sensors, streams).unwrap();
+    });
+
+    let mut client = Client::connect(&path).unwrap();
+    client.authenticate("s3cret").unwrap();
+    assert!(!format!("{client:?}").contains("s3cret"));
+    client.read_all().unwrap();
+
+    // The server closes idle connections, and the client authenticates
+    // again after reconnecting.
+    thread::sleep(Duration::from_millis(200));
+    client.ping().unwrap();
+    client.read_all().unwrap();
+    drop(client);
+
+    server_thread.join().unwrap();
+}
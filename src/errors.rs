@@ -1,14 +1,31 @@
 //! Errors.
 
+use core::fmt;
+#[cfg(target_os = "linux")]
 use core::sync::atomic;
-use core::sync::atomic::AtomicPtr;
-use core::{cmp, fmt, ptr};
-use std::os::raw::{c_char, c_int};
+#[cfg(target_os = "linux")]
+use core::sync::atomic::{AtomicBool, AtomicPtr};
+use core::time::Duration;
+#[cfg(target_os = "linux")]
+use core::{cmp, ptr};
+use std::io;
+#[cfg(target_os = "linux")]
+use std::os::raw::c_char;
+use std::os::raw::c_int;
+#[cfg(target_os = "linux")]
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
-use std::{io, process};
+#[cfg(target_os = "linux")]
+use std::process;
+#[cfg(target_os = "linux")]
+use std::sync::{Arc, Mutex, PoisonError};
 
+#[cfg(target_os = "linux")]
 use crate::utils::*;
 
+#[cfg(all(test, target_os = "linux"))]
+mod tests;
+
 /// Result of a fallible function.
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -40,6 +57,34 @@ pub enum Error {
     #[error("path {0} is not valid UTF-8")]
     PathIsNotUTF8(PathBuf),
 
+    #[error("error listener panicked during {operation}")]
+    ListenerPanicked { operation: &'static str },
+
+    #[error("{operation} is unsupported: {needed} is unavailable")]
+    Unsupported {
+        operation: &'static str,
+        needed: Capability,
+    },
+
+    #[error(
+        "LM sensors library is unsupported on this platform ({}), \
+         consider `backend::native()` instead",
+        std::env::consts::OS
+    )]
+    UnsupportedPlatform,
+
+    #[error("LM sensors library was called reentrantly, e.g., from an error listener")]
+    Reentrant,
+
+    #[error("schema version {found} is newer than supported version {supported}")]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+
+    #[error("previous configuration of the LM sensors library could not be restored")]
+    ConfigNotRestored { source: Box<Error> },
+
+    #[error(transparent)]
+    InvalidChipName(#[from] crate::chip_name::ChipNameError),
+
     #[error(transparent)]
     PoisonedLMSensors(#[from] std::sync::PoisonError<std::sync::MutexGuard<'static, ()>>),
 
@@ -56,11 +101,279 @@ pub enum Error {
     NotInteger(#[from] core::num::ParseIntError),
 }
 
+/// Kind of an error reported by the LM sensors library.
+///
+/// See: [`Error::lm_sensors_error`], [`describe_raw`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Wildcard found in chip name.
+    Wildcards,
+    /// No such sub-feature known.
+    NoEntry,
+    /// Sub-feature cannot be read.
+    ReadAccess,
+    /// Kernel interface error.
+    Kernel,
+    /// Division by zero while computing a value.
+    DivisionByZero,
+    /// Chip name cannot be parsed.
+    ChipName,
+    /// Bus name cannot be parsed.
+    BusName,
+    /// General configuration parse error.
+    Parse,
+    /// Sub-feature cannot be written.
+    WriteAccess,
+    /// Input or output error.
+    IO,
+    /// Evaluation of an expression recurses too deep.
+    Recursion,
+}
+
+impl ErrorKind {
+    /// Return the kind of the given LM sensors library error number,
+    /// if known. Negated error numbers, as returned by the library
+    /// functions, are accepted.
+    #[must_use]
+    pub fn from_raw(number: c_int) -> Option<Self> {
+        use crate::model::raw::sensors_error::*;
+
+        match number.checked_abs()? {
+            SENSORS_ERR_WILDCARDS => Some(Self::Wildcards),
+            SENSORS_ERR_NO_ENTRY => Some(Self::NoEntry),
+            SENSORS_ERR_ACCESS_R => Some(Self::ReadAccess),
+            SENSORS_ERR_KERNEL => Some(Self::Kernel),
+            SENSORS_ERR_DIV_ZERO => Some(Self::DivisionByZero),
+            SENSORS_ERR_CHIP_NAME => Some(Self::ChipName),
+            SENSORS_ERR_BUS_NAME => Some(Self::BusName),
+            SENSORS_ERR_PARSE => Some(Self::Parse),
+            SENSORS_ERR_ACCESS_W => Some(Self::WriteAccess),
+            SENSORS_ERR_IO => Some(Self::IO),
+            SENSORS_ERR_RECURSION => Some(Self::Recursion),
+            _ => None,
+        }
+    }
+
+    /// Return the positive LM sensors library error number of this kind,
+    /// *e.g.,* `SENSORS_ERR_KERNEL`.
+    #[must_use]
+    pub fn to_raw(self) -> c_int {
+        use crate::model::raw::sensors_error::*;
+
+        match self {
+            Self::Wildcards => SENSORS_ERR_WILDCARDS,
+            Self::NoEntry => SENSORS_ERR_NO_ENTRY,
+            Self::ReadAccess => SENSORS_ERR_ACCESS_R,
+            Self::Kernel => SENSORS_ERR_KERNEL,
+            Self::DivisionByZero => SENSORS_ERR_DIV_ZERO,
+            Self::ChipName => SENSORS_ERR_CHIP_NAME,
+            Self::BusName => SENSORS_ERR_BUS_NAME,
+            Self::Parse => SENSORS_ERR_PARSE,
+            Self::WriteAccess => SENSORS_ERR_ACCESS_W,
+            Self::IO => SENSORS_ERR_IO,
+            Self::Recursion => SENSORS_ERR_RECURSION,
+        }
+    }
+}
+
+/// Same descriptions as [`describe_raw`].
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::Wildcards => "Wildcard found in chip name",
+            Self::NoEntry => "No such subfeature known",
+            Self::ReadAccess => "Can't read",
+            Self::Kernel => "Kernel interface error",
+            Self::DivisionByZero => "Divide by zero",
+            Self::ChipName => "Can't parse chip name",
+            Self::BusName => "Can't parse bus name",
+            Self::Parse => "General parse error",
+            Self::WriteAccess => "Can't write",
+            Self::IO => "I/O error",
+            Self::Recursion => "Evaluation recurses too deep",
+        };
+        f.write_str(description)
+    }
+}
+
+/**
+Return the description of the given LM sensors library error number,
+*e.g.,* parsed from logs of other programs using the library.
+
+Negated error numbers, as returned by the library functions, are accepted.
+Unknown error numbers are described as `Unknown error`.
+
+See: `sensors_strerror()` of the LM sensors library.
+
+# Example
+
+```rust
+use lm_sensors::errors::{describe_raw, ErrorKind};
+
+assert_eq!(describe_raw(-4), "Kernel interface error");
+assert_eq!(describe_raw(ErrorKind::Parse.to_raw()), ErrorKind::Parse.to_string());
+```
+*/
+#[must_use]
+pub fn describe_raw(number: c_int) -> String {
+    #[cfg(target_os = "linux")]
+    {
+        // Safety: we assume `sensors_strerror()` can be called anytime,
+        // including before `sensors_init()` and after `sensors_cleanup()`.
+        let description = unsafe { sensors_sys::sensors_strerror(number) };
+        lossy_string_from_c_str(description, "").into_owned()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    ErrorKind::from_raw(number).map_or_else(|| "Unknown error".into(), |kind| kind.to_string())
+}
+
+/// Facility required by an operation, which might be unavailable
+/// on some systems or for some hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Capability {
+    /// Hardware monitoring directory of a chip, *e.g.,*
+    /// `/sys/class/hwmon/hwmon2`.
+    SysfsPath,
+    /// `update_interval` attribute of a chip driver.
+    UpdateInterval,
+    /// `pwmN_enable` attribute of a chip driver.
+    PwmControl,
+    /// Tools reading sensors of a baseboard management controller,
+    /// *i.e.,* `ipmi-sensors` of FreeIPMI.
+    Ipmi,
+    /// NVIDIA management library, *i.e.,* `libnvidia-ml.so.1`.
+    Nvml,
+    /// WMI namespace of a hardware monitor, *i.e.,* LibreHardwareMonitor
+    /// or OpenHardwareMonitor.
+    Wmi,
+    /// Tool reading the System Management Controller of Apple computers,
+    /// *i.e.,* `smc` of smcFanControl.
+    Smc,
+    /// Writing sub-features through a backend.
+    SubFeatureWrite,
+}
+
+impl Capability {
+    /// Return a stable identifier of this capability,
+    /// *e.g.,* `"sysfs_path"`.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::SysfsPath => "sysfs_path",
+            Self::UpdateInterval => "update_interval",
+            Self::PwmControl => "pwm_control",
+            Self::Ipmi => "ipmi",
+            Self::Nvml => "nvml",
+            Self::Wmi => "wmi",
+            Self::Smc => "smc",
+            Self::SubFeatureWrite => "sub_feature_write",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::SysfsPath => "hardware monitoring directory",
+            Self::UpdateInterval => "driver update interval",
+            Self::PwmControl => "PWM control",
+            Self::Ipmi => "IPMI sensors tool",
+            Self::Nvml => "NVIDIA management library",
+            Self::Wmi => "WMI hardware monitor",
+            Self::Smc => "SMC tool",
+            Self::SubFeatureWrite => "writing sub-features",
+        };
+        f.write_str(name)
+    }
+}
+
 impl Error {
+    /// Return a stable identifier of the kind of this error,
+    /// *e.g.,* `"io"`.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::LMSensors { .. } => "lm_sensors",
+            Self::IO { .. } => "io",
+            Self::IO1Path { .. } => "io_path",
+            Self::PathIsNotUTF8(_) => "path_is_not_utf8",
+            Self::ListenerPanicked { .. } => "listener_panicked",
+            Self::Unsupported { .. } => "unsupported",
+            Self::UnsupportedPlatform => "unsupported_platform",
+            Self::Reentrant => "reentrant",
+            Self::UnsupportedSchemaVersion { .. } => "unsupported_schema_version",
+            Self::ConfigNotRestored { .. } => "config_not_restored",
+            Self::InvalidChipName(_) => "invalid_chip_name",
+            Self::PoisonedLMSensors(_) => "poisoned_lm_sensors",
+            Self::UnexpectedNul(_) => "unexpected_nul",
+            Self::InvalidUTF8CString(_) | Self::InvalidUTF8(_) => "invalid_utf8",
+            Self::NotInteger(_) => "not_integer",
+        }
+    }
+
+    /// Return the operation that failed, if known.
+    #[must_use]
+    pub fn operation(&self) -> Option<&'static str> {
+        match self {
+            Self::LMSensors { operation, .. }
+            | Self::IO { operation, .. }
+            | Self::IO1Path { operation, .. }
+            | Self::ListenerPanicked { operation }
+            | Self::Unsupported { operation, .. } => Some(operation),
+            _ => None,
+        }
+    }
+
+    /// Return the path involved in the failure, if any.
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::IO1Path { path, .. } | Self::PathIsNotUTF8(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Return the operating system error number of the failure, if any.
+    #[must_use]
+    pub fn errno(&self) -> Option<c_int> {
+        match self {
+            Self::IO { source, .. } | Self::IO1Path { source, .. } => source.raw_os_error(),
+            _ => None,
+        }
+    }
+
+    /// Return the capability whose absence caused the failure, if any.
+    #[must_use]
+    pub fn capability(&self) -> Option<Capability> {
+        match self {
+            Self::Unsupported { needed, .. } => Some(*needed),
+            _ => None,
+        }
+    }
+
+    /// Return the LM sensors library error number of the failure, if any,
+    /// *e.g.,* [`SENSORS_ERR_KERNEL`].
+    ///
+    /// [`SENSORS_ERR_KERNEL`]: sensors_sys::SENSORS_ERR_KERNEL
+    #[must_use]
+    pub fn lm_sensors_error(&self) -> Option<c_int> {
+        match self {
+            Self::LMSensors { number, .. } => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn unsupported(operation: &'static str, needed: Capability) -> Self {
+        Self::Unsupported { operation, needed }
+    }
+
     pub(crate) fn from_io(operation: &'static str, source: io::Error) -> Self {
         Error::IO { operation, source }
     }
 
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
     pub(crate) fn from_io_path(
         operation: &'static str,
         path: impl Into<PathBuf>,
@@ -73,21 +386,70 @@ impl Error {
         }
     }
 
+    #[cfg(target_os = "linux")]
     pub(crate) fn from_lm_sensors(operation: &'static str, number: c_int) -> Self {
-        // Safety: we assume `sensors_strerror()` can be called anytime,
-        // including before `sensors_init()` and after `sensors_cleanup()`.
-        let description = unsafe { sensors_sys::sensors_strerror(number) };
-        let description = lossy_string_from_c_str(description, "").into_owned();
-
         Error::LMSensors {
             operation,
             number: c_int::abs(number),
-            description,
+            description: describe_raw(number),
         }
     }
 }
 
+/// Serialized as a structure with the fields `code`, `message`, `operation`,
+/// `path`, `errno`, `capability` and `lm_sensors_error`, absent values
+/// being `None`.
+///
+/// See: [`Error::code`], [`Error::operation`], [`Error::path`],
+/// [`Error::errno`], [`Error::capability`], [`Error::lm_sensors_error`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for Error {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("Error", 7)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.serialize_field("operation", &self.operation())?;
+        s.serialize_field("path", &self.path())?;
+        s.serialize_field("errno", &self.errno())?;
+        s.serialize_field("capability", &self.capability().map(Capability::code))?;
+        s.serialize_field("lm_sensors_error", &self.lm_sensors_error())?;
+        s.end()
+    }
+}
+
 /// Listener for fatal errors reported by LM sensors.
+///
+/// # Callback context
+///
+/// Listener methods are called by the LM sensors library while it is busy
+/// serving another call, *e.g.,* [`Initializer::initialize`].
+/// Calling any function of this crate that uses the LM sensors library from
+/// within a listener method fails with [`Error::Reentrant`].
+///
+/// # Severity levels
+///
+/// - Warnings are reported by this crate, *e.g.,* when a sampler misses
+///   sampling periods, through [`Listener::on_warning`], which ignores them
+///   by default.
+/// - Configuration errors are reported by the LM sensors library during
+///   initialization, through [`Listener::on_config_error`].
+/// - Fatal errors are reported by the LM sensors library before aborting
+///   this process, through [`Listener::on_fatal_error`].
+///
+/// # Listener context
+///
+/// The LM sensors library calls [`Listener::on_config_error`] and
+/// [`Listener::on_fatal_error`], along with a [`ListenerContext`] identifying
+/// the configuration source and the tag of the library instance.
+/// By default, these call [`Listener::on_lm_sensors_config_error`] and
+/// [`Listener::on_lm_sensors_fatal_error`], ignoring the context.
+///
+/// [`Initializer::initialize`]: crate::Initializer::initialize
 pub trait Listener: fmt::Debug {
     /// This function is called when a configuration parsing error happens.
     fn on_lm_sensors_config_error(&self, error: &str, file_name: Option<&Path>, line_number: usize);
@@ -100,11 +462,209 @@ pub trait Listener: fmt::Debug {
     /// Due to requirements of the LM sensors library, this process is aborted
     /// after this function returns.
     fn on_lm_sensors_fatal_error(&self, error: &str, procedure: &str);
+
+    /// This function is called when a configuration parsing error happens,
+    /// with the context of the library instance reporting it.
+    fn on_config_error(
+        &self,
+        context: &ListenerContext,
+        error: &str,
+        file_name: Option<&Path>,
+        line_number: usize,
+    ) {
+        let _ = context;
+        self.on_lm_sensors_config_error(error, file_name, line_number);
+    }
+
+    /// This function is called when a fatal error happens,
+    /// with the context of the library instance reporting it.
+    ///
+    /// # Warning
+    ///
+    /// Due to requirements of the LM sensors library, this process is aborted
+    /// after this function returns.
+    fn on_fatal_error(&self, context: &ListenerContext, error: &str, procedure: &str) {
+        let _ = context;
+        self.on_lm_sensors_fatal_error(error, procedure);
+    }
+
+    /// This function is called when this crate reports a warning,
+    /// with the context of the current library instance.
+    ///
+    /// By default, warnings are ignored.
+    fn on_warning(&self, context: &ListenerContext, warning: &Warning) {
+        let _ = (context, warning);
+    }
 }
 
+/// Context of errors reported to a [`Listener`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct ListenerContext {
+    /// Source of the configuration loaded by the library instance.
+    pub config_source: ConfigSource,
+    /// Tag of the library instance, if set.
+    ///
+    /// See: [`Initializer::tag`].
+    ///
+    /// [`Initializer::tag`]: crate::Initializer::tag
+    pub tag: Option<String>,
+}
+
+/// Warning reported by this crate to a [`Listener`].
+///
+/// See: [`Listener::on_warning`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// Sampling periods of a sampler elapsed without being drained,
+    /// *e.g.,* because its event loop was busy.
+    SamplerOverrun {
+        /// Number of sampling periods that were missed.
+        missed_periods: u64,
+        /// Sampling period of the sampler.
+        interval: Duration,
+    },
+    /// An audit record could not be written to a file.
+    AuditRecordLost {
+        /// Path of the audit file.
+        path: PathBuf,
+        /// Description of the failure.
+        message: String,
+    },
+}
+
+impl Warning {
+    /// Return a stable identifier of the kind of this warning,
+    /// *e.g.,* `"sampler_overrun"`.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::SamplerOverrun { .. } => "sampler_overrun",
+            Self::AuditRecordLost { .. } => "audit_record_lost",
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SamplerOverrun {
+                missed_periods,
+                interval,
+            } => write!(
+                f,
+                "sampler missed {missed_periods} sampling periods of {interval:?}"
+            ),
+            Self::AuditRecordLost { path, message } => write!(
+                f,
+                "failed to write audit record to '{}': {message}",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Report the given warning to the error listener of the current library
+/// instance, or to `stderr` if there is none, and publish it as an event.
+///
+/// Panics of the listener are ignored.
+#[cfg(target_os = "linux")]
+pub(crate) fn report_warning(warning: &Warning) {
+    // The listener is only used while holding the API access lock.
+    let _guard = match api_access_lock().lock() {
+        Ok(guard) => Some(guard),
+        // This thread already holds the lock, *e.g.,* within a listener.
+        Err(Error::Reentrant) => None,
+        Err(_) => {
+            DefaultListener.on_warning(&DEFAULT_LISTENER_CONTEXT, warning);
+            return;
+        }
+    };
+
+    let listener = unsafe { ERROR_LISTENER.load(atomic::Ordering::Acquire).as_ref() }
+        .map_or(&DefaultListener as &dyn Listener, |v| &**v);
+
+    let _ignored = panic::catch_unwind(AssertUnwindSafe(|| {
+        listener.on_warning(Reporter::context(), warning);
+    }));
+
+    crate::events::publish(&crate::events::Event::Warning(warning.clone()));
+}
+
+/// Report the given warning to `stderr`.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn report_warning(warning: &Warning) {
+    eprintln!("[WARNING] lm-sensors: {warning}.");
+}
+
+#[cfg(target_os = "linux")]
+/// Context of errors reported outside of any library instance.
+static DEFAULT_LISTENER_CONTEXT: ListenerContext = ListenerContext {
+    config_source: ConfigSource::Default,
+    tag: None,
+};
+
+/// Source of the configuration loaded by a library instance.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ConfigSource {
+    /// Default configuration files,
+    /// *e.g.,* `/etc/sensors3.conf` and files in `/etc/sensors.d`.
+    #[default]
+    Default,
+    /// Configuration file at the given path.
+    ///
+    /// See: [`Initializer::config_path`].
+    ///
+    /// [`Initializer::config_path`]: crate::Initializer::config_path
+    Path(PathBuf),
+    /// Configuration file opened by the caller.
+    ///
+    /// See: [`Initializer::config_file`].
+    ///
+    /// [`Initializer::config_file`]: crate::Initializer::config_file
+    File,
+    /// Concatenation of configuration layers.
+    ///
+    /// Configuration errors are reported at their location in their source:
+    /// the path of a configuration file, or the name of a configuration held
+    /// in memory, and the line number in that source.
+    ///
+    /// See: `Initializer::config_layers`.
+    Layered(std::sync::Arc<crate::config::LayeredConfig>),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default configuration"),
+            Self::Path(path) => write!(f, "configuration file '{}'", path.display()),
+            Self::File => f.write_str("provided configuration file"),
+            Self::Layered(config) => {
+                f.write_str("layered configuration of ")?;
+                for (index, source) in config.sources().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{source}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+// Listener methods are called from `extern "C"` functions, where unwinding
+// is not allowed. Panics are caught there, and recorded in this flag.
+static LISTENER_PANICKED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(target_os = "linux")]
 #[derive(Debug)]
 pub(crate) struct DefaultListener;
 
+#[cfg(target_os = "linux")]
 impl Listener for DefaultListener {
     fn on_lm_sensors_config_error(
         &self,
@@ -127,48 +687,305 @@ impl Listener for DefaultListener {
     fn on_lm_sensors_fatal_error(&self, error: &str, procedure: &str) {
         eprintln!("[FATAL] lm-sensors: {error}, at procedure '{procedure}'.");
     }
+
+    fn on_warning(&self, _context: &ListenerContext, warning: &Warning) {
+        eprintln!("[WARNING] lm-sensors: {warning}.");
+    }
+}
+
+/// Configuration error reported by LM sensors during initialization.
+///
+/// See: [`Initializer::initialize_with_report`].
+///
+/// [`Initializer::initialize_with_report`]: crate::Initializer::initialize_with_report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Description of the error.
+    pub message: String,
+    /// Configuration file where the error was found, if known.
+    pub file_name: Option<PathBuf>,
+    /// Line number where the error was found, starting from 1.
+    pub line_number: usize,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file_name) = &self.file_name {
+            write!(
+                f,
+                "{}, at file '{}' line {}",
+                self.message,
+                file_name.display(),
+                self.line_number
+            )
+        } else {
+            write!(f, "{}, at line {}", self.message, self.line_number)
+        }
+    }
+}
+
+/// Failure of [`Initializer::initialize_with_report`], along with the
+/// configuration errors reported until then, which often explain it.
+///
+/// [`Initializer::initialize_with_report`]: crate::Initializer::initialize_with_report
+#[derive(Debug)]
+pub struct InitFailure {
+    /// Error that made initialization fail.
+    pub error: Error,
+    /// Configuration errors reported during initialization, in order.
+    pub config_diagnostics: Vec<Diagnostic>,
+}
+
+impl fmt::Display for InitFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for InitFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(&self.error)
+    }
+}
+
+impl From<InitFailure> for Error {
+    fn from(failure: InitFailure) -> Self {
+        failure.error
+    }
+}
+
+#[cfg(target_os = "linux")]
+/// Listener collecting configuration errors into a list of [`Diagnostic`]s,
+/// and forwarding fatal errors to another listener.
+#[derive(Debug)]
+pub(crate) struct DiagnosticCollector {
+    diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
+    inner: Option<Box<dyn Listener>>,
+}
+
+#[cfg(target_os = "linux")]
+impl DiagnosticCollector {
+    pub(crate) fn new(
+        diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
+        inner: Option<Box<dyn Listener>>,
+    ) -> Self {
+        Self { diagnostics, inner }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Listener for DiagnosticCollector {
+    fn on_lm_sensors_config_error(
+        &self,
+        error: &str,
+        file_name: Option<&Path>,
+        line_number: usize,
+    ) {
+        self.on_config_error(&DEFAULT_LISTENER_CONTEXT, error, file_name, line_number);
+    }
+
+    fn on_lm_sensors_fatal_error(&self, error: &str, procedure: &str) {
+        self.on_fatal_error(&DEFAULT_LISTENER_CONTEXT, error, procedure);
+    }
+
+    fn on_config_error(
+        &self,
+        context: &ListenerContext,
+        error: &str,
+        file_name: Option<&Path>,
+        line_number: usize,
+    ) {
+        let diagnostic = Diagnostic {
+            message: error.into(),
+            file_name: file_name.map(Path::to_path_buf),
+            line_number,
+        };
+
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(diagnostic);
+
+        if let Some(inner) = &self.inner {
+            inner.on_config_error(context, error, file_name, line_number);
+        }
+    }
+
+    fn on_fatal_error(&self, context: &ListenerContext, error: &str, procedure: &str) {
+        self.inner
+            .as_deref()
+            .unwrap_or(&DefaultListener)
+            .on_fatal_error(context, error, procedure);
+    }
+
+    fn on_warning(&self, context: &ListenerContext, warning: &Warning) {
+        self.inner
+            .as_deref()
+            .unwrap_or(&DefaultListener)
+            .on_warning(context, warning);
+    }
 }
 
+#[cfg(target_os = "linux")]
+/// Listener notified by the call backs of [`Reporter`], or null to notify
+/// [`DefaultListener`].
 static ERROR_LISTENER: AtomicPtr<Box<dyn Listener>> = AtomicPtr::new(ptr::null_mut());
 
+#[cfg(target_os = "linux")]
+/// Context passed by the call backs of [`Reporter`] to the listener,
+/// or null to pass [`DEFAULT_LISTENER_CONTEXT`].
+static LISTENER_CONTEXT: AtomicPtr<ListenerContext> = AtomicPtr::new(ptr::null_mut());
+
+#[cfg(target_os = "linux")]
+/// Error call backs installed in the LM sensors library, and the listener
+/// they notify.
+///
+/// # Swap protocol
+///
+/// The call backs are plain global function pointers of the LM sensors
+/// library, [`ERROR_LISTENER`] holds the listener they notify, and
+/// [`LISTENER_CONTEXT`] the context they pass to it.
+/// These are only written while holding the API access lock, which the
+/// [`ApiAccessGuard`] parameters prove:
+///
+/// 1. [`Reporter::new`] installs the call backs of this crate, a listener
+///    and its context, before `sensors_init()`.
+/// 2. [`Reporter::swap_listener`] replaces the listener, while the library
+///    is initialized.
+/// 3. [`Reporter::restore`] reinstalls the previous call backs, listener
+///    and context, after `sensors_cleanup()`, or after `sensors_init()` failed.
+///
+/// The LM sensors library only calls the call backs from within calls
+/// made by this crate, *i.e.,* on the thread holding the same lock.
+/// Unlocking the lock happens-before locking it again, so call backs always
+/// observe the last installed listener, and a replaced listener is never
+/// used after it is returned to its owner.
 #[derive(Debug)]
 pub(crate) struct Reporter {
     previous_error_listener: *mut Box<dyn Listener>,
+    previous_context: *mut ListenerContext,
     previous_call_backs: CallBacks,
 }
 
+#[cfg(target_os = "linux")]
 impl Reporter {
-    pub(crate) fn new(error_listener: *mut Box<dyn Listener>) -> Self {
+    pub(crate) fn new(
+        error_listener: *mut Box<dyn Listener>,
+        context: ListenerContext,
+        guard: &ApiAccessGuard,
+    ) -> Self {
         let call_backs =
             CallBacks::new(Self::parse_error, Self::parse_error_wfn, Self::fatal_error);
 
+        let context = Box::into_raw(Box::new(context));
+
         let previous_error_listener = ERROR_LISTENER.swap(error_listener, atomic::Ordering::AcqRel);
-        let previous_call_backs = unsafe { call_backs.replace() };
+        let previous_context = LISTENER_CONTEXT.swap(context, atomic::Ordering::AcqRel);
+        let previous_call_backs = unsafe { call_backs.replace(guard) };
 
         Self {
             previous_error_listener,
+            previous_context,
             previous_call_backs,
         }
     }
 
-    pub(crate) fn restore(&self) -> *mut Box<dyn Listener> {
-        unsafe { self.previous_call_backs.set() };
+    /// Replace the current listener by the given one, or by
+    /// [`DefaultListener`] if null, and return the replaced listener.
+    pub(crate) fn swap_listener(
+        &self,
+        error_listener: *mut Box<dyn Listener>,
+        _guard: &ApiAccessGuard,
+    ) -> *mut Box<dyn Listener> {
+        ERROR_LISTENER.swap(error_listener, atomic::Ordering::AcqRel)
+    }
+
+    /// Reinstall the previous call backs, listener and context,
+    /// and return the replaced listener.
+    ///
+    /// This must be called at most once.
+    pub(crate) fn restore(&self, guard: &ApiAccessGuard) -> *mut Box<dyn Listener> {
+        unsafe { self.previous_call_backs.set(guard) };
+
+        let context = LISTENER_CONTEXT.swap(self.previous_context, atomic::Ordering::AcqRel);
+        if !context.is_null() {
+            // Safety: context was allocated by `Reporter::new`, and the call backs
+            // cannot use it anymore.
+            drop(unsafe { Box::from_raw(context) });
+        }
+
         ERROR_LISTENER.swap(self.previous_error_listener, atomic::Ordering::AcqRel)
     }
 
+    /// Return a copy of the current context.
+    pub(crate) fn current_context(&self, _guard: &ApiAccessGuard) -> ListenerContext {
+        Self::context().clone()
+    }
+
+    /// Replace the current context by the given one.
+    pub(crate) fn replace_context(&self, context: ListenerContext, _guard: &ApiAccessGuard) {
+        let context = Box::into_raw(Box::new(context));
+        let previous = LISTENER_CONTEXT.swap(context, atomic::Ordering::AcqRel);
+        if !previous.is_null() {
+            // Safety: the previous context was allocated by `Reporter::new` or
+            // by this function, and the call backs cannot use it anymore.
+            drop(unsafe { Box::from_raw(previous) });
+        }
+    }
+
+    fn context<'t>() -> &'t ListenerContext {
+        // Safety: the context is only replaced while the API access lock is held,
+        // and the call backs are called while it is held.
+        unsafe { LISTENER_CONTEXT.load(atomic::Ordering::Acquire).as_ref() }
+            .unwrap_or(&DEFAULT_LISTENER_CONTEXT)
+    }
+
+    /// Return `true` if an error listener panicked since the last call.
+    pub(crate) fn take_listener_panic() -> bool {
+        LISTENER_PANICKED.swap(false, atomic::Ordering::AcqRel)
+    }
+
     extern "C" fn parse_error(err: *const c_char, line_number: c_int) {
         Self::parse_error_wfn(err, ptr::null(), line_number);
     }
 
     extern "C" fn parse_error_wfn(err: *const c_char, file_name: *const c_char, line_no: c_int) {
         let error = lossy_string_from_c_str(err, "<unknown-error>");
-        let file_name = path_from_c_str(file_name);
+        let file_name = path_from_c_str(file_name).map(Path::to_path_buf);
         let line_number = cmp::max(line_no, 1) as usize;
 
+        // Locate errors of layered configurations in their sources.
+        let (file_name, line_number) = match &Self::context().config_source {
+            ConfigSource::Layered(config) if file_name.is_none() => config
+                .origin(line_number)
+                .map_or((None, line_number), |origin| {
+                    let source = origin.source;
+                    let name = source
+                        .path
+                        .clone()
+                        .unwrap_or_else(|| source.name.clone().into());
+                    (Some(name), origin.line_number)
+                }),
+            _ => (file_name, line_number),
+        };
+        let file_name = file_name.as_deref();
+
+        crate::events::record_config_error(Diagnostic {
+            message: error.clone().into_owned(),
+            file_name: file_name.map(Path::to_path_buf),
+            line_number,
+        });
+
         let listener = unsafe { ERROR_LISTENER.load(atomic::Ordering::Acquire).as_ref() }
             .map_or(&crate::errors::DefaultListener as &dyn Listener, |v| &**v);
 
-        listener.on_lm_sensors_config_error(&error, file_name, line_number);
+        let r = panic::catch_unwind(AssertUnwindSafe(|| {
+            listener.on_config_error(Self::context(), &error, file_name, line_number);
+        }));
+
+        if r.is_err() {
+            LISTENER_PANICKED.store(true, atomic::Ordering::Release);
+        }
     }
 
     extern "C" fn fatal_error(procedure: *const c_char, err: *const c_char) {
@@ -178,7 +995,13 @@ impl Reporter {
         let listener = unsafe { ERROR_LISTENER.load(atomic::Ordering::Acquire).as_ref() }
             .map_or(&crate::errors::DefaultListener as &dyn Listener, |v| &**v);
 
-        listener.on_lm_sensors_fatal_error(&error, procedure);
+        let r = panic::catch_unwind(AssertUnwindSafe(|| {
+            listener.on_fatal_error(Self::context(), &error, procedure);
+        }));
+
+        if r.is_err() {
+            eprintln!("[FATAL] lm-sensors: error listener panicked, aborting.");
+        }
         process::abort();
     }
 }
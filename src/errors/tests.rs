@@ -0,0 +1,116 @@
+#![cfg(test)]
+
+use std::io;
+use std::os::raw::c_int;
+use std::path::Path;
+
+use assert_matches::assert_matches;
+
+use super::{Capability, Error};
+
+#[test]
+fn accessors() {
+    let err = io::Error::from_raw_os_error(libc::ENOENT);
+    let err = Error::from_io_path("fs::read()", "/nonexistent", err);
+    assert_eq!(err.code(), "io_path");
+    assert_eq!(err.operation(), Some("fs::read()"));
+    assert_eq!(err.path(), Some(Path::new("/nonexistent")));
+    assert_eq!(err.errno(), Some(libc::ENOENT));
+    assert_eq!(err.lm_sensors_error(), None);
+
+    let err = Error::from_lm_sensors("sensors_get_value()", sensors_sys::SENSORS_ERR_KERNEL);
+    assert_eq!(err.code(), "lm_sensors");
+    assert_eq!(err.operation(), Some("sensors_get_value()"));
+    assert_eq!(err.path(), None);
+    assert_eq!(err.errno(), None);
+    assert_eq!(
+        err.lm_sensors_error(),
+        Some(sensors_sys::SENSORS_ERR_KERNEL)
+    );
+
+    let err = Error::unsupported("ChipRef::path()", Capability::SysfsPath);
+    assert_eq!(err.code(), "unsupported");
+    assert_eq!(err.operation(), Some("ChipRef::path()"));
+    assert_eq!(err.capability(), Some(Capability::SysfsPath));
+    assert_eq!(err.errno(), None);
+
+    assert_eq!(Error::Reentrant.code(), "reentrant");
+    assert_eq!(Error::Reentrant.operation(), None);
+    assert_eq!(Error::Reentrant.capability(), None);
+}
+
+#[test]
+fn warning() {
+    use core::time::Duration;
+
+    use super::Warning;
+
+    let warning = Warning::SamplerOverrun {
+        missed_periods: 3,
+        interval: Duration::from_secs(2),
+    };
+    assert_eq!(warning.code(), "sampler_overrun");
+    assert_eq!(
+        warning.to_string(),
+        "sampler missed 3 sampling periods of 2s"
+    );
+
+    let warning = Warning::AuditRecordLost {
+        path: "/var/log/audit.log".into(),
+        message: "disk full".into(),
+    };
+    assert_eq!(warning.code(), "audit_record_lost");
+    assert_eq!(
+        warning.to_string(),
+        "failed to write audit record to '/var/log/audit.log': disk full"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize() {
+    let err = io::Error::from_raw_os_error(libc::EACCES);
+    let err = Error::from_io_path("fs::write()", "/sys/pwm1", err);
+    let json = serde_json::to_value(&err).unwrap();
+
+    assert_eq!(json["code"], "io_path");
+    assert_eq!(json["message"], err.to_string());
+    assert_eq!(json["operation"], "fs::write()");
+    assert_eq!(json["path"], "/sys/pwm1");
+    assert_eq!(json["errno"], libc::EACCES);
+    assert!(json["capability"].is_null());
+    assert!(json["lm_sensors_error"].is_null());
+
+    let err = Error::unsupported("RestoreGuard::pwm_automatic()", Capability::PwmControl);
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["code"], "unsupported");
+    assert_eq!(json["capability"], "pwm_control");
+
+    let json = serde_json::to_value(Error::Reentrant).unwrap();
+    assert_eq!(json["code"], "reentrant");
+    assert!(json["operation"].is_null());
+    assert!(json["path"].is_null());
+    assert!(json["errno"].is_null());
+}
+
+#[test]
+fn describe_raw() {
+    use super::{describe_raw, ErrorKind};
+
+    for number in 1..=11 {
+        let kind = ErrorKind::from_raw(number).unwrap();
+        assert_eq!(kind.to_raw(), number);
+        assert_eq!(ErrorKind::from_raw(-number), Some(kind));
+        assert_eq!(describe_raw(number), kind.to_string());
+        assert_eq!(describe_raw(-number), kind.to_string());
+    }
+
+    assert_eq!(ErrorKind::from_raw(0), None);
+    assert_eq!(ErrorKind::from_raw(12), None);
+    assert_eq!(ErrorKind::from_raw(c_int::MIN), None);
+    assert_eq!(describe_raw(12), "Unknown error");
+
+    let err = Error::from_lm_sensors("sensors_init()", -sensors_sys::SENSORS_ERR_PARSE);
+    assert_matches!(err, Error::LMSensors { description, .. }
+        if description == ErrorKind::Parse.to_string());
+}
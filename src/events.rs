@@ -0,0 +1,177 @@
+//! Notifications of all subsystems of this crate, as a single stream.
+//!
+//! [`LMSensors::events`] returns an [`Events`] subscription receiving every
+//! [`Event`] published while the library instance exists: configuration
+//! errors reported during initialization, warnings also reported to the
+//! error listener, writes also recorded by the audit sink, sub-features
+//! quarantined by samplers, limits crossed by values tracked for their
+//! status, chips found by rescans, and resumptions from suspension detected
+//! by samplers.
+//!
+//! [`LMSensors::events`]: crate::LMSensors::events
+
+#[cfg(test)]
+mod tests;
+
+use core::time::Duration;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, PoisonError};
+
+use crate::audit::AuditRecord;
+use crate::errors::{Diagnostic, Warning};
+use crate::status::Status;
+use crate::LMSensors;
+
+/// Notification published by a subsystem of this crate.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Event {
+    /// Configuration error reported by the LM sensors library
+    /// during initialization.
+    ConfigError(Diagnostic),
+    /// Warning reported by this crate, *e.g.,* a sampler overrun.
+    ///
+    /// See: [`Listener::on_warning`].
+    ///
+    /// [`Listener::on_warning`]: crate::errors::Listener::on_warning
+    Warning(Warning),
+    /// Write performed through the [`Capabilities`] of the instance,
+    /// whether it succeeded or not.
+    ///
+    /// [`Capabilities`]: crate::Capabilities
+    WriteAudited(AuditRecord),
+    /// A sub-feature failed to be read too many times in a row, and is
+    /// no longer read by a sampler.
+    ///
+    /// See: `sampler::Sampler::set_quarantine_threshold`.
+    SensorQuarantined {
+        /// Name of the chip, *e.g.,* `nct6775-isa-0290`.
+        chip: String,
+        /// Name of the sub-feature, *e.g.,* `temp1_input`.
+        sub_feature: String,
+        /// Number of consecutive failed readings.
+        failures: u32,
+    },
+    /// The status of a value tracked by a [`StatusTracker`] changed,
+    /// *e.g.,* a limit was crossed or cleared.
+    ///
+    /// [`StatusTracker`]: crate::status::StatusTracker
+    ThresholdCrossed {
+        /// Status of the previous value.
+        previous: Status,
+        /// Status of the new value.
+        status: Status,
+        /// New value.
+        value: f64,
+    },
+    /// A chip was found by a rescan of sub-features.
+    ///
+    /// See: `remap::Enumeration::rescan`.
+    DeviceAdded {
+        /// Name of the chip, *e.g.,* `nct6775-isa-0290`.
+        chip: String,
+    },
+    /// The system resumed from a suspension, detected by a sampler.
+    ///
+    /// See: `sampler::Sample::resumed`.
+    Resumed {
+        /// Time during which the system was suspended, approximately.
+        gap: Duration,
+    },
+}
+
+/**
+Subscription to the events of an [`LMSensors`] instance.
+
+Iterating over a subscription blocks until the next event, and ends when
+the library instance is dropped.
+
+# Example
+
+```rust
+let sensors = lm_sensors::Initializer::default().initialize()?;
+let events = sensors.events();
+while let Some(event) = events.try_next() {
+    println!("{event:?}");
+}
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Debug)]
+pub struct Events {
+    receiver: Receiver<Event>,
+}
+
+impl Events {
+    /// Return the next event, if one is pending. This does not block.
+    #[must_use]
+    pub fn try_next(&self) -> Option<Event> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Return the next event, waiting for at most the given duration.
+    #[must_use]
+    pub fn next_timeout(&self, timeout: Duration) -> Option<Event> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+}
+
+impl Iterator for Events {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// Senders of all subscriptions to the events of the library instance.
+static SUBSCRIBERS: Mutex<Vec<Sender<Event>>> = Mutex::new(Vec::new());
+
+/// Configuration errors reported during the current initialization.
+static CONFIG_ERRORS: Mutex<Vec<Diagnostic>> = Mutex::new(Vec::new());
+
+impl LMSensors {
+    /// Return a new subscription to the events of this instance.
+    ///
+    /// The subscription first receives the configuration errors reported
+    /// during initialization, then all events published afterwards.
+    #[must_use]
+    pub fn events(&self) -> Events {
+        let (sender, receiver) = mpsc::channel();
+        for diagnostic in &self.config_errors {
+            let _ignored = sender.send(Event::ConfigError(diagnostic.clone()));
+        }
+
+        lock(&SUBSCRIBERS).push(sender);
+        Events { receiver }
+    }
+}
+
+/// Return `true` if any subscription might receive events.
+pub(crate) fn has_subscribers() -> bool {
+    !lock(&SUBSCRIBERS).is_empty()
+}
+
+/// Send the given event to all subscriptions, forgetting dropped ones.
+pub(crate) fn publish(event: &Event) {
+    lock(&SUBSCRIBERS).retain(|sender| sender.send(event.clone()).is_ok());
+}
+
+/// End all subscriptions, when the library instance is dropped.
+pub(crate) fn unsubscribe_all() {
+    lock(&SUBSCRIBERS).clear();
+}
+
+/// Record a configuration error reported during initialization.
+pub(crate) fn record_config_error(diagnostic: Diagnostic) {
+    lock(&CONFIG_ERRORS).push(diagnostic);
+}
+
+/// Return the configuration errors reported since the last call.
+pub(crate) fn take_config_errors() -> Vec<Diagnostic> {
+    core::mem::take(&mut *lock(&CONFIG_ERRORS))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
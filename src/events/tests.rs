@@ -0,0 +1,150 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::io::Write;
+
+use serial_test::serial;
+
+use super::Event;
+use crate::errors::Warning;
+use crate::status::{Status, StatusTracker, Thresholds};
+use crate::value::Value;
+
+#[test]
+#[serial]
+fn config_errors() {
+    let mut config = tempfile::NamedTempFile::new().unwrap();
+    writeln!(config, "chip \"*-isa-*\"").unwrap();
+    writeln!(config, "this is not a valid configuration statement").unwrap();
+
+    let report = crate::Initializer::default()
+        .config_path(config.path())
+        .initialize_with_report()
+        .unwrap();
+
+    // Every subscription receives the configuration errors.
+    for _ in 0..2 {
+        let events = report.sensors.events();
+        let config_errors: Vec<_> = core::iter::from_fn(|| events.try_next())
+            .map(|event| match event {
+                Event::ConfigError(diagnostic) => diagnostic,
+                event => panic!("unexpected event: {event:?}"),
+            })
+            .collect();
+        assert_eq!(config_errors, report.config_diagnostics);
+    }
+}
+
+#[test]
+#[serial]
+fn overlay_config_errors() {
+    let mut sensors = crate::Initializer::isolated().initialize().unwrap();
+    let events = sensors.events();
+    assert!(events.try_next().is_none());
+
+    let overlay = "chip \"*-isa-*\"\nthis is not a valid configuration statement\n";
+    let _ignored = sensors.with_config_overlay(overlay, |_| ());
+
+    let config_errors: Vec<_> = core::iter::from_fn(|| events.try_next())
+        .map(|event| match event {
+            Event::ConfigError(diagnostic) => diagnostic,
+            event => panic!("unexpected event: {event:?}"),
+        })
+        .collect();
+    assert!(!config_errors.is_empty());
+    for diagnostic in &config_errors {
+        assert_eq!(diagnostic.file_name.as_deref(), Some("overlay".as_ref()));
+        assert_eq!(diagnostic.line_number, 2);
+    }
+
+    // The previous configuration has no errors.
+    assert!(sensors.events().try_next().is_none());
+}
+
+#[test]
+#[serial]
+fn warnings() {
+    let warning = Warning::SamplerOverrun {
+        missed_periods: 2,
+        interval: Duration::from_millis(10),
+    };
+
+    let sensors = crate::Initializer::isolated().initialize().unwrap();
+    let events = sensors.events();
+    assert!(events.try_next().is_none());
+
+    crate::errors::report_warning(&warning);
+    assert_eq!(
+        events.next_timeout(Duration::from_secs(1)),
+        Some(Event::Warning(warning.clone()))
+    );
+
+    // Dropped subscriptions are forgotten.
+    drop(sensors.events());
+    crate::errors::report_warning(&warning);
+    assert_eq!(events.try_next(), Some(Event::Warning(warning)));
+    assert_eq!(super::lock(&super::SUBSCRIBERS).len(), 1);
+
+    // Subscriptions end when the library instance is dropped.
+    drop(sensors);
+    assert_eq!(events.count(), 0);
+    assert!(!super::has_subscribers());
+}
+
+#[test]
+#[serial]
+fn threshold_crossed() {
+    let sensors = crate::Initializer::isolated().initialize().unwrap();
+    let events = sensors.events();
+
+    let limits = [Value::TemperatureMaximum(80.0)];
+    let mut tracker = StatusTracker::new(Thresholds::from_values(&limits, 5.0));
+    tracker.update(50.0);
+    tracker.update(81.0);
+    tracker.update(79.0);
+    tracker.update(70.0);
+
+    let crossed: Vec<_> = core::iter::from_fn(|| events.try_next()).collect();
+    assert_eq!(
+        crossed,
+        [
+            Event::ThresholdCrossed {
+                previous: Status::Normal,
+                status: Status::High,
+                value: 81.0,
+            },
+            Event::ThresholdCrossed {
+                previous: Status::High,
+                status: Status::Normal,
+                value: 70.0,
+            },
+        ]
+    );
+}
+
+#[test]
+#[serial]
+fn device_added() {
+    use crate::remap::Enumeration;
+
+    let sensors = crate::Initializer::default().initialize().unwrap();
+    let events = sensors.events();
+    let _config_errors: Vec<_> = core::iter::from_fn(|| events.try_next()).collect();
+
+    let mut enumeration = Enumeration::default();
+    enumeration.rescan(&sensors);
+    let mut chips: Vec<_> = enumeration.iter().map(|(id, _)| id.chip.clone()).collect();
+    chips.dedup();
+
+    let added: Vec<_> = core::iter::from_fn(|| events.try_next())
+        .map(|event| match event {
+            Event::DeviceAdded { chip } => chip,
+            event => panic!("unexpected event: {event:?}"),
+        })
+        .collect();
+    assert_eq!(added, chips);
+
+    // Known chips are not published again.
+    enumeration.rescan(&sensors);
+    assert!(events.try_next().is_none());
+}
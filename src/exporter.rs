@@ -0,0 +1,21 @@
+//! Exporters of readings to monitoring systems.
+//!
+//! - [`collectd`]: output of the collectd `exec` plugin protocol.
+//! - [`compression`]: suppression of samples that did not change
+//!   meaningfully.
+//! - [`labels`]: sanitization of labels, and guarding of the cardinality
+//!   of series.
+//! - [`metadata`]: names, units and help strings of metrics, shared by
+//!   exporters.
+//! - [`nagios`]: output of Nagios and Icinga checks.
+//! - `otel`: OpenTelemetry gauges, with the `otel` feature.
+//! - [`telegraf`]: JSON input of the telegraf `exec` plugin.
+
+pub mod collectd;
+pub mod compression;
+pub mod labels;
+pub mod metadata;
+pub mod nagios;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod telegraf;
@@ -0,0 +1,138 @@
+//! Output of the collectd `exec` plugin protocol.
+//!
+//! [`format`] renders the values of input sub-features, *e.g.,*
+//! `temp1_input`, as `PUTVAL` commands, one per line, to be printed by
+//! programs run by the `exec` plugin of collectd:
+//!
+//! ```text
+//! PUTVAL "host/lm_sensors/temperature-coretemp_isa_0000_temp1" interval=10.000 1700000000.000:47.5
+//! ```
+//!
+//! Values are identified by the host name, the plugin `lm_sensors`,
+//! the type of the value in `types.db`, *e.g.,* `temperature`, and the
+//! [`StableName`] of the feature as type instance, or the name given by
+//! a [`NameTemplate`], see: [`format_with`].
+//!
+//! [`StableName`]: crate::naming::StableName
+//!
+//! The `exec` plugin provides the host name and the interval through
+//! the environment variables [`HOSTNAME_VAR`] and [`INTERVAL_VAR`].
+
+#[cfg(test)]
+mod tests;
+
+use core::time::Duration;
+use std::env;
+use std::fmt::Write;
+use std::time::UNIX_EPOCH;
+
+use crate::naming::NameTemplate;
+use crate::snapshot::Snapshot;
+use crate::value;
+
+/// Name of the plugin in value identifiers.
+pub const PLUGIN: &str = "lm_sensors";
+
+/// Environment variable holding the host name, set by the `exec` plugin.
+pub const HOSTNAME_VAR: &str = "COLLECTD_HOSTNAME";
+
+/// Environment variable holding the interval in seconds, set by the `exec`
+/// plugin.
+pub const INTERVAL_VAR: &str = "COLLECTD_INTERVAL";
+
+/// Return the type in `types.db` of values of the given sub-feature kind,
+/// *e.g.,* `temperature`, if it is an input sub-feature.
+#[must_use]
+pub fn type_of(kind: value::Kind) -> Option<&'static str> {
+    match kind {
+        value::Kind::TemperatureInput => Some("temperature"),
+        value::Kind::FanInput => Some("fanspeed"),
+        value::Kind::VoltageInput => Some("voltage"),
+        value::Kind::PowerInput => Some("power"),
+        value::Kind::EnergyInput => Some("energy"),
+        value::Kind::CurrentInput => Some("current"),
+        value::Kind::HumidityInput => Some("humidity"),
+        _ => None,
+    }
+}
+
+/// Return the host name and the interval provided by the `exec` plugin
+/// through the environment, if any.
+#[must_use]
+pub fn from_env() -> (Option<String>, Option<Duration>) {
+    let host = env::var(HOSTNAME_VAR).ok().filter(|host| !host.is_empty());
+    let interval = env::var(INTERVAL_VAR)
+        .ok()
+        .and_then(|interval| interval.trim().parse::<f64>().ok())
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+    (host, interval)
+}
+
+/**
+Return `PUTVAL` commands holding the values of the input sub-features of
+the given snapshot, one per line, identified by the given host name.
+
+The interval is only stated if given. Values are time-stamped with the
+time of the snapshot.
+
+# Example
+
+```rust
+use lm_sensors::backend::Reading;
+use lm_sensors::exporter::collectd;
+use lm_sensors::snapshot::Snapshot;
+
+let snapshot = Snapshot::new(vec![Reading {
+    chip: "coretemp-isa-0000".into(),
+    feature: "temp1".into(),
+    sub_feature: "temp1_input".into(),
+    raw_kind: lm_sensors::value::Kind::TemperatureInput.as_raw(),
+    raw_value: Some(47.5),
+}]);
+
+let (host, interval) = collectd::from_env();
+let output = collectd::format(&snapshot, host.as_deref().unwrap_or("localhost"), interval);
+assert!(output.starts_with("PUTVAL \"localhost/lm_sensors/temperature-coretemp_isa_0000_temp1\""));
+```
+*/
+#[must_use]
+pub fn format(snapshot: &Snapshot, host: &str, interval: Option<Duration>) -> String {
+    format_with(snapshot, host, interval, &NameTemplate::default())
+}
+
+/// Return `PUTVAL` commands like [`format`], with type instances named by
+/// the given template.
+///
+/// Characters that are not allowed in type instances, *i.e.,* `/` and `"`,
+/// are replaced by underscores.
+#[must_use]
+pub fn format_with(
+    snapshot: &Snapshot,
+    host: &str,
+    interval: Option<Duration>,
+    template: &NameTemplate,
+) -> String {
+    let time = snapshot
+        .time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    let mut output = String::default();
+    for reading in &snapshot.readings {
+        let Some(value) = reading.value() else {
+            continue;
+        };
+        let Some(kind) = type_of(value.kind()) else {
+            continue;
+        };
+
+        let name = template.render_reading(reading).replace(['/', '"'], "_");
+        let _ignored = write!(output, "PUTVAL \"{host}/{PLUGIN}/{kind}-{name}\"");
+        if let Some(interval) = interval {
+            let _ignored = write!(output, " interval={:.3}", interval.as_secs_f64());
+        }
+        let _ignored = writeln!(output, " {time:.3}:{}", value.raw_value());
+    }
+    output
+}
@@ -0,0 +1,90 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{format, format_with, type_of};
+use crate::backend::Reading;
+use crate::naming::NameTemplate;
+use crate::snapshot::Snapshot;
+use crate::value;
+
+#[test]
+fn types() {
+    assert_eq!(type_of(value::Kind::TemperatureInput), Some("temperature"));
+    assert_eq!(type_of(value::Kind::FanInput), Some("fanspeed"));
+    assert_eq!(type_of(value::Kind::TemperatureMaximum), None);
+}
+
+#[test]
+fn putval() {
+    let snapshot = Snapshot {
+        time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_250),
+        readings: vec![
+            Reading::for_test(
+                "coretemp-isa-0000",
+                "temp1_input",
+                value::Kind::TemperatureInput,
+                Some(47.5),
+            ),
+            Reading::for_test(
+                "coretemp-isa-0000",
+                "temp1_max",
+                value::Kind::TemperatureMaximum,
+                Some(80.0),
+            ),
+            Reading::for_test(
+                "lm75-i2c-1-48",
+                "temp1_input",
+                value::Kind::TemperatureInput,
+                None,
+            ),
+            Reading::for_test(
+                "nct6775-isa-0290",
+                "fan2_input",
+                value::Kind::FanInput,
+                Some(1200.0),
+            ),
+        ],
+    };
+
+    assert_eq!(
+        format(&snapshot, "node1", Some(Duration::from_secs(10))),
+        "PUTVAL \"node1/lm_sensors/temperature-coretemp_isa_0000_temp1\" \
+         interval=10.000 1700000000.250:47.5\n\
+         PUTVAL \"node1/lm_sensors/fanspeed-nct6775_isa_0290_fan2\" \
+         interval=10.000 1700000000.250:1200\n"
+    );
+
+    let snapshot = Snapshot {
+        time: SystemTime::UNIX_EPOCH,
+        readings: snapshot.readings[..1].to_vec(),
+    };
+    assert_eq!(
+        format(&snapshot, "node1", None),
+        "PUTVAL \"node1/lm_sensors/temperature-coretemp_isa_0000_temp1\" 0.000:47.5\n"
+    );
+}
+
+#[test]
+fn putval_with_template() {
+    let snapshot = Snapshot {
+        time: SystemTime::UNIX_EPOCH,
+        readings: vec![Reading::for_test(
+            "lm75-i2c-1-48",
+            "temp1_input",
+            value::Kind::TemperatureInput,
+            Some(30.0),
+        )],
+    };
+
+    let template = NameTemplate::new("{prefix}-{addr:04x}/{feature}").unwrap();
+    assert_eq!(
+        format_with(&snapshot, "node1", None, &template),
+        "PUTVAL \"node1/lm_sensors/temperature-lm75-0048_temp1\" 0.000:30\n"
+    );
+    assert_eq!(
+        format_with(&snapshot, "node1", None, &NameTemplate::default()),
+        format(&snapshot, "node1", None)
+    );
+}
@@ -0,0 +1,152 @@
+//! Suppression of samples that did not change meaningfully.
+//!
+//! Sensors are often stable, *e.g.,* a fan spinning at a constant speed,
+//! so that most samples of a time series repeat the previous one, within
+//! the noise of readings. A [`ChangeFilter`] removes from snapshots the
+//! readings approximately equal to the previously emitted ones, see:
+//! [`Value::approx_eq`], unless they were emitted longer than a maximum
+//! interval ago, so that series do not look stale to monitoring systems.
+//!
+//! Filtered snapshots are given to exporters, *e.g.,*
+//! [`telegraf::format`](super::telegraf::format).
+
+#[cfg(test)]
+mod tests;
+
+use core::time::Duration;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use crate::backend::Reading;
+use crate::snapshot::Snapshot;
+use crate::value::{Value, ValueEpsilon};
+
+/**
+Filter of readings that did not change meaningfully since they were
+last emitted.
+
+A reading is emitted if no reading of the same sub-feature was emitted
+before, or if its value differs from the value last emitted by more than
+the tolerance of its type, or if the value was last emitted at least
+the maximum interval before. Readings without values are always emitted.
+
+# Example
+
+```rust
+use core::time::Duration;
+use lm_sensors::backend::Reading;
+use lm_sensors::exporter::compression::ChangeFilter;
+use lm_sensors::snapshot::Snapshot;
+
+let reading = |raw_value| Reading {
+    chip: "coretemp-isa-0000".into(),
+    feature: "temp1".into(),
+    sub_feature: "temp1_input".into(),
+    raw_kind: lm_sensors::value::Kind::TemperatureInput.as_raw(),
+    raw_value: Some(raw_value),
+};
+
+let mut filter = ChangeFilter::new(Duration::from_secs(300));
+assert_eq!(filter.filter(&Snapshot::new(vec![reading(47.0)])).readings.len(), 1);
+assert_eq!(filter.filter(&Snapshot::new(vec![reading(47.5)])).readings.len(), 0);
+assert_eq!(filter.filter(&Snapshot::new(vec![reading(48.0)])).readings.len(), 1);
+assert_eq!(filter.suppressed(), 1);
+```
+*/
+#[derive(Debug, Clone)]
+pub struct ChangeFilter {
+    max_interval: Duration,
+    epsilon: Option<ValueEpsilon>,
+    emitted: HashMap<(String, String), (Value, SystemTime)>,
+    suppressed: u64,
+}
+
+impl ChangeFilter {
+    /// Return a filter emitting readings at least once per `max_interval`,
+    /// with the default tolerances of their types.
+    ///
+    /// See: [`ValueEpsilon::default_for_kind`].
+    #[must_use]
+    pub fn new(max_interval: Duration) -> Self {
+        Self {
+            max_interval,
+            epsilon: None,
+            emitted: HashMap::default(),
+            suppressed: 0,
+        }
+    }
+
+    /// Use the given tolerance for readings of all types, instead of
+    /// the default tolerances of their types.
+    #[must_use]
+    pub fn with_epsilon(self, epsilon: ValueEpsilon) -> Self {
+        Self {
+            epsilon: Some(epsilon),
+            ..self
+        }
+    }
+
+    /// Return the maximum interval between emissions of a reading.
+    #[must_use]
+    pub fn max_interval(&self) -> Duration {
+        self.max_interval
+    }
+
+    /// Return the number of readings suppressed so far.
+    #[must_use]
+    pub fn suppressed(&self) -> u64 {
+        self.suppressed
+    }
+
+    /// Return `true` if the given reading, taken at the given time, must be
+    /// emitted, and remember it as emitted in that case.
+    pub fn admit(&mut self, reading: &Reading, time: SystemTime) -> bool {
+        let Some(value) = reading.value() else {
+            return true;
+        };
+
+        let key = (reading.chip.clone(), reading.sub_feature.clone());
+        if let Some((emitted, emitted_time)) = self.emitted.get(&key) {
+            let epsilon = self
+                .epsilon
+                .unwrap_or_else(|| ValueEpsilon::default_for_kind(value.kind()));
+            // Time going backward is a meaningful change.
+            let fresh = time
+                .duration_since(*emitted_time)
+                .is_ok_and(|age| age < self.max_interval);
+
+            if fresh && value.approx_eq(emitted, epsilon) {
+                self.suppressed += 1;
+                return false;
+            }
+        }
+
+        self.emitted.insert(key, (value, time));
+        true
+    }
+
+    /// Return the given snapshot without the readings that must not be
+    /// emitted, and remember the other readings as emitted.
+    ///
+    /// See: [`ChangeFilter::admit`].
+    #[must_use]
+    pub fn filter(&mut self, snapshot: &Snapshot) -> Snapshot {
+        let readings = snapshot
+            .readings
+            .iter()
+            .filter(|reading| self.admit(reading, snapshot.time))
+            .cloned();
+
+        Snapshot {
+            time: snapshot.time,
+            readings: readings.collect(),
+        }
+    }
+
+    /// Forget all emitted readings, so that the next readings are all
+    /// emitted, *e.g.,* when an exporter reconnected.
+    pub fn reset(&mut self) {
+        self.emitted.clear();
+        self.suppressed = 0;
+    }
+}
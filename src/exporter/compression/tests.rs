@@ -0,0 +1,95 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use super::ChangeFilter;
+use crate::backend::Reading;
+use crate::snapshot::Snapshot;
+use crate::value::{self, ValueEpsilon};
+
+const CHIP: &str = "nct6775-isa-0290";
+
+fn snapshot(secs: u64, temp: f64, fan: f64) -> Snapshot {
+    Snapshot {
+        time: UNIX_EPOCH + Duration::from_secs(secs),
+        readings: vec![
+            Reading::for_test(
+                CHIP,
+                "temp1_input",
+                value::Kind::TemperatureInput,
+                Some(temp),
+            ),
+            Reading::for_test(CHIP, "fan1_input", value::Kind::FanInput, Some(fan)),
+            Reading::for_test(CHIP, "in0_input", value::Kind::VoltageInput, None),
+        ],
+    }
+}
+
+fn emitted(filter: &mut ChangeFilter, snapshot: &Snapshot) -> Vec<String> {
+    let filtered = filter.filter(snapshot);
+    assert_eq!(filtered.time, snapshot.time);
+    filtered
+        .readings
+        .into_iter()
+        .map(|r| r.sub_feature)
+        .collect()
+}
+
+#[test]
+fn default_tolerances() {
+    let mut filter = ChangeFilter::new(Duration::from_secs(60));
+    assert_eq!(filter.max_interval(), Duration::from_secs(60));
+
+    let all = ["temp1_input", "fan1_input", "in0_input"];
+    assert_eq!(emitted(&mut filter, &snapshot(0, 40.0, 1200.0)), all);
+    assert_eq!(
+        emitted(&mut filter, &snapshot(10, 40.5, 1190.0)),
+        ["in0_input"]
+    );
+    assert_eq!(
+        emitted(&mut filter, &snapshot(20, 40.6, 1205.0)),
+        ["temp1_input", "in0_input"]
+    );
+    assert_eq!(
+        emitted(&mut filter, &snapshot(30, 40.6, 1211.0)),
+        ["fan1_input", "in0_input"]
+    );
+    assert_eq!(filter.suppressed(), 4);
+
+    // Readings are emitted again after the maximum interval.
+    assert_eq!(
+        emitted(&mut filter, &snapshot(80, 40.6, 1211.0)),
+        ["temp1_input", "in0_input"]
+    );
+    assert_eq!(
+        emitted(&mut filter, &snapshot(90, 40.6, 1211.0)),
+        ["fan1_input", "in0_input"]
+    );
+
+    // Time going backward emits readings.
+    assert_eq!(emitted(&mut filter, &snapshot(0, 40.6, 1211.0)), all);
+
+    filter.reset();
+    assert_eq!(filter.suppressed(), 0);
+    assert_eq!(emitted(&mut filter, &snapshot(1, 40.6, 1211.0)), all);
+}
+
+#[test]
+fn custom_tolerance() {
+    let epsilon = ValueEpsilon::new(100.0).unwrap();
+    let mut filter = ChangeFilter::new(Duration::from_secs(60)).with_epsilon(epsilon);
+
+    emitted(&mut filter, &snapshot(0, 40.0, 1200.0));
+    assert_eq!(
+        emitted(&mut filter, &snapshot(10, 90.0, 1290.0)),
+        ["in0_input"]
+    );
+
+    let mut filter = ChangeFilter::new(Duration::from_secs(60)).with_epsilon(ValueEpsilon::EXACT);
+    emitted(&mut filter, &snapshot(0, 40.0, 1200.0));
+    assert_eq!(
+        emitted(&mut filter, &snapshot(10, 40.0, 1201.0)),
+        ["fan1_input", "in0_input"]
+    );
+}
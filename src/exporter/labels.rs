@@ -0,0 +1,195 @@
+//! Sanitization of labels, and guarding of the cardinality of series.
+//!
+//! Chip and feature names are not valid label names of Prometheus,
+//! *e.g.,* `thinkpad-isa-0000`, and labels of features are arbitrary text.
+//! [`sanitize_name`] and [`sanitize_value`] make them valid, and
+//! [`escape_value`] escapes values in the text exposition format.
+//!
+//! Labels coming from hardware can multiply series unexpectedly, *e.g.,*
+//! when devices are hot-plugged repeatedly. A [`CardinalityGuard`] admits
+//! a bounded number of distinct series, and folds the others into a few
+//! overflow series, whose labels are derived from a hash of the original
+//! labels.
+
+#[cfg(test)]
+mod tests;
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Default maximum length of label values, in bytes.
+pub const DEFAULT_MAX_VALUE_LEN: usize = 128;
+
+/// Default number of overflow series of a [`CardinalityGuard`].
+pub const DEFAULT_OVERFLOW_BUCKETS: u64 = 16;
+
+/// Prefix of labels of overflow series.
+pub const OVERFLOW_PREFIX: &str = "overflow_";
+
+/// Return the given name, with characters invalid in label and metric
+/// names replaced by underscores, *i.e.,* other than ASCII letters, digits
+/// and underscores, and prefixed by an underscore if it starts with a digit.
+///
+/// An empty name becomes `_`.
+#[must_use]
+pub fn sanitize_name(name: &str) -> Cow<'_, str> {
+    let valid = name.bytes().enumerate().all(|(index, byte)| {
+        byte == b'_' || byte.is_ascii_alphabetic() || (index > 0 && byte.is_ascii_digit())
+    });
+    if valid && !name.is_empty() {
+        return Cow::Borrowed(name);
+    }
+
+    let mut result = String::with_capacity(name.len() + 1);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        result.push('_');
+    }
+    result.extend(
+        name.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }),
+    );
+    Cow::Owned(result)
+}
+
+/// Return the given value, without control characters, and truncated to
+/// at most `max_len` bytes, at a character boundary.
+#[must_use]
+pub fn sanitize_value(value: &str, max_len: usize) -> Cow<'_, str> {
+    let mut end = value.len().min(max_len);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    let value = &value[..end];
+
+    if value.contains(char::is_control) {
+        Cow::Owned(value.chars().filter(|c| !c.is_control()).collect())
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Return the given label value, with backslashes, double quotes and line
+/// feeds escaped, as required by the text exposition format.
+#[must_use]
+pub fn escape_value(value: &str) -> Cow<'_, str> {
+    if !value.contains(['\\', '"', '\n']) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut result = String::with_capacity(value.len() + 4);
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            c => result.push(c),
+        }
+    }
+    Cow::Owned(result)
+}
+
+/**
+Guard bounding the number of distinct series.
+
+Series are identified by keys, *e.g.,* their labels joined together.
+The first `max_series` distinct keys are admitted unchanged. Other keys are
+replaced by [`OVERFLOW_PREFIX`] followed by a hash of the key, modulo the
+number of overflow buckets, so that at most `max_series` plus the number of
+overflow buckets series exist. Hashes are stable across runs.
+
+# Example
+
+```rust
+use lm_sensors::exporter::labels::CardinalityGuard;
+
+let mut guard = CardinalityGuard::new(2).with_overflow_buckets(1);
+assert_eq!(guard.admit("coretemp-isa-0000"), "coretemp-isa-0000");
+assert_eq!(guard.admit("nct6775-isa-0290"), "nct6775-isa-0290");
+assert_eq!(guard.admit("nvme-pci-0100"), "overflow_0");
+assert_eq!(guard.admit("coretemp-isa-0000"), "coretemp-isa-0000");
+assert_eq!(guard.overflowed(), 1);
+```
+*/
+#[derive(Debug, Clone)]
+pub struct CardinalityGuard {
+    max_series: usize,
+    overflow_buckets: u64,
+    series: HashSet<String>,
+    overflowed: u64,
+}
+
+impl CardinalityGuard {
+    /// Return a guard admitting at most `max_series` distinct keys,
+    /// with [`DEFAULT_OVERFLOW_BUCKETS`] overflow series.
+    #[must_use]
+    pub fn new(max_series: usize) -> Self {
+        Self {
+            max_series,
+            overflow_buckets: DEFAULT_OVERFLOW_BUCKETS,
+            series: HashSet::default(),
+            overflowed: 0,
+        }
+    }
+
+    /// Set the number of overflow series, at least one.
+    #[must_use]
+    pub fn with_overflow_buckets(self, buckets: u64) -> Self {
+        Self {
+            overflow_buckets: buckets.max(1),
+            ..self
+        }
+    }
+
+    /// Return the maximum number of distinct keys admitted unchanged.
+    #[must_use]
+    pub fn max_series(&self) -> usize {
+        self.max_series
+    }
+
+    /// Return the number of distinct keys admitted unchanged.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    /// Return `true` if no keys were admitted unchanged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    /// Return the number of keys replaced by overflow labels.
+    #[must_use]
+    pub fn overflowed(&self) -> u64 {
+        self.overflowed
+    }
+
+    /// Return the given key if it is admitted, or its overflow label
+    /// otherwise.
+    pub fn admit<'a>(&mut self, key: &'a str) -> Cow<'a, str> {
+        if self.series.contains(key) {
+            return Cow::Borrowed(key);
+        }
+        if self.series.len() < self.max_series {
+            self.series.insert(key.to_owned());
+            return Cow::Borrowed(key);
+        }
+
+        self.overflowed += 1;
+        let bucket = fnv1a(key.as_bytes()) % self.overflow_buckets;
+        Cow::Owned(format!("{OVERFLOW_PREFIX}{bucket:x}"))
+    }
+
+    /// Forget all admitted keys, *e.g.,* when the set of devices changed.
+    pub fn reset(&mut self) {
+        self.series.clear();
+        self.overflowed = 0;
+    }
+}
+
+/// Return the 64-bit FNV-1a hash of the given bytes.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
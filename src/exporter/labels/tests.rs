@@ -0,0 +1,58 @@
+#![cfg(test)]
+
+use std::borrow::Cow;
+
+use super::{escape_value, fnv1a, sanitize_name, sanitize_value, CardinalityGuard};
+
+#[test]
+fn names() {
+    assert!(matches!(sanitize_name("temp1_input"), Cow::Borrowed(_)));
+    assert_eq!(sanitize_name("thinkpad-isa-0000"), "thinkpad_isa_0000");
+    assert_eq!(sanitize_name("0day"), "_0day");
+    assert_eq!(sanitize_name("Core 0 (°C)"), "Core_0___C_");
+    assert_eq!(sanitize_name(""), "_");
+}
+
+#[test]
+fn values() {
+    assert!(matches!(sanitize_value("Core 0", 128), Cow::Borrowed(_)));
+    assert_eq!(sanitize_value("CPU\tTemp\n", 128), "CPUTemp");
+    assert_eq!(sanitize_value("abcdef", 4), "abcd");
+    // Truncation happens at character boundaries.
+    assert_eq!(sanitize_value("°C°C", 4), "°C");
+
+    assert!(matches!(escape_value("Core 0"), Cow::Borrowed(_)));
+    assert_eq!(escape_value("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+}
+
+#[test]
+fn cardinality_guard() {
+    let mut guard = CardinalityGuard::new(2);
+    assert!(guard.is_empty());
+    assert_eq!(guard.admit("a"), "a");
+    assert_eq!(guard.admit("b"), "b");
+    assert_eq!(guard.admit("a"), "a");
+    assert_eq!(guard.len(), 2);
+
+    let overflow = guard.admit("c").into_owned();
+    assert!(overflow.starts_with("overflow_"));
+    assert_eq!(guard.admit("c"), overflow);
+    assert_eq!(guard.overflowed(), 2);
+    assert_eq!(guard.len(), 2);
+
+    // Overflow labels are bounded by the number of buckets.
+    let mut guard = CardinalityGuard::new(0).with_overflow_buckets(4);
+    let labels: std::collections::HashSet<String> = (0..100)
+        .map(|index| guard.admit(&format!("chip-{index}")).into_owned())
+        .collect();
+    assert!(labels.len() <= 4);
+
+    guard.reset();
+    assert_eq!(guard.overflowed(), 0);
+}
+
+#[test]
+fn stable_hash() {
+    assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+}
@@ -0,0 +1,255 @@
+//! Names, units and help strings of metrics, shared by exporters.
+//!
+//! [`MetricInfo::of`] maps each sub-feature kind to a canonical metric,
+//! named after its quantity and suffixed by its unit, *e.g.,*
+//! `lm_sensors_temperature_celsius` for temperature inputs, or
+//! `lm_sensors_fan_maximum_rpm` for fan maximums. Input sub-features are
+//! named after their quantity only. Cumulative energy inputs are counters,
+//! suffixed by `_total`, and all other metrics are gauges.
+//!
+//! [`Metadata`] overrides this mapping, per kind or through a hook.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use super::labels;
+use crate::value::{self, Unit};
+
+/// Prefix of canonical metric names.
+pub const PREFIX: &str = "lm_sensors";
+
+/// Return the suffix of names of metrics in the given unit, *e.g.,*
+/// `celsius`, or `None` for unitless metrics.
+#[must_use]
+pub fn unit_suffix(unit: Unit) -> Option<&'static str> {
+    match unit {
+        Unit::Volt => Some("volts"),
+        Unit::Amp => Some("amperes"),
+        Unit::Watt => Some("watts"),
+        Unit::Joule => Some("joules"),
+        Unit::Celcius => Some("celsius"),
+        Unit::Second => Some("seconds"),
+        Unit::RotationPerMinute => Some("rpm"),
+        Unit::Percentage => Some("percent"),
+        _ => None,
+    }
+}
+
+/// Type of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricType {
+    /// Value that can go up and down, *e.g.,* a temperature.
+    Gauge,
+    /// Cumulative value that only goes up, *e.g.,* consumed energy.
+    Counter,
+}
+
+impl MetricType {
+    /// Return the name of this type in `# TYPE` lines, *e.g.,* `gauge`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gauge => "gauge",
+            Self::Counter => "counter",
+        }
+    }
+}
+
+impl fmt::Display for MetricType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/**
+Name, unit, help string and type of a metric.
+
+# Example
+
+```rust
+use lm_sensors::exporter::metadata::MetricInfo;
+use lm_sensors::value::Kind;
+
+let info = MetricInfo::of(Kind::TemperatureInput).unwrap();
+assert_eq!(info.name, "lm_sensors_temperature_celsius");
+assert_eq!(info.help, "Temperature of hardware sensors, in celsius.");
+assert!(info.header().ends_with("\n# TYPE lm_sensors_temperature_celsius gauge\n"));
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricInfo {
+    /// Name of the metric, *e.g.,* `lm_sensors_temperature_celsius`.
+    pub name: String,
+    /// Unit of values of the metric.
+    pub unit: Unit,
+    /// Description of the metric, in one sentence.
+    pub help: String,
+    /// Type of the metric.
+    pub metric_type: MetricType,
+}
+
+impl MetricInfo {
+    /// Return the canonical metric of sub-features of the given kind,
+    /// or `None` if the kind is unknown.
+    #[must_use]
+    pub fn of(kind: value::Kind) -> Option<Self> {
+        if kind == value::Kind::Unknown {
+            return None;
+        }
+
+        let mut words = words(&kind.to_string());
+        if words.len() > 1 && words.last().is_some_and(|word| word == "input") {
+            words.pop();
+        }
+        let quantity = words.join("_");
+
+        let unit = kind.unit();
+        let metric_type = if kind == value::Kind::EnergyInput {
+            MetricType::Counter
+        } else {
+            MetricType::Gauge
+        };
+
+        let mut name = format!("{PREFIX}_{quantity}");
+        let mut help = words.join(" ");
+        help[..1].make_ascii_uppercase();
+        help.push_str(" of hardware sensors");
+        if let Some(suffix) = unit_suffix(unit) {
+            let _ignored = write!(name, "_{suffix}");
+            let _ignored = write!(help, ", in {suffix}");
+        }
+        if metric_type == MetricType::Counter {
+            name.push_str("_total");
+        }
+        help.push('.');
+
+        Some(Self {
+            name,
+            unit,
+            help,
+            metric_type,
+        })
+    }
+
+    /// Return the `# HELP` and `# TYPE` lines of this metric, in the text
+    /// exposition format of Prometheus.
+    #[must_use]
+    pub fn header(&self) -> String {
+        let help = self.help.replace('\\', "\\\\").replace('\n', "\\n");
+        format!(
+            "# HELP {name} {help}\n# TYPE {name} {metric_type}\n",
+            name = self.name,
+            metric_type = self.metric_type,
+        )
+    }
+}
+
+/// Function adjusting metrics of sub-feature kinds.
+type Hook = Arc<dyn Fn(value::Kind, &mut MetricInfo) + Send + Sync>;
+
+/**
+Mapping of sub-feature kinds to metrics, with user overrides.
+
+Metrics are looked up in overrides first, then given by [`MetricInfo::of`].
+Hooks are then applied, in the order they were added, and names are made
+valid by [`sanitize_name`](labels::sanitize_name).
+
+# Example
+
+```rust
+use lm_sensors::exporter::metadata::{Metadata, MetricInfo};
+use lm_sensors::value::Kind;
+
+let metadata = Metadata::new()
+    .without(Kind::TemperatureMaximum)
+    .with_hook(|_kind, info| info.name = info.name.replacen("lm_sensors", "node_hwmon", 1));
+
+let info = metadata.get(Kind::FanInput).unwrap();
+assert_eq!(info.name, "node_hwmon_fan_rpm");
+assert_eq!(metadata.get(Kind::TemperatureMaximum), None);
+```
+*/
+#[derive(Clone, Default)]
+#[must_use]
+pub struct Metadata {
+    overrides: BTreeMap<value::Kind, Option<MetricInfo>>,
+    hooks: Vec<Hook>,
+}
+
+impl Metadata {
+    /// Return a mapping of sub-feature kinds to canonical metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map sub-features of the given kind to the given metric.
+    pub fn with(mut self, kind: value::Kind, info: MetricInfo) -> Self {
+        self.overrides.insert(kind, Some(info));
+        self
+    }
+
+    /// Map sub-features of the given kind to no metric, so that exporters
+    /// skip them.
+    pub fn without(mut self, kind: value::Kind) -> Self {
+        self.overrides.insert(kind, None);
+        self
+    }
+
+    /// Adjust all metrics by the given function, *e.g.,* to rename them.
+    pub fn with_hook(
+        mut self,
+        hook: impl Fn(value::Kind, &mut MetricInfo) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Return the metric of sub-features of the given kind, if any.
+    #[must_use]
+    pub fn get(&self, kind: value::Kind) -> Option<MetricInfo> {
+        let mut info = match self.overrides.get(&kind) {
+            Some(info) => info.clone()?,
+            None => MetricInfo::of(kind)?,
+        };
+        for hook in &self.hooks {
+            hook(kind, &mut info);
+        }
+        if let Cow::Owned(name) = labels::sanitize_name(&info.name) {
+            info.name = name;
+        }
+        Some(info)
+    }
+}
+
+impl fmt::Debug for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metadata")
+            .field("overrides", &self.overrides)
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+/// Return the lowercase words of the given name in camel case, *e.g.,*
+/// `["voltage", "l", "critical"]` for `VoltageLCritical`.
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words: Vec<String> = Vec::default();
+    for (index, &c) in chars.iter().enumerate() {
+        let starts_word = c.is_ascii_uppercase()
+            && index > 0
+            && (chars[index - 1].is_ascii_lowercase()
+                || chars.get(index + 1).is_some_and(char::is_ascii_lowercase));
+        match words.last_mut() {
+            Some(word) if !starts_word => word.push(c.to_ascii_lowercase()),
+            _ => words.push(c.to_ascii_lowercase().to_string()),
+        }
+    }
+    words
+}
@@ -0,0 +1,97 @@
+#![cfg(test)]
+
+use super::{unit_suffix, words, Metadata, MetricInfo, MetricType};
+use crate::value::{Kind, Unit};
+
+#[test]
+fn canonical_names() {
+    let cases = [
+        (Kind::TemperatureInput, "lm_sensors_temperature_celsius"),
+        (
+            Kind::TemperatureMaximum,
+            "lm_sensors_temperature_maximum_celsius",
+        ),
+        (Kind::FanInput, "lm_sensors_fan_rpm"),
+        (Kind::PowerInput, "lm_sensors_power_watts"),
+        (Kind::PowerAverage, "lm_sensors_power_average_watts"),
+        (Kind::VoltageInput, "lm_sensors_voltage_volts"),
+        (
+            Kind::VoltageLCritical,
+            "lm_sensors_voltage_l_critical_volts",
+        ),
+        (Kind::CurrentInput, "lm_sensors_current_amperes"),
+        (Kind::HumidityInput, "lm_sensors_humidity_percent"),
+        (Kind::EnergyInput, "lm_sensors_energy_joules_total"),
+        (Kind::TemperatureAlarm, "lm_sensors_temperature_alarm"),
+        (Kind::VoltageID, "lm_sensors_voltage_id_volts"),
+    ];
+    for (kind, name) in cases {
+        assert_eq!(MetricInfo::of(kind).unwrap().name, name, "{kind}");
+    }
+
+    assert_eq!(MetricInfo::of(Kind::Unknown), None);
+    assert_eq!(
+        MetricInfo::of(Kind::EnergyInput).unwrap().metric_type,
+        MetricType::Counter
+    );
+    assert_eq!(
+        MetricInfo::of(Kind::FanInput).unwrap().help,
+        "Fan of hardware sensors, in rpm."
+    );
+}
+
+#[test]
+fn suffixes() {
+    assert_eq!(unit_suffix(Unit::Celcius), Some("celsius"));
+    assert_eq!(unit_suffix(Unit::RotationPerMinute), Some("rpm"));
+    assert_eq!(unit_suffix(Unit::None), None);
+
+    assert_eq!(words("VoltageLCritical"), ["voltage", "l", "critical"]);
+    assert_eq!(words("VoltageID"), ["voltage", "id"]);
+    assert_eq!(words("FanInput"), ["fan", "input"]);
+}
+
+#[test]
+fn header() {
+    let info = MetricInfo {
+        name: "fans".into(),
+        unit: Unit::RotationPerMinute,
+        help: "Fan\\speed\nof things.".into(),
+        metric_type: MetricType::Gauge,
+    };
+    assert_eq!(
+        info.header(),
+        "# HELP fans Fan\\\\speed\\nof things.\n# TYPE fans gauge\n"
+    );
+}
+
+#[test]
+fn overrides() {
+    let custom = MetricInfo {
+        name: "cpu_temperature_celsius".into(),
+        unit: Unit::Celcius,
+        help: "CPU temperature.".into(),
+        metric_type: MetricType::Gauge,
+    };
+    let metadata = Metadata::new()
+        .with(Kind::TemperatureInput, custom.clone())
+        .without(Kind::FanAlarm);
+
+    assert_eq!(metadata.get(Kind::TemperatureInput), Some(custom));
+    assert_eq!(metadata.get(Kind::FanAlarm), None);
+    assert_eq!(metadata.get(Kind::FanInput), MetricInfo::of(Kind::FanInput));
+
+    let metadata = metadata.with_hook(|kind, info| {
+        if kind == Kind::TemperatureInput {
+            info.name.insert_str(0, "node_");
+        }
+    });
+    assert_eq!(
+        metadata.get(Kind::TemperatureInput).unwrap().name,
+        "node_cpu_temperature_celsius"
+    );
+    assert_eq!(
+        metadata.get(Kind::FanInput).unwrap().name,
+        "lm_sensors_fan_rpm"
+    );
+}
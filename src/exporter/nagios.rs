@@ -0,0 +1,180 @@
+//! Output of Nagios and Icinga checks.
+//!
+//! [`check`] evaluates the raw values of selected sub-features against
+//! a warning and a critical threshold, and returns the exit code and the
+//! output line expected from a check plugin, so that replacements of
+//! `check_lm_sensors` take a few lines:
+//!
+//! ```text
+//! LM_SENSORS WARNING - coretemp-isa-0000/temp1_input=75 | 'coretemp-isa-0000/temp1_input'=75;70;90
+//! ```
+//!
+//! A value is a warning if it is greater than the warning threshold,
+//! and critical if it is greater than the critical threshold. Selected
+//! sub-features that could not be read make the check [`State::Unknown`],
+//! unless another value is a warning or critical.
+//! Performance data holds every selected value that was read, along with
+//! the thresholds.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::fmt::Write;
+use std::process::ExitCode;
+
+use crate::backend::{self, Reading};
+use crate::selector::Selector;
+use crate::snapshot::Snapshot;
+
+/// Name of the service, prefixing output lines.
+pub const SERVICE: &str = "LM_SENSORS";
+
+/// State of a check, ordered by severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum State {
+    /// All values are within the thresholds.
+    Ok,
+    /// Some values could not be read, or no sub-features were selected.
+    Unknown,
+    /// Some values are greater than the warning threshold.
+    Warning,
+    /// Some values are greater than the critical threshold.
+    Critical,
+}
+
+impl State {
+    /// Return the exit code of check plugins in this state.
+    #[must_use]
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Ok => 0,
+            Self::Warning => 1,
+            Self::Critical => 2,
+            Self::Unknown => 3,
+        }
+    }
+
+    /// Return the label of this state in output lines, *e.g.,* `WARNING`.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Warning => "WARNING",
+            Self::Critical => "CRITICAL",
+            Self::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl From<State> for ExitCode {
+    fn from(state: State) -> Self {
+        Self::from(state.exit_code())
+    }
+}
+
+/**
+Check the raw values of the sub-features selected by the given selector,
+read from the native backend, against the given thresholds.
+
+Return the exit code and the output line of the check. Failures to read
+the native backend make the check [`State::Unknown`].
+
+# Example
+
+```no_run
+use std::process::ExitCode;
+
+use lm_sensors::exporter::nagios;
+use lm_sensors::selector::Selector;
+
+fn main() -> ExitCode {
+    let selector = Selector::new().sensor("coretemp-isa-????", "temp*_input");
+    let (exit_code, output) = nagios::check(&selector, 70.0, 90.0);
+    println!("{output}");
+    exit_code
+}
+```
+*/
+#[must_use]
+pub fn check(selector: &Selector, warning: f64, critical: f64) -> (ExitCode, String) {
+    let snapshot = backend::native().and_then(|mut backend| Snapshot::read(&mut backend));
+    let (state, output) = match snapshot {
+        Ok(snapshot) => check_snapshot(&snapshot, selector, warning, critical),
+        Err(err) => (State::Unknown, format!("{SERVICE} UNKNOWN - {err}")),
+    };
+    (state.into(), output)
+}
+
+/// Check the raw values of the sub-features of the given snapshot selected
+/// by the given selector, against the given thresholds.
+///
+/// Return the state and the output line of the check.
+#[must_use]
+pub fn check_snapshot(
+    snapshot: &Snapshot,
+    selector: &Selector,
+    warning: f64,
+    critical: f64,
+) -> (State, String) {
+    let selected: Vec<&Reading> = snapshot
+        .readings
+        .iter()
+        .filter(|reading| selector.matches(&reading.chip, &reading.sub_feature))
+        .collect();
+    if selected.is_empty() {
+        return (
+            State::Unknown,
+            format!("{SERVICE} UNKNOWN - no sensors selected"),
+        );
+    }
+
+    let mut state = State::Ok;
+    let mut problems = Vec::default();
+    let mut perf_data = String::default();
+    for reading in &selected {
+        let name = format!("{}/{}", reading.chip, reading.sub_feature);
+        let Some(value) = reading.raw_value else {
+            state = state.max(State::Unknown);
+            problems.push((State::Unknown, format!("{name} unreadable")));
+            continue;
+        };
+
+        let reading_state = if value > critical {
+            State::Critical
+        } else if value > warning {
+            State::Warning
+        } else {
+            State::Ok
+        };
+        if reading_state != State::Ok {
+            state = state.max(reading_state);
+            problems.push((reading_state, format!("{name}={value}")));
+        }
+
+        let label = name.replace('\'', "''");
+        let _ignored = write!(perf_data, " '{label}'={value};{warning};{critical}");
+    }
+
+    // Report the most severe problems first.
+    problems.sort_by(|(a, _), (b, _)| b.cmp(a));
+    let summary = if problems.is_empty() {
+        format!("{} sensors OK", selected.len())
+    } else {
+        let problems: Vec<_> = problems.into_iter().map(|(_, problem)| problem).collect();
+        problems.join(", ")
+    };
+
+    let mut output = format!("{SERVICE} {state} - {summary}");
+    if !perf_data.is_empty() {
+        output.push_str(" |");
+        output.push_str(&perf_data);
+    }
+    (state, output)
+}
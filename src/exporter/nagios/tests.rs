@@ -0,0 +1,106 @@
+#![cfg(test)]
+
+use std::process::ExitCode;
+
+use super::{check_snapshot, State};
+use crate::backend::Reading;
+use crate::selector::Selector;
+use crate::snapshot::Snapshot;
+use crate::value;
+
+fn snapshot(temp1: Option<f64>, temp2: Option<f64>) -> Snapshot {
+    Snapshot::new(vec![
+        Reading::for_test(
+            "coretemp-isa-0000",
+            "temp1_input",
+            value::Kind::TemperatureInput,
+            temp1,
+        ),
+        Reading::for_test(
+            "coretemp-isa-0000",
+            "temp1_max",
+            value::Kind::TemperatureMaximum,
+            Some(100.0),
+        ),
+        Reading::for_test(
+            "coretemp-isa-0000",
+            "temp2_input",
+            value::Kind::TemperatureInput,
+            temp2,
+        ),
+    ])
+}
+
+fn selector() -> Selector {
+    Selector::new().sensor("coretemp-isa-*", "temp*_input")
+}
+
+#[test]
+fn state() {
+    assert!(State::Ok < State::Unknown);
+    assert!(State::Unknown < State::Warning);
+    assert!(State::Warning < State::Critical);
+
+    let codes: Vec<_> = [State::Ok, State::Warning, State::Critical, State::Unknown]
+        .into_iter()
+        .map(State::exit_code)
+        .collect();
+    assert_eq!(codes, [0, 1, 2, 3]);
+    assert_eq!(ExitCode::from(State::Critical), ExitCode::from(2));
+    assert_eq!(State::Warning.to_string(), "WARNING");
+}
+
+#[test]
+fn check() {
+    let all = snapshot(Some(47.5), Some(45.0));
+    assert_eq!(
+        check_snapshot(&all, &selector(), 70.0, 90.0),
+        (
+            State::Ok,
+            "LM_SENSORS OK - 2 sensors OK | 'coretemp-isa-0000/temp1_input'=47.5;70;90 \
+             'coretemp-isa-0000/temp2_input'=45;70;90"
+                .into()
+        )
+    );
+
+    let single = Snapshot::new(vec![Reading::for_test(
+        "coretemp-isa-0000",
+        "temp1_input",
+        value::Kind::TemperatureInput,
+        Some(75.0),
+    )]);
+    assert_eq!(
+        check_snapshot(&single, &selector(), 70.0, 90.0),
+        (
+            State::Warning,
+            "LM_SENSORS WARNING - coretemp-isa-0000/temp1_input=75 | \
+             'coretemp-isa-0000/temp1_input'=75;70;90"
+                .into()
+        )
+    );
+
+    let (state, output) =
+        check_snapshot(&snapshot(Some(75.0), Some(95.0)), &selector(), 70.0, 90.0);
+    assert_eq!(state, State::Critical);
+    assert!(output.starts_with(
+        "LM_SENSORS CRITICAL - coretemp-isa-0000/temp2_input=95, coretemp-isa-0000/temp1_input=75 |"
+    ));
+}
+
+#[test]
+fn unknown() {
+    let (state, output) = check_snapshot(&snapshot(None, Some(45.0)), &selector(), 70.0, 90.0);
+    assert_eq!(state, State::Unknown);
+    assert_eq!(
+        output,
+        "LM_SENSORS UNKNOWN - coretemp-isa-0000/temp1_input unreadable | \
+         'coretemp-isa-0000/temp2_input'=45;70;90"
+    );
+
+    let (state, _) = check_snapshot(&snapshot(None, Some(95.0)), &selector(), 70.0, 90.0);
+    assert_eq!(state, State::Critical);
+
+    let (state, output) = check_snapshot(&snapshot(Some(1.0), None), &Selector::new(), 70.0, 90.0);
+    assert_eq!(state, State::Unknown);
+    assert_eq!(output, "LM_SENSORS UNKNOWN - no sensors selected");
+}
@@ -0,0 +1,220 @@
+//! Export of readings as OpenTelemetry gauges.
+//!
+//! An [`Exporter`] records the values of input sub-features, *e.g.,*
+//! `temp1_input`, into gauges created from an OpenTelemetry [`Meter`].
+//! The application configures the meter provider, *e.g.,* with an OTLP
+//! exporter pushing metrics periodically, and calls [`Exporter::record`]
+//! with fresh snapshots.
+//!
+//! Gauges are named after the hardware semantic conventions of
+//! OpenTelemetry, *e.g.,* `hw.temperature` or `hw.fan.speed`. Each value
+//! is recorded with the attributes:
+//!
+//! - `hw.id`: chip and feature names, *e.g.,* `coretemp-isa-0000/temp1`,
+//! - `hw.name`: feature name, *e.g.,* `temp1`,
+//! - `hw.parent`: chip name, *e.g.,* `coretemp-isa-0000`,
+//! - `hw.type`: [`Metric::hw_type`], *e.g.,* `temperature`.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+
+use opentelemetry::metrics::{Gauge, Meter};
+use opentelemetry::KeyValue;
+
+use crate::backend::Reading;
+use crate::snapshot::Snapshot;
+use crate::value;
+
+/// Gauge of input sub-features of one kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Metric {
+    /// Temperature, in degrees Celsius.
+    Temperature,
+    /// Fan speed, in revolutions per minute.
+    FanSpeed,
+    /// Voltage, in volts.
+    Voltage,
+    /// Power, in watts.
+    Power,
+    /// Energy, in joules.
+    Energy,
+    /// Current, in amperes.
+    Current,
+    /// Relative humidity, in percents.
+    Humidity,
+}
+
+impl Metric {
+    /// All metrics, in order.
+    pub const ALL: [Self; 7] = [
+        Self::Temperature,
+        Self::FanSpeed,
+        Self::Voltage,
+        Self::Power,
+        Self::Energy,
+        Self::Current,
+        Self::Humidity,
+    ];
+
+    /// Return the metric of the given sub-feature kind, if it is
+    /// an input sub-feature.
+    #[must_use]
+    pub fn from_kind(kind: value::Kind) -> Option<Self> {
+        match kind {
+            value::Kind::TemperatureInput => Some(Self::Temperature),
+            value::Kind::FanInput => Some(Self::FanSpeed),
+            value::Kind::VoltageInput => Some(Self::Voltage),
+            value::Kind::PowerInput => Some(Self::Power),
+            value::Kind::EnergyInput => Some(Self::Energy),
+            value::Kind::CurrentInput => Some(Self::Current),
+            value::Kind::HumidityInput => Some(Self::Humidity),
+            _ => None,
+        }
+    }
+
+    /// Return the name of the gauge, *e.g.,* `hw.temperature`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Temperature => "hw.temperature",
+            Self::FanSpeed => "hw.fan.speed",
+            Self::Voltage => "hw.voltage",
+            Self::Power => "hw.power",
+            Self::Energy => "hw.energy",
+            Self::Current => "hw.current",
+            Self::Humidity => "hw.humidity",
+        }
+    }
+
+    /// Return the unit of the gauge, in UCUM notation, *e.g.,* `Cel`.
+    #[must_use]
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::Temperature => "Cel",
+            Self::FanSpeed => "rpm",
+            Self::Voltage => "V",
+            Self::Power => "W",
+            Self::Energy => "J",
+            Self::Current => "A",
+            Self::Humidity => "%",
+        }
+    }
+
+    /// Return the value of the `hw.type` attribute, *e.g.,* `temperature`.
+    #[must_use]
+    pub fn hw_type(self) -> &'static str {
+        match self {
+            Self::Temperature => "temperature",
+            Self::FanSpeed => "fan",
+            Self::Voltage => "voltage",
+            Self::Power => "power",
+            Self::Energy => "energy",
+            Self::Current => "current",
+            Self::Humidity => "humidity",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Temperature => "Temperature reported by a hardware sensor.",
+            Self::FanSpeed => "Fan speed reported by a hardware sensor.",
+            Self::Voltage => "Voltage reported by a hardware sensor.",
+            Self::Power => "Power reported by a hardware sensor.",
+            Self::Energy => "Energy reported by a hardware sensor.",
+            Self::Current => "Current reported by a hardware sensor.",
+            Self::Humidity => "Relative humidity reported by a hardware sensor.",
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/**
+Exporter recording readings into OpenTelemetry gauges.
+
+# Example
+
+```rust
+use lm_sensors::backend::{self, Reading};
+use lm_sensors::exporter::otel::Exporter;
+use lm_sensors::snapshot::Snapshot;
+
+let meter = opentelemetry::global::meter("lm-sensors");
+let exporter = Exporter::new(&meter);
+
+let snapshot = Snapshot::new(vec![Reading {
+    chip: "coretemp-isa-0000".into(),
+    feature: "temp1".into(),
+    sub_feature: "temp1_input".into(),
+    raw_kind: lm_sensors::value::Kind::TemperatureInput.as_raw(),
+    raw_value: Some(45.0),
+}]);
+assert_eq!(exporter.record(&snapshot), 1);
+```
+*/
+#[derive(Debug)]
+pub struct Exporter {
+    gauges: Vec<Gauge<f64>>,
+}
+
+impl Exporter {
+    /// Return a new exporter, creating one gauge per [`Metric`] from
+    /// the given meter.
+    #[must_use]
+    pub fn new(meter: &Meter) -> Self {
+        let gauges = Metric::ALL.iter().map(|&metric| {
+            meter
+                .f64_gauge(metric.name())
+                .with_unit(metric.unit())
+                .with_description(metric.description())
+                .build()
+        });
+
+        Self {
+            gauges: gauges.collect(),
+        }
+    }
+
+    /// Record the values of the input sub-features of the given snapshot,
+    /// and return the number of values recorded.
+    ///
+    /// Readings of other sub-features, or that failed, are ignored.
+    pub fn record(&self, snapshot: &Snapshot) -> usize {
+        snapshot
+            .readings
+            .iter()
+            .filter(|reading| self.record_reading(reading))
+            .count()
+    }
+
+    /// Record the value of the given reading, and return `true` if it is
+    /// the reading of an input sub-feature that succeeded.
+    pub fn record_reading(&self, reading: &Reading) -> bool {
+        let Some(value) = reading.value() else {
+            return false;
+        };
+        let Some(metric) = Metric::from_kind(value.kind()) else {
+            return false;
+        };
+
+        self.gauges[metric as usize].record(value.raw_value(), &attributes(metric, reading));
+        true
+    }
+}
+
+/// Return the attributes of values of the given reading.
+fn attributes(metric: Metric, reading: &Reading) -> [KeyValue; 4] {
+    [
+        KeyValue::new("hw.id", format!("{}/{}", reading.chip, reading.feature)),
+        KeyValue::new("hw.name", reading.feature.clone()),
+        KeyValue::new("hw.parent", reading.chip.clone()),
+        KeyValue::new("hw.type", metric.hw_type()),
+    ]
+}
@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use opentelemetry::KeyValue;
+
+use super::{attributes, Exporter, Metric};
+use crate::backend::Reading;
+use crate::snapshot::Snapshot;
+use crate::value;
+
+const CHIP: &str = "nct6775-isa-0290";
+
+#[test]
+fn metric() {
+    for (index, metric) in Metric::ALL.into_iter().enumerate() {
+        assert_eq!(metric as usize, index);
+        assert!(metric.name().starts_with("hw."));
+        assert_eq!(metric.to_string(), metric.name());
+    }
+
+    let metric = Metric::from_kind(value::Kind::TemperatureInput).unwrap();
+    assert_eq!(metric, Metric::Temperature);
+    assert_eq!((metric.name(), metric.unit()), ("hw.temperature", "Cel"));
+
+    let metric = Metric::from_kind(value::Kind::FanInput).unwrap();
+    assert_eq!((metric.name(), metric.unit()), ("hw.fan.speed", "rpm"));
+
+    assert_eq!(Metric::from_kind(value::Kind::TemperatureMaximum), None);
+    assert_eq!(Metric::from_kind(value::Kind::Unknown), None);
+}
+
+#[test]
+fn reading_attributes() {
+    let reading = Reading::for_test(CHIP, "fan2_input", value::Kind::FanInput, Some(1200.0));
+    assert_eq!(
+        attributes(Metric::FanSpeed, &reading),
+        [
+            KeyValue::new("hw.id", "nct6775-isa-0290/fan2"),
+            KeyValue::new("hw.name", "fan2"),
+            KeyValue::new("hw.parent", "nct6775-isa-0290"),
+            KeyValue::new("hw.type", "fan"),
+        ]
+    );
+}
+
+#[test]
+fn record() {
+    let meter = opentelemetry::global::meter("lm-sensors-tests");
+    let exporter = Exporter::new(&meter);
+
+    let snapshot = Snapshot::new(vec![
+        Reading::for_test(
+            CHIP,
+            "temp1_input",
+            value::Kind::TemperatureInput,
+            Some(45.0),
+        ),
+        Reading::for_test(
+            CHIP,
+            "temp1_max",
+            value::Kind::TemperatureMaximum,
+            Some(80.0),
+        ),
+        Reading::for_test(CHIP, "in0_input", value::Kind::VoltageInput, Some(1.1)),
+        Reading::for_test(CHIP, "fan1_input", value::Kind::FanInput, None),
+    ]);
+    assert_eq!(exporter.record(&snapshot), 2);
+    assert!(!exporter.record_reading(&snapshot.readings[3]));
+}
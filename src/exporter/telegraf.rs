@@ -0,0 +1,126 @@
+//! Output of the JSON input of the telegraf `exec` plugin.
+//!
+//! [`format`] renders the values of input sub-features, *e.g.,*
+//! `temp1_input`, as a JSON array of objects, one per value, to be printed
+//! by programs run by the `exec` input plugin of telegraf:
+//!
+//! ```text
+//! [{"name":"lm_sensors","sensor":"coretemp_isa_0000_temp1","chip":"coretemp-isa-0000",
+//!   "feature":"temp1","type":"temperature","value":47.5,"time":1700000000.25}]
+//! ```
+//!
+//! Objects hold the [`StableName`] of the feature as `sensor`, or the name
+//! given by a [`NameTemplate`], see: [`format_with`], and the collectd type
+//! of the value as `type`, see: [`collectd::type_of`].
+//! The plugin is configured as follows:
+//!
+//! ```toml
+//! [[inputs.exec]]
+//!   commands = ["/usr/local/bin/sensors-telegraf"]
+//!   data_format = "json"
+//!   json_name_key = "name"
+//!   tag_keys = ["sensor", "chip", "feature", "type"]
+//!   json_time_key = "time"
+//!   json_time_format = "unix"
+//! ```
+//!
+//! [`StableName`]: crate::naming::StableName
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+use std::time::UNIX_EPOCH;
+
+use super::collectd;
+use crate::naming::NameTemplate;
+use crate::snapshot::Snapshot;
+
+/// Name of the measurement of values.
+pub const MEASUREMENT: &str = "lm_sensors";
+
+/**
+Return a JSON array holding the values of the input sub-features of
+the given snapshot, time-stamped with the time of the snapshot.
+
+# Example
+
+```rust
+use lm_sensors::backend::Reading;
+use lm_sensors::exporter::telegraf;
+use lm_sensors::snapshot::Snapshot;
+
+let snapshot = Snapshot::new(vec![Reading {
+    chip: "coretemp-isa-0000".into(),
+    feature: "temp1".into(),
+    sub_feature: "temp1_input".into(),
+    raw_kind: lm_sensors::value::Kind::TemperatureInput.as_raw(),
+    raw_value: Some(47.5),
+}]);
+assert!(telegraf::format(&snapshot).contains(r#""sensor":"coretemp_isa_0000_temp1""#));
+```
+*/
+#[must_use]
+pub fn format(snapshot: &Snapshot) -> String {
+    format_with(snapshot, &NameTemplate::default())
+}
+
+/// Return a JSON array like [`format`], with sensors named by the given
+/// template.
+#[must_use]
+pub fn format_with(snapshot: &Snapshot, template: &NameTemplate) -> String {
+    let time = snapshot
+        .time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    let mut output = String::from("[");
+    for reading in &snapshot.readings {
+        let Some(value) = reading.value() else {
+            continue;
+        };
+        let Some(kind) = collectd::type_of(value.kind()) else {
+            continue;
+        };
+        let raw_value = value.raw_value();
+        if !raw_value.is_finite() {
+            continue;
+        }
+
+        if output.len() > 1 {
+            output.push(',');
+        }
+        let name = template.render_reading(reading);
+        let _ignored = write!(
+            output,
+            r#"{{"name":"{MEASUREMENT}","sensor":{},"chip":{},"feature":{},"type":"{kind}","value":{raw_value},"time":{time}}}"#,
+            JsonString(&name),
+            JsonString(&reading.chip),
+            JsonString(&reading.feature),
+        );
+    }
+    output.push(']');
+    output
+}
+
+/// String formatted as a JSON string literal.
+struct JsonString<'a>(&'a str);
+
+impl core::fmt::Display for JsonString<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
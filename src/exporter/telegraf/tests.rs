@@ -0,0 +1,83 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use super::{format, format_with, JsonString};
+use crate::backend::Reading;
+use crate::naming::NameTemplate;
+use crate::snapshot::Snapshot;
+use crate::value;
+
+#[test]
+fn json() {
+    let snapshot = Snapshot {
+        time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_250),
+        readings: vec![
+            Reading::for_test(
+                "coretemp-isa-0000",
+                "temp1_input",
+                value::Kind::TemperatureInput,
+                Some(47.5),
+            ),
+            Reading::for_test(
+                "coretemp-isa-0000",
+                "temp1_max",
+                value::Kind::TemperatureMaximum,
+                Some(80.0),
+            ),
+            Reading::for_test(
+                "nct6775-isa-0290",
+                "fan2_input",
+                value::Kind::FanInput,
+                Some(1200.0),
+            ),
+            Reading::for_test(
+                "nct6775-isa-0290",
+                "fan3_input",
+                value::Kind::FanInput,
+                None,
+            ),
+        ],
+    };
+
+    assert_eq!(
+        format(&snapshot),
+        concat!(
+            r#"[{"name":"lm_sensors","sensor":"coretemp_isa_0000_temp1","chip":"coretemp-isa-0000","#,
+            r#""feature":"temp1","type":"temperature","value":47.5,"time":1700000000.25},"#,
+            r#"{"name":"lm_sensors","sensor":"nct6775_isa_0290_fan2","chip":"nct6775-isa-0290","#,
+            r#""feature":"fan2","type":"fanspeed","value":1200,"time":1700000000.25}]"#,
+        )
+    );
+
+    assert_eq!(format(&Snapshot::new(Vec::default())), "[]");
+}
+
+#[test]
+fn json_with_template() {
+    let snapshot = Snapshot {
+        time: UNIX_EPOCH,
+        readings: vec![Reading::for_test(
+            "coretemp-isa-0000",
+            "temp1_input",
+            value::Kind::TemperatureInput,
+            Some(47.5),
+        )],
+    };
+
+    let template = NameTemplate::new("{chip}/\"{feature}\"").unwrap();
+    assert!(format_with(&snapshot, &template).contains(r#""sensor":"coretemp-isa-0000/\"temp1\"""#));
+    assert_eq!(
+        format_with(&snapshot, &NameTemplate::default()),
+        format(&snapshot)
+    );
+}
+
+#[test]
+fn escaping() {
+    assert_eq!(
+        JsonString("a\"b\\c\nd\u{1}").to_string(),
+        r#""a\"b\\c\nd\u0001""#
+    );
+}
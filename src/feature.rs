@@ -9,19 +9,23 @@ use std::ffi::CString;
 use std::io;
 use std::os::raw::{c_int, c_uint};
 
-use sensors_sys::sensors_feature_type::*;
 use sensors_sys::*;
 
+use crate::capabilities::Capabilities;
 use crate::chip::ChipRef;
 use crate::errors::{Error, Result};
+use crate::redact::Redacted;
+use crate::status::Thresholds;
 use crate::sub_feature::SubFeatureRef;
 use crate::utils::api_access_lock;
 
+pub use crate::model::feature::Kind;
+
 /// Shared reference to a feature of some [`Kind`] (*e.g.,* temperature),
 /// provided by a [`Chip`].
 ///
 /// [`Kind`]: crate::feature::Kind
-#[derive(Debug, Clone, Copy, Eq)]
+#[derive(Clone, Copy, Eq)]
 pub struct FeatureRef<'a> {
     pub(crate) chip: ChipRef<'a>,
     pub(crate) raw: &'a sensors_feature,
@@ -34,6 +38,16 @@ impl<'a> FeatureRef<'a> {
         self.raw
     }
 
+    /// Returns a pointer to the raw data structure [`sensors_feature`].
+    ///
+    /// The pointer is valid for the lifetime `'a`, and must not be used
+    /// to change the raw data structure. It is typically passed to
+    /// `sensors-sys` functions along with [`ChipRef::as_raw_ptr`].
+    #[must_use]
+    pub fn as_raw_ptr(self) -> *const sensors_feature {
+        self.raw
+    }
+
     /// Return the chip controlling this feature.
     #[must_use]
     pub fn chip(self) -> ChipRef<'a> {
@@ -47,6 +61,14 @@ impl<'a> FeatureRef<'a> {
             .map(|name| name.to_str().map_err(Into::into))
     }
 
+    /// Return this feature, formatted with serial-number-like strings
+    /// redacted, *e.g.,* for public bug reports.
+    ///
+    /// See: [`Redacted`].
+    pub fn redact(self) -> Redacted<Self> {
+        Redacted::new(self)
+    }
+
     /// Return the label of this feature, if it is valid UTF-8.
     pub fn label(self) -> Result<String> {
         self.raw_label()?.into_string().map_err(Into::into)
@@ -70,6 +92,31 @@ impl<'a> FeatureRef<'a> {
         self.sub_feature_by_raw_kind(c_uint::from(kind))
     }
 
+    /// Return the sub-feature of the given name (*e.g.,* `temp1_crit`)
+    /// belonging to this feature, if it exists, or an error otherwise.
+    pub fn sub_feature_by_name(self, name: &str) -> Result<SubFeatureRef<'a>> {
+        self.sub_feature_iter()
+            .find(|sub_feature| sub_feature.raw_name().map(CStr::to_bytes) == Some(name.as_bytes()))
+            .ok_or_else(|| {
+                let err = io::ErrorKind::NotFound.into();
+                Error::from_io("FeatureRef::sub_feature_by_name()", err)
+            })
+    }
+
+    /// Return the canonical input sub-feature of this feature, *e.g.,*
+    /// `temp1_input`, if it exists, or an error otherwise.
+    ///
+    /// See: [`Kind::input`].
+    pub fn input(self) -> Result<SubFeatureRef<'a>> {
+        match self.kind().and_then(Kind::input) {
+            Some(kind) => self.sub_feature_by_kind(kind),
+            None => {
+                let err = io::ErrorKind::NotFound.into();
+                Err(Error::from_io("FeatureRef::input()", err))
+            }
+        }
+    }
+
     /// Return an iterator which yields all sub-features belonging
     /// to this feature.
     pub fn sub_feature_iter(self) -> crate::sub_feature::Iter<'a> {
@@ -79,6 +126,65 @@ impl<'a> FeatureRef<'a> {
         }
     }
 
+    /// Return the limits of this feature, *e.g.,* `temp1_max`, with their
+    /// hysteresis.
+    ///
+    /// Limits that cannot be read are ignored. Limits lacking a hysteresis
+    /// sub-feature are cleared `hysteresis` away from their trigger.
+    ///
+    /// See: [`Thresholds::from_values`].
+    #[must_use]
+    pub fn thresholds(self, hysteresis: f64) -> Thresholds {
+        let values: Vec<_> = self
+            .sub_feature_iter()
+            .filter_map(|sub_feature| sub_feature.value().ok())
+            .collect();
+        Thresholds::from_values(&values, hysteresis)
+    }
+
+    /**
+    Set the power cap of this power feature, in watts.
+
+    This fails with [`io::ErrorKind::InvalidInput`] if `watts` is not
+    a positive number, if it is above the maximum power of this feature,
+    or if it is not above the hysteresis of the power cap, when these can
+    be read. This fails with [`io::ErrorKind::NotFound`] if this feature
+    has no power cap.
+
+    # Example
+
+    ```no_run
+    # fn f(sensors: &lm_sensors::LMSensors, power: lm_sensors::FeatureRef)
+    #     -> lm_sensors::errors::Result<()> {
+    power.set_power_cap(sensors.capabilities(), 125.0)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_power_cap(self, capabilities: Capabilities<'_>, watts: f64) -> Result<()> {
+        use crate::value::Kind;
+
+        let read = |kind| {
+            self.sub_feature_by_kind(kind)
+                .and_then(SubFeatureRef::raw_value)
+                .ok()
+        };
+        let maximum = read(Kind::PowerMaximum);
+        let hysteresis = read(Kind::PowerCapHysteresis);
+
+        let valid = watts.is_finite()
+            && watts > 0.0
+            && maximum.is_none_or(|maximum| watts <= maximum)
+            && hysteresis.is_none_or(|hysteresis| watts > hysteresis);
+        if !valid {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io("FeatureRef::set_power_cap()", err));
+        }
+
+        self.sub_feature_by_kind(Kind::PowerCap)?
+            .set_value(capabilities, &crate::Value::PowerCap(watts))
+    }
+
     /// Return the raw name of this feature, if available.
     #[must_use]
     pub fn raw_name(self) -> Option<&'a CStr> {
@@ -151,6 +257,19 @@ impl<'a> PartialEq for FeatureRef<'a> {
     }
 }
 
+/// Formats the fields of the feature that are readable without locking,
+/// *i.e.,* not its label.
+impl<'a> fmt::Debug for FeatureRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeatureRef")
+            .field("chip", &self.chip)
+            .field("name", &self.raw_name())
+            .field("number", &self.number())
+            .field("kind", &self.kind())
+            .finish()
+    }
+}
+
 impl<'a> fmt::Display for FeatureRef<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Ok(label) = self.raw_label() {
@@ -161,78 +280,6 @@ impl<'a> fmt::Display for FeatureRef<'a> {
     }
 }
 
-/// Type of a sensor or actuator (*a.k.a.,* feature) controlled by a chip.
-#[allow(missing_docs)] // Enum variant names are self-explanatory.
-#[repr(u32)]
-#[non_exhaustive]
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    num_enum::TryFromPrimitive,
-    num_enum::IntoPrimitive,
-)]
-pub enum Kind {
-    Voltage = SENSORS_FEATURE_IN,
-    Fan = SENSORS_FEATURE_FAN,
-    Temperature = SENSORS_FEATURE_TEMP,
-    Power = SENSORS_FEATURE_POWER,
-    Energy = SENSORS_FEATURE_ENERGY,
-    Current = SENSORS_FEATURE_CURR,
-    Humidity = SENSORS_FEATURE_HUMIDITY,
-    //MaximumMain = SENSORS_FEATURE_MAX_MAIN,
-    VoltageID = SENSORS_FEATURE_VID,
-    Intrusion = SENSORS_FEATURE_INTRUSION,
-    //MaximumOther = SENSORS_FEATURE_MAX_OTHER,
-    BeepEnable = SENSORS_FEATURE_BEEP_ENABLE,
-    // Maximum = SENSORS_FEATURE_MAX,
-    Unknown = SENSORS_FEATURE_UNKNOWN,
-}
-
-impl Kind {
-    /// Return an instance from one of the `SENSORS_FEATURE_*` values,
-    /// *e.g.,* [`SENSORS_FEATURE_TEMP`].
-    #[must_use]
-    pub fn from_raw(kind: c_uint) -> Option<Self> {
-        Self::try_from(kind).ok()
-    }
-
-    /// Return one of the `SENSORS_FEATURE_*` values
-    /// (*e.g.,* [`SENSORS_FEATURE_TEMP`]) equivalent to this instance.
-    #[must_use]
-    pub fn as_raw(self) -> c_uint {
-        self.into()
-    }
-}
-
-impl Default for Kind {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
-impl fmt::Display for Kind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            Self::Voltage => write!(f, "Voltage"),
-            Self::Fan => write!(f, "Fan"),
-            Self::Temperature => write!(f, "Temperature"),
-            Self::Power => write!(f, "Power"),
-            Self::Energy => write!(f, "Energy"),
-            Self::Current => write!(f, "Current"),
-            Self::Humidity => write!(f, "Humidity"),
-            Self::VoltageID => write!(f, "VoltageID"),
-            Self::Intrusion => write!(f, "Intrusion"),
-            Self::BeepEnable => write!(f, "BeepEnable"),
-            Self::Unknown => write!(f, "Unknown"),
-        }
-    }
-}
-
 /// Iterator over available features of a chip. Yields [`FeatureRef`]s.
 #[derive(Debug)]
 #[must_use]
@@ -259,3 +306,25 @@ impl<'a> Iterator for Iter<'a> {
             })
     }
 }
+
+/// Iterator over available features of a chip, joined with the values of
+/// their canonical input sub-features.
+/// Yields [`FeatureRef`]s and [`Value`](crate::Value)s.
+///
+/// Values are `None` if the input sub-feature does not exist,
+/// or cannot be read.
+#[derive(Debug)]
+#[must_use]
+pub struct Readings<'a> {
+    pub(crate) iter: Iter<'a>,
+}
+
+impl<'a> Iterator for Readings<'a> {
+    type Item = (FeatureRef<'a>, Option<crate::Value>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let feature = self.iter.next()?;
+        let value = feature.input().and_then(SubFeatureRef::value).ok();
+        Some((feature, value))
+    }
+}
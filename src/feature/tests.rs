@@ -1,6 +1,5 @@
 #![cfg(test)]
 
-use sensors_sys::sensors_feature_type::*;
 use serial_test::serial;
 
 #[test]
@@ -15,7 +14,9 @@ fn new_feature_ref() {
             .as_ref()
             .unwrap()
     };
-    let _feature_ref = unsafe { s.new_feature_ref(chip0.as_ref(), feature) };
+    let feature_ref = unsafe { s.new_feature_ref(chip0.as_ref(), feature) };
+    assert_eq!(feature_ref.as_raw_ptr(), feature as *const _);
+    assert_eq!(feature_ref.raw_ref(), feature);
 }
 
 #[test]
@@ -66,32 +67,56 @@ fn sub_feature_by_kind() {
         .sub_feature_by_kind(sub_feature.kind().unwrap())
         .unwrap();
     assert_eq!(sub_feature0, sub_feature1);
+
+    let sub_feature2 = feature0
+        .sub_feature_by_name(sub_feature.name().unwrap().unwrap())
+        .unwrap();
+    assert_eq!(sub_feature0, sub_feature2);
+    feature0.sub_feature_by_name("").unwrap_err();
 }
 
 #[test]
-fn kind() {
-    use super::Kind;
-
-    let k0 = Kind::default();
-    assert_eq!(k0, Kind::Unknown);
-    assert_eq!(k0.as_raw(), SENSORS_FEATURE_UNKNOWN);
-    assert!(!k0.to_string().is_empty());
-
-    for (k, n, s) in [
-        (Kind::Voltage, SENSORS_FEATURE_IN, "Voltage"),
-        (Kind::Fan, SENSORS_FEATURE_FAN, "Fan"),
-        (Kind::Temperature, SENSORS_FEATURE_TEMP, "Temperature"),
-        (Kind::Power, SENSORS_FEATURE_POWER, "Power"),
-        (Kind::Energy, SENSORS_FEATURE_ENERGY, "Energy"),
-        (Kind::Current, SENSORS_FEATURE_CURR, "Current"),
-        (Kind::Humidity, SENSORS_FEATURE_HUMIDITY, "Humidity"),
-        (Kind::VoltageID, SENSORS_FEATURE_VID, "VoltageID"),
-        (Kind::Intrusion, SENSORS_FEATURE_INTRUSION, "Intrusion"),
-        (Kind::BeepEnable, SENSORS_FEATURE_BEEP_ENABLE, "BeepEnable"),
-        (Kind::Unknown, SENSORS_FEATURE_UNKNOWN, "Unknown"),
-    ] {
-        assert_eq!(Kind::from_raw(n).unwrap(), k);
-        assert_eq!(n, k.as_raw());
-        assert_eq!(k.to_string(), s);
+#[serial]
+fn set_power_cap() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let capabilities = s.capabilities();
+
+    let is_power = |feature: crate::FeatureRef| feature.kind() == Some(crate::feature::Kind::Power);
+    let features: Vec<_> = s
+        .chip_iter(None)
+        .flat_map(|chip| chip.feature_iter())
+        .collect();
+
+    for feature in features.iter().filter(|feature| !is_power(**feature)) {
+        let err = feature.set_power_cap(capabilities, 100.0).unwrap_err();
+        assert_eq!(err.code(), "io");
+    }
+
+    for feature in features.iter().filter(|feature| is_power(**feature)) {
+        for watts in [f64::NAN, f64::INFINITY, -1.0, 0.0] {
+            assert!(feature.set_power_cap(capabilities, watts).is_err());
+        }
     }
 }
+
+#[test]
+#[serial]
+fn debug() {
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    let chip0 = s.chip_iter(None).next().unwrap();
+    let feature0 = chip0.feature_iter().next().unwrap();
+    let sub_feature0 = feature0.sub_feature_iter().next().unwrap();
+
+    let name = feature0.name().unwrap().unwrap();
+    let debug = format!("{feature0:?}");
+    assert!(debug.starts_with("FeatureRef {"));
+    assert!(debug.contains(name));
+    assert!(!format!("{:#?}", feature0.redact()).is_empty());
+
+    let name = sub_feature0.name().unwrap().unwrap();
+    let debug = format!("{sub_feature0:?}");
+    assert!(debug.starts_with("SubFeatureRef {"));
+    assert!(debug.contains(name));
+    assert!(!sub_feature0.redact().to_string().is_empty());
+}
@@ -0,0 +1,258 @@
+//! Composite health of a system, from a [`Snapshot`] of its sensors.
+//!
+//! [`score`] combines alarms, faults, limits, the distance of temperatures
+//! to their critical limits, stopped fans and unreadable sub-features into
+//! one [`Health`] level, with the [`Cause`]s that lowered it, *e.g.,* to
+//! drive a status LED or a readiness probe.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+
+use crate::backend::Reading;
+use crate::snapshot::Snapshot;
+use crate::status::{Status, Thresholds};
+use crate::value::Value;
+
+/// Health level, ordered by severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Health {
+    /// Everything is fine.
+    #[default]
+    Ok,
+    /// Something needs attention, but the system is not in danger.
+    Degraded,
+    /// The system is in danger.
+    Critical,
+}
+
+impl fmt::Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ok => "ok",
+            Self::Degraded => "degraded",
+            Self::Critical => "critical",
+        })
+    }
+}
+
+/// Policy of health scoring.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    /// Temperatures closer than this to their critical limit,
+    /// in degrees Celsius, degrade health.
+    pub temperature_margin: f64,
+    /// Whether fans reporting a speed of zero degrade health.
+    pub stopped_fans: bool,
+    /// Number of unreadable sub-features tolerated before health degrades.
+    pub tolerated_failures: usize,
+}
+
+impl Default for Policy {
+    /// Return a policy with a temperature margin of 5 degrees Celsius,
+    /// tolerating stopped fans, and no unreadable sub-features.
+    fn default() -> Self {
+        Self {
+            temperature_margin: 5.0,
+            stopped_fans: false,
+            tolerated_failures: 0,
+        }
+    }
+}
+
+/// Reason of a lowered health level.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Reason {
+    /// An alarm sub-feature is set, *e.g.,* `temp1_crit_alarm`.
+    Alarm(String),
+    /// A fault sub-feature is set, *e.g.,* `fan1_fault`.
+    Fault(String),
+    /// The input value is beyond a limit.
+    Limit(Status),
+    /// The temperature is this close to its critical limit,
+    /// in degrees Celsius.
+    NearCritical(f64),
+    /// The fan reports a speed of zero.
+    FanStopped,
+    /// The value of this sub-feature could not be read.
+    Unreadable(String),
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Alarm(sub_feature) => write!(f, "alarm {sub_feature} is set"),
+            Self::Fault(sub_feature) => write!(f, "fault {sub_feature} is set"),
+            Self::Limit(status) => write!(f, "value is {status}"),
+            Self::NearCritical(distance) => write!(f, "{distance} C below critical"),
+            Self::FanStopped => f.write_str("fan is stopped"),
+            Self::Unreadable(sub_feature) => write!(f, "{sub_feature} is unreadable"),
+        }
+    }
+}
+
+/// Cause of a lowered health level, in one feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cause {
+    /// Name of the chip, *e.g.,* `coretemp-isa-0000`.
+    pub chip: String,
+    /// Name of the feature, *e.g.,* `temp1`.
+    pub feature: String,
+    /// Health level implied by this cause.
+    pub health: Health,
+    /// Reason of this cause.
+    pub reason: Reason,
+}
+
+impl fmt::Display for Cause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            chip,
+            feature,
+            health,
+            reason,
+        } = self;
+        write!(f, "{health}: {chip}: {feature}: {reason}")
+    }
+}
+
+/// Result of [`score`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HealthReport {
+    /// Overall health level, the most severe level of all causes.
+    pub health: Health,
+    /// Causes of the lowered health level, in the order of the readings.
+    pub causes: Vec<Cause>,
+}
+
+impl HealthReport {
+    /// Return `true` if the overall health level is [`Health::Ok`].
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.health == Health::Ok
+    }
+
+    fn push(&mut self, readings: &[Reading], health: Health, reason: Reason) {
+        self.health = self.health.max(health);
+        self.causes.push(Cause {
+            chip: readings[0].chip.clone(),
+            feature: readings[0].feature.clone(),
+            health,
+            reason,
+        });
+    }
+}
+
+/// Return the health of the system whose sensors were read in `snapshot`.
+pub fn score(snapshot: &Snapshot, policy: &Policy) -> HealthReport {
+    let mut report = HealthReport::default();
+    let mut failures = Vec::default();
+
+    for readings in snapshot.features() {
+        let mut values = Vec::with_capacity(readings.len());
+        for reading in readings {
+            match reading.value() {
+                Some(value) => values.push((reading, value)),
+                None if reading.raw_value.is_none() => failures.push(reading),
+                None => {}
+            }
+        }
+
+        for &(reading, value) in &values {
+            if let Some((health, is_fault)) = flag(value) {
+                let name = reading.sub_feature.clone();
+                let reason = if is_fault {
+                    Reason::Fault(name)
+                } else {
+                    Reason::Alarm(name)
+                };
+                report.push(readings, health, reason);
+            }
+        }
+
+        let thresholds = Thresholds::from_values(values.iter().map(|(_, v)| v), 0.0);
+        let input = values.iter().find_map(|&(_, v)| input(v));
+        if let Some(input) = input {
+            let status = thresholds.status(input.raw_value(), Status::Normal);
+            match status {
+                Status::Normal => {}
+                Status::Low | Status::High => {
+                    report.push(readings, Health::Degraded, Reason::Limit(status))
+                }
+                _ => report.push(readings, Health::Critical, Reason::Limit(status)),
+            }
+
+            let distance = thresholds.critical.map(|c| c.trigger - input.raw_value());
+            if let (Value::TemperatureInput(_), Some(distance)) = (input, distance) {
+                if status == Status::Normal && distance < policy.temperature_margin {
+                    report.push(readings, Health::Degraded, Reason::NearCritical(distance));
+                }
+            }
+
+            if policy.stopped_fans && input == Value::FanInput(0.0) {
+                report.push(readings, Health::Degraded, Reason::FanStopped);
+            }
+        }
+    }
+
+    if failures.len() > policy.tolerated_failures {
+        for reading in failures {
+            let reason = Reason::Unreadable(reading.sub_feature.clone());
+            report.push(core::slice::from_ref(reading), Health::Degraded, reason);
+        }
+    }
+    report
+}
+
+/// Return the level implied by a set alarm or fault, and whether it is
+/// a fault.
+fn flag(value: Value) -> Option<(Health, bool)> {
+    let (set, health, is_fault) = match value {
+        Value::VoltageCriticalAlarm(set)
+        | Value::VoltageLCriticalAlarm(set)
+        | Value::TemperatureCriticalAlarm(set)
+        | Value::TemperatureLCriticalAlarm(set)
+        | Value::TemperatureEmergencyAlarm(set)
+        | Value::PowerCriticalAlarm(set)
+        | Value::PowerLCriticalAlarm(set)
+        | Value::CurrentCriticalAlarm(set)
+        | Value::CurrentLCriticalAlarm(set) => (set, Health::Critical, false),
+
+        Value::VoltageAlarm(set)
+        | Value::VoltageMinimumAlarm(set)
+        | Value::VoltageMaximumAlarm(set)
+        | Value::FanAlarm(set)
+        | Value::FanMinimumAlarm(set)
+        | Value::FanMaximumAlarm(set)
+        | Value::TemperatureAlarm(set)
+        | Value::TemperatureMaximumAlarm(set)
+        | Value::TemperatureMinimumAlarm(set)
+        | Value::PowerAlarm(set)
+        | Value::PowerCapAlarm(set)
+        | Value::PowerMaximumAlarm(set)
+        | Value::PowerMinimumAlarm(set)
+        | Value::CurrentAlarm(set)
+        | Value::CurrentMinimumAlarm(set)
+        | Value::CurrentMaximumAlarm(set)
+        | Value::IntrusionAlarm(set) => (set, Health::Degraded, false),
+
+        Value::FanFault(set) | Value::TemperatureFault(set) => (set, Health::Degraded, true),
+
+        _ => return None,
+    };
+    set.then_some((health, is_fault))
+}
+
+/// Return the value if it is the input of its feature.
+fn input(value: Value) -> Option<Value> {
+    match value {
+        Value::VoltageInput(_)
+        | Value::FanInput(_)
+        | Value::TemperatureInput(_)
+        | Value::PowerInput(_)
+        | Value::CurrentInput(_) => Some(value),
+        _ => None,
+    }
+}
@@ -0,0 +1,153 @@
+#![cfg(test)]
+
+use crate::backend::Reading;
+use crate::health::{score, Cause, Health, Policy, Reason};
+use crate::snapshot::Snapshot;
+use crate::status::Status;
+use crate::value::Kind;
+
+const CHIP: &str = "nct6775-isa-0290";
+
+fn temperature(feature: &str, input: f64) -> Vec<Reading> {
+    vec![
+        Reading::for_test(
+            CHIP,
+            &format!("{feature}_input"),
+            Kind::TemperatureInput,
+            Some(input),
+        ),
+        Reading::for_test(
+            CHIP,
+            &format!("{feature}_max"),
+            Kind::TemperatureMaximum,
+            Some(80.0),
+        ),
+        Reading::for_test(
+            CHIP,
+            &format!("{feature}_crit"),
+            Kind::TemperatureCritical,
+            Some(100.0),
+        ),
+    ]
+}
+
+#[test]
+fn ok() {
+    let mut readings = temperature("temp1", 50.0);
+    readings.push(Reading::for_test(
+        CHIP,
+        "fan1_input",
+        Kind::FanInput,
+        Some(0.0),
+    ));
+    readings.push(Reading::for_test(
+        CHIP,
+        "fan2_alarm",
+        Kind::FanAlarm,
+        Some(0.0),
+    ));
+
+    let report = score(&Snapshot::new(readings), &Policy::default());
+    assert!(report.is_ok());
+    assert_eq!(report.health, Health::Ok);
+    assert!(report.causes.is_empty());
+}
+
+#[test]
+fn causes() {
+    let mut readings = temperature("temp1", 85.0);
+    readings.extend(temperature("temp2", 101.0));
+    readings.extend(temperature("temp3", 79.0));
+    readings.push(Reading::for_test(
+        CHIP,
+        "fan1_input",
+        Kind::FanInput,
+        Some(0.0),
+    ));
+    readings.push(Reading::for_test(
+        CHIP,
+        "fan2_fault",
+        Kind::FanFault,
+        Some(1.0),
+    ));
+    readings.push(Reading::for_test(
+        CHIP,
+        "in0_crit_alarm",
+        Kind::VoltageCriticalAlarm,
+        Some(1.0),
+    ));
+    readings.push(Reading::for_test(
+        CHIP,
+        "in1_input",
+        Kind::VoltageInput,
+        None,
+    ));
+
+    let policy = Policy {
+        temperature_margin: 25.0,
+        stopped_fans: true,
+        tolerated_failures: 0,
+    };
+    let report = score(&Snapshot::new(readings), &policy);
+    assert_eq!(report.health, Health::Critical);
+
+    let causes: Vec<_> = report
+        .causes
+        .iter()
+        .map(|cause| (cause.feature.as_str(), cause.health, cause.reason.clone()))
+        .collect();
+    assert_eq!(
+        causes,
+        [
+            ("temp1", Health::Degraded, Reason::Limit(Status::High)),
+            ("temp2", Health::Critical, Reason::Limit(Status::Critical)),
+            ("temp3", Health::Degraded, Reason::NearCritical(21.0)),
+            ("fan1", Health::Degraded, Reason::FanStopped),
+            ("fan2", Health::Degraded, Reason::Fault("fan2_fault".into())),
+            (
+                "in0",
+                Health::Critical,
+                Reason::Alarm("in0_crit_alarm".into())
+            ),
+            (
+                "in1",
+                Health::Degraded,
+                Reason::Unreadable("in1_input".into())
+            ),
+        ]
+    );
+
+    let cause = Cause {
+        chip: "nct6775-isa-0290".into(),
+        feature: "temp1".into(),
+        health: Health::Degraded,
+        reason: Reason::Limit(Status::High),
+    };
+    assert_eq!(
+        cause.to_string(),
+        "degraded: nct6775-isa-0290: temp1: value is high"
+    );
+}
+
+#[test]
+fn tolerated_failures() {
+    let readings = vec![
+        Reading::for_test(CHIP, "in0_input", Kind::VoltageInput, None),
+        Reading::for_test(CHIP, "in1_input", Kind::VoltageInput, None),
+    ];
+    let snapshot = Snapshot::new(readings);
+
+    let policy = Policy {
+        tolerated_failures: 2,
+        ..Policy::default()
+    };
+    assert!(score(&snapshot, &policy).is_ok());
+
+    let policy = Policy {
+        tolerated_failures: 1,
+        ..Policy::default()
+    };
+    let report = score(&snapshot, &policy);
+    assert_eq!(report.health, Health::Degraded);
+    assert_eq!(report.causes.len(), 2);
+}
@@ -1,4 +1,5 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(unsafe_op_in_unsafe_fn, missing_docs)]
 /*
 #![warn(clippy::all, clippy::pedantic, clippy::restriction)]
@@ -18,53 +19,332 @@
 )]
 */
 
+#[cfg(feature = "std")]
+pub mod access;
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "std")]
+pub mod backend;
+#[cfg(all(feature = "std", target_os = "linux"))]
 pub mod bus;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod cache;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod capabilities;
+#[cfg(all(feature = "std", target_os = "linux"))]
 pub mod chip;
+#[cfg(feature = "std")]
+pub mod chip_name;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(all(feature = "serde", feature = "std"))]
+pub mod compat;
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod control;
+#[cfg(all(feature = "daemon", target_os = "linux"))]
+pub mod daemon;
+#[cfg(feature = "std")]
 pub mod errors;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod events;
+#[cfg(feature = "std")]
+pub mod exporter;
+#[cfg(all(feature = "std", target_os = "linux"))]
 pub mod feature;
+#[cfg(feature = "std")]
+pub mod health;
+pub mod model;
+#[cfg(feature = "std")]
+pub mod naming;
+#[cfg(feature = "std")]
+pub mod power;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod presets;
+#[cfg(feature = "std")]
+pub mod probe;
+#[cfg(feature = "std")]
+pub mod quirks;
+#[cfg(feature = "std")]
+pub mod redact;
+#[cfg(feature = "std")]
+pub mod remap;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(all(feature = "sampler", target_os = "linux"))]
+pub mod sampler;
+#[cfg(feature = "std")]
+pub mod selector;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod sensor_set;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod shared;
+#[cfg(feature = "simulation")]
+pub mod simulation;
+#[cfg(feature = "std")]
+pub mod snapshot;
+#[cfg(feature = "snmp")]
+pub mod snmp;
+#[cfg(all(feature = "std", not(target_os = "linux")))]
+mod stub;
+#[cfg(all(feature = "std", target_os = "linux"))]
 pub mod sub_feature;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod system;
+#[cfg(all(any(test, feature = "testing"), feature = "std", target_os = "linux"))]
+pub mod testing;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod typed;
+#[cfg(all(feature = "std", target_os = "linux"))]
 mod utils;
-pub mod value;
 
-#[cfg(test)]
+pub use crate::model::status;
+pub use crate::model::value;
+
+#[cfg(all(test, feature = "std", target_os = "linux"))]
 mod tests;
 
+#[cfg(all(feature = "std", target_os = "linux"))]
 use core::ffi::CStr;
+#[cfg(all(feature = "std", target_os = "linux"))]
 use core::marker::PhantomData;
-use core::ptr;
+#[cfg(all(feature = "std", target_os = "linux"))]
 use core::sync::atomic;
+#[cfg(all(feature = "std", target_os = "linux"))]
 use core::sync::atomic::AtomicBool;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use core::time::Duration;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use core::{fmt, ptr};
+#[cfg(all(feature = "std", target_os = "linux"))]
 use std::fs::File;
+#[cfg(all(feature = "std", target_os = "linux"))]
 use std::io;
+#[cfg(all(feature = "std", target_os = "linux"))]
 use std::os::raw::c_short;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(all(feature = "std", target_os = "linux"))]
 use std::path::PathBuf;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use std::sync::{Arc, Mutex, PoisonError};
 
+#[cfg(all(feature = "std", target_os = "linux"))]
 use sensors_sys::*;
 
-use crate::errors::{Error, Listener, Reporter, Result};
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::audit::AuditSink;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::cache::{DisplayCache, FeatureCache};
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::config::{ConfigLayer, LayeredConfig};
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::control::RestoreGuard;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::errors::{
+    ConfigSource, Diagnostic, DiagnosticCollector, Error, InitFailure, Listener, ListenerContext,
+    Reporter, Result,
+};
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::selector::Selector;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::shared::SharedSensors;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::snapshot::owned::ChipSnapshot;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use crate::snapshot::Snapshot;
+#[cfg(all(feature = "std", target_os = "linux"))]
 use crate::utils::{api_access_lock, LibCFileStream};
 
+#[cfg(all(feature = "std", target_os = "linux"))]
 pub use crate::bus::Bus;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use crate::capabilities::{Capabilities, ReadOnlySensors};
+#[cfg(all(feature = "std", target_os = "linux"))]
 pub use crate::chip::{Chip, ChipRef};
+#[cfg(all(feature = "std", target_os = "linux"))]
 pub use crate::feature::FeatureRef;
-pub use crate::sub_feature::SubFeatureRef;
+#[cfg(all(feature = "std", not(target_os = "linux")))]
+pub use crate::stub::{InitReport, Initializer, LMSensors, SharedSensors};
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use crate::sub_feature::{SubFeatureRef, Timed};
 pub use crate::value::Value;
 
+#[cfg(all(feature = "std", target_os = "linux"))]
 /// LM sensors library initializer, producing an instance of [`LMSensors`].
 #[derive(Debug, Default)]
 pub struct Initializer {
     error_listener: Option<Box<dyn Listener>>,
     config_path: Option<PathBuf>,
     config_file: Option<File>,
+    config_layers: Option<Vec<ConfigLayer>>,
+    deny_chip_sets: bool,
+    audit_sink: Option<Box<dyn AuditSink>>,
+    tag: Option<String>,
+    wait_for_release: Option<Duration>,
 }
 
+#[cfg(all(feature = "std", target_os = "linux"))]
 /// LM sensors library instance, producing instances of [`Chip`]s, [`Bus`]es, etc.
 #[derive(Debug)]
 pub struct LMSensors {
     error_reporter: Reporter,
+    display_cache: DisplayCache,
+    feature_cache: FeatureCache,
+    audit_sink: Option<Box<dyn AuditSink>>,
+    config_errors: Vec<Diagnostic>,
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+/// Result of [`Initializer::initialize_with_report`].
+#[derive(Debug)]
+pub struct InitReport {
+    /// Loaded and initialized LM sensors library.
+    pub sensors: LMSensors,
+    /// Configuration errors reported during initialization, in order.
+    pub config_diagnostics: Vec<Diagnostic>,
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+/// Steps performed by [`LMSensors::shutdown`], in order.
+///
+/// 1. Shutdown hooks are called, *e.g.,* to stop samplers and flush recorders.
+/// 2. Restore guards write back the values they recorded.
+/// 3. Optionally, all armed restore guards of the process write back
+///    their values.
+/// 4. The LM sensors library is cleaned up.
+#[derive(Default)]
+#[must_use]
+pub struct ShutdownOptions {
+    hooks: Vec<Box<dyn FnOnce() -> Result<()>>>,
+    restore_guards: Vec<RestoreGuard>,
+    emergency_restore: bool,
 }
 
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl ShutdownOptions {
+    /// Add a hook to be called at the start of the shutdown,
+    /// *e.g.,* to stop a sampler or flush a recorder.
+    ///
+    /// Hooks are called in the order they were added.
+    pub fn hook(mut self, hook: impl FnOnce() -> Result<()> + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Add a restore guard that writes back its recorded value after
+    /// all hooks were called.
+    ///
+    /// Restore guards are restored in the reverse order they were added.
+    pub fn restore(mut self, guard: RestoreGuard) -> Self {
+        self.restore_guards.push(guard);
+        self
+    }
+
+    /// Set whether all armed restore guards of the process write back
+    /// their recorded values, after the restore guards added to these options.
+    ///
+    /// See: [`control::emergency_restore`].
+    pub fn emergency_restore(self, emergency_restore: bool) -> Self {
+        Self {
+            emergency_restore,
+            ..self
+        }
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl fmt::Debug for ShutdownOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownOptions")
+            .field("hooks", &self.hooks.len())
+            .field("restore_guards", &self.restore_guards)
+            .field("emergency_restore", &self.emergency_restore)
+            .finish()
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
 impl Initializer {
+    /**
+    Return an initializer loading no configuration at all,
+    *e.g.,* for tests.
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::isolated().initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn isolated() -> Self {
+        Self::default().config_path("/dev/null")
+    }
+
+    /**
+    Return an initializer loading the default configuration files,
+    like `sensors(1)` does.
+
+    The default configuration files are typically `/etc/sensors3.conf`
+    or `/etc/sensors.conf`, then all files in `/etc/sensors.d`.
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::default_paths().initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn default_paths() -> Self {
+        Self::default()
+    }
+
+    /**
+    Return an initializer loading the default configuration files,
+    and denying execution of their set statements.
+
+    See: [`Initializer::allow_chip_sets`].
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::minimal().initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn minimal() -> Self {
+        Self::default().allow_chip_sets(false)
+    }
+
+    /**
+    Set whether set statements of the configuration can be executed.
+    This is allowed by default.
+
+    If not allowed, then [`ChipRef::do_chip_sets`] fails.
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::default()
+        .allow_chip_sets(false)
+        .initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn allow_chip_sets(self, allowed: bool) -> Self {
+        Self {
+            deny_chip_sets: !allowed,
+            ..self
+        }
+    }
+
     /**
     Set the path of the configuration file to be read during LM sensors
     library initialization.
@@ -81,9 +361,10 @@ impl Initializer {
     #[must_use]
     pub fn config_path(self, path: impl Into<PathBuf>) -> Self {
         Self {
-            error_listener: self.error_listener,
             config_path: Some(path.into()),
             config_file: None,
+            config_layers: None,
+            ..self
         }
     }
 
@@ -105,15 +386,54 @@ impl Initializer {
     #[must_use]
     pub fn config_file(self, file: File) -> Self {
         Self {
-            error_listener: self.error_listener,
             config_path: None,
             config_file: Some(file),
+            config_layers: None,
+            ..self
+        }
+    }
+
+    /**
+    Set the configuration layers to be concatenated, in order, and used
+    during LM sensors library initialization.
+
+    Statements of later layers take precedence over statements of earlier
+    layers. Configuration errors are reported at their location in their
+    layer, and the error listener receives the concatenated configuration
+    in [`ConfigSource::Layered`].
+
+    See: [`config`].
+
+    # Example
+
+    ```rust
+    use lm_sensors::config::ConfigLayer;
+
+    let sensors = lm_sensors::Initializer::default()
+        .config_layers([
+            ConfigLayer::Default,
+            ConfigLayer::text("overrides", "chip \"coretemp-*\"\n    label temp1 \"Package\"\n"),
+        ])
+        .initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn config_layers(self, layers: impl IntoIterator<Item = ConfigLayer>) -> Self {
+        Self {
+            config_path: None,
+            config_file: None,
+            config_layers: Some(layers.into_iter().collect()),
+            ..self
         }
     }
 
     /**
     Set the error listener to be used during LM sensors library initialization.
 
+    The listener can be replaced after initialization by
+    [`LMSensors::set_error_listener`].
+
     # Example
 
     ```rust
@@ -148,8 +468,84 @@ impl Initializer {
     pub fn error_listener(self, listener: Box<dyn Listener>) -> Self {
         Self {
             error_listener: Some(listener),
-            config_path: self.config_path,
-            config_file: self.config_file,
+            ..self
+        }
+    }
+
+    /**
+    Set the tag of the initialized instance, passed to error listeners
+    in a [`ListenerContext`], *e.g.,* to tell which service failed to
+    load its configuration.
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::default()
+        .tag("fan-control")
+        .initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn tag(self, tag: impl Into<String>) -> Self {
+        Self {
+            tag: Some(tag.into()),
+            ..self
+        }
+    }
+
+    /**
+    Set the sink notified of every write performed through the
+    [`Capabilities`] of the initialized instance.
+
+    See: [`audit`].
+
+    # Example
+
+    ```no_run
+    use lm_sensors::audit::FileSink;
+
+    let sensors = lm_sensors::Initializer::default()
+        .audit_sink(Box::new(FileSink::open("/var/log/lm-sensors-audit.log")?))
+        .initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn audit_sink(self, sink: Box<dyn AuditSink>) -> Self {
+        Self {
+            audit_sink: Some(sink),
+            ..self
+        }
+    }
+
+    /**
+    Set how long initialization waits for an existing instance of the LM
+    sensors library to be dropped, instead of failing immediately.
+
+    Only one instance can exist at a time. By default, initialization fails
+    with [`io::ErrorKind::AlreadyExists`] if another instance exists, which
+    makes components lazily initializing the library at the same time fail
+    depending on their ordering. With a timeout, initialization blocks until
+    the other instance is dropped, and fails as before only if the timeout
+    elapses first.
+
+    # Example
+
+    ```rust
+    use std::time::Duration;
+
+    let sensors = lm_sensors::Initializer::default()
+        .wait_for_release(Duration::from_secs(5))
+        .initialize()?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn wait_for_release(self, timeout: Duration) -> Self {
+        Self {
+            wait_for_release: Some(timeout),
+            ..self
         }
     }
 
@@ -164,18 +560,43 @@ impl Initializer {
     ```
     */
     pub fn initialize(self) -> Result<LMSensors> {
-        let config_file_fp = match (self.config_path, self.config_file) {
-            (None, None) => None,
-            (None, Some(config_file)) => LibCFileStream::from_file(config_file).map(Some)?,
-            (Some(config_path), None) => LibCFileStream::from_path(&config_path).map(Some)?,
+        let (config_file_fp, config_source) = match (self.config_path, self.config_file) {
+            (None, None) if self.config_layers.is_some() => {
+                let layers = self.config_layers.unwrap_or_default();
+                let config = Arc::new(LayeredConfig::load(layers)?);
+                let fp = LibCFileStream::from_bytes(config.text().as_bytes())?;
+                (Some(fp), ConfigSource::Layered(config))
+            }
+            (None, None) => (None, ConfigSource::Default),
+            (None, Some(config_file)) => {
+                let fp = LibCFileStream::from_file(config_file)?;
+                (Some(fp), ConfigSource::File)
+            }
+            (Some(config_path), None) => {
+                let fp = LibCFileStream::from_path(&config_path)?;
+                (Some(fp), ConfigSource::Path(config_path))
+            }
             _ => unreachable!(),
         };
 
+        let context = ListenerContext {
+            config_source,
+            tag: self.tag,
+        };
+
         let error_listener = self
             .error_listener
             .map_or_else(ptr::null_mut, |v| Box::into_raw(Box::new(v)));
 
-        let result = LMSensors::new(config_file_fp, error_listener);
+        let options = InitOptions {
+            deny_chip_sets: self.deny_chip_sets,
+            wait_for_release: self.wait_for_release,
+        };
+        let result =
+            LMSensors::new(config_file_fp, error_listener, context, options).map(|mut sensors| {
+                sensors.audit_sink = self.audit_sink;
+                sensors
+            });
 
         if result.is_err() && !error_listener.is_null() {
             // Safety: error_listener was allocated locally and is now unused.
@@ -183,10 +604,110 @@ impl Initializer {
         }
         result
     }
+
+    /**
+    Return an instance of a loaded and initialized LM sensors library,
+    along with all configuration errors reported during initialization.
+
+    Configuration errors are collected instead of being reported to stderr.
+    If an error listener was set, then it is still notified of all errors.
+    If initialization fails, then the configuration errors reported until
+    then are returned along with the error, in an [`InitFailure`].
+
+    # Example
+
+    ```rust
+    let report = lm_sensors::Initializer::default().initialize_with_report()?;
+    for diagnostic in &report.config_diagnostics {
+        eprintln!("[ERROR] lm-sensors configuration: {diagnostic}.");
+    }
+    let sensors = report.sensors;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    pub fn initialize_with_report(self) -> core::result::Result<InitReport, InitFailure> {
+        let diagnostics = Arc::new(Mutex::new(Vec::new()));
+        let collector = DiagnosticCollector::new(Arc::clone(&diagnostics), self.error_listener);
+
+        let result = Self {
+            error_listener: Some(Box::new(collector)),
+            ..self
+        }
+        .initialize();
+
+        let config_diagnostics =
+            core::mem::take(&mut *diagnostics.lock().unwrap_or_else(PoisonError::into_inner));
+
+        match result {
+            Ok(sensors) => Ok(InitReport {
+                sensors,
+                config_diagnostics,
+            }),
+
+            Err(error) => Err(InitFailure {
+                error,
+                config_diagnostics,
+            }),
+        }
+    }
+
+    /**
+    Return the instance of the LM sensors library shared in this process,
+    or a new shared instance if none exists.
+
+    Independent components of an application calling this share the same
+    instance, instead of failing with [`io::ErrorKind::AlreadyExists`],
+    even if they run in different threads. The options of this initializer
+    are only used if a new instance is created. The instance is dropped when
+    its last handle is dropped. If that happens in another thread while
+    this is called, then this waits for it to be dropped, *e.g.,* for the
+    duration set by [`Initializer::wait_for_release`].
+
+    Shared instances have no error listener and no audit sink, so this fails
+    with [`io::ErrorKind::InvalidInput`] if either is set.
+    If an instance that is not shared exists, then this fails like
+    [`Initializer::initialize`].
+
+    See: [`LMSensors::into_shared`].
+
+    # Example
+
+    ```rust
+    let sensors1 = lm_sensors::Initializer::default().try_initialize_or_attach()?;
+    let sensors2 = lm_sensors::Initializer::default().try_initialize_or_attach()?;
+    assert!(sensors1.ptr_eq(&sensors2));
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    pub fn try_initialize_or_attach(self) -> Result<SharedSensors> {
+        if self.error_listener.is_some() || self.audit_sink.is_some() {
+            let message = "shared instances have no error listener and no audit sink";
+            let err = io::Error::new(io::ErrorKind::InvalidInput, message);
+            return Err(Error::from_io(
+                "Initializer::try_initialize_or_attach()",
+                err,
+            ));
+        }
+        SharedSensors::attach(self)
+    }
+}
+
+/// Options of [`LMSensors::new`].
+#[cfg(all(feature = "std", target_os = "linux"))]
+#[derive(Debug)]
+struct InitOptions {
+    deny_chip_sets: bool,
+    wait_for_release: Option<Duration>,
 }
 
-static INITIALIZED: AtomicBool = AtomicBool::new(false);
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub(crate) static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+/// Set if set statements of the configuration must not be executed.
+pub(crate) static CHIP_SETS_DENIED: AtomicBool = AtomicBool::new(false);
 
+#[cfg(all(feature = "std", target_os = "linux"))]
 impl LMSensors {
     /// Returns the version of the LM sensors library,
     /// if available and valid UTF-8.
@@ -205,6 +726,120 @@ impl LMSensors {
         (!version.is_null()).then(|| unsafe { CStr::from_ptr(version) })
     }
 
+    /**
+    Replace the error listener of this instance by the given one,
+    and return the replaced listener.
+
+    Specifying `None` for the `listener` restores the default listener,
+    reporting errors to `stderr`. `None` is returned if the replaced listener
+    was the default one.
+
+    This fails with [`Error::Reentrant`] if called from within a listener.
+
+    # Example
+
+    ```rust
+    #[derive(Debug)]
+    struct EL;
+
+    impl lm_sensors::errors::Listener for EL {
+        fn on_lm_sensors_config_error(&self, error: &str,
+            _file_name: Option<&std::path::Path>, _line_number: usize)
+        {
+            eprintln!("[ERROR] lm-sensors config: {}", error);
+        }
+
+        fn on_lm_sensors_fatal_error(&self, error: &str, procedure: &str) {
+            eprintln!("[FATAL] lm-sensors: {} @{}", error, procedure);
+        }
+    }
+
+    let sensors = lm_sensors::Initializer::default().initialize()?;
+    let previous = sensors.swap_error_listener(Some(Box::new(EL)))?;
+    assert!(previous.is_none());
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    pub fn swap_error_listener(
+        &self,
+        listener: Option<Box<dyn Listener>>,
+    ) -> Result<Option<Box<dyn Listener>>> {
+        let listener = listener.map_or_else(ptr::null_mut, |v| Box::into_raw(Box::new(v)));
+
+        let previous = match api_access_lock().lock() {
+            Ok(guard) => self.error_reporter.swap_listener(listener, &guard),
+            Err(err) => {
+                if !listener.is_null() {
+                    // Safety: listener was allocated locally and is now unused.
+                    drop(unsafe { Box::from_raw(listener) });
+                }
+                return Err(err);
+            }
+        };
+
+        // Safety: previous was allocated before, and the call backs cannot
+        // use it anymore, as the API access lock was released.
+        Ok((!previous.is_null()).then(|| *unsafe { Box::from_raw(previous) }))
+    }
+
+    /**
+    Set the error listener of this instance, and return the replaced listener,
+    if it was not the default one.
+
+    This allows switching logging sinks, *e.g.,* after daemonizing or
+    rotating log files, without cleaning up the library.
+
+    See: [`LMSensors::swap_error_listener`].
+
+    # Example
+
+    ```rust
+    #[derive(Debug)]
+    struct EL(std::path::PathBuf);
+
+    impl lm_sensors::errors::Listener for EL {
+        fn on_lm_sensors_config_error(&self, error: &str,
+            _file_name: Option<&std::path::Path>, _line_number: usize)
+        {
+            eprintln!("[ERROR] lm-sensors config ({}): {}", self.0.display(), error);
+        }
+
+        fn on_lm_sensors_fatal_error(&self, error: &str, procedure: &str) {
+            eprintln!("[FATAL] lm-sensors ({}): {} @{}", self.0.display(), error, procedure);
+        }
+    }
+
+    let sensors = lm_sensors::Initializer::default()
+        .error_listener(Box::new(EL("/var/log/sensors.log".into())))
+        .initialize()?;
+
+    // The log file was rotated.
+    let previous = sensors.set_error_listener(Box::new(EL("/var/log/sensors.log.1".into())))?;
+    assert!(previous.is_some());
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    pub fn set_error_listener(
+        &self,
+        listener: Box<dyn Listener>,
+    ) -> Result<Option<Box<dyn Listener>>> {
+        self.swap_error_listener(Some(listener))
+    }
+
+    /// Return the cache of formatted chip names, feature labels and
+    /// adapter names of this instance.
+    #[must_use]
+    pub fn display_cache(&self) -> &DisplayCache {
+        &self.display_cache
+    }
+
+    /// Return the cache of the features of chips, and of the sub-features
+    /// of features, of this instance.
+    #[must_use]
+    pub fn feature_cache(&self) -> &FeatureCache {
+        &self.feature_cache
+    }
+
     /// Return a new instance of [`ChipRef`], given a shared reference
     /// to a raw chip.
     ///
@@ -232,6 +867,9 @@ impl LMSensors {
     }
 
     /// Return a new instance of [`Chip`], given a chip name.
+    ///
+    /// This fails with [`Error::InvalidChipName`] if the chip name is
+    /// malformed, reporting which part of it is malformed.
     pub fn new_chip<'a>(&'a self, name: &str) -> Result<Chip<'a>> {
         Chip::new(name)
     }
@@ -296,6 +934,273 @@ impl LMSensors {
         SubFeatureRef { feature, raw }
     }
 
+    /// Shut down this instance of the LM sensors library, in a well-defined
+    /// order described by [`ShutdownOptions`].
+    ///
+    /// All steps are performed even if some of them fail.
+    /// The first failure is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let sensors = lm_sensors::Initializer::default().initialize()?;
+    /// let options = lm_sensors::ShutdownOptions::default()
+    ///     .hook(|| {
+    ///         println!("Stopping...");
+    ///         Ok(())
+    ///     })
+    ///     .emergency_restore(true);
+    /// sensors.shutdown(options)?;
+    /// # Ok::<(), lm_sensors::errors::Error>(())
+    /// ```
+    pub fn shutdown(self, options: ShutdownOptions) -> Result<()> {
+        let mut result = Ok(());
+
+        for hook in options.hooks {
+            let r = hook();
+            if result.is_ok() {
+                result = r;
+            }
+        }
+
+        for guard in options.restore_guards.into_iter().rev() {
+            let r = guard.restore();
+            if result.is_ok() {
+                result = r;
+            }
+        }
+
+        if options.emergency_restore {
+            control::emergency_restore();
+        }
+
+        drop(self);
+        result
+    }
+
+    /**
+    Return a snapshot of the readings of the sub-features selected by
+    the given selector.
+
+    Chips and sub-features that are not selected are never read, which
+    matters on systems with slow devices, *e.g.,* on SMBus.
+
+    # Example
+
+    ```rust
+    use lm_sensors::selector::Selector;
+
+    let sensors = lm_sensors::Initializer::default().initialize()?;
+    let selector: Selector = "coretemp-isa-0000/temp1_input, nct6775-isa-0290/fan?_input".parse()?;
+    for reading in sensors.snapshot_where(&selector).readings {
+        println!("{}: {}: {:?}", reading.chip, reading.sub_feature, reading.value());
+    }
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn snapshot_where(&self, selector: &Selector) -> Snapshot {
+        Snapshot::new(backend::read_lm_sensors_where(self, selector))
+    }
+
+    /**
+    Return owned snapshots of all chips, with the names, labels and types
+    of their features and sub-features, and the values of readable
+    sub-features.
+
+    Snapshots do not borrow this instance, unlike [`ChipRef`]s, so that
+    they can be stored in structures of applications.
+
+    # Example
+
+    ```rust
+    let sensors = lm_sensors::Initializer::default().initialize()?;
+    let chips = sensors.chip_snapshots();
+    drop(sensors);
+
+    for chip in &chips {
+        for feature in &chip.features {
+            let label = feature.label.as_deref().unwrap_or(&feature.name);
+            for sub_feature in &feature.sub_features {
+                println!("{}: {label}: {}: {:?}", chip.name, sub_feature.name, sub_feature.value());
+            }
+        }
+    }
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[must_use]
+    pub fn chip_snapshots(&self) -> Vec<ChipSnapshot> {
+        self.chip_iter(None).map(ChipSnapshot::from).collect()
+    }
+
+    /**
+    Return this instance, reference-counted so that it can be shared
+    between threads, along with its chips, features and sub-features.
+
+    The error listener and the audit sink of this instance might not be
+    usable from other threads, so they are dropped. Errors are reported
+    by the default listener afterwards. Shared instances cannot write
+    to sensors, so they do not need an audit sink.
+
+    This fails with [`Error::Reentrant`] if called from within a listener.
+
+    # Example
+
+    ```rust
+    use std::thread;
+
+    let sensors = lm_sensors::Initializer::default().initialize()?.into_shared()?;
+    let sub_features: Vec<_> = sensors
+        .chip_iter()
+        .flat_map(|chip| chip.feature_iter().collect::<Vec<_>>())
+        .flat_map(|feature| feature.sub_feature_iter().collect::<Vec<_>>())
+        .collect();
+
+    thread::spawn(move || {
+        for sub_feature in sub_features {
+            println!("{sub_feature}: {:?}", sub_feature.value());
+        }
+    })
+    .join()
+    .unwrap();
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    pub fn into_shared(mut self) -> Result<SharedSensors> {
+        drop(self.swap_error_listener(None)?);
+        self.audit_sink = None;
+        Ok(SharedSensors::new(self))
+    }
+
+    /**
+    Call the given function with this instance reinitialized with the given
+    configuration overlay, then reinitialize it with its previous
+    configuration, even if the function panics.
+
+    The overlay is appended to the configuration of this instance, so that
+    its statements take precedence, *e.g.,* to experiment with compute
+    statements or labels in tools. Configuration errors of the overlay are
+    reported to the error listener, in the `overlay` layer, and published
+    as [`Event::ConfigError`](crate::events::Event::ConfigError)s.
+
+    Values written while the overlay is active, *e.g.,* by set statements,
+    are not restored. This fails if this instance was initialized with
+    [`Initializer::config_file`], whose contents cannot be read again.
+    If the previous configuration cannot be restored, then this fails with
+    [`Error::ConfigNotRestored`], after propagating any panic of the
+    function.
+
+    # Example
+
+    ```rust
+    let mut sensors = lm_sensors::Initializer::isolated().initialize()?;
+    let overlay = "chip \"coretemp-*\"\n    compute temp1 @*2, @/2\n";
+    let chip_count = sensors.with_config_overlay(overlay, |sensors| {
+        sensors.chip_iter(None).count()
+    })?;
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    pub fn with_config_overlay<R>(
+        &mut self,
+        overlay: &str,
+        f: impl FnOnce(&Self) -> R,
+    ) -> Result<R> {
+        let context = self
+            .error_reporter
+            .current_context(&api_access_lock().lock()?);
+
+        let (base_layer, base_stream) = match &context.config_source {
+            ConfigSource::Default => (ConfigLayer::Default, None),
+            ConfigSource::Path(path) => (
+                ConfigLayer::path(path),
+                Some(LibCFileStream::from_path(path)?),
+            ),
+            ConfigSource::Layered(config) => (
+                ConfigLayer::text("base", config.text()),
+                Some(LibCFileStream::from_bytes(config.text().as_bytes())?),
+            ),
+            ConfigSource::File => {
+                let message = "configuration file cannot be read again";
+                let err = io::Error::new(io::ErrorKind::Unsupported, message);
+                return Err(Error::from_io("LMSensors::with_config_overlay()", err));
+            }
+        };
+
+        let config = LayeredConfig::load([base_layer, ConfigLayer::text("overlay", overlay)])?;
+        let overlay_stream = LibCFileStream::from_bytes(config.text().as_bytes())?;
+        let overlay_context = ListenerContext {
+            config_source: ConfigSource::Layered(Arc::new(config)),
+            tag: context.tag.clone(),
+        };
+
+        let result = self
+            .reinitialize(Some(&overlay_stream), overlay_context)
+            .map(|()| panic::catch_unwind(AssertUnwindSafe(|| f(self))));
+        let restored = self.reinitialize(base_stream.as_ref(), context);
+
+        let result = match result {
+            Ok(Ok(r)) => Ok(r),
+            Ok(Err(payload)) => panic::resume_unwind(payload),
+            Err(err) => Err(err),
+        };
+        match restored {
+            Ok(()) => result,
+            Err(err) => Err(Error::ConfigNotRestored {
+                source: Box::new(err),
+            }),
+        }
+    }
+
+    /// Clean up the LM sensors library, then initialize it again with
+    /// the given configuration, reporting errors in the given context.
+    ///
+    /// Configuration errors are published, and replace those received by
+    /// new subscriptions.
+    ///
+    /// See: [`sensors_cleanup`], [`sensors_init`].
+    fn reinitialize(
+        &mut self,
+        config_file_stream: Option<&LibCFileStream>,
+        context: ListenerContext,
+    ) -> Result<()> {
+        let config_file_fp = config_file_stream.map_or(ptr::null_mut(), LibCFileStream::as_mut_ptr);
+
+        let locked_self = api_access_lock().lock()?;
+
+        // Cached names and features refer to the previous initialization.
+        self.display_cache.invalidate();
+        self.feature_cache.invalidate();
+        self.error_reporter.replace_context(context, &locked_self);
+
+        // Safety: this instance is borrowed exclusively, so no references
+        // to chips, features or sub-features of the library exist.
+        let r = unsafe {
+            sensors_cleanup();
+            sensors_init(config_file_fp.cast())
+        };
+        let listener_panicked = Reporter::take_listener_panic();
+        let config_errors = events::take_config_errors();
+
+        drop(locked_self); // Unlock early.
+
+        for diagnostic in &config_errors {
+            events::publish(&events::Event::ConfigError(diagnostic.clone()));
+        }
+        self.config_errors = config_errors;
+
+        if listener_panicked {
+            Err(Error::ListenerPanicked {
+                operation: "sensors_init()",
+            })
+        } else if r == 0 {
+            Ok(())
+        } else {
+            Err(Error::from_lm_sensors("sensors_init()", r))
+        }
+    }
+
     /// Return an iterator which yields all chips matching the given pattern.
     ///
     /// Specifying `None` for the `match_pattern` yields all chips.
@@ -310,14 +1215,22 @@ impl LMSensors {
     fn new(
         config_file_stream: Option<LibCFileStream>,
         error_listener: *mut Box<dyn Listener>,
+        context: ListenerContext,
+        options: InitOptions,
     ) -> Result<Self> {
         let config_file_fp = config_file_stream
             .as_ref()
             .map_or(ptr::null_mut(), LibCFileStream::as_mut_ptr);
 
-        let locked_self = api_access_lock().lock()?;
+        let mut locked_self = api_access_lock().lock()?;
+
+        let initialized = || INITIALIZED.load(atomic::Ordering::Acquire);
+        let already_exists = match options.wait_for_release {
+            Some(timeout) if initialized() => locked_self.wait_while(timeout, initialized),
+            _ => initialized(),
+        };
 
-        if INITIALIZED.load(atomic::Ordering::Acquire) {
+        if already_exists {
             drop(locked_self); // Unlock early.
 
             let err = io::ErrorKind::AlreadyExists.into();
@@ -325,38 +1238,63 @@ impl LMSensors {
         }
 
         // We're creating the only instance.
-        let error_reporter = Reporter::new(error_listener);
+        let error_reporter = Reporter::new(error_listener, context, &locked_self);
 
         // Safety: this is assumed to be safe.
         let r = unsafe { sensors_init(config_file_fp.cast()) };
-        if r == 0 {
+        let listener_panicked = Reporter::take_listener_panic();
+        let config_errors = events::take_config_errors();
+
+        if r == 0 && !listener_panicked {
+            CHIP_SETS_DENIED.store(options.deny_chip_sets, atomic::Ordering::Release);
             INITIALIZED.store(true, atomic::Ordering::Release);
 
-            return Ok(Self { error_reporter });
+            return Ok(Self {
+                error_reporter,
+                display_cache: DisplayCache::default(),
+                feature_cache: FeatureCache::default(),
+                audit_sink: None,
+                config_errors,
+            });
         }
 
-        // sensors_init() failed.
+        if r == 0 {
+            // Safety: sensors_init() succeeded, so this is assumed to be safe.
+            unsafe { sensors_cleanup() }
+        }
+
+        // sensors_init() failed, or the error listener panicked.
         // Restore previous global state.
-        error_reporter.restore();
+        error_reporter.restore(&locked_self);
 
         drop(locked_self); // Unlock early.
 
-        Err(Error::from_lm_sensors("sensors_init()", r))
+        if listener_panicked {
+            Err(Error::ListenerPanicked {
+                operation: "sensors_init()",
+            })
+        } else {
+            Err(Error::from_lm_sensors("sensors_init()", r))
+        }
     }
 }
 
+#[cfg(all(feature = "std", target_os = "linux"))]
 impl Drop for LMSensors {
     /// See: [`sensors_cleanup`].
     fn drop(&mut self) {
         let error_listener = api_access_lock()
             .lock()
-            .map(|_guard| {
+            .map(|guard| {
                 // Safety: this is assumed to be safe.
                 unsafe { sensors_cleanup() }
 
-                let error_listener = self.error_reporter.restore();
+                let error_listener = self.error_reporter.restore(&guard);
 
                 INITIALIZED.store(false, atomic::Ordering::Release);
+                CHIP_SETS_DENIED.store(false, atomic::Ordering::Release);
+                events::unsubscribe_all();
+                api_access_lock().notify_released();
 
                 error_listener
             })
@@ -0,0 +1,12 @@
+//! Data model of sensors and actuators, usable without `std`.
+//!
+//! This is independent of the LM sensors library, *e.g.,* for firmware or
+//! agents that evaluate values collected elsewhere.
+//! Without the `std` feature, this is the only module of this crate.
+
+#[cfg(feature = "compact")]
+pub mod compact;
+pub mod feature;
+pub mod raw;
+pub mod status;
+pub mod value;
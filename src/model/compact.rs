@@ -0,0 +1,121 @@
+//! Compact binary encoding of values and types, without `std`.
+//!
+//! This suits agents shipping readings over constrained links, *e.g.,*
+//! from small ARM boards to a gateway, where textual or self-describing
+//! formats are too expensive. Encodings have a fixed length, are
+//! little-endian, and carry no schema:
+//!
+//! - a feature [`Kind`](feature::Kind) takes one byte, its raw type, or
+//!   `0xFF` for [`Unknown`](feature::Kind::Unknown),
+//! - a sub-feature [`Kind`](value::Kind) takes two bytes, its raw type, or
+//!   `0xFFFF` for [`Unknown`](value::Kind::Unknown),
+//! - a [`Value`] takes six bytes, its kind followed by its raw value as
+//!   a 32-bit float.
+//!
+//! Raw values are narrowed to 32-bit floats, which keeps about 7 significant
+//! digits, more than sensors report.
+//!
+//! Events carry text, *e.g.,* diagnostics, and are serialized with the
+//! `serde` feature instead.
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::c_uint;
+
+use super::feature;
+use super::value::{self, Value};
+
+/// Type encoded in a fixed number of bytes.
+pub trait Compact: Sized {
+    /// Number of bytes of the encoding.
+    const ENCODED_LEN: usize;
+
+    /// Write the encoding of this instance to the start of the given buffer,
+    /// and return the number of bytes written, or `None` if the buffer is
+    /// too short.
+    fn encode(&self, buffer: &mut [u8]) -> Option<usize>;
+
+    /// Return the instance encoded at the start of the given buffer, and
+    /// the number of bytes read, or `None` if the buffer is too short, or
+    /// does not hold a valid encoding.
+    fn decode(buffer: &[u8]) -> Option<(Self, usize)>;
+}
+
+/// Encoding of unknown feature types.
+const UNKNOWN_FEATURE: u8 = u8::MAX;
+
+/// Encoding of unknown sub-feature types.
+const UNKNOWN_SUB_FEATURE: u16 = u16::MAX;
+
+impl Compact for feature::Kind {
+    const ENCODED_LEN: usize = 1;
+
+    fn encode(&self, buffer: &mut [u8]) -> Option<usize> {
+        let encoded = u8::try_from(self.as_raw())
+            .ok()
+            .filter(|&raw| raw != UNKNOWN_FEATURE)
+            .unwrap_or(UNKNOWN_FEATURE);
+        write(buffer, &[encoded])
+    }
+
+    fn decode(buffer: &[u8]) -> Option<(Self, usize)> {
+        let [encoded] = read::<1>(buffer)?;
+        let kind = match encoded {
+            UNKNOWN_FEATURE => Self::Unknown,
+            raw => Self::from_raw(c_uint::from(raw))?,
+        };
+        Some((kind, Self::ENCODED_LEN))
+    }
+}
+
+impl Compact for value::Kind {
+    const ENCODED_LEN: usize = 2;
+
+    fn encode(&self, buffer: &mut [u8]) -> Option<usize> {
+        let encoded = u16::try_from(self.as_raw())
+            .ok()
+            .filter(|&raw| raw != UNKNOWN_SUB_FEATURE)
+            .unwrap_or(UNKNOWN_SUB_FEATURE);
+        write(buffer, &encoded.to_le_bytes())
+    }
+
+    fn decode(buffer: &[u8]) -> Option<(Self, usize)> {
+        let kind = match u16::from_le_bytes(read(buffer)?) {
+            UNKNOWN_SUB_FEATURE => Self::Unknown,
+            raw => Self::from_raw(c_uint::from(raw))?,
+        };
+        Some((kind, Self::ENCODED_LEN))
+    }
+}
+
+impl Compact for Value {
+    const ENCODED_LEN: usize = value::Kind::ENCODED_LEN + 4;
+
+    fn encode(&self, buffer: &mut [u8]) -> Option<usize> {
+        if buffer.len() < Self::ENCODED_LEN {
+            return None;
+        }
+
+        let len = self.kind().encode(buffer)?;
+        #[allow(clippy::cast_possible_truncation)] // Narrowing is documented.
+        let raw_value = self.raw_value() as f32;
+        write(&mut buffer[len..], &raw_value.to_le_bytes()).map(|n| len + n)
+    }
+
+    fn decode(buffer: &[u8]) -> Option<(Self, usize)> {
+        let (kind, len) = value::Kind::decode(buffer)?;
+        let raw_value = f32::from_le_bytes(read(buffer.get(len..)?)?);
+        let value = Self::new(kind, f64::from(raw_value))?;
+        Some((value, Self::ENCODED_LEN))
+    }
+}
+
+fn write(buffer: &mut [u8], bytes: &[u8]) -> Option<usize> {
+    buffer.get_mut(..bytes.len())?.copy_from_slice(bytes);
+    Some(bytes.len())
+}
+
+fn read<const N: usize>(buffer: &[u8]) -> Option<[u8; N]> {
+    buffer.get(..N)?.try_into().ok()
+}
@@ -0,0 +1,67 @@
+#![cfg(test)]
+
+use super::Compact;
+use crate::model::feature;
+use crate::model::value::{self, Value};
+
+#[test]
+fn feature_kind() {
+    let mut buffer = [0_u8; 1];
+    for kind in [
+        feature::Kind::Voltage,
+        feature::Kind::Temperature,
+        feature::Kind::BeepEnable,
+        feature::Kind::Unknown,
+    ] {
+        assert_eq!(kind.encode(&mut buffer), Some(1));
+        assert_eq!(feature::Kind::decode(&buffer), Some((kind, 1)));
+    }
+    assert_eq!(buffer, [0xFF]);
+
+    assert_eq!(feature::Kind::Fan.encode(&mut []), None);
+    assert_eq!(feature::Kind::decode(&[]), None);
+    assert_eq!(feature::Kind::decode(&[0xFE]), None);
+}
+
+#[test]
+fn value_kind() {
+    let mut buffer = [0_u8; 2];
+    for kind in [
+        value::Kind::VoltageInput,
+        value::Kind::TemperatureInput,
+        value::Kind::BeepEnable,
+        value::Kind::Unknown,
+    ] {
+        assert_eq!(kind.encode(&mut buffer), Some(2));
+        assert_eq!(value::Kind::decode(&buffer), Some((kind, 2)));
+    }
+    assert_eq!(buffer, [0xFF, 0xFF]);
+
+    assert_eq!(value::Kind::FanInput.encode(&mut [0]), None);
+    assert_eq!(value::Kind::decode(&[0]), None);
+    assert_eq!(value::Kind::decode(&[0xFE, 0xFF]), None);
+}
+
+#[test]
+fn value() {
+    let mut buffer = [0_u8; 8];
+    for value in [
+        Value::TemperatureInput(45.5),
+        Value::FanInput(1200.0),
+        Value::VoltageInput(-12.0),
+        Value::IntrusionAlarm(true),
+        Value::BeepEnable(false),
+    ] {
+        assert_eq!(value.encode(&mut buffer), Some(Value::ENCODED_LEN));
+        assert_eq!(Value::decode(&buffer), Some((value, Value::ENCODED_LEN)));
+    }
+
+    // Raw values are narrowed to 32-bit floats.
+    let value = Value::VoltageInput(1.1);
+    value.encode(&mut buffer).unwrap();
+    let (decoded, _) = Value::decode(&buffer).unwrap();
+    assert!((decoded.raw_value() - 1.1).abs() < 1e-6);
+
+    assert_eq!(value.encode(&mut buffer[..5]), None);
+    assert_eq!(Value::decode(&buffer[..5]), None);
+}
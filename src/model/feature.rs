@@ -0,0 +1,141 @@
+//! Types of sensors or actuators (*a.k.a.,* features).
+
+#[cfg(test)]
+mod tests;
+
+use core::ffi::c_uint;
+use core::fmt;
+
+use super::raw::sensors_feature_type::*;
+
+/// Type of a sensor or actuator (*a.k.a.,* feature) controlled by a chip.
+#[allow(missing_docs)] // Enum variant names are self-explanatory.
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Kind {
+    Voltage = SENSORS_FEATURE_IN,
+    Fan = SENSORS_FEATURE_FAN,
+    Temperature = SENSORS_FEATURE_TEMP,
+    Power = SENSORS_FEATURE_POWER,
+    Energy = SENSORS_FEATURE_ENERGY,
+    Current = SENSORS_FEATURE_CURR,
+    Humidity = SENSORS_FEATURE_HUMIDITY,
+    VoltageID = SENSORS_FEATURE_VID,
+    Intrusion = SENSORS_FEATURE_INTRUSION,
+    BeepEnable = SENSORS_FEATURE_BEEP_ENABLE,
+    #[default]
+    Unknown = SENSORS_FEATURE_UNKNOWN,
+}
+
+impl Kind {
+    /// Upper bound (exclusive) of raw main feature types,
+    /// *i.e.,* [`SENSORS_FEATURE_MAX_MAIN`].
+    pub const RAW_MAX_MAIN: c_uint = SENSORS_FEATURE_MAX_MAIN;
+
+    /// Upper bound (exclusive) of raw other feature types that are not
+    /// beep enable, *i.e.,* [`SENSORS_FEATURE_MAX_OTHER`].
+    pub const RAW_MAX_OTHER: c_uint = SENSORS_FEATURE_MAX_OTHER;
+
+    /// Upper bound (exclusive) of all known raw feature types,
+    /// *i.e.,* [`SENSORS_FEATURE_MAX`].
+    pub const RAW_MAX: c_uint = SENSORS_FEATURE_MAX;
+
+    /// Return `true` if the given raw feature type is a main feature type,
+    /// *i.e.,* one of [`SENSORS_FEATURE_IN`] to [`SENSORS_FEATURE_HUMIDITY`].
+    #[must_use]
+    pub fn is_raw_main(kind: c_uint) -> bool {
+        kind < Self::RAW_MAX_MAIN
+    }
+
+    /// Return `true` if the given raw feature type is an other feature type,
+    /// *i.e.,* one of [`SENSORS_FEATURE_VID`] to [`SENSORS_FEATURE_BEEP_ENABLE`].
+    #[must_use]
+    pub fn is_raw_other(kind: c_uint) -> bool {
+        (SENSORS_FEATURE_VID..Self::RAW_MAX_OTHER).contains(&kind)
+            || kind == SENSORS_FEATURE_BEEP_ENABLE
+    }
+
+    /// Return `true` if this is a main feature type,
+    /// *e.g.,* [`Kind::Temperature`].
+    #[must_use]
+    pub fn is_main(self) -> bool {
+        Self::is_raw_main(self.as_raw())
+    }
+
+    /// Return `true` if this is an other feature type,
+    /// *e.g.,* [`Kind::Intrusion`].
+    #[must_use]
+    pub fn is_other(self) -> bool {
+        Self::is_raw_other(self.as_raw())
+    }
+
+    /// Return the type of the canonical input sub-feature of the features of
+    /// this type, *e.g.,* [`TemperatureInput`] for [`Kind::Temperature`],
+    /// or `None` for [`Kind::Unknown`].
+    ///
+    /// [`TemperatureInput`]: super::value::Kind::TemperatureInput
+    #[must_use]
+    pub fn input(self) -> Option<super::value::Kind> {
+        use super::value::Kind as ValueKind;
+
+        match self {
+            Self::Voltage => Some(ValueKind::VoltageInput),
+            Self::Fan => Some(ValueKind::FanInput),
+            Self::Temperature => Some(ValueKind::TemperatureInput),
+            Self::Power => Some(ValueKind::PowerInput),
+            Self::Energy => Some(ValueKind::EnergyInput),
+            Self::Current => Some(ValueKind::CurrentInput),
+            Self::Humidity => Some(ValueKind::HumidityInput),
+            Self::VoltageID => Some(ValueKind::VoltageID),
+            Self::Intrusion => Some(ValueKind::IntrusionAlarm),
+            Self::BeepEnable => Some(ValueKind::BeepEnable),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Return an instance from one of the `SENSORS_FEATURE_*` values,
+    /// *e.g.,* [`SENSORS_FEATURE_TEMP`].
+    #[must_use]
+    pub fn from_raw(kind: c_uint) -> Option<Self> {
+        Self::try_from(kind).ok()
+    }
+
+    /// Return one of the `SENSORS_FEATURE_*` values
+    /// (*e.g.,* [`SENSORS_FEATURE_TEMP`]) equivalent to this instance.
+    #[must_use]
+    pub fn as_raw(self) -> c_uint {
+        self.into()
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Voltage => write!(f, "Voltage"),
+            Self::Fan => write!(f, "Fan"),
+            Self::Temperature => write!(f, "Temperature"),
+            Self::Power => write!(f, "Power"),
+            Self::Energy => write!(f, "Energy"),
+            Self::Current => write!(f, "Current"),
+            Self::Humidity => write!(f, "Humidity"),
+            Self::VoltageID => write!(f, "VoltageID"),
+            Self::Intrusion => write!(f, "Intrusion"),
+            Self::BeepEnable => write!(f, "BeepEnable"),
+            Self::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+#![cfg(test)]
+
+// Raw types are checked against the LM sensors library, where available.
+#[cfg(not(all(feature = "std", target_os = "linux")))]
+use crate::model::raw::sensors_feature_type::*;
+#[cfg(all(feature = "std", target_os = "linux"))]
+use sensors_sys::sensors_feature_type::*;
+
+#[test]
+fn kind() {
+    use super::Kind;
+
+    let k0 = Kind::default();
+    assert_eq!(k0, Kind::Unknown);
+    assert_eq!(k0.as_raw(), SENSORS_FEATURE_UNKNOWN);
+    assert!(!k0.to_string().is_empty());
+
+    for (k, n, s) in [
+        (Kind::Voltage, SENSORS_FEATURE_IN, "Voltage"),
+        (Kind::Fan, SENSORS_FEATURE_FAN, "Fan"),
+        (Kind::Temperature, SENSORS_FEATURE_TEMP, "Temperature"),
+        (Kind::Power, SENSORS_FEATURE_POWER, "Power"),
+        (Kind::Energy, SENSORS_FEATURE_ENERGY, "Energy"),
+        (Kind::Current, SENSORS_FEATURE_CURR, "Current"),
+        (Kind::Humidity, SENSORS_FEATURE_HUMIDITY, "Humidity"),
+        (Kind::VoltageID, SENSORS_FEATURE_VID, "VoltageID"),
+        (Kind::Intrusion, SENSORS_FEATURE_INTRUSION, "Intrusion"),
+        (Kind::BeepEnable, SENSORS_FEATURE_BEEP_ENABLE, "BeepEnable"),
+        (Kind::Unknown, SENSORS_FEATURE_UNKNOWN, "Unknown"),
+    ] {
+        assert_eq!(Kind::from_raw(n).unwrap(), k);
+        assert_eq!(n, k.as_raw());
+        assert_eq!(k.to_string(), s);
+    }
+}
+
+#[test]
+fn kind_partition() {
+    use super::Kind;
+
+    for k in [
+        Kind::Voltage,
+        Kind::Fan,
+        Kind::Temperature,
+        Kind::Power,
+        Kind::Energy,
+        Kind::Current,
+        Kind::Humidity,
+    ] {
+        assert!(k.is_main());
+        assert!(!k.is_other());
+        assert!(k.as_raw() < Kind::RAW_MAX_MAIN);
+    }
+
+    for k in [Kind::VoltageID, Kind::Intrusion, Kind::BeepEnable] {
+        assert!(!k.is_main());
+        assert!(k.is_other());
+        assert!(k.as_raw() < Kind::RAW_MAX);
+    }
+
+    assert!(!Kind::Unknown.is_main());
+    assert!(!Kind::Unknown.is_other());
+
+    assert_eq!(Kind::RAW_MAX_MAIN, SENSORS_FEATURE_MAX_MAIN);
+    assert_eq!(Kind::RAW_MAX_OTHER, SENSORS_FEATURE_MAX_OTHER);
+    assert_eq!(Kind::RAW_MAX, SENSORS_FEATURE_MAX);
+    assert!(!Kind::is_raw_main(SENSORS_FEATURE_MAX_MAIN));
+    assert!(!Kind::is_raw_other(SENSORS_FEATURE_MAX_MAIN));
+    assert!(!Kind::is_raw_other(SENSORS_FEATURE_MAX_OTHER));
+    assert!(!Kind::is_raw_other(SENSORS_FEATURE_MAX));
+}
+
+#[test]
+fn kind_input() {
+    use super::Kind;
+    use crate::model::value::Kind as ValueKind;
+
+    assert_eq!(Kind::Temperature.input(), Some(ValueKind::TemperatureInput));
+    assert_eq!(Kind::Fan.input(), Some(ValueKind::FanInput));
+    assert_eq!(Kind::Voltage.input(), Some(ValueKind::VoltageInput));
+    assert_eq!(Kind::Intrusion.input(), Some(ValueKind::IntrusionAlarm));
+    assert_eq!(Kind::Unknown.input(), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn kind_serde() {
+    use super::Kind;
+
+    for k in [
+        Kind::Voltage,
+        Kind::Temperature,
+        Kind::BeepEnable,
+        Kind::Unknown,
+    ] {
+        let json = serde_json::to_string(&k).unwrap();
+        assert_eq!(serde_json::from_str::<Kind>(&json).unwrap(), k);
+    }
+    assert_eq!(serde_json::to_string(&Kind::Fan).unwrap(), r#""Fan""#);
+}
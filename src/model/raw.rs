@@ -0,0 +1,140 @@
+//! Raw constants of the LM sensors library.
+//!
+//! These mirror the values of `sensors.h`, and are available without
+//! linking to the library.
+
+/// Raw types of features, *i.e.,* `sensors_feature_type`.
+#[allow(missing_docs)]
+pub mod sensors_feature_type {
+    /// Raw type.
+    pub type Type = core::ffi::c_uint;
+
+    pub const SENSORS_FEATURE_IN: Type = 0;
+    pub const SENSORS_FEATURE_FAN: Type = 1;
+    pub const SENSORS_FEATURE_TEMP: Type = 2;
+    pub const SENSORS_FEATURE_POWER: Type = 3;
+    pub const SENSORS_FEATURE_ENERGY: Type = 4;
+    pub const SENSORS_FEATURE_CURR: Type = 5;
+    pub const SENSORS_FEATURE_HUMIDITY: Type = 6;
+    pub const SENSORS_FEATURE_MAX_MAIN: Type = 7;
+    pub const SENSORS_FEATURE_VID: Type = 16;
+    pub const SENSORS_FEATURE_INTRUSION: Type = 17;
+    pub const SENSORS_FEATURE_MAX_OTHER: Type = 18;
+    pub const SENSORS_FEATURE_BEEP_ENABLE: Type = 24;
+    pub const SENSORS_FEATURE_MAX: Type = 25;
+    pub const SENSORS_FEATURE_UNKNOWN: Type = 2147483647;
+}
+
+/// Raw types of sub-features, *i.e.,* `sensors_subfeature_type`.
+#[allow(missing_docs)]
+pub mod sensors_subfeature_type {
+    /// Raw type.
+    pub type Type = core::ffi::c_uint;
+
+    pub const SENSORS_SUBFEATURE_IN_INPUT: Type = 0;
+    pub const SENSORS_SUBFEATURE_IN_MIN: Type = 1;
+    pub const SENSORS_SUBFEATURE_IN_MAX: Type = 2;
+    pub const SENSORS_SUBFEATURE_IN_LCRIT: Type = 3;
+    pub const SENSORS_SUBFEATURE_IN_CRIT: Type = 4;
+    pub const SENSORS_SUBFEATURE_IN_AVERAGE: Type = 5;
+    pub const SENSORS_SUBFEATURE_IN_LOWEST: Type = 6;
+    pub const SENSORS_SUBFEATURE_IN_HIGHEST: Type = 7;
+    pub const SENSORS_SUBFEATURE_IN_ALARM: Type = 128;
+    pub const SENSORS_SUBFEATURE_IN_MIN_ALARM: Type = 129;
+    pub const SENSORS_SUBFEATURE_IN_MAX_ALARM: Type = 130;
+    pub const SENSORS_SUBFEATURE_IN_BEEP: Type = 131;
+    pub const SENSORS_SUBFEATURE_IN_LCRIT_ALARM: Type = 132;
+    pub const SENSORS_SUBFEATURE_IN_CRIT_ALARM: Type = 133;
+    pub const SENSORS_SUBFEATURE_FAN_INPUT: Type = 256;
+    pub const SENSORS_SUBFEATURE_FAN_MIN: Type = 257;
+    pub const SENSORS_SUBFEATURE_FAN_MAX: Type = 258;
+    pub const SENSORS_SUBFEATURE_FAN_ALARM: Type = 384;
+    pub const SENSORS_SUBFEATURE_FAN_FAULT: Type = 385;
+    pub const SENSORS_SUBFEATURE_FAN_DIV: Type = 386;
+    pub const SENSORS_SUBFEATURE_FAN_BEEP: Type = 387;
+    pub const SENSORS_SUBFEATURE_FAN_PULSES: Type = 388;
+    pub const SENSORS_SUBFEATURE_FAN_MIN_ALARM: Type = 389;
+    pub const SENSORS_SUBFEATURE_FAN_MAX_ALARM: Type = 390;
+    pub const SENSORS_SUBFEATURE_TEMP_INPUT: Type = 512;
+    pub const SENSORS_SUBFEATURE_TEMP_MAX: Type = 513;
+    pub const SENSORS_SUBFEATURE_TEMP_MAX_HYST: Type = 514;
+    pub const SENSORS_SUBFEATURE_TEMP_MIN: Type = 515;
+    pub const SENSORS_SUBFEATURE_TEMP_CRIT: Type = 516;
+    pub const SENSORS_SUBFEATURE_TEMP_CRIT_HYST: Type = 517;
+    pub const SENSORS_SUBFEATURE_TEMP_LCRIT: Type = 518;
+    pub const SENSORS_SUBFEATURE_TEMP_EMERGENCY: Type = 519;
+    pub const SENSORS_SUBFEATURE_TEMP_EMERGENCY_HYST: Type = 520;
+    pub const SENSORS_SUBFEATURE_TEMP_LOWEST: Type = 521;
+    pub const SENSORS_SUBFEATURE_TEMP_HIGHEST: Type = 522;
+    pub const SENSORS_SUBFEATURE_TEMP_MIN_HYST: Type = 523;
+    pub const SENSORS_SUBFEATURE_TEMP_LCRIT_HYST: Type = 524;
+    pub const SENSORS_SUBFEATURE_TEMP_ALARM: Type = 640;
+    pub const SENSORS_SUBFEATURE_TEMP_MAX_ALARM: Type = 641;
+    pub const SENSORS_SUBFEATURE_TEMP_MIN_ALARM: Type = 642;
+    pub const SENSORS_SUBFEATURE_TEMP_CRIT_ALARM: Type = 643;
+    pub const SENSORS_SUBFEATURE_TEMP_FAULT: Type = 644;
+    pub const SENSORS_SUBFEATURE_TEMP_TYPE: Type = 645;
+    pub const SENSORS_SUBFEATURE_TEMP_OFFSET: Type = 646;
+    pub const SENSORS_SUBFEATURE_TEMP_BEEP: Type = 647;
+    pub const SENSORS_SUBFEATURE_TEMP_EMERGENCY_ALARM: Type = 648;
+    pub const SENSORS_SUBFEATURE_TEMP_LCRIT_ALARM: Type = 649;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE: Type = 768;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE_HIGHEST: Type = 769;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE_LOWEST: Type = 770;
+    pub const SENSORS_SUBFEATURE_POWER_INPUT: Type = 771;
+    pub const SENSORS_SUBFEATURE_POWER_INPUT_HIGHEST: Type = 772;
+    pub const SENSORS_SUBFEATURE_POWER_INPUT_LOWEST: Type = 773;
+    pub const SENSORS_SUBFEATURE_POWER_CAP: Type = 774;
+    pub const SENSORS_SUBFEATURE_POWER_CAP_HYST: Type = 775;
+    pub const SENSORS_SUBFEATURE_POWER_MAX: Type = 776;
+    pub const SENSORS_SUBFEATURE_POWER_CRIT: Type = 777;
+    pub const SENSORS_SUBFEATURE_POWER_MIN: Type = 778;
+    pub const SENSORS_SUBFEATURE_POWER_LCRIT: Type = 779;
+    pub const SENSORS_SUBFEATURE_POWER_AVERAGE_INTERVAL: Type = 896;
+    pub const SENSORS_SUBFEATURE_POWER_ALARM: Type = 897;
+    pub const SENSORS_SUBFEATURE_POWER_CAP_ALARM: Type = 898;
+    pub const SENSORS_SUBFEATURE_POWER_MAX_ALARM: Type = 899;
+    pub const SENSORS_SUBFEATURE_POWER_CRIT_ALARM: Type = 900;
+    pub const SENSORS_SUBFEATURE_POWER_MIN_ALARM: Type = 901;
+    pub const SENSORS_SUBFEATURE_POWER_LCRIT_ALARM: Type = 902;
+    pub const SENSORS_SUBFEATURE_ENERGY_INPUT: Type = 1024;
+    pub const SENSORS_SUBFEATURE_CURR_INPUT: Type = 1280;
+    pub const SENSORS_SUBFEATURE_CURR_MIN: Type = 1281;
+    pub const SENSORS_SUBFEATURE_CURR_MAX: Type = 1282;
+    pub const SENSORS_SUBFEATURE_CURR_LCRIT: Type = 1283;
+    pub const SENSORS_SUBFEATURE_CURR_CRIT: Type = 1284;
+    pub const SENSORS_SUBFEATURE_CURR_AVERAGE: Type = 1285;
+    pub const SENSORS_SUBFEATURE_CURR_LOWEST: Type = 1286;
+    pub const SENSORS_SUBFEATURE_CURR_HIGHEST: Type = 1287;
+    pub const SENSORS_SUBFEATURE_CURR_ALARM: Type = 1408;
+    pub const SENSORS_SUBFEATURE_CURR_MIN_ALARM: Type = 1409;
+    pub const SENSORS_SUBFEATURE_CURR_MAX_ALARM: Type = 1410;
+    pub const SENSORS_SUBFEATURE_CURR_BEEP: Type = 1411;
+    pub const SENSORS_SUBFEATURE_CURR_LCRIT_ALARM: Type = 1412;
+    pub const SENSORS_SUBFEATURE_CURR_CRIT_ALARM: Type = 1413;
+    pub const SENSORS_SUBFEATURE_HUMIDITY_INPUT: Type = 1536;
+    pub const SENSORS_SUBFEATURE_VID: Type = 4096;
+    pub const SENSORS_SUBFEATURE_INTRUSION_ALARM: Type = 4352;
+    pub const SENSORS_SUBFEATURE_INTRUSION_BEEP: Type = 4353;
+    pub const SENSORS_SUBFEATURE_BEEP_ENABLE: Type = 6144;
+    pub const SENSORS_SUBFEATURE_UNKNOWN: Type = 2147483647;
+}
+
+/// Error numbers, *i.e.,* `SENSORS_ERR_*` of `error.h`.
+#[allow(missing_docs)]
+pub mod sensors_error {
+    /// Raw error number.
+    pub type Type = core::ffi::c_int;
+
+    pub const SENSORS_ERR_WILDCARDS: Type = 1;
+    pub const SENSORS_ERR_NO_ENTRY: Type = 2;
+    pub const SENSORS_ERR_ACCESS_R: Type = 3;
+    pub const SENSORS_ERR_KERNEL: Type = 4;
+    pub const SENSORS_ERR_DIV_ZERO: Type = 5;
+    pub const SENSORS_ERR_CHIP_NAME: Type = 6;
+    pub const SENSORS_ERR_BUS_NAME: Type = 7;
+    pub const SENSORS_ERR_PARSE: Type = 8;
+    pub const SENSORS_ERR_ACCESS_W: Type = 9;
+    pub const SENSORS_ERR_IO: Type = 10;
+    pub const SENSORS_ERR_RECURSION: Type = 11;
+}
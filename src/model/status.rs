@@ -0,0 +1,294 @@
+//! Status of values relative to their limits, *e.g.,* alarms.
+//!
+//! [`Thresholds`] hold the limits of one feature, each with the value that
+//! clears it once crossed. Clearing values come from the hysteresis
+//! sub-features provided by the chip, *e.g.,* `temp1_max_hyst`, or from
+//! a hysteresis supplied by the user, so that the status does not flap
+//! while a value hovers at a limit.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+
+use crate::value::Value;
+
+/// Status of a value relative to its limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Status {
+    /// Value is within its limits.
+    #[default]
+    Normal,
+    /// Value is below its minimum.
+    Low,
+    /// Value is below its lower critical limit.
+    LowCritical,
+    /// Value is above its maximum.
+    High,
+    /// Value is above its critical limit.
+    Critical,
+    /// Value is above its emergency limit.
+    Emergency,
+}
+
+impl Status {
+    /// Return `true` unless the value is within its limits.
+    #[must_use]
+    pub fn is_alarm(self) -> bool {
+        self != Self::Normal
+    }
+
+    /// Return a signed severity, negative below limits,
+    /// and positive above limits.
+    fn rank(self) -> i8 {
+        match self {
+            Self::LowCritical => -2,
+            Self::Low => -1,
+            Self::Normal => 0,
+            Self::High => 1,
+            Self::Critical => 2,
+            Self::Emergency => 3,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Normal => "normal",
+            Self::Low => "low",
+            Self::LowCritical => "low critical",
+            Self::High => "high",
+            Self::Critical => "critical",
+            Self::Emergency => "emergency",
+        })
+    }
+}
+
+/// Limit of a value, and the value clearing it once crossed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    /// Value beyond which the limit is crossed.
+    pub trigger: f64,
+    /// Value that must be reached back to clear the crossed limit.
+    pub clear: f64,
+}
+
+impl Limit {
+    /// Return an upper limit cleared `hysteresis` below `trigger`.
+    #[must_use]
+    pub fn upper(trigger: f64, hysteresis: f64) -> Self {
+        Self {
+            trigger,
+            clear: trigger - hysteresis.max(0.0),
+        }
+    }
+
+    /// Return a lower limit cleared `hysteresis` above `trigger`.
+    #[must_use]
+    pub fn lower(trigger: f64, hysteresis: f64) -> Self {
+        Self {
+            trigger,
+            clear: trigger + hysteresis.max(0.0),
+        }
+    }
+}
+
+/// Effective limits of one feature.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Thresholds {
+    /// Lower critical limit, *e.g.,* `in0_lcrit`.
+    pub low_critical: Option<Limit>,
+    /// Minimum, *e.g.,* `fan1_min`.
+    pub minimum: Option<Limit>,
+    /// Maximum, *e.g.,* `temp1_max`.
+    pub maximum: Option<Limit>,
+    /// Critical limit, *e.g.,* `temp1_crit`.
+    pub critical: Option<Limit>,
+    /// Emergency limit, *e.g.,* `temp1_emergency`.
+    pub emergency: Option<Limit>,
+}
+
+impl Thresholds {
+    /// Return the limits found in the given values of the sub-features
+    /// of one feature.
+    ///
+    /// Limits provided with a hysteresis value by the chip, *e.g.,*
+    /// `temp1_max` and `temp1_max_hyst`, are cleared at that value.
+    /// Other limits are cleared `hysteresis` away from their trigger.
+    pub fn from_values<'v>(values: impl IntoIterator<Item = &'v Value>, hysteresis: f64) -> Self {
+        #[derive(Default)]
+        struct Found {
+            trigger: Option<f64>,
+            clear: Option<f64>,
+        }
+
+        let mut found: [Found; 5] = Default::default();
+        for value in values {
+            let (index, is_clear, v) = match *value {
+                Value::VoltageLCritical(v)
+                | Value::TemperatureLCritical(v)
+                | Value::PowerLCritical(v)
+                | Value::CurrentLCritical(v) => (0, false, v),
+                Value::TemperatureLCriticalHysteresis(v) => (0, true, v),
+
+                Value::VoltageMinimum(v)
+                | Value::FanMinimum(v)
+                | Value::TemperatureMinimum(v)
+                | Value::PowerMinimum(v)
+                | Value::CurrentMinimum(v) => (1, false, v),
+                Value::TemperatureMinimumHysteresis(v) => (1, true, v),
+
+                Value::VoltageMaximum(v)
+                | Value::FanMaximum(v)
+                | Value::TemperatureMaximum(v)
+                | Value::PowerMaximum(v)
+                | Value::CurrentMaximum(v) => (2, false, v),
+                Value::TemperatureMaximumHysteresis(v) => (2, true, v),
+
+                Value::VoltageCritical(v)
+                | Value::TemperatureCritical(v)
+                | Value::PowerCritical(v)
+                | Value::CurrentCritical(v) => (3, false, v),
+                Value::TemperatureCriticalHysteresis(v) => (3, true, v),
+
+                Value::TemperatureEmergency(v) => (4, false, v),
+                Value::TemperatureEmergencyHysteresis(v) => (4, true, v),
+
+                _ => continue,
+            };
+
+            if is_clear {
+                found[index].clear = Some(v);
+            } else {
+                found[index].trigger = Some(v);
+            }
+        }
+
+        let limit = |found: &Found, upper: bool| {
+            let trigger = found.trigger?;
+            Some(match (found.clear, upper) {
+                (Some(clear), true) => Limit {
+                    trigger,
+                    clear: clear.min(trigger),
+                },
+                (Some(clear), false) => Limit {
+                    trigger,
+                    clear: clear.max(trigger),
+                },
+                (None, true) => Limit::upper(trigger, hysteresis),
+                (None, false) => Limit::lower(trigger, hysteresis),
+            })
+        };
+
+        Self {
+            low_critical: limit(&found[0], false),
+            minimum: limit(&found[1], false),
+            maximum: limit(&found[2], true),
+            critical: limit(&found[3], true),
+            emergency: limit(&found[4], true),
+        }
+    }
+
+    /// Return the status of `value`, given the `previous` status of
+    /// the same feature.
+    ///
+    /// A limit is crossed when the value gets beyond its trigger. While
+    /// the previous status is at least as severe, it remains crossed until
+    /// the value gets back beyond its clearing value.
+    #[must_use]
+    pub fn status(&self, value: f64, previous: Status) -> Status {
+        let upper = [
+            (self.emergency, Status::Emergency),
+            (self.critical, Status::Critical),
+            (self.maximum, Status::High),
+        ];
+        for (limit, status) in upper {
+            if let Some(limit) = limit {
+                let held = previous.rank() >= status.rank();
+                if value > limit.trigger || (held && value > limit.clear) {
+                    return status;
+                }
+            }
+        }
+
+        let lower = [
+            (self.low_critical, Status::LowCritical),
+            (self.minimum, Status::Low),
+        ];
+        for (limit, status) in lower {
+            if let Some(limit) = limit {
+                let held = previous.rank() <= status.rank();
+                if value < limit.trigger || (held && value < limit.clear) {
+                    return status;
+                }
+            }
+        }
+
+        Status::Normal
+    }
+}
+
+/// Tracker of the status of successive values of one feature.
+///
+/// # Example
+///
+/// ```rust
+/// use lm_sensors::status::{Status, StatusTracker, Thresholds};
+/// use lm_sensors::value::Value;
+///
+/// let limits = [Value::TemperatureMaximum(80.0)];
+/// let mut tracker = StatusTracker::new(Thresholds::from_values(&limits, 5.0));
+///
+/// assert_eq!(tracker.update(81.0), Status::High);
+/// assert_eq!(tracker.update(79.0), Status::High);
+/// assert_eq!(tracker.update(74.0), Status::Normal);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StatusTracker {
+    thresholds: Thresholds,
+    status: Status,
+}
+
+impl StatusTracker {
+    /// Return a tracker evaluating values against the given thresholds.
+    #[must_use]
+    pub fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            status: Status::Normal,
+        }
+    }
+
+    /// Return the thresholds used to evaluate values.
+    #[must_use]
+    pub fn thresholds(&self) -> &Thresholds {
+        &self.thresholds
+    }
+
+    /// Return the status of the last value.
+    #[must_use]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Evaluate a new value, and return its status.
+    ///
+    /// With the `std` feature on Linux, changes of status are published as
+    /// `events::Event::ThresholdCrossed`.
+    pub fn update(&mut self, value: f64) -> Status {
+        let previous = self.status;
+        self.status = self.thresholds.status(value, previous);
+
+        #[cfg(all(feature = "std", target_os = "linux"))]
+        if self.status != previous {
+            crate::events::publish(&crate::events::Event::ThresholdCrossed {
+                previous,
+                status: self.status,
+                value,
+            });
+        }
+        self.status
+    }
+}
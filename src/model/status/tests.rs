@@ -0,0 +1,89 @@
+#![cfg(test)]
+
+use serial_test::serial;
+
+use crate::status::{Limit, Status, StatusTracker, Thresholds};
+use crate::value::Value;
+
+#[test]
+fn from_values() {
+    let values = [
+        Value::TemperatureInput(50.0),
+        Value::TemperatureMaximum(80.0),
+        Value::TemperatureMaximumHysteresis(75.0),
+        Value::TemperatureCritical(100.0),
+        Value::TemperatureMinimum(10.0),
+        Value::TemperatureLCritical(0.0),
+        // Nonsensical hysteresis is clamped to the trigger.
+        Value::TemperatureLCriticalHysteresis(-5.0),
+    ];
+    let thresholds = Thresholds::from_values(&values, 2.0);
+
+    assert_eq!(
+        thresholds,
+        Thresholds {
+            low_critical: Some(Limit {
+                trigger: 0.0,
+                clear: 0.0
+            }),
+            minimum: Some(Limit::lower(10.0, 2.0)),
+            maximum: Some(Limit {
+                trigger: 80.0,
+                clear: 75.0
+            }),
+            critical: Some(Limit::upper(100.0, 2.0)),
+            emergency: None,
+        }
+    );
+    assert_eq!(Limit::upper(100.0, 2.0).clear, 98.0);
+    assert_eq!(Limit::lower(10.0, -2.0).clear, 10.0);
+
+    let values = [Value::FanMinimum(600.0), Value::VoltageCritical(1.5)];
+    let thresholds = Thresholds::from_values(&values, 0.0);
+    assert_eq!(thresholds.minimum, Some(Limit::lower(600.0, 0.0)));
+    assert_eq!(thresholds.critical, Some(Limit::upper(1.5, 0.0)));
+
+    // A hysteresis without its limit is ignored.
+    let values = [Value::TemperatureEmergencyHysteresis(90.0)];
+    assert_eq!(Thresholds::from_values(&values, 0.0), Thresholds::default());
+}
+
+#[test]
+#[serial] // Changes of status are published as events.
+fn status() {
+    let values = [
+        Value::TemperatureMaximum(80.0),
+        Value::TemperatureCritical(100.0),
+        Value::TemperatureMinimum(10.0),
+    ];
+    let mut tracker = StatusTracker::new(Thresholds::from_values(&values, 5.0));
+    assert_eq!(tracker.status(), Status::Normal);
+    assert_eq!(tracker.thresholds().maximum, Some(Limit::upper(80.0, 5.0)));
+
+    let statuses = [
+        (50.0, Status::Normal),
+        (80.0, Status::Normal),
+        (80.5, Status::High),
+        (79.0, Status::High),
+        (101.0, Status::Critical),
+        (96.0, Status::Critical),
+        (94.0, Status::High),
+        (99.0, Status::High),
+        (76.0, Status::High),
+        (75.0, Status::Normal),
+        (79.0, Status::Normal),
+        (9.0, Status::Low),
+        (14.0, Status::Low),
+        (15.0, Status::Normal),
+        (120.0, Status::Critical),
+        (50.0, Status::Normal),
+    ];
+    for (value, status) in statuses {
+        assert_eq!(tracker.update(value), status, "value {value}");
+        assert_eq!(tracker.status(), status);
+    }
+
+    assert!(Status::High.is_alarm());
+    assert!(!Status::Normal.is_alarm());
+    assert_eq!(Status::LowCritical.to_string(), "low critical");
+}
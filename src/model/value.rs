@@ -3,12 +3,13 @@
 #[cfg(test)]
 mod tests;
 
+use core::ffi::{c_int, c_uint};
 use core::fmt;
+#[cfg(feature = "std")]
 use std::io;
-use std::os::raw::{c_int, c_uint};
-
-use sensors_sys::sensors_subfeature_type::*;
 
+use super::raw::sensors_subfeature_type::*;
+#[cfg(feature = "std")]
 use crate::errors::{Error, Result};
 
 /// Value reported by a sensor or set for an actuator,
@@ -16,6 +17,7 @@ use crate::errors::{Error, Result};
 #[allow(missing_docs)]
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     VoltageInput(f64),
     VoltageMinimum(f64),
@@ -561,8 +563,20 @@ impl Value {
         }
     }
 
-    /// Set the raw value of this instance.
+    /// Set the raw value of this instance, and return the previous raw value.
+    ///
+    /// See: [`Value::try_set_raw_value`].
+    #[cfg(feature = "std")]
     pub fn set_raw_value(&mut self, new_value: f64) -> Result<f64> {
+        self.try_set_raw_value(new_value).ok_or_else(|| {
+            let err = io::ErrorKind::InvalidData.into();
+            Error::from_io("TemperatureSensorKind::new", err)
+        })
+    }
+
+    /// Set the raw value of this instance, and return the previous raw value,
+    /// or return `None` if the new raw value is invalid for this instance.
+    pub fn try_set_raw_value(&mut self, new_value: f64) -> Option<f64> {
         match self {
             // Voltage
             Self::VoltageInput(value)
@@ -627,7 +641,7 @@ impl Value {
             | Self::Unknown { value, .. } => {
                 let result = *value;
                 *value = new_value;
-                Ok(result)
+                Some(result)
             }
 
             // Voltage
@@ -673,16 +687,15 @@ impl Value {
             | Self::BeepEnable(value) => {
                 let result: f64 = if *value { 1.0 } else { 0.0 };
                 *value = new_value != 0.0_f64;
-                Ok(result)
+                Some(result)
             },
 
             Self::TemperatureType(value) => {
-                let new_value = TemperatureSensorKind::from_raw(new_value)
-                    .ok_or_else(|| Error::from_io("TemperatureSensorKind::new", io::ErrorKind::InvalidData.into()))?;
+                let new_value = TemperatureSensorKind::from_raw(new_value)?;
 
                 let result = value.as_raw() as f64;
                 *value = new_value;
-                Ok(result)
+                Some(result)
             },
         }
     }
@@ -692,6 +705,51 @@ impl Value {
     pub fn unit(&self) -> Unit {
         self.kind().unit()
     }
+
+    /**
+    Return `true` if this instance and the given one have the same type,
+    and raw values differing by at most the given tolerance.
+
+    Values that are not numbers are never approximately equal.
+
+    # Example
+
+    ```rust
+    use lm_sensors::value::{Kind, Value, ValueEpsilon};
+
+    let epsilon = ValueEpsilon::default_for_kind(Kind::TemperatureInput);
+    let value = Value::TemperatureInput(45.0);
+    assert!(value.approx_eq(&Value::TemperatureInput(45.4), epsilon));
+    assert!(!value.approx_eq(&Value::TemperatureInput(46.0), epsilon));
+    assert!(!value.approx_eq(&Value::TemperatureMaximum(45.0), epsilon));
+    ```
+    */
+    #[must_use]
+    pub fn approx_eq(&self, other: &Self, epsilon: ValueEpsilon) -> bool {
+        self.kind() == other.kind()
+            && (self.raw_value() - other.raw_value()).abs() <= epsilon.tolerance()
+    }
+}
+
+/// Values are generated from an arbitrary [`Kind`], so that the kind of
+/// [`Value::Unknown`] is always [`Kind::Unknown`]. Raw values are zero
+/// about half of the time.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let kind = Kind::arbitrary(u)?;
+        if kind == Kind::TemperatureType {
+            let value = TemperatureSensorKind::arbitrary(u)?;
+            return Ok(Self::TemperatureType(value));
+        }
+
+        let value = if u.arbitrary()? {
+            f64::arbitrary(u)?
+        } else {
+            0.0
+        };
+        Self::new(kind, value).ok_or(arbitrary::Error::IncorrectFormat)
+    }
 }
 
 impl fmt::Display for Value {
@@ -832,9 +890,12 @@ impl fmt::Display for Value {
     Eq,
     PartialOrd,
     Ord,
+    Default,
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
     VoltageInput = SENSORS_SUBFEATURE_IN_INPUT,
     VoltageMinimum = SENSORS_SUBFEATURE_IN_MIN,
@@ -932,6 +993,7 @@ pub enum Kind {
 
     BeepEnable = SENSORS_SUBFEATURE_BEEP_ENABLE,
 
+    #[default]
     Unknown = SENSORS_SUBFEATURE_UNKNOWN,
 }
 
@@ -1061,12 +1123,6 @@ impl Kind {
     }
 }
 
-impl Default for Kind {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 impl fmt::Display for Kind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let name = match *self {
@@ -1165,8 +1221,11 @@ impl fmt::Display for Kind {
 /// Unit of a value of a sensor or actuator.
 #[allow(missing_docs)] // Enum variant names are self-explanatory.
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Unit {
+    #[default]
     None,
     Volt,
     Amp,
@@ -1178,12 +1237,6 @@ pub enum Unit {
     Percentage,
 }
 
-impl Default for Unit {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl fmt::Display for Unit {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -1200,6 +1253,72 @@ impl fmt::Display for Unit {
     }
 }
 
+/**
+Tolerance of approximate comparisons of values, in the unit of the values.
+
+Default tolerances depend on the unit of values, and are chosen above
+the usual noise of readings:
+
+| Unit | Tolerance |
+|------|-----------|
+| [`Celcius`](Unit::Celcius) | 0.5 °C |
+| [`RotationPerMinute`](Unit::RotationPerMinute) | 10 RPM |
+| [`Volt`](Unit::Volt) | 5 mV |
+| [`Amp`](Unit::Amp) | 10 mA |
+| [`Watt`](Unit::Watt) | 0.1 W |
+| [`Joule`](Unit::Joule) | 1 J |
+| [`Percentage`](Unit::Percentage) | 1 % |
+| Others | exact |
+
+See: [`Value::approx_eq`].
+*/
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct ValueEpsilon(f64);
+
+impl ValueEpsilon {
+    /// Tolerance of exact comparisons.
+    pub const EXACT: Self = Self(0.0);
+
+    /// Return the given tolerance, or `None` if it is negative or not
+    /// a number.
+    #[must_use]
+    pub fn new(tolerance: f64) -> Option<Self> {
+        (tolerance >= 0.0).then_some(Self(tolerance))
+    }
+
+    /// Return the default tolerance of values of the given type.
+    #[must_use]
+    pub fn default_for_kind(kind: Kind) -> Self {
+        Self::default_for_unit(kind.unit())
+    }
+
+    /// Return the default tolerance of values of the given unit.
+    #[must_use]
+    pub fn default_for_unit(unit: Unit) -> Self {
+        match unit {
+            Unit::Celcius => Self(0.5),
+            Unit::RotationPerMinute => Self(10.0),
+            Unit::Volt => Self(0.005),
+            Unit::Amp => Self(0.01),
+            Unit::Watt => Self(0.1),
+            Unit::Joule | Unit::Percentage => Self(1.0),
+            Unit::None | Unit::Second => Self::EXACT,
+        }
+    }
+
+    /// Return the tolerance, in the unit of the compared values.
+    #[must_use]
+    pub fn tolerance(self) -> f64 {
+        self.0
+    }
+}
+
+impl Default for ValueEpsilon {
+    fn default() -> Self {
+        Self::EXACT
+    }
+}
+
 /// Type of a temperature sensor.
 #[allow(missing_docs)] // Enum variant names are self-explanatory.
 #[non_exhaustive]
@@ -1212,10 +1331,14 @@ impl fmt::Display for Unit {
     Eq,
     PartialOrd,
     Ord,
+    Default,
     num_enum::TryFromPrimitive,
     num_enum::IntoPrimitive,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TemperatureSensorKind {
+    #[default]
     Disabled = 0_i32,
     CPUDiode = 1_i32,
     Transistor = 2_i32,
@@ -1235,8 +1358,7 @@ impl TemperatureSensorKind {
             return value.is_sign_positive().then_some(Self::Thermistor);
         }
 
-        // Safety: `value` is finite.
-        let int_value: i64 = unsafe { value.round().to_int_unchecked() };
+        let int_value = round_to_i64(value);
         if int_value < 0 {
             None
         } else if int_value > 1000 {
@@ -1254,12 +1376,6 @@ impl TemperatureSensorKind {
     }
 }
 
-impl Default for TemperatureSensorKind {
-    fn default() -> Self {
-        Self::Disabled
-    }
-}
-
 impl fmt::Display for TemperatureSensorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -1273,3 +1389,20 @@ impl fmt::Display for TemperatureSensorKind {
         }
     }
 }
+
+/// Round a finite value to the nearest integer, rounding half-way cases away
+/// from zero, as `f64::round()` does, without requiring `std`.
+///
+/// Values out of the range of `i64` saturate.
+fn round_to_i64(value: f64) -> i64 {
+    let truncated = value as i64;
+    // The subtraction is exact whenever `value` has a fractional part.
+    let fraction = value - truncated as f64;
+    if fraction >= 0.5 {
+        truncated.saturating_add(1)
+    } else if fraction <= -0.5 {
+        truncated.saturating_sub(1)
+    } else {
+        truncated
+    }
+}
@@ -2,6 +2,10 @@
 
 use std::os::raw::{c_int, c_uint};
 
+// Raw types are checked against the LM sensors library, where available.
+#[cfg(not(all(feature = "std", target_os = "linux")))]
+use crate::model::raw::sensors_subfeature_type::*;
+#[cfg(all(feature = "std", target_os = "linux"))]
 use sensors_sys::sensors_subfeature_type::*;
 
 use super::{Kind, Unit};
@@ -658,6 +662,41 @@ fn unit() {
     }
 }
 
+#[test]
+fn value_approx_eq() {
+    use super::{Value, ValueEpsilon};
+
+    assert_eq!(ValueEpsilon::default(), ValueEpsilon::EXACT);
+    assert!(ValueEpsilon::new(-1.0).is_none());
+    assert!(ValueEpsilon::new(f64::NAN).is_none());
+    assert_eq!(ValueEpsilon::new(0.25).unwrap().tolerance(), 0.25);
+
+    for (kind, tolerance) in [
+        (Kind::TemperatureInput, 0.5),
+        (Kind::FanInput, 10.0),
+        (Kind::VoltageInput, 0.005),
+        (Kind::FanDivisor, 0.0),
+        (Kind::TemperatureAlarm, 0.0),
+    ] {
+        let epsilon = ValueEpsilon::default_for_kind(kind);
+        assert_eq!(epsilon.tolerance(), tolerance);
+
+        let value = Value::new(kind, 1.0).unwrap();
+        assert!(value.approx_eq(&value, epsilon));
+    }
+
+    let epsilon = ValueEpsilon::default_for_kind(Kind::FanInput);
+    let fan = Value::FanInput(1200.0);
+    assert!(fan.approx_eq(&Value::FanInput(1210.0), epsilon));
+    assert!(fan.approx_eq(&Value::FanInput(1190.0), epsilon));
+    assert!(!fan.approx_eq(&Value::FanInput(1215.0), epsilon));
+    assert!(!fan.approx_eq(&Value::FanMinimum(1200.0), epsilon));
+    assert!(fan.approx_eq(&Value::FanInput(1200.0), ValueEpsilon::EXACT));
+
+    let nan = Value::TemperatureInput(f64::NAN);
+    assert!(!nan.approx_eq(&nan, ValueEpsilon::default_for_kind(nan.kind())));
+}
+
 #[test]
 fn temperature_sensor_kind() {
     use super::TemperatureSensorKind;
@@ -712,7 +751,7 @@ fn sensor_value_new() {
             assert!((v0.raw_value() - 3.0).abs() <= f64::EPSILON);
             assert_eq!(v0.unit(), Unit::None);
 
-            v0.set_raw_value(-1.0).unwrap_err();
+            assert!(v0.try_set_raw_value(-1.0).is_none());
         } else {
             assert!((v0.raw_value() - 3.49).abs() <= f64::EPSILON);
         }
@@ -730,7 +769,7 @@ fn sensor_value_new() {
             assert!(!v1.to_string().is_empty());
         }
 
-        let old_value = v1.set_raw_value(3.49).unwrap();
+        let old_value = v1.try_set_raw_value(3.49).unwrap();
         assert!((old_value - 0.0).abs() <= f64::EPSILON);
 
         assert_eq!(v0, v1);
@@ -776,3 +815,143 @@ fn sensor_value_new_temperature_sensor_kind() {
     )
     .is_none());
 }
+
+#[test]
+fn round_to_i64() {
+    for value in [
+        0.0, -0.0, 0.49, 0.5, 0.51, -0.49, -0.5, -0.51, 2.5, -2.5, 1000.49, 1e16, -1e16,
+    ] {
+        assert_eq!(super::round_to_i64(value), value.round() as i64, "{value}");
+    }
+
+    assert_eq!(super::round_to_i64(1e300), i64::MAX);
+    assert_eq!(super::round_to_i64(-1e300), i64::MIN);
+}
+
+#[test]
+fn try_set_raw_value() {
+    use super::{TemperatureSensorKind, Value};
+
+    let mut v = Value::TemperatureType(TemperatureSensorKind::Transistor);
+    assert!(v.try_set_raw_value(-1.0).is_none());
+    assert_eq!(v, Value::TemperatureType(TemperatureSensorKind::Transistor));
+
+    let old_value = v.try_set_raw_value(4.0).unwrap();
+    assert!((old_value - 2.0).abs() <= f64::EPSILON);
+    assert_eq!(v, Value::TemperatureType(TemperatureSensorKind::Thermistor));
+
+    let mut v = Value::FanAlarm(false);
+    assert!((v.try_set_raw_value(1.0).unwrap() - 0.0).abs() <= f64::EPSILON);
+    assert_eq!(v, Value::FanAlarm(true));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn set_raw_value() {
+    use super::{TemperatureSensorKind, Value};
+
+    let mut v = Value::TemperatureType(TemperatureSensorKind::Transistor);
+    v.set_raw_value(-1.0).unwrap_err();
+    assert_eq!(v, Value::TemperatureType(TemperatureSensorKind::Transistor));
+
+    let old_value = v.set_raw_value(4.0).unwrap();
+    assert!((old_value - 2.0).abs() <= f64::EPSILON);
+    assert_eq!(v, Value::TemperatureType(TemperatureSensorKind::Thermistor));
+}
+
+/// Call `f` with many arbitrary values.
+#[cfg(feature = "arbitrary")]
+fn for_arbitrary_values(mut f: impl FnMut(super::Value, f64)) {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    // Miri is slow, so it checks fewer values.
+    let len = if cfg!(miri) { 1 << 12 } else { 1 << 20 };
+
+    // Deterministic pseudo-random bytes, by xorshift64.
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    let bytes: Vec<u8> = (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect();
+
+    let mut u = Unstructured::new(&bytes);
+    let mut count = 0;
+    while !u.is_empty() {
+        let Ok((value, new_value)) = <(super::Value, f64)>::arbitrary(&mut u) else {
+            break;
+        };
+        f(value, new_value);
+        count += 1;
+    }
+    assert!(count > len / 128);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_new_round_trip() {
+    use super::Value;
+
+    for_arbitrary_values(|value, _| {
+        let copy = Value::new(value.kind(), value.raw_value()).unwrap();
+        assert_eq!(copy.kind(), value.kind());
+        assert_eq!(copy.raw_value().to_bits(), value.raw_value().to_bits());
+        assert_eq!(copy.unit(), value.kind().unit());
+    });
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_set_raw_value_round_trip() {
+    use super::Value;
+
+    for_arbitrary_values(|value, new_value| {
+        let mut changed = value;
+        let expected = Value::new(value.kind(), new_value);
+
+        match changed.try_set_raw_value(new_value) {
+            Some(old_value) => {
+                assert_eq!(old_value.to_bits(), value.raw_value().to_bits());
+                let expected = expected.unwrap();
+                assert_eq!(changed.kind(), value.kind());
+                assert_eq!(
+                    changed.raw_value().to_bits(),
+                    expected.raw_value().to_bits()
+                );
+            }
+            None => {
+                assert!(expected.is_none());
+                assert_eq!(changed.raw_value().to_bits(), value.raw_value().to_bits());
+            }
+        }
+    });
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    use super::{TemperatureSensorKind, Value};
+
+    for (k, _n, u, _b, _s) in KIND_LIST {
+        let json = serde_json::to_string(&k).unwrap();
+        assert_eq!(serde_json::from_str::<Kind>(&json).unwrap(), k);
+
+        let json = serde_json::to_string(&u).unwrap();
+        assert_eq!(serde_json::from_str::<Unit>(&json).unwrap(), u);
+
+        let value = Value::new(k, 1.0).unwrap();
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+    }
+
+    let value = Value::TemperatureType(TemperatureSensorKind::Thermistor);
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"TemperatureType":"Thermistor"}"#);
+    assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+
+    let json = serde_json::to_string(&Value::TemperatureInput(47.5)).unwrap();
+    assert_eq!(json, r#"{"TemperatureInput":47.5}"#);
+}
@@ -0,0 +1,525 @@
+//! Names of features that are stable across reboots.
+//!
+//! Hardware monitoring devices are numbered in the order they are probed,
+//! *e.g.,* `hwmon2`, and I2C bus numbers can change between boots, so names
+//! based on them break dashboards. A [`StableName`] is made only of the
+//! prefix, bus type and address of a chip, and of the label of a feature,
+//! *e.g.,* `nct6775_isa_0290_cpu_temp`, and is meant to key the readings
+//! of features, *e.g.,* in exporters of metrics.
+//!
+//! Identical chips at the same address on different I2C buses have the same
+//! stable names.
+//!
+//! Monitoring systems impose different naming conventions, so a
+//! [`NameTemplate`], *e.g.,* `{prefix}-{bus_kind}-{addr:04x}_{label_or_name}`,
+//! names features instead, uniformly in exporters, snapshots and displayed
+//! features.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::str::FromStr;
+use std::io;
+
+use crate::backend::Reading;
+use crate::errors::{Error, Result};
+
+/// Bus types used in chip names.
+const BUS_NAMES: [&str; 9] = [
+    "i2c", "isa", "pci", "spi", "virtual", "acpi", "hid", "mdio", "scsi",
+];
+
+/**
+Name of a feature, stable across reboots and reordering of devices.
+
+Names are made of lowercase ASCII letters, digits and underscores.
+
+# Example
+
+```rust
+use lm_sensors::naming::StableName;
+
+let name = StableName::new("nct6775", "isa", Some(0x290), "CPU Temp");
+assert_eq!(name.as_str(), "nct6775_isa_0290_cpu_temp");
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StableName(String);
+
+impl StableName {
+    /// Return the stable name of the feature having the given label, of
+    /// the chip having the given prefix, bus type and address.
+    ///
+    /// The bus type is the one used in chip names, *e.g.,* `isa` or `i2c`.
+    /// Addresses are formatted in hexadecimal, with the width used in chip
+    /// names, *e.g.,* four digits on ISA and PCI buses.
+    #[must_use]
+    pub fn new(prefix: &str, bus: &str, address: Option<i32>, label: &str) -> Self {
+        let address = address.map(|address| match bus {
+            "i2c" => format!("{address:02x}"),
+            "isa" | "pci" => format!("{address:04x}"),
+            _ => format!("{address:x}"),
+        });
+
+        let mut result = String::with_capacity(prefix.len() + bus.len() + label.len() + 8);
+        for part in [Some(prefix), Some(bus), address.as_deref(), Some(label)]
+            .into_iter()
+            .flatten()
+        {
+            push_sanitized(&mut result, part);
+        }
+        Self(result)
+    }
+
+    /// Return the stable name of the feature having the given label, of
+    /// the chip having the given name, *e.g.,* `lm75-i2c-1-48`.
+    ///
+    /// Bus numbers in chip names are dropped, *e.g.,* `lm75-i2c-1-48`
+    /// and `lm75-i2c-3-48` give the same name. Names without a known bus
+    /// type, *e.g.,* names prefixed by a backend, are used as prefixes.
+    #[must_use]
+    pub fn from_chip_name(chip: &str, label: &str) -> Self {
+        let parts = ChipParts::parse(chip);
+        Self::new(&parts.prefix, parts.bus, parts.address, label)
+    }
+
+    /// Return the stable name of the given feature.
+    #[cfg(target_os = "linux")]
+    pub fn of(feature: crate::FeatureRef<'_>) -> Result<Self> {
+        let chip = feature.chip();
+        let prefix = chip.prefix().transpose()?.unwrap_or_default();
+        let bus = chip.bus().kind().map_or("any", bus_name);
+        let label = feature.label()?;
+        Ok(Self::new(prefix, bus, chip.address(), &label))
+    }
+
+    /// Return this name as a string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for StableName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for StableName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<StableName> for String {
+    fn from(name: StableName) -> Self {
+        name.0
+    }
+}
+
+/// Parts of a chip name, *e.g.,* `lm75-i2c-1-48`.
+struct ChipParts<'a> {
+    prefix: String,
+    bus: &'a str,
+    bus_number: Option<&'a str>,
+    address: Option<i32>,
+}
+
+impl<'a> ChipParts<'a> {
+    /// Return the parts of the given chip name.
+    ///
+    /// Names without a known bus type, *e.g.,* names prefixed by a backend,
+    /// are used as prefixes.
+    fn parse(chip: &'a str) -> Self {
+        let parts: Vec<&str> = chip.split('-').collect();
+        let bus_index = parts
+            .iter()
+            .rposition(|part| BUS_NAMES.contains(part))
+            .filter(|&index| index > 0);
+
+        let Some(index) = bus_index else {
+            return Self {
+                prefix: chip.into(),
+                bus: "",
+                bus_number: None,
+                address: None,
+            };
+        };
+
+        let bus = parts[index];
+        let numbered = matches!(bus, "i2c" | "spi" | "hid" | "scsi");
+        let address = parts
+            .get(index + 1 + usize::from(numbered))
+            .and_then(|address| i32::from_str_radix(address, 16).ok());
+        Self {
+            prefix: parts[..index].join("-"),
+            bus,
+            bus_number: numbered.then(|| parts.get(index + 1).copied()).flatten(),
+            address,
+        }
+    }
+}
+
+/// Names of a feature given to [`NameTemplate::render`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NameFields<'a> {
+    /// Name of the chip, *e.g.,* `nct6775-isa-0290`.
+    pub chip: &'a str,
+    /// Name of the feature, *e.g.,* `temp1`.
+    pub feature: &'a str,
+    /// Label of the feature, *e.g.,* `CPU Temp`, if known.
+    pub label: Option<&'a str>,
+    /// Name of the sub-feature, *e.g.,* `temp1_input`, if any.
+    pub sub_feature: Option<&'a str>,
+}
+
+impl<'a> From<&'a Reading> for NameFields<'a> {
+    fn from(reading: &'a Reading) -> Self {
+        Self {
+            chip: &reading.chip,
+            feature: &reading.feature,
+            label: None,
+            sub_feature: Some(&reading.sub_feature),
+        }
+    }
+}
+
+/// Field of a [`NameTemplate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Chip,
+    Prefix,
+    BusKind,
+    BusNumber,
+    Address,
+    Feature,
+    Label,
+    LabelOrName,
+    SubFeature,
+    Stable,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "chip" => Self::Chip,
+            "prefix" => Self::Prefix,
+            "bus_kind" => Self::BusKind,
+            "bus_nr" => Self::BusNumber,
+            "addr" => Self::Address,
+            "feature" => Self::Feature,
+            "label" => Self::Label,
+            "label_or_name" => Self::LabelOrName,
+            "sub_feature" => Self::SubFeature,
+            "stable" => Self::Stable,
+            _ => return None,
+        })
+    }
+}
+
+/// Format of a numeric field of a [`NameTemplate`], *e.g.,* `04x`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct NumberFormat {
+    width: usize,
+    zero_padded: bool,
+    radix: Radix,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Radix {
+    #[default]
+    Decimal,
+    LowerHex,
+    UpperHex,
+}
+
+impl NumberFormat {
+    fn parse(spec: &str) -> Option<Self> {
+        let (digits, radix) = match spec.as_bytes().last() {
+            Some(b'x') => (&spec[..spec.len() - 1], Radix::LowerHex),
+            Some(b'X') => (&spec[..spec.len() - 1], Radix::UpperHex),
+            Some(b'd') => (&spec[..spec.len() - 1], Radix::Decimal),
+            _ => (spec, Radix::Decimal),
+        };
+        let width = if digits.is_empty() {
+            0
+        } else if digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse().ok()?
+        } else {
+            return None;
+        };
+        Some(Self {
+            width,
+            zero_padded: digits.starts_with('0'),
+            radix,
+        })
+    }
+
+    fn write(self, name: &mut String, number: i64) {
+        use fmt::Write;
+
+        let width = self.width;
+        let _ignored = match (self.radix, self.zero_padded) {
+            (Radix::Decimal, false) => write!(name, "{number:width$}"),
+            (Radix::Decimal, true) => write!(name, "{number:0width$}"),
+            (Radix::LowerHex, false) => write!(name, "{number:width$x}"),
+            (Radix::LowerHex, true) => write!(name, "{number:0width$x}"),
+            (Radix::UpperHex, false) => write!(name, "{number:width$X}"),
+            (Radix::UpperHex, true) => write!(name, "{number:0width$X}"),
+        };
+    }
+}
+
+/// Segment of a [`NameTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Field(Field, NumberFormat),
+}
+
+/**
+Template of names of features, *e.g.,* `{prefix}-{bus_kind}-{addr:04x}`.
+
+Templates are made of text and of fields between braces, replaced by
+names of the feature:
+
+| Field | Replacement |
+|-------|-------------|
+| `{chip}` | name of the chip, *e.g.,* `lm75-i2c-1-48` |
+| `{prefix}` | prefix of the chip name, *e.g.,* `lm75` |
+| `{bus_kind}` | bus type of the chip name, *e.g.,* `i2c` |
+| `{bus_nr}` | bus number of the chip name, *e.g.,* `1`, if any |
+| `{addr}` | address of the chip name, *e.g.,* `72` |
+| `{feature}` | name of the feature, *e.g.,* `temp1` |
+| `{label}` | label of the feature, if known |
+| `{label_or_name}` | label of the feature if known, or its name |
+| `{sub_feature}` | name of the sub-feature, *e.g.,* `temp1_input`, if any |
+| `{stable}` | [`StableName`] of the feature |
+
+Numeric fields, *i.e.,* `{bus_nr}` and `{addr}`, accept a format after
+a colon: a minimal width, zero-padded if it starts with `0`, followed by
+`x` or `X` for hexadecimal, *e.g.,* `{addr:04x}`. Missing fields are
+replaced by nothing. Braces are escaped by doubling them, *e.g.,* `{{`.
+
+The default template is `{stable}`, which exporters use unless told
+otherwise.
+
+# Example
+
+```rust
+use lm_sensors::naming::{NameFields, NameTemplate};
+
+let fields = NameFields {
+    chip: "nct6775-isa-0290",
+    feature: "temp2",
+    label: Some("CPU Temp"),
+    sub_feature: None,
+};
+
+let template: NameTemplate = "{prefix}-{bus_kind}-{addr:04x}".parse()?;
+assert_eq!(template.render(&fields), "nct6775-isa-0290");
+
+let template: NameTemplate = "{label_or_name}".parse()?;
+assert_eq!(template.render(&fields), "CPU Temp");
+assert_eq!(NameTemplate::default().render(&fields), "nct6775_isa_0290_cpu_temp");
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameTemplate {
+    text: String,
+    segments: Vec<Segment>,
+}
+
+impl NameTemplate {
+    /// Template of [`StableName`]s.
+    pub const STABLE: &'static str = "{stable}";
+
+    /// Return the given template, or an error if it is malformed.
+    pub fn new(template: &str) -> Result<Self> {
+        let error = |message: String| {
+            let err = io::Error::new(io::ErrorKind::InvalidInput, message);
+            Error::from_io("NameTemplate::new()", err)
+        };
+
+        let mut segments = Vec::default();
+        let mut text = String::default();
+        let mut rest = template;
+        while let Some(index) = rest.find(['{', '}']) {
+            text.push_str(&rest[..index]);
+            let (brace, after) = rest[index..].split_at(1);
+
+            if let Some(after) = after.strip_prefix(brace) {
+                text.push_str(brace);
+                rest = after;
+                continue;
+            }
+            if brace == "}" {
+                return Err(error(format!(
+                    "unmatched '}}' in name template: '{template}'"
+                )));
+            }
+
+            let Some((field, after)) = after.split_once('}') else {
+                return Err(error(format!(
+                    "unmatched '{{' in name template: '{template}'"
+                )));
+            };
+            let (name, spec) = field.split_once(':').unwrap_or((field, ""));
+            let Some(field) = Field::from_name(name) else {
+                return Err(error(format!(
+                    "unknown field in name template: '{{{name}}}'"
+                )));
+            };
+            let numeric = matches!(field, Field::BusNumber | Field::Address);
+            let format = NumberFormat::parse(spec).filter(|_| numeric || spec.is_empty());
+            let Some(format) = format else {
+                return Err(error(format!(
+                    "invalid format of field '{name}' in name template: '{spec}'"
+                )));
+            };
+
+            if !text.is_empty() {
+                segments.push(Segment::Text(core::mem::take(&mut text)));
+            }
+            segments.push(Segment::Field(field, format));
+            rest = after;
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Self {
+            text: template.into(),
+            segments,
+        })
+    }
+
+    /// Return the text of this template.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Return the name of the given feature.
+    #[must_use]
+    pub fn render(&self, fields: &NameFields<'_>) -> String {
+        let parts = ChipParts::parse(fields.chip);
+        let label_or_name = fields.label.unwrap_or(fields.feature);
+
+        let mut name = String::default();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => name.push_str(text),
+                Segment::Field(field, format) => match field {
+                    Field::Chip => name.push_str(fields.chip),
+                    Field::Prefix => name.push_str(&parts.prefix),
+                    Field::BusKind => name.push_str(parts.bus),
+                    Field::BusNumber => {
+                        if let Some(number) = parts.bus_number.and_then(|n| n.parse().ok()) {
+                            format.write(&mut name, number);
+                        }
+                    }
+                    Field::Address => {
+                        if let Some(address) = parts.address {
+                            format.write(&mut name, i64::from(address));
+                        }
+                    }
+                    Field::Feature => name.push_str(fields.feature),
+                    Field::Label => name.push_str(fields.label.unwrap_or_default()),
+                    Field::LabelOrName => name.push_str(label_or_name),
+                    Field::SubFeature => name.push_str(fields.sub_feature.unwrap_or_default()),
+                    Field::Stable => {
+                        let stable =
+                            StableName::new(&parts.prefix, parts.bus, parts.address, label_or_name);
+                        name.push_str(stable.as_str());
+                    }
+                },
+            }
+        }
+        name
+    }
+
+    /// Return the name of the feature of the given reading.
+    ///
+    /// Labels are not part of readings, so `{label}` is replaced by
+    /// nothing, and `{label_or_name}` by the name of the feature.
+    #[must_use]
+    pub fn render_reading(&self, reading: &Reading) -> String {
+        self.render(&NameFields::from(reading))
+    }
+
+    /// Return the name of the given feature.
+    #[cfg(target_os = "linux")]
+    pub fn render_feature(&self, feature: crate::FeatureRef<'_>) -> Result<String> {
+        let chip = feature.chip().to_string();
+        let name = feature.name().transpose()?.unwrap_or_default();
+        let label = feature.label()?;
+        Ok(self.render(&NameFields {
+            chip: &chip,
+            feature: name,
+            label: Some(&label),
+            sub_feature: None,
+        }))
+    }
+}
+
+impl Default for NameTemplate {
+    fn default() -> Self {
+        Self {
+            text: Self::STABLE.into(),
+            segments: vec![Segment::Field(Field::Stable, NumberFormat::default())],
+        }
+    }
+}
+
+impl FromStr for NameTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for NameTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Append `part` to `name`, separated by an underscore, replacing
+/// characters other than ASCII letters and digits by single underscores.
+fn push_sanitized(name: &mut String, part: &str) {
+    let words = part
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty());
+
+    for word in words {
+        if !name.is_empty() {
+            name.push('_');
+        }
+        name.extend(word.chars().map(|c| c.to_ascii_lowercase()));
+    }
+}
+
+/// Return the bus type used in chip names.
+#[cfg(target_os = "linux")]
+fn bus_name(kind: crate::bus::Kind) -> &'static str {
+    use crate::bus::Kind;
+
+    match kind {
+        Kind::Any => "any",
+        Kind::I2C => "i2c",
+        Kind::ISA => "isa",
+        Kind::PCI => "pci",
+        Kind::SPI => "spi",
+        Kind::Virtual => "virtual",
+        Kind::ACPI => "acpi",
+        Kind::HID => "hid",
+        Kind::MDIO => "mdio",
+        Kind::SCSI => "scsi",
+    }
+}
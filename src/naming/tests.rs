@@ -0,0 +1,143 @@
+#![cfg(test)]
+
+use crate::naming::StableName;
+
+#[test]
+fn new() {
+    let cases = [
+        (
+            ("nct6775", "isa", Some(0x290), "CPU Temp"),
+            "nct6775_isa_0290_cpu_temp",
+        ),
+        (("lm75", "i2c", Some(0x48), "temp1"), "lm75_i2c_48_temp1"),
+        (
+            ("amdgpu", "pci", Some(0x300), "edge"),
+            "amdgpu_pci_0300_edge",
+        ),
+        (("acpitz", "acpi", Some(0), "temp1"), "acpitz_acpi_0_temp1"),
+        (("coretemp", "isa", None, "Core 0"), "coretemp_isa_core_0"),
+        (
+            ("it8688", "isa", Some(0xa40), " +12V -- (rail) "),
+            "it8688_isa_0a40_12v_rail",
+        ),
+    ];
+    for ((prefix, bus, address, label), expected) in cases {
+        let name = StableName::new(prefix, bus, address, label);
+        assert_eq!(name.as_str(), expected);
+        assert_eq!(name.to_string(), expected);
+        assert_eq!(String::from(name), expected);
+    }
+}
+
+#[test]
+fn stable_across_reordering() {
+    let a = StableName::new("nct6775", "isa", Some(0x290), "SYSTIN");
+    let b = StableName::new("nct6775", "isa", Some(0x290), "SYSTIN");
+    assert_eq!(a, b);
+    assert_ne!(a, StableName::new("nct6775", "isa", Some(0x2a0), "SYSTIN"));
+    assert_ne!(a, StableName::new("nct6775", "isa", Some(0x290), "CPUTIN"));
+}
+
+#[test]
+fn from_chip_name() {
+    let cases = [
+        (
+            ("nct6775-isa-0290", "CPU Temp"),
+            "nct6775_isa_0290_cpu_temp",
+        ),
+        (("lm75-i2c-1-48", "temp1"), "lm75_i2c_48_temp1"),
+        (("lm75-i2c-3-48", "temp1"), "lm75_i2c_48_temp1"),
+        (("amdgpu-pci-0300", "edge"), "amdgpu_pci_0300_edge"),
+        (("acpitz-acpi-0", "temp1"), "acpitz_acpi_0_temp1"),
+        (("dell_smm-virtual-0", "fan1"), "dell_smm_virtual_0_fan1"),
+        (
+            ("thermal/cpu_thermal-thermal-0", "temp1"),
+            "thermal_cpu_thermal_thermal_0_temp1",
+        ),
+    ];
+    for ((chip, label), expected) in cases {
+        assert_eq!(StableName::from_chip_name(chip, label).as_str(), expected);
+    }
+
+    assert_eq!(
+        StableName::from_chip_name("nct6775-isa-0290", "SYSTIN"),
+        StableName::new("nct6775", "isa", Some(0x290), "SYSTIN")
+    );
+}
+
+#[test]
+fn template() {
+    use crate::naming::{NameFields, NameTemplate};
+
+    let fields = NameFields {
+        chip: "lm75-i2c-1-48",
+        feature: "temp1",
+        label: Some("Board Temp"),
+        sub_feature: Some("temp1_input"),
+    };
+    let cases = [
+        ("{prefix}-{bus_kind}-{addr:04x}", "lm75-i2c-0048"),
+        ("{prefix}.{bus_nr:02}.{addr:X}.{addr}", "lm75.01.48.72"),
+        ("{label_or_name}", "Board Temp"),
+        (
+            "{chip}/{feature}/{sub_feature}",
+            "lm75-i2c-1-48/temp1/temp1_input",
+        ),
+        ("{{{label}}}", "{Board Temp}"),
+        ("{stable}", "lm75_i2c_48_board_temp"),
+        ("sensors", "sensors"),
+        ("", ""),
+    ];
+    for (text, expected) in cases {
+        let template: NameTemplate = text.parse().unwrap();
+        assert_eq!(template.as_str(), text);
+        assert_eq!(template.to_string(), text);
+        assert_eq!(template.render(&fields), expected, "{text}");
+    }
+
+    // Missing fields are replaced by nothing.
+    let fields = NameFields {
+        chip: "dell_smm-virtual-0",
+        feature: "fan1",
+        ..NameFields::default()
+    };
+    let template = NameTemplate::new("{bus_nr}{label}{sub_feature}|{label_or_name}").unwrap();
+    assert_eq!(template.render(&fields), "|fan1");
+
+    for text in [
+        "{name}",
+        "{prefix",
+        "prefix}",
+        "{label:04x}",
+        "{addr:4z}",
+        "{addr:-1}",
+    ] {
+        assert!(NameTemplate::new(text).is_err(), "{text}");
+    }
+}
+
+#[test]
+fn default_template() {
+    use crate::backend::Reading;
+    use crate::naming::NameTemplate;
+
+    let template = NameTemplate::default();
+    assert_eq!(template.as_str(), NameTemplate::STABLE);
+    assert_eq!(template, NameTemplate::new(NameTemplate::STABLE).unwrap());
+
+    for chip in [
+        "nct6775-isa-0290",
+        "lm75-i2c-3-48",
+        "thermal/cpu_thermal-thermal-0",
+    ] {
+        let reading = Reading {
+            chip: chip.into(),
+            feature: "temp1".into(),
+            sub_feature: "temp1_input".into(),
+            raw_kind: crate::value::Kind::TemperatureInput.as_raw(),
+            raw_value: None,
+        };
+        let expected = StableName::from_chip_name(chip, "temp1");
+        assert_eq!(template.render_reading(&reading), expected.as_str());
+    }
+}
@@ -0,0 +1,332 @@
+//! Enforcement of power budgets.
+//!
+//! A [`PowerBudget`] sums the readings of power input sensors of a
+//! [`Backend`], and when the sum exceeds a budget, lowers power caps,
+//! *e.g.,* `power1_cap` sub-features, step by step on each update. Once the
+//! sum falls below the budget by a margin, caps are raised step by step back
+//! to their maximum. Listeners are notified when the budget is exceeded, and
+//! when consumption is back within the budget.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::io;
+
+use crate::backend::{Backend, Reading};
+use crate::errors::{Error, Result};
+use crate::model::raw::sensors_subfeature_type::SENSORS_SUBFEATURE_POWER_INPUT;
+
+/// Power cap written by a [`PowerBudget`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Actuator {
+    chip: String,
+    sub_feature: String,
+    minimum: f64,
+    maximum: f64,
+}
+
+impl Actuator {
+    /// Return a power cap written between `minimum` and `maximum` watts.
+    ///
+    /// This fails if `minimum` or `maximum` is not a finite number,
+    /// or if `minimum` is greater than `maximum`.
+    pub fn new(
+        chip: impl Into<String>,
+        sub_feature: impl Into<String>,
+        minimum: f64,
+        maximum: f64,
+    ) -> Result<Self> {
+        if !(minimum.is_finite() && maximum.is_finite() && minimum <= maximum) {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io("Actuator::new()", err));
+        }
+
+        Ok(Self {
+            chip: chip.into(),
+            sub_feature: sub_feature.into(),
+            minimum,
+            maximum,
+        })
+    }
+
+    /// Return the name of the chip, *e.g.,* `amdgpu-pci-0300`.
+    #[must_use]
+    pub fn chip(&self) -> &str {
+        &self.chip
+    }
+
+    /// Return the name of the sub-feature, *e.g.,* `power1_cap`.
+    #[must_use]
+    pub fn sub_feature(&self) -> &str {
+        &self.sub_feature
+    }
+
+    /// Return the lowest cap written, in watts.
+    #[must_use]
+    pub fn minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    /// Return the highest cap written, in watts, written when within budget.
+    #[must_use]
+    pub fn maximum(&self) -> f64 {
+        self.maximum
+    }
+}
+
+/// Change of the state of a [`PowerBudget`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum BudgetEvent {
+    /// Total power exceeded the budget.
+    Exceeded {
+        /// Total power, in watts.
+        total: f64,
+        /// Budget, in watts.
+        budget: f64,
+    },
+    /// Total power is back below the budget, by at least the margin.
+    Restored {
+        /// Total power, in watts.
+        total: f64,
+        /// Budget, in watts.
+        budget: f64,
+    },
+}
+
+impl fmt::Display for BudgetEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exceeded { total, budget } => {
+                write!(f, "power {total} W exceeds budget {budget} W")
+            }
+            Self::Restored { total, budget } => {
+                write!(f, "power {total} W is back within budget {budget} W")
+            }
+        }
+    }
+}
+
+/// Result of an update of a [`PowerBudget`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetStatus {
+    /// Total power of the monitored sensors, in watts, or `None` if none
+    /// of them could be read.
+    pub total: Option<f64>,
+    /// `true` if the budget is being enforced.
+    pub over_budget: bool,
+    /// Current caps, in watts, in the order of the actuators.
+    pub caps: Vec<f64>,
+}
+
+type Listener = Box<dyn FnMut(&BudgetEvent) + Send>;
+
+/**
+Controller keeping the total power of sensors within a budget.
+
+When the monitored sensors cannot be read, caps are kept unchanged.
+
+# Example
+
+```rust
+use lm_sensors::backend::Backend;
+use lm_sensors::power::{Actuator, PowerBudget};
+
+fn enforce(backend: &mut dyn Backend) -> lm_sensors::errors::Result<()> {
+    let cap = Actuator::new("amdgpu-pci-0300", "power1_cap", 100.0, 250.0)?;
+    let mut budget = PowerBudget::new(400.0)?
+        .sensor("amdgpu-pci-0300", "power1_input")
+        .actuator(cap)
+        .step(10.0)
+        .on_event(|event| eprintln!("{event}"));
+
+    loop {
+        let status = budget.update(backend)?;
+        println!("{:?} W, caps: {:?}", status.total, status.caps);
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
+```
+*/
+#[must_use]
+pub struct PowerBudget {
+    budget: f64,
+    margin: f64,
+    step: f64,
+    sensors: Vec<(String, String)>,
+    actuators: Vec<Actuator>,
+    caps: Vec<Option<f64>>,
+    over_budget: bool,
+    listeners: Vec<Listener>,
+}
+
+impl PowerBudget {
+    /// Return a controller keeping the total power within the given budget,
+    /// in watts.
+    ///
+    /// By default, all power input sensors are monitored, the margin is 5%
+    /// of the budget, and caps change by 5 watts per update.
+    ///
+    /// This fails if the budget is not a positive number.
+    pub fn new(budget: f64) -> Result<Self> {
+        if !(budget.is_finite() && budget > 0.0) {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io("PowerBudget::new()", err));
+        }
+
+        Ok(Self {
+            budget,
+            margin: budget * 0.05,
+            step: 5.0,
+            sensors: Vec::default(),
+            actuators: Vec::default(),
+            caps: Vec::default(),
+            over_budget: false,
+            listeners: Vec::default(),
+        })
+    }
+
+    /// Monitor the given power input sensor, instead of all of them.
+    pub fn sensor(mut self, chip: impl Into<String>, sub_feature: impl Into<String>) -> Self {
+        self.sensors.push((chip.into(), sub_feature.into()));
+        self
+    }
+
+    /// Write the given power cap when enforcing the budget.
+    pub fn actuator(mut self, actuator: Actuator) -> Self {
+        self.actuators.push(actuator);
+        self.caps.push(None);
+        self
+    }
+
+    /// Raise caps only once the total power is below the budget by
+    /// the given margin, in watts.
+    pub fn margin(self, margin: f64) -> Self {
+        Self {
+            margin: margin.max(0.0),
+            ..self
+        }
+    }
+
+    /// Change caps by the given number of watts on each update.
+    pub fn step(self, step: f64) -> Self {
+        Self {
+            step: step.max(0.0),
+            ..self
+        }
+    }
+
+    /// Call the given function when the budget is exceeded,
+    /// and when consumption is back within the budget.
+    pub fn on_event(mut self, f: impl FnMut(&BudgetEvent) + Send + 'static) -> Self {
+        self.listeners.push(Box::new(f));
+        self
+    }
+
+    /// Return the budget, in watts.
+    #[must_use]
+    pub fn budget(&self) -> f64 {
+        self.budget
+    }
+
+    /// Read the monitored sensors, then lower or raise caps by one step,
+    /// and notify listeners if the budget was exceeded or restored.
+    ///
+    /// Caps start from their maximum, and are written only when they change.
+    pub fn update(&mut self, backend: &mut dyn Backend) -> Result<BudgetStatus> {
+        let readings = backend.read_all()?;
+        let total = self.total(&readings);
+
+        let event = match total {
+            Some(total) if total > self.budget => {
+                let event = (!self.over_budget).then_some(BudgetEvent::Exceeded {
+                    total,
+                    budget: self.budget,
+                });
+                self.over_budget = true;
+                self.ramp(backend, -self.step)?;
+                event
+            }
+
+            Some(total) if total <= self.budget - self.margin => {
+                let event = self.over_budget.then_some(BudgetEvent::Restored {
+                    total,
+                    budget: self.budget,
+                });
+                self.over_budget = false;
+                self.ramp(backend, self.step)?;
+                event
+            }
+
+            // Unknown, or within the margin.
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            for listener in &mut self.listeners {
+                listener(&event);
+            }
+        }
+
+        let caps = self.caps.iter().zip(&self.actuators);
+        Ok(BudgetStatus {
+            total,
+            over_budget: self.over_budget,
+            caps: caps
+                .map(|(cap, actuator)| cap.unwrap_or(actuator.maximum))
+                .collect(),
+        })
+    }
+
+    fn total(&self, readings: &[Reading]) -> Option<f64> {
+        let monitored = |reading: &&Reading| {
+            if self.sensors.is_empty() {
+                reading.raw_kind == SENSORS_SUBFEATURE_POWER_INPUT
+            } else {
+                let key = |(chip, sub_feature): &(String, String)| {
+                    *chip == reading.chip && *sub_feature == reading.sub_feature
+                };
+                self.sensors.iter().any(key)
+            }
+        };
+
+        let values = readings
+            .iter()
+            .filter(monitored)
+            .filter_map(|r| r.raw_value);
+        values.fold(None, |total, value| Some(total.unwrap_or(0.0) + value))
+    }
+
+    /// Change all caps by the given number of watts, within their limits.
+    fn ramp(&mut self, backend: &mut dyn Backend, delta: f64) -> Result<()> {
+        for (cap, actuator) in self.caps.iter_mut().zip(&self.actuators) {
+            let new_cap = match *cap {
+                None if delta > 0.0 => actuator.maximum,
+                None => actuator.maximum + delta,
+                Some(cap) => cap + delta,
+            };
+            let new_cap = new_cap.clamp(actuator.minimum, actuator.maximum);
+
+            if *cap != Some(new_cap) {
+                backend.write(&actuator.chip, &actuator.sub_feature, new_cap)?;
+                *cap = Some(new_cap);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PowerBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PowerBudget")
+            .field("budget", &self.budget)
+            .field("margin", &self.margin)
+            .field("step", &self.step)
+            .field("sensors", &self.sensors)
+            .field("actuators", &self.actuators)
+            .field("caps", &self.caps)
+            .field("over_budget", &self.over_budget)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,155 @@
+#![cfg(test)]
+
+use std::sync::{Arc, Mutex};
+
+use crate::backend::{Backend, Reading};
+use crate::errors::Result;
+use crate::model::raw::sensors_subfeature_type::*;
+use crate::power::{Actuator, BudgetEvent, PowerBudget};
+
+#[derive(Debug, Default)]
+struct Rack {
+    power: Vec<Option<f64>>,
+    written: Vec<(String, String, f64)>,
+}
+
+impl Backend for Rack {
+    fn name(&self) -> &'static str {
+        "rack"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let mut readings: Vec<_> = self
+            .power
+            .iter()
+            .enumerate()
+            .map(|(index, &raw_value)| Reading {
+                chip: format!("psu-virtual-{index}"),
+                feature: "power1".into(),
+                sub_feature: "power1_input".into(),
+                raw_kind: SENSORS_SUBFEATURE_POWER_INPUT,
+                raw_value,
+            })
+            .collect();
+        readings.push(Reading {
+            chip: "psu-virtual-0".into(),
+            feature: "power1".into(),
+            sub_feature: "power1_cap".into(),
+            raw_kind: SENSORS_SUBFEATURE_POWER_CAP,
+            raw_value: Some(1000.0),
+        });
+        Ok(readings)
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        self.written
+            .push((chip.into(), sub_feature.into(), raw_value));
+        Ok(())
+    }
+}
+
+#[test]
+fn new() {
+    assert!(PowerBudget::new(0.0).is_err());
+    assert!(PowerBudget::new(-1.0).is_err());
+    assert!(PowerBudget::new(f64::NAN).is_err());
+    assert_eq!(PowerBudget::new(100.0).unwrap().budget(), 100.0);
+}
+
+#[test]
+fn actuator() {
+    let actuator = Actuator::new("psu-virtual-0", "power1_cap", 80.0, 100.0).unwrap();
+    assert_eq!(actuator.chip(), "psu-virtual-0");
+    assert_eq!(actuator.sub_feature(), "power1_cap");
+    assert_eq!((actuator.minimum(), actuator.maximum()), (80.0, 100.0));
+
+    assert!(Actuator::new("psu-virtual-0", "power1_cap", 80.0, 80.0).is_ok());
+    assert!(Actuator::new("psu-virtual-0", "power1_cap", 100.0, 80.0).is_err());
+    assert!(Actuator::new("psu-virtual-0", "power1_cap", f64::NAN, 100.0).is_err());
+    assert!(Actuator::new("psu-virtual-0", "power1_cap", 80.0, f64::INFINITY).is_err());
+}
+
+#[test]
+fn update() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_clone = Arc::clone(&events);
+
+    let mut budget = PowerBudget::new(300.0)
+        .unwrap()
+        .actuator(Actuator::new("psu-virtual-0", "power1_cap", 80.0, 100.0).unwrap())
+        .margin(20.0)
+        .step(15.0)
+        .on_event(move |event| events_clone.lock().unwrap().push(*event));
+
+    let mut rack = Rack {
+        power: vec![Some(200.0), Some(120.0)],
+        ..Rack::default()
+    };
+
+    // Over budget: ramp down, and saturate at the minimum.
+    let status = budget.update(&mut rack).unwrap();
+    assert_eq!(status.total, Some(320.0));
+    assert!(status.over_budget);
+    assert_eq!(status.caps, [85.0]);
+    let status = budget.update(&mut rack).unwrap();
+    assert_eq!(status.caps, [80.0]);
+    budget.update(&mut rack).unwrap();
+    assert_eq!(rack.written.len(), 2);
+
+    // Within the margin, or unknown: hold.
+    rack.power = vec![Some(200.0), Some(90.0)];
+    let status = budget.update(&mut rack).unwrap();
+    assert!(status.over_budget);
+    assert_eq!(status.caps, [80.0]);
+    rack.power = vec![None, None];
+    let status = budget.update(&mut rack).unwrap();
+    assert_eq!(status.total, None);
+    assert_eq!(status.caps, [80.0]);
+
+    // Below the margin: ramp up, and saturate at the maximum.
+    rack.power = vec![Some(200.0), None];
+    let status = budget.update(&mut rack).unwrap();
+    assert!(!status.over_budget);
+    assert_eq!(status.caps, [95.0]);
+    let status = budget.update(&mut rack).unwrap();
+    assert_eq!(status.caps, [100.0]);
+    budget.update(&mut rack).unwrap();
+
+    let written: Vec<_> = rack.written.iter().map(|w| w.2).collect();
+    assert_eq!(written, [85.0, 80.0, 95.0, 100.0]);
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            BudgetEvent::Exceeded {
+                total: 320.0,
+                budget: 300.0
+            },
+            BudgetEvent::Restored {
+                total: 200.0,
+                budget: 300.0
+            },
+        ]
+    );
+}
+
+#[test]
+fn sensors() {
+    let mut rack = Rack {
+        power: vec![Some(200.0), Some(120.0)],
+        ..Rack::default()
+    };
+
+    let mut budget = PowerBudget::new(300.0)
+        .unwrap()
+        .sensor("psu-virtual-1", "power1_input")
+        .actuator(Actuator::new("psu-virtual-0", "power1_cap", 80.0, 100.0).unwrap());
+
+    // The first update writes maximum caps.
+    let status = budget.update(&mut rack).unwrap();
+    assert_eq!(status.total, Some(120.0));
+    assert!(!status.over_budget);
+    assert_eq!(
+        rack.written,
+        [("psu-virtual-0".into(), "power1_cap".into(), 100.0)]
+    );
+}
@@ -0,0 +1,9 @@
+//! Commonly used traits and types.
+//!
+//! ```rust
+//! use lm_sensors::prelude::*;
+//! ```
+
+pub use crate::bus::SharedBus;
+pub use crate::chip::SharedChip;
+pub use crate::{Bus, Chip, ChipRef, FeatureRef, Initializer, LMSensors, SubFeatureRef, Value};
@@ -0,0 +1,169 @@
+//! Default fan profiles for common Super-I/O chips.
+//!
+//! A [`Profile`] maps temperatures to PWM values, from `0` to [`PWM_MAX`],
+//! and never drives fans below a minimum PWM value keeping them spinning.
+//! Profiles are safe starting points for the fans driven by
+//! [`SUPPORTED_CHIPS`], *e.g.,* `nct6798` and `it8688`, and are selected by
+//! name: [`quiet`], [`balanced`] and [`performance`].
+//!
+//! Profiles are only data: writing the PWM values they compute is up to the
+//! caller, typically after arming a `control::RestoreGuard`.
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum PWM value, driving a fan at full speed.
+pub const PWM_MAX: f64 = 255.0;
+
+/// Prefixes of the chips driving fans with 8-bit PWM values, for which
+/// profiles are intended.
+pub const SUPPORTED_CHIPS: &[&str] = &[
+    "nct6775", "nct6776", "nct6779", "nct6791", "nct6792", "nct6793", "nct6795", "nct6796",
+    "nct6797", "nct6798", "it8620", "it8628", "it8655", "it8665", "it8686", "it8688", "it8689",
+];
+
+/// Fan profile mapping temperatures to PWM values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    name: &'static str,
+    description: &'static str,
+    points: &'static [(f64, f64)],
+    minimum_pwm: f64,
+}
+
+static PROFILES: [Profile; 3] = [
+    Profile {
+        name: "quiet",
+        description: "Low fan speeds, ramping up steeply above 70 °C.",
+        points: &[(40.0, 64.0), (55.0, 90.0), (70.0, 140.0), (80.0, PWM_MAX)],
+        minimum_pwm: 64.0,
+    },
+    Profile {
+        name: "balanced",
+        description: "Moderate fan speeds, reaching full speed at 75 °C.",
+        points: &[(35.0, 80.0), (50.0, 115.0), (65.0, 175.0), (75.0, PWM_MAX)],
+        minimum_pwm: 80.0,
+    },
+    Profile {
+        name: "performance",
+        description: "High fan speeds, reaching full speed at 70 °C.",
+        points: &[(30.0, 110.0), (45.0, 160.0), (60.0, 215.0), (70.0, PWM_MAX)],
+        minimum_pwm: 110.0,
+    },
+];
+
+impl Profile {
+    /// Return the name of this profile, *e.g.,* `quiet`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Return a short description of this profile.
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Return the points of this profile, each made of a temperature in
+    /// degrees Celsius and a PWM value, sorted by temperature.
+    #[must_use]
+    pub fn points(&self) -> &'static [(f64, f64)] {
+        self.points
+    }
+
+    /// Return the lowest PWM value driven by this profile.
+    #[must_use]
+    pub fn minimum_pwm(&self) -> f64 {
+        self.minimum_pwm
+    }
+
+    /// Return the PWM value for the given temperature, in degrees Celsius.
+    ///
+    /// PWM values are linearly interpolated between points, and are constant
+    /// below the first point and above the last one. Temperatures that are
+    /// not numbers drive fans at full speed.
+    #[must_use]
+    pub fn pwm(&self, temperature: f64) -> f64 {
+        if temperature.is_nan() {
+            return PWM_MAX;
+        }
+
+        let upper = self.points.partition_point(|&(t, _)| t <= temperature);
+        let pwm = if upper == 0 {
+            self.points[0].1
+        } else if upper == self.points.len() {
+            self.points[upper - 1].1
+        } else {
+            let (t0, pwm0) = self.points[upper - 1];
+            let (t1, pwm1) = self.points[upper];
+            pwm0 + (pwm1 - pwm0) * (temperature - t0) / (t1 - t0)
+        };
+        pwm.clamp(self.minimum_pwm, PWM_MAX)
+    }
+
+    /// Return the fan curve of this profile, *e.g.,* to simulate it.
+    #[cfg(feature = "simulation")]
+    #[must_use]
+    pub fn fan_curve(&self) -> crate::simulation::FanCurve {
+        crate::simulation::FanCurve::new(self.points.iter().copied())
+            .expect("preset points are valid")
+    }
+}
+
+/// Return the profile favoring low noise.
+#[must_use]
+pub fn quiet() -> &'static Profile {
+    &PROFILES[0]
+}
+
+/// Return the profile balancing noise and temperatures.
+#[must_use]
+pub fn balanced() -> &'static Profile {
+    &PROFILES[1]
+}
+
+/// Return the profile favoring low temperatures.
+#[must_use]
+pub fn performance() -> &'static Profile {
+    &PROFILES[2]
+}
+
+/// Return all profiles, from the quietest to the most performant.
+#[must_use]
+pub fn all() -> &'static [Profile] {
+    &PROFILES
+}
+
+/**
+Return the profile of the given name, if any.
+
+# Example
+
+```rust
+let profile = lm_sensors::presets::by_name("quiet").unwrap();
+assert_eq!(profile.pwm(30.0), profile.minimum_pwm());
+assert_eq!(profile.pwm(90.0), lm_sensors::presets::PWM_MAX);
+```
+*/
+#[must_use]
+pub fn by_name(name: &str) -> Option<&'static Profile> {
+    PROFILES.iter().find(|profile| profile.name == name)
+}
+
+/// Return the profile of the given name for the given chip, *e.g.,*
+/// `nct6798-isa-0290`, if the chip is supported.
+///
+/// See: [`SUPPORTED_CHIPS`].
+#[must_use]
+pub fn for_chip(chip: &str, name: &str) -> Option<&'static Profile> {
+    supports(chip).then(|| by_name(name)).flatten()
+}
+
+/// Return `true` if the given chip, *e.g.,* `nct6798-isa-0290`,
+/// is supported by the profiles.
+#[must_use]
+pub fn supports(chip: &str) -> bool {
+    let prefix = chip.split_once('-').map_or(chip, |(prefix, _)| prefix);
+    SUPPORTED_CHIPS.contains(&prefix)
+}
@@ -0,0 +1,71 @@
+#![cfg(test)]
+
+use crate::presets::{self, PWM_MAX};
+
+#[test]
+fn profiles() {
+    let names: Vec<_> = presets::all().iter().map(presets::Profile::name).collect();
+    assert_eq!(names, ["quiet", "balanced", "performance"]);
+    assert_eq!(presets::quiet().name(), "quiet");
+    assert_eq!(presets::balanced().name(), "balanced");
+    assert_eq!(presets::performance().name(), "performance");
+    assert_eq!(
+        presets::by_name("performance"),
+        Some(presets::performance())
+    );
+    assert_eq!(presets::by_name("loud"), None);
+
+    for profile in presets::all() {
+        let points = profile.points();
+        assert!(points
+            .windows(2)
+            .all(|w| w[0].0 < w[1].0 && w[0].1 <= w[1].1));
+        assert_eq!(points.last().unwrap().1, PWM_MAX);
+        assert!(points.iter().all(|&(_, pwm)| pwm >= profile.minimum_pwm()));
+        assert!(!profile.description().is_empty());
+    }
+}
+
+#[test]
+fn pwm() {
+    let profile = presets::balanced();
+    assert_eq!(profile.pwm(0.0), 80.0);
+    assert_eq!(profile.pwm(35.0), 80.0);
+    assert_eq!(profile.pwm(42.5), 97.5);
+    assert_eq!(profile.pwm(75.0), PWM_MAX);
+    assert_eq!(profile.pwm(f64::INFINITY), PWM_MAX);
+    assert_eq!(profile.pwm(f64::NEG_INFINITY), 80.0);
+    assert_eq!(profile.pwm(f64::NAN), PWM_MAX);
+
+    // Quieter profiles never drive fans faster.
+    for temperature in 0..100 {
+        let temperature = f64::from(temperature);
+        let pwm = presets::all()
+            .iter()
+            .map(|profile| profile.pwm(temperature));
+        let pwm: Vec<_> = pwm.collect();
+        assert!(pwm.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
+
+#[test]
+fn chips() {
+    assert!(presets::supports("nct6798-isa-0290"));
+    assert!(presets::supports("it8688-isa-0a40"));
+    assert!(!presets::supports("coretemp-isa-0000"));
+
+    let profile = presets::for_chip("nct6775-isa-0290", "quiet");
+    assert_eq!(profile, Some(presets::quiet()));
+    assert_eq!(presets::for_chip("coretemp-isa-0000", "quiet"), None);
+    assert_eq!(presets::for_chip("nct6775-isa-0290", "loud"), None);
+}
+
+#[cfg(feature = "simulation")]
+#[test]
+fn fan_curve() {
+    for profile in presets::all() {
+        let curve = profile.fan_curve();
+        assert_eq!(curve.points(), profile.points());
+        assert_eq!(curve.pwm(50.0), profile.pwm(50.0));
+    }
+}
@@ -0,0 +1,192 @@
+//! Readiness and liveness probes, *e.g.,* for orchestrators.
+//!
+//! [`check`] succeeds only when the LM sensors library is initialized,
+//! enough sub-features are readable, and no part of the system is in
+//! [`Health::Critical`] state. [`http_handler`] answers HTTP probes with
+//! the result of [`check`].
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use crate::backend::Backend;
+use crate::errors::{Error, Result};
+use crate::health::{self, Health, HealthReport, Policy};
+use crate::snapshot::Snapshot;
+
+/// Maximum length of the head of an HTTP request, in bytes.
+pub const MAX_REQUEST_HEAD_LEN: u64 = 8 << 10;
+
+/// Requirements of a successful probe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probe {
+    /// Whether the LM sensors library must be initialized.
+    ///
+    /// The library is never initialized on platforms other than Linux.
+    pub require_library: bool,
+    /// Minimum number of readable sub-features.
+    pub min_readable: usize,
+    /// Policy of health scoring.
+    pub health: Policy,
+}
+
+impl Default for Probe {
+    /// Return requirements of an initialized library, at least one readable
+    /// sub-feature, and the default health policy.
+    fn default() -> Self {
+        Self {
+            require_library: true,
+            min_readable: 1,
+            health: Policy::default(),
+        }
+    }
+}
+
+/// Reason of a failed probe.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Failure {
+    /// The LM sensors library is not initialized.
+    Uninitialized,
+    /// Reading sub-features failed.
+    Read(Error),
+    /// Fewer sub-features than required are readable.
+    TooFewReadable {
+        /// Number of readable sub-features.
+        readable: usize,
+        /// Minimum number of readable sub-features.
+        required: usize,
+    },
+    /// Some part of the system is in critical state.
+    Critical(HealthReport),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uninitialized => f.write_str("LM sensors library is not initialized"),
+            Self::Read(err) => write!(f, "failed to read sub-features: {err}"),
+            Self::TooFewReadable { readable, required } => {
+                write!(
+                    f,
+                    "{readable} sub-features are readable, {required} are required"
+                )
+            }
+            Self::Critical(report) => {
+                f.write_str("health is critical")?;
+                let critical = report
+                    .causes
+                    .iter()
+                    .filter(|c| c.health == Health::Critical);
+                for cause in critical {
+                    write!(f, "\n{cause}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Read all sub-features of `backend`, and check that they satisfy
+/// the requirements of `probe`.
+///
+/// On success, this returns the health of the system, which might be
+/// [`Health::Degraded`].
+pub fn check(
+    backend: &mut dyn Backend,
+    probe: &Probe,
+) -> core::result::Result<HealthReport, Failure> {
+    if probe.require_library && !library_initialized() {
+        return Err(Failure::Uninitialized);
+    }
+
+    let snapshot = Snapshot::read(backend).map_err(Failure::Read)?;
+
+    let readable = snapshot
+        .readings
+        .iter()
+        .filter(|reading| reading.raw_value.is_some())
+        .count();
+    if readable < probe.min_readable {
+        let required = probe.min_readable;
+        return Err(Failure::TooFewReadable { readable, required });
+    }
+
+    let report = health::score(&snapshot, &probe.health);
+    if report.health == Health::Critical {
+        Err(Failure::Critical(report))
+    } else {
+        Ok(report)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn library_initialized() -> bool {
+    crate::INITIALIZED.load(core::sync::atomic::Ordering::Acquire)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn library_initialized() -> bool {
+    false
+}
+
+/// Answer one HTTP request received through the given stream with
+/// the result of [`check`].
+///
+/// `GET` and `HEAD` requests of any path are answered by `200 OK` if the
+/// probe succeeds, or by `503 Service Unavailable` otherwise, with the
+/// reason in a plain text body. Other methods are answered by
+/// `405 Method Not Allowed`.
+pub fn http_handler(
+    backend: &mut dyn Backend,
+    probe: &Probe,
+    stream: impl Read + Write,
+) -> Result<()> {
+    handle_http(backend, probe, stream).map_err(|r| Error::from_io("http_handler()", r))
+}
+
+fn handle_http(
+    backend: &mut dyn Backend,
+    probe: &Probe,
+    mut stream: impl Read + Write,
+) -> io::Result<()> {
+    let mut reader = BufReader::new((&mut stream).take(MAX_REQUEST_HEAD_LEN));
+
+    let mut request_line = String::default();
+    reader.read_line(&mut request_line)?;
+    loop {
+        let mut line = String::default();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if line.trim_end().is_empty() {
+            break;
+        }
+    }
+    drop(reader);
+
+    let method = request_line.split_whitespace().next().unwrap_or_default();
+    let (status, body) = match method {
+        "GET" | "HEAD" => match check(backend, probe) {
+            Ok(report) => ("200 OK", format!("{}\n", report.health)),
+            Err(failure) => ("503 Service Unavailable", format!("{failure}\n")),
+        },
+        _ => (
+            "405 Method Not Allowed",
+            String::from("method not allowed\n"),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    if method != "HEAD" {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()
+}
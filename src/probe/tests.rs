@@ -0,0 +1,143 @@
+#![cfg(test)]
+
+use std::io::{self, Cursor, Read, Write};
+
+use crate::backend::{Backend, Reading};
+use crate::errors::Result;
+use crate::health::Health;
+use crate::probe::{check, http_handler, Failure, Probe};
+use crate::value::Kind;
+
+const CHIP: &str = "coretemp-isa-0000";
+
+#[derive(Debug)]
+struct Fixed(Vec<Reading>);
+
+impl Backend for Fixed {
+    fn name(&self) -> &'static str {
+        "fixed"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Ok(self.0.clone())
+    }
+}
+
+fn temperature(input: f64) -> Fixed {
+    Fixed(vec![
+        Reading::for_test(CHIP, "temp1_input", Kind::TemperatureInput, Some(input)),
+        Reading::for_test(CHIP, "temp1_crit", Kind::TemperatureCritical, Some(100.0)),
+    ])
+}
+
+fn anywhere() -> Probe {
+    Probe {
+        require_library: false,
+        ..Probe::default()
+    }
+}
+
+struct Duplex {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl Duplex {
+    fn new(input: &str) -> Self {
+        Self {
+            input: Cursor::new(input.into()),
+            output: Vec::default(),
+        }
+    }
+}
+
+impl Read for Duplex {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for Duplex {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn checks() {
+    let probe = anywhere();
+    let report = check(&mut temperature(50.0), &probe).unwrap();
+    assert_eq!(report.health, Health::Ok);
+    let report = check(&mut temperature(97.0), &probe).unwrap();
+    assert_eq!(report.health, Health::Degraded);
+
+    let failure = check(&mut temperature(101.0), &probe).unwrap_err();
+    assert!(matches!(failure, Failure::Critical(_)));
+    assert!(failure
+        .to_string()
+        .starts_with("health is critical\ncritical: "));
+
+    let probe = Probe {
+        min_readable: 2,
+        ..anywhere()
+    };
+    check(&mut temperature(50.0), &probe).unwrap();
+
+    let mut backend = Fixed(vec![Reading::for_test(
+        CHIP,
+        "temp1_input",
+        Kind::TemperatureInput,
+        None,
+    )]);
+    let failure = check(&mut backend, &probe).unwrap_err();
+    assert!(matches!(
+        failure,
+        Failure::TooFewReadable {
+            readable: 0,
+            required: 2
+        }
+    ));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[serial_test::serial]
+fn library() {
+    let probe = Probe::default();
+    let failure = check(&mut temperature(50.0), &probe).unwrap_err();
+    assert!(matches!(failure, Failure::Uninitialized));
+
+    let _sensors = crate::Initializer::isolated().initialize().unwrap();
+    check(&mut temperature(50.0), &probe).unwrap();
+}
+
+#[test]
+fn http() {
+    let probe = anywhere();
+
+    let mut stream = Duplex::new("GET /readyz HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    http_handler(&mut temperature(50.0), &probe, &mut stream).unwrap();
+    let response = String::from_utf8(stream.output).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("\r\nContent-Length: 3\r\n"));
+    assert!(response.ends_with("\r\n\r\nok\n"));
+
+    let mut stream = Duplex::new("HEAD /livez HTTP/1.1\r\n\r\n");
+    http_handler(&mut temperature(101.0), &probe, &mut stream).unwrap();
+    let response = String::from_utf8(stream.output).unwrap();
+    assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+    assert!(response.ends_with("\r\n\r\n"));
+
+    let mut stream = Duplex::new("POST / HTTP/1.1\r\n\r\n");
+    http_handler(&mut temperature(50.0), &probe, &mut stream).unwrap();
+    let response = String::from_utf8(stream.output).unwrap();
+    assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+
+    let mut stream = Duplex::new("GET / HTTP/1.1\r\n");
+    http_handler(&mut temperature(50.0), &probe, &mut stream).unwrap_err();
+    assert!(stream.output.is_empty());
+}
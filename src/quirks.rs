@@ -0,0 +1,272 @@
+//! Registry of known defects of chips, and their corrections.
+//!
+//! Some chips report meaningless readings, or readings needing scaling,
+//! depending on how they are wired on the board, *e.g.,* a fan input that
+//! is not connected. A [`Registry`] holds [`Quirk`]s keyed by chip prefix,
+//! and optionally by board. Corrections are never applied implicitly:
+//! a [`Corrected`] backend applies them to the readings of another backend.
+//!
+//! [`Registry::builtin`] holds the quirks known to this crate, and users can
+//! register additional quirks at runtime.
+
+#[cfg(test)]
+mod tests;
+
+use crate::backend::{Backend, Reading};
+use crate::errors::Result;
+
+/// Identity of a board, as reported by DMI.
+///
+/// See: `system::BoardInfo::board()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Board {
+    /// Vendor of the board, *e.g.,* `ASUSTeK COMPUTER INC.`.
+    pub vendor: String,
+    /// Name of the board, *e.g.,* `PRIME X570-PRO`.
+    pub name: String,
+}
+
+impl Board {
+    /// Return the identity of a board.
+    #[must_use]
+    pub fn new(vendor: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            vendor: vendor.into(),
+            name: name.into(),
+        }
+    }
+}
+
+/// Correction of a defect of a chip.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Correction {
+    /// The sub-feature reports meaningless readings, which are dropped.
+    Ignore,
+    /// Raw values are multiplied by the given factor, and written values
+    /// are divided by it.
+    Scale(f64),
+    /// The feature of the sub-feature has the given label.
+    Label(String),
+}
+
+/// Known defect of a chip, and its correction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quirk {
+    /// Prefix of the chip, *e.g.,* `nct6775`.
+    pub chip_prefix: String,
+    /// Board on which the defect appears, or `None` for all boards.
+    pub board: Option<Board>,
+    /// Name of the affected sub-feature, *e.g.,* `in3_input`.
+    pub sub_feature: String,
+    /// Correction of the defect.
+    pub correction: Correction,
+    /// Short description of the defect.
+    pub description: String,
+}
+
+impl Quirk {
+    /// Return a quirk of the given sub-feature of all chips having the given
+    /// prefix, on all boards.
+    #[must_use]
+    pub fn new(
+        chip_prefix: impl Into<String>,
+        sub_feature: impl Into<String>,
+        correction: Correction,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            chip_prefix: chip_prefix.into(),
+            board: None,
+            sub_feature: sub_feature.into(),
+            correction,
+            description: description.into(),
+        }
+    }
+
+    /// Restrict this quirk to the given board.
+    #[must_use]
+    pub fn on_board(self, board: Board) -> Self {
+        Self {
+            board: Some(board),
+            ..self
+        }
+    }
+
+    /// Return `true` if this quirk applies to the given sub-feature of
+    /// the given chip, on the given board.
+    #[must_use]
+    pub fn matches(&self, chip: &str, sub_feature: &str, board: Option<&Board>) -> bool {
+        chip_prefix(chip) == self.chip_prefix
+            && sub_feature == self.sub_feature
+            && self.board.as_ref().is_none_or(|b| Some(b) == board)
+    }
+}
+
+/**
+Registry of chip quirks.
+
+# Example
+
+```rust
+use lm_sensors::quirks::{Board, Correction, Quirk, Registry};
+
+let mut registry = Registry::builtin();
+registry.register(
+    Quirk::new("nct6775", "in3_input", Correction::Scale(2.0), "Halved 3VCC input.")
+        .on_board(Board::new("Vendor", "Board")),
+);
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+#[must_use]
+pub struct Registry {
+    quirks: Vec<Quirk>,
+}
+
+impl Registry {
+    /// Return a registry of no quirks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a registry of the quirks known to this crate.
+    pub fn builtin() -> Self {
+        let quirks = vec![Quirk::new(
+            "it8688",
+            "fan6_input",
+            Correction::Ignore,
+            "Fan 6 input is not connected, and always reads 0 RPM.",
+        )];
+        Self { quirks }
+    }
+
+    /// Add the given quirk. Quirks registered later take precedence.
+    pub fn register(&mut self, quirk: Quirk) {
+        self.quirks.push(quirk);
+    }
+
+    /// Return an iterator over the registered quirks.
+    pub fn iter(&self) -> impl Iterator<Item = &Quirk> {
+        self.quirks.iter()
+    }
+
+    /// Return the correction of the value of the given sub-feature of
+    /// the given chip, on the given board, if any.
+    #[must_use]
+    pub fn correction(
+        &self,
+        chip: &str,
+        sub_feature: &str,
+        board: Option<&Board>,
+    ) -> Option<&Correction> {
+        self.quirks
+            .iter()
+            .rev()
+            .filter(|quirk| !matches!(quirk.correction, Correction::Label(_)))
+            .find(|quirk| quirk.matches(chip, sub_feature, board))
+            .map(|quirk| &quirk.correction)
+    }
+
+    /// Return the corrected label of the feature of the given sub-feature of
+    /// the given chip, on the given board, if any.
+    #[must_use]
+    pub fn label(&self, chip: &str, sub_feature: &str, board: Option<&Board>) -> Option<&str> {
+        self.quirks
+            .iter()
+            .rev()
+            .filter(|quirk| quirk.matches(chip, sub_feature, board))
+            .find_map(|quirk| match &quirk.correction {
+                Correction::Label(label) => Some(label.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Apply corrections of values to the given readings, taken on
+    /// the given board.
+    pub fn apply(&self, readings: &mut Vec<Reading>, board: Option<&Board>) {
+        readings.retain_mut(|reading| {
+            match self.correction(&reading.chip, &reading.sub_feature, board) {
+                Some(Correction::Ignore) => return false,
+                Some(Correction::Scale(factor)) => {
+                    reading.raw_value = reading.raw_value.map(|value| value * factor);
+                }
+                Some(Correction::Label(_)) | None => {}
+            }
+            true
+        });
+    }
+}
+
+/// Backend applying the corrections of a [`Registry`] to the readings of
+/// another backend.
+#[derive(Debug)]
+#[must_use]
+pub struct Corrected<B> {
+    backend: B,
+    registry: Registry,
+    board: Option<Board>,
+}
+
+impl<B: Backend> Corrected<B> {
+    /// Return a backend applying the corrections of the given registry to
+    /// the readings of the given backend, taken on the given board.
+    pub fn new(backend: B, registry: Registry, board: Option<Board>) -> Self {
+        Self {
+            backend,
+            registry,
+            board,
+        }
+    }
+
+    /// Return a backend applying the corrections of the given registry to
+    /// the readings of the given backend, taken on the board running this
+    /// process.
+    ///
+    /// See: [`system::board_info`](crate::system::board_info).
+    #[cfg(target_os = "linux")]
+    pub fn on_this_board(backend: B, registry: Registry) -> Self {
+        Self::new(backend, registry, crate::system::board_info().board())
+    }
+
+    /// Return the registry of corrections.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Return the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.backend
+    }
+}
+
+impl<B: Backend> Backend for Corrected<B> {
+    fn name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        let mut readings = self.backend.read_all()?;
+        self.registry.apply(&mut readings, self.board.as_ref());
+        Ok(readings)
+    }
+
+    /// Write through the wrapped backend, undoing the scaling of
+    /// the sub-feature, if any.
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        let raw_value = match self
+            .registry
+            .correction(chip, sub_feature, self.board.as_ref())
+        {
+            Some(Correction::Scale(factor)) => raw_value / factor,
+            _ => raw_value,
+        };
+        self.backend.write(chip, sub_feature, raw_value)
+    }
+}
+
+/// Return the prefix of the given chip name, *e.g.,* `nct6775` for
+/// `nct6775-isa-0290`.
+fn chip_prefix(chip: &str) -> &str {
+    chip.split_once('-').map_or(chip, |(prefix, _)| prefix)
+}
@@ -0,0 +1,116 @@
+#![cfg(test)]
+
+use crate::backend::{Backend, Reading};
+use crate::errors::Result;
+use crate::quirks::{Board, Corrected, Correction, Quirk, Registry};
+use crate::value::Kind;
+
+#[derive(Debug, Default)]
+struct Fixed {
+    written: Vec<(String, String, f64)>,
+}
+
+impl Backend for Fixed {
+    fn name(&self) -> &'static str {
+        "fixed"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Ok(vec![
+            Reading::for_test(
+                "it8688-isa-0a40",
+                "fan5_input",
+                Kind::FanInput,
+                Some(1200.0),
+            ),
+            Reading::for_test("it8688-isa-0a40", "fan6_input", Kind::FanInput, Some(0.0)),
+            Reading::for_test(
+                "nct6775-isa-0290",
+                "in3_input",
+                Kind::VoltageInput,
+                Some(1.65),
+            ),
+        ])
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        self.written
+            .push((chip.into(), sub_feature.into(), raw_value));
+        Ok(())
+    }
+}
+
+#[test]
+fn matches() {
+    let board = Board::new("Vendor", "Board");
+    let quirk = Quirk::new("nct6775", "in3_input", Correction::Scale(2.0), "");
+    assert!(quirk.matches("nct6775-isa-0290", "in3_input", None));
+    assert!(quirk.matches("nct6775-isa-0290", "in3_input", Some(&board)));
+    assert!(!quirk.matches("nct6776-isa-0290", "in3_input", None));
+    assert!(!quirk.matches("nct6775-isa-0290", "in4_input", None));
+
+    let quirk = quirk.on_board(board.clone());
+    assert!(quirk.matches("nct6775-isa-0290", "in3_input", Some(&board)));
+    assert!(!quirk.matches("nct6775-isa-0290", "in3_input", None));
+    let other = Board::new("Vendor", "Other");
+    assert!(!quirk.matches("nct6775-isa-0290", "in3_input", Some(&other)));
+}
+
+#[test]
+fn registry() {
+    let board = Board::new("Vendor", "Board");
+    let mut registry = Registry::builtin();
+    assert!(registry.iter().count() > 0);
+
+    let quirk = Quirk::new("nct6775", "in3_input", Correction::Scale(2.0), "");
+    registry.register(quirk.on_board(board.clone()));
+    let quirk = Quirk::new("nct6775", "in3_input", Correction::Label("3VCC".into()), "");
+    registry.register(quirk);
+
+    let mut readings = Fixed::default().read_all().unwrap();
+    registry.apply(&mut readings, None);
+    let values: Vec<_> = readings.iter().map(|r| r.raw_value.unwrap()).collect();
+    assert_eq!(values, [1200.0, 1.65]);
+
+    let mut readings = Fixed::default().read_all().unwrap();
+    registry.apply(&mut readings, Some(&board));
+    let values: Vec<_> = readings.iter().map(|r| r.raw_value.unwrap()).collect();
+    assert_eq!(values, [1200.0, 3.3]);
+
+    assert_eq!(
+        registry.label("nct6775-isa-0290", "in3_input", None),
+        Some("3VCC")
+    );
+    assert_eq!(registry.label("nct6775-isa-0290", "in4_input", None), None);
+
+    // Quirks registered later take precedence.
+    registry.register(Quirk::new(
+        "it8688",
+        "fan6_input",
+        Correction::Scale(1.0),
+        "",
+    ));
+    let correction = registry.correction("it8688-isa-0a40", "fan6_input", None);
+    assert_eq!(correction, Some(&Correction::Scale(1.0)));
+}
+
+#[test]
+fn corrected() {
+    let board = Board::new("Vendor", "Board");
+    let mut registry = Registry::new();
+    let quirk = Quirk::new("nct6775", "in3_input", Correction::Scale(2.0), "");
+    registry.register(quirk);
+
+    let mut backend = Corrected::new(Fixed::default(), registry, Some(board));
+    assert_eq!(backend.name(), "fixed");
+
+    let readings = backend.read_all().unwrap();
+    assert_eq!(readings.len(), 3);
+    assert_eq!(readings[2].raw_value, Some(3.3));
+
+    backend.write("nct6775-isa-0290", "in3_input", 3.0).unwrap();
+    backend.write("it8688-isa-0a40", "fan5_input", 3.0).unwrap();
+    let written = backend.into_inner().written;
+    assert_eq!(written[0].2, 1.5);
+    assert_eq!(written[1].2, 3.0);
+}
@@ -0,0 +1,113 @@
+//! Redaction of serial-number-like strings from formatted values.
+//!
+//! Debug output of chips and features might include strings identifying
+//! a specific device, *e.g.,* serial numbers of drives in labels or paths.
+//! [`Redacted`] formats a value, then replaces such strings by
+//! [`REDACTED`], so that the output can be pasted into public bug reports.
+//!
+//! A word, *i.e.,* a run of ASCII alphanumeric characters, is considered
+//! serial-number-like if it has at least [`MIN_SERIAL_LEN`] characters,
+//! of which at least one is a digit. Names of chips, features and
+//! sub-features, *e.g.,* `nct6775-isa-0290` or `temp1_input`, are kept.
+
+#[cfg(test)]
+mod tests;
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// Replacement of serial-number-like strings.
+pub const REDACTED: &str = "<redacted>";
+
+/// Minimum length of serial-number-like words.
+pub const MIN_SERIAL_LEN: usize = 8;
+
+/**
+Value formatted with serial-number-like strings replaced by [`REDACTED`].
+
+Both [`Debug`](fmt::Debug) and [`Display`](fmt::Display) are supported,
+including the alternate form of [`Debug`](fmt::Debug).
+
+# Example
+
+```rust
+use lm_sensors::redact::Redacted;
+
+let label = "WD-WCC4N1234567";
+assert_eq!(Redacted::new(label).to_string(), "WD-<redacted>");
+assert_eq!(format!("{:?}", Redacted::new("temp1_input")), "\"temp1_input\"");
+```
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Redacted<T>(pub T);
+
+impl<T> Redacted<T> {
+    /// Return the given value, formatted with serial-number-like strings
+    /// redacted.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Return the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = if f.alternate() {
+            format!("{:#?}", self.0)
+        } else {
+            format!("{:?}", self.0)
+        };
+        f.write_str(&redact(&text))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&redact(&self.0.to_string()))
+    }
+}
+
+/// Return the given text with serial-number-like words replaced by
+/// [`REDACTED`].
+#[must_use]
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut result = String::default();
+    let mut copied = 0;
+
+    for (start, word) in words(text) {
+        if is_serial_like(word) {
+            result.push_str(&text[copied..start]);
+            result.push_str(REDACTED);
+            copied = start + word.len();
+        }
+    }
+
+    if copied == 0 {
+        Cow::Borrowed(text)
+    } else {
+        result.push_str(&text[copied..]);
+        Cow::Owned(result)
+    }
+}
+
+/// Return the runs of ASCII alphanumeric characters of the given text,
+/// with their positions.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut rest = text.char_indices().peekable();
+    core::iter::from_fn(move || {
+        let (start, _) = rest.find(|(_, c)| c.is_ascii_alphanumeric())?;
+        let mut end = start + 1;
+        while let Some((index, _)) = rest.next_if(|(_, c)| c.is_ascii_alphanumeric()) {
+            end = index + 1;
+        }
+        Some((start, &text[start..end]))
+    })
+}
+
+fn is_serial_like(word: &str) -> bool {
+    word.len() >= MIN_SERIAL_LEN && word.bytes().any(|b| b.is_ascii_digit())
+}
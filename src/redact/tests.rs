@@ -0,0 +1,48 @@
+#![cfg(test)]
+
+use std::borrow::Cow;
+
+use super::{redact, Redacted, REDACTED};
+
+#[test]
+fn redact_text() {
+    for kept in [
+        "",
+        "nct6775-isa-0290",
+        "temp1_input",
+        "coretemp-isa-0000",
+        "/sys/devices/platform/coretemp.0/hwmon/hwmon3",
+        "Composite",
+        "DELLSMMDRIVER",
+    ] {
+        assert!(matches!(redact(kept), Cow::Borrowed(text) if text == kept));
+    }
+
+    assert_eq!(redact("WD-WCC4N1234567"), format!("WD-{REDACTED}"));
+    assert_eq!(redact("serial 1234567890."), format!("serial {REDACTED}."));
+    assert_eq!(
+        redact("S4EWNX0R123456 and 9876543210"),
+        format!("{REDACTED} and {REDACTED}")
+    );
+    assert_eq!(redact("é-ABCD1234-é"), format!("é-{REDACTED}-é"));
+}
+
+#[test]
+fn redacted() {
+    #[derive(Debug)]
+    struct Drive {
+        label: &'static str,
+    }
+
+    let drive = Drive {
+        label: "WD-WCC4N1234567",
+    };
+    let expected = format!("Drive {{ label: \"WD-{REDACTED}\" }}");
+    assert_eq!(format!("{:?}", Redacted::new(&drive)), expected);
+    assert!(!format!("{:#?}", Redacted::new(&drive)).contains("WCC4N1234567"));
+    assert_eq!(
+        Redacted::new(drive.label).to_string(),
+        format!("WD-{REDACTED}")
+    );
+    assert_eq!(Redacted::new(42).into_inner(), 42);
+}
@@ -0,0 +1,223 @@
+//! Stable identities of sub-features across rescans of chips.
+//!
+//! Hardware monitoring devices are numbered in the order they are probed,
+//! *e.g.,* `/sys/class/hwmon/hwmon2`, so their numbers can change after
+//! a resume or a hot-plug. A [`SubFeatureId`], made of the names of a chip
+//! and of a sub-feature, does not depend on these numbers. An
+//! [`Enumeration`] binds identities to the current paths of sub-features,
+//! and [`Enumeration::rebind`] binds them to a new enumeration, reporting
+//! the sub-features that moved, disappeared or appeared.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::collections::btree_map::{self, BTreeMap};
+#[cfg(target_os = "linux")]
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Stable identity of a sub-feature.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubFeatureId {
+    /// Name of the chip, *e.g.,* `nct6775-isa-0290`.
+    pub chip: String,
+    /// Name of the sub-feature, *e.g.,* `temp1_input`.
+    pub sub_feature: String,
+}
+
+impl SubFeatureId {
+    /// Return the identity of the given sub-feature of the given chip.
+    #[must_use]
+    pub fn new(chip: impl Into<String>, sub_feature: impl Into<String>) -> Self {
+        Self {
+            chip: chip.into(),
+            sub_feature: sub_feature.into(),
+        }
+    }
+}
+
+impl fmt::Display for SubFeatureId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.chip, self.sub_feature)
+    }
+}
+
+/// Sub-feature whose path changed during a [rebinding](Enumeration::rebind).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Moved {
+    /// Identity of the sub-feature.
+    pub id: SubFeatureId,
+    /// Path of the sub-feature before the rebinding.
+    pub old_path: PathBuf,
+    /// Path of the sub-feature after the rebinding.
+    pub new_path: PathBuf,
+}
+
+/// Changes of an [`Enumeration`], reported by [`Enumeration::rebind`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Remapping {
+    /// Sub-features whose path changed, sorted by identity.
+    pub moved: Vec<Moved>,
+    /// Sub-features that are no longer enumerated, sorted by identity.
+    pub disappeared: Vec<SubFeatureId>,
+    /// Sub-features that were not enumerated before, sorted by identity.
+    pub appeared: Vec<SubFeatureId>,
+}
+
+impl Remapping {
+    /// Return `true` if no sub-feature moved, disappeared or appeared.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty() && self.disappeared.is_empty() && self.appeared.is_empty()
+    }
+}
+
+/**
+Paths of sub-features, keyed by their stable identities.
+
+# Example
+
+```rust
+use lm_sensors::remap::{Enumeration, SubFeatureId};
+
+let id = SubFeatureId::new("nct6775-isa-0290", "temp1_input");
+let mut enumeration = Enumeration::default();
+enumeration.insert(id.clone(), "/sys/class/hwmon/hwmon2/temp1_input");
+
+let rescanned = [(id.clone(), "/sys/class/hwmon/hwmon3/temp1_input".into())];
+let remapping = enumeration.rebind(rescanned.into_iter().collect());
+assert_eq!(remapping.moved.len(), 1);
+assert_eq!(
+    enumeration.path(&id).unwrap(),
+    std::path::Path::new("/sys/class/hwmon/hwmon3/temp1_input")
+);
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Enumeration {
+    paths: BTreeMap<SubFeatureId, PathBuf>,
+}
+
+impl Enumeration {
+    /// Return the enumeration of the sub-features of all chips known to
+    /// the given library instance.
+    ///
+    /// Sub-features of chips lacking a path are not enumerated.
+    #[cfg(target_os = "linux")]
+    #[must_use]
+    pub fn read(sensors: &crate::LMSensors) -> Self {
+        let mut result = Self::default();
+        for chip in sensors.chip_iter(None) {
+            let Some(chip_path) = chip.path() else {
+                continue;
+            };
+            let chip_name = chip.to_string();
+
+            for feature in chip.feature_iter() {
+                for sub_feature in feature.sub_feature_iter() {
+                    if let Some(Ok(name)) = sub_feature.name() {
+                        let id = SubFeatureId::new(chip_name.as_str(), name);
+                        result.insert(id, chip_path.join(name));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Bind the given identity to the given path, returning the previous
+    /// path, if any.
+    pub fn insert(&mut self, id: SubFeatureId, path: impl Into<PathBuf>) -> Option<PathBuf> {
+        self.paths.insert(id, path.into())
+    }
+
+    /// Return the path bound to the given identity, if any.
+    #[must_use]
+    pub fn path(&self, id: &SubFeatureId) -> Option<&Path> {
+        self.paths.get(id).map(PathBuf::as_path)
+    }
+
+    /// Return an iterator over identities and their paths, sorted by identity.
+    pub fn iter(&self) -> btree_map::Iter<'_, SubFeatureId, PathBuf> {
+        self.paths.iter()
+    }
+
+    /// Return the number of enumerated sub-features.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Return `true` if no sub-features are enumerated.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Replace this enumeration by the given one, *e.g.,* after a rescan,
+    /// and return the changes.
+    pub fn rebind(&mut self, new: Self) -> Remapping {
+        let mut result = Remapping::default();
+
+        for (id, old_path) in &self.paths {
+            match new.paths.get(id) {
+                None => result.disappeared.push(id.clone()),
+                Some(new_path) if new_path != old_path => result.moved.push(Moved {
+                    id: id.clone(),
+                    old_path: old_path.clone(),
+                    new_path: new_path.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+
+        let appeared = new.paths.keys().filter(|id| !self.paths.contains_key(id));
+        result.appeared.extend(appeared.cloned());
+
+        *self = new;
+        result
+    }
+
+    /// Rescan the sub-features of all chips known to the given library
+    /// instance, and return the changes.
+    ///
+    /// Chips that were not enumerated before are published as
+    /// [`Event::DeviceAdded`](crate::events::Event::DeviceAdded).
+    ///
+    /// See: [`Enumeration::read`], [`Enumeration::rebind`].
+    #[cfg(target_os = "linux")]
+    pub fn rescan(&mut self, sensors: &crate::LMSensors) -> Remapping {
+        use crate::events::{self, Event};
+
+        let new = Self::read(sensors);
+        let known: BTreeSet<&str> = self.paths.keys().map(|id| id.chip.as_str()).collect();
+        let added: BTreeSet<String> = new
+            .paths
+            .keys()
+            .filter(|id| !known.contains(id.chip.as_str()))
+            .map(|id| id.chip.clone())
+            .collect();
+        for chip in added {
+            events::publish(&Event::DeviceAdded { chip });
+        }
+        self.rebind(new)
+    }
+}
+
+impl FromIterator<(SubFeatureId, PathBuf)> for Enumeration {
+    fn from_iter<T: IntoIterator<Item = (SubFeatureId, PathBuf)>>(iter: T) -> Self {
+        Self {
+            paths: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Enumeration {
+    type Item = (&'a SubFeatureId, &'a PathBuf);
+    type IntoIter = btree_map::Iter<'a, SubFeatureId, PathBuf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.paths.iter()
+    }
+}
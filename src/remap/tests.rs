@@ -0,0 +1,81 @@
+#![cfg(test)]
+
+use std::path::{Path, PathBuf};
+
+use crate::remap::{Enumeration, Moved, SubFeatureId};
+
+fn enumeration(entries: &[(&str, &str, &str)]) -> Enumeration {
+    entries
+        .iter()
+        .map(|&(chip, sub_feature, path)| (SubFeatureId::new(chip, sub_feature), path.into()))
+        .collect()
+}
+
+#[test]
+fn sub_feature_id() {
+    let id = SubFeatureId::new("nct6775-isa-0290", "temp1_input");
+    assert_eq!(id.to_string(), "nct6775-isa-0290/temp1_input");
+    assert!(id < SubFeatureId::new("nct6775-isa-0290", "temp2_input"));
+}
+
+#[test]
+fn rebind() {
+    let mut current = enumeration(&[
+        ("coretemp-isa-0000", "temp1_input", "/hwmon1/temp1_input"),
+        ("nct6775-isa-0290", "fan1_input", "/hwmon2/fan1_input"),
+        ("nct6775-isa-0290", "temp1_input", "/hwmon2/temp1_input"),
+        ("nvme-pci-0100", "temp1_input", "/hwmon3/temp1_input"),
+    ]);
+    assert_eq!(current.len(), 4);
+
+    let unchanged = current.clone();
+    assert!(current.rebind(unchanged.clone()).is_empty());
+    assert_eq!(current, unchanged);
+
+    let rescanned = enumeration(&[
+        ("coretemp-isa-0000", "temp1_input", "/hwmon1/temp1_input"),
+        ("nct6775-isa-0290", "fan1_input", "/hwmon3/fan1_input"),
+        ("nct6775-isa-0290", "temp1_input", "/hwmon3/temp1_input"),
+        ("amdgpu-pci-0300", "temp1_input", "/hwmon2/temp1_input"),
+    ]);
+    let remapping = current.rebind(rescanned.clone());
+    assert!(!remapping.is_empty());
+    assert_eq!(current, rescanned);
+
+    let moved = |sub_feature: &str| Moved {
+        id: SubFeatureId::new("nct6775-isa-0290", sub_feature),
+        old_path: PathBuf::from(format!("/hwmon2/{sub_feature}")),
+        new_path: PathBuf::from(format!("/hwmon3/{sub_feature}")),
+    };
+    assert_eq!(remapping.moved, [moved("fan1_input"), moved("temp1_input")]);
+    assert_eq!(
+        remapping.disappeared,
+        [SubFeatureId::new("nvme-pci-0100", "temp1_input")]
+    );
+    assert_eq!(
+        remapping.appeared,
+        [SubFeatureId::new("amdgpu-pci-0300", "temp1_input")]
+    );
+
+    let id = SubFeatureId::new("nct6775-isa-0290", "temp1_input");
+    assert_eq!(current.path(&id), Some(Path::new("/hwmon3/temp1_input")));
+    assert_eq!(
+        current.path(&SubFeatureId::new("nvme-pci-0100", "temp1_input")),
+        None
+    );
+}
+
+#[test]
+fn insert() {
+    let mut enumeration = Enumeration::default();
+    assert!(enumeration.is_empty());
+
+    let id = SubFeatureId::new("lm75-i2c-0-48", "temp1_input");
+    assert_eq!(enumeration.insert(id.clone(), "/hwmon0/temp1_input"), None);
+    assert_eq!(
+        enumeration.insert(id.clone(), "/hwmon1/temp1_input"),
+        Some(PathBuf::from("/hwmon0/temp1_input"))
+    );
+    assert_eq!(enumeration.iter().count(), 1);
+    assert_eq!((&enumeration).into_iter().next().unwrap().0, &id);
+}
@@ -0,0 +1,584 @@
+//! Reading sensors of remote machines.
+//!
+//! An [`Agent`] serves readings of a local [`Backend`] over TCP, and a
+//! [`Client`] reads them from a remote agent, so that a central collector
+//! can poll many machines using only this crate on both ends. [`Client`]
+//! implements [`Backend`] itself, so remote machines can be merged with
+//! [`MultiSensors`](crate::backend::MultiSensors).
+//!
+//! Agents are read-only by default, and may require clients to
+//! authenticate. See: [`AccessPolicy`].
+//!
+//! An agent answers each client on its own thread, while the backend is
+//! read by the thread running [`Agent::serve`], so that subscribers do not
+//! block other clients. Connections are closed when reads or writes take
+//! longer than the timeout of the agent, including idle connections, and
+//! connections beyond the maximum number of clients of the agent are closed
+//! as soon as they are accepted.
+//!
+//! Connections are not encrypted, and this module does not implement TLS.
+//! Securing connections is the job of callers, *e.g.,* by establishing TLS
+//! sessions themselves, then handing them to [`serve_connection`] and
+//! [`Client::with_stream`].
+//!
+//! # Protocol
+//!
+//! Each message is a frame made of its length in bytes, as a big-endian
+//! 32-bits integer, followed by the message encoded in CBOR. Messages are
+//! at most [`MAX_FRAME_LEN`] bytes long, and requests of clients that did not
+//! authenticate yet are at most [`MAX_UNAUTHENTICATED_FRAME_LEN`] bytes long.
+//!
+//! A client sends either the request `"Snapshot"`, answered by one response,
+//! or the request `{"Subscribe": {"interval_ms": N}}`, answered by one
+//! response every `N` milliseconds until the connection is closed. The agent
+//! clamps `N` between [`MIN_SUBSCRIPTION_INTERVAL`] and
+//! [`MAX_SUBSCRIPTION_INTERVAL`].
+//! A response is either `{"Readings": [...]}`, holding readings with the
+//! fields of [`Reading`], or `{"Error": {"code": ..., "message": ...}}`,
+//! where `code` is given by [`Error::code`], or is `permission_denied` for
+//! denied requests.
+//!
+//! A client authenticates by sending `{"Authenticate": {"token": ...}}`, and
+//! writes a sub-feature by sending
+//! `{"Write": {"chip": ..., "sub_feature": ..., "raw_value": ...}}`.
+//! The agent answers either of these by the response `"Done"`, or an error.
+
+#[cfg(feature = "mdns")]
+pub mod mdns;
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::time::Instant;
+use std::{panic, thread};
+
+use serde::{Deserialize, Serialize};
+
+use crate::access::{self, AccessPolicy, Connections, Session, DEFAULT_MAX_CONNECTIONS};
+use crate::backend::{Backend, Reading};
+use crate::errors::{Error, Result};
+
+/// Default TCP port of an [`Agent`].
+pub const DEFAULT_PORT: u16 = 8473;
+
+/// Maximum length of a frame, in bytes.
+pub const MAX_FRAME_LEN: u32 = 16 << 20;
+
+/// Maximum length of a frame received by an agent from a client that did
+/// not authenticate yet, in bytes.
+pub const MAX_UNAUTHENTICATED_FRAME_LEN: u32 = 4 << 10;
+
+/// Default timeout of reads and writes of connections of an [`Agent`],
+/// after which connections are closed.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Shortest interval between readings sent to a subscribed client.
+pub const MIN_SUBSCRIPTION_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest interval between readings sent to a subscribed client.
+pub const MAX_SUBSCRIPTION_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Longest time a subscription waits before noticing that the agent stops.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Request {
+    Snapshot,
+    Subscribe {
+        interval_ms: u64,
+    },
+    Authenticate {
+        token: String,
+    },
+    Write {
+        chip: String,
+        sub_feature: String,
+        raw_value: f64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Response {
+    Readings(Vec<Reading>),
+    Done,
+    Error { code: String, message: String },
+}
+
+impl Response {
+    fn new(readings: Result<Vec<Reading>>) -> Self {
+        readings.map_or_else(Self::from_error, Self::Readings)
+    }
+
+    fn done(result: Result<()>) -> Self {
+        result.map_or_else(Self::from_error, |()| Self::Done)
+    }
+
+    fn from_error(err: Error) -> Self {
+        let code = if access::is_denied(&err) {
+            access::PERMISSION_DENIED
+        } else {
+            err.code()
+        };
+
+        let message = std::error::Error::source(&err)
+            .map_or_else(|| err.to_string(), |source| format!("{err}: {source}"));
+
+        Self::Error {
+            code: code.into(),
+            message,
+        }
+    }
+
+    fn into_readings(self, operation: &'static str) -> Result<Vec<Reading>> {
+        match self {
+            Self::Readings(readings) => Ok(readings),
+            Self::Done => Err(Error::from_io(operation, io::ErrorKind::InvalidData.into())),
+            Self::Error { code, message } => Err(Self::error(operation, &code, &message)),
+        }
+    }
+
+    fn into_done(self, operation: &'static str) -> Result<()> {
+        match self {
+            Self::Done => Ok(()),
+            Self::Readings(_) => Err(Error::from_io(operation, io::ErrorKind::InvalidData.into())),
+            Self::Error { code, message } => Err(Self::error(operation, &code, &message)),
+        }
+    }
+
+    fn error(operation: &'static str, code: &str, message: &str) -> Error {
+        let kind = if code == access::PERMISSION_DENIED {
+            io::ErrorKind::PermissionDenied
+        } else {
+            io::ErrorKind::Other
+        };
+        Error::from_io(
+            operation,
+            io::Error::new(kind, format!("{code}: {message}")),
+        )
+    }
+}
+
+fn write_frame(writer: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    let mut frame = vec![0_u8; 4];
+    ciborium::into_writer(message, &mut frame).map_err(io::Error::other)?;
+
+    let len = u32::try_from(frame.len() - 4)
+        .ok()
+        .filter(|&len| len <= MAX_FRAME_LEN)
+        .ok_or(io::ErrorKind::InvalidInput)?;
+    frame[..4].copy_from_slice(&len.to_be_bytes());
+
+    writer.write_all(&frame)?;
+    writer.flush()
+}
+
+fn read_frame<T: for<'de> Deserialize<'de>>(reader: &mut impl Read, max_len: u32) -> io::Result<T> {
+    let mut len = [0_u8; 4];
+    reader.read_exact(&mut len)?;
+
+    let len = u32::from_be_bytes(len);
+    if len > max_len {
+        return Err(io::ErrorKind::InvalidData.into());
+    }
+
+    let mut payload = vec![0_u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    ciborium::from_reader(payload.as_slice())
+        .map_err(|r| io::Error::new(io::ErrorKind::InvalidData, r.to_string()))
+}
+
+/// Agent serving readings of a local backend to [`Client`]s.
+#[derive(Debug)]
+pub struct Agent {
+    listener: TcpListener,
+    policy: AccessPolicy,
+    timeout: Option<Duration>,
+    max_connections: usize,
+}
+
+impl Agent {
+    /// Return an agent listening on the given address, *e.g.,*
+    /// `("0.0.0.0", DEFAULT_PORT)`.
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self> {
+        TcpListener::bind(address)
+            .map(|listener| Self {
+                listener,
+                policy: AccessPolicy::default(),
+                timeout: Some(DEFAULT_TIMEOUT),
+                max_connections: DEFAULT_MAX_CONNECTIONS,
+            })
+            .map_err(|r| Error::from_io("TcpListener::bind()", r))
+    }
+
+    /// Set the access policy enforced on clients.
+    ///
+    /// By default, clients need no authentication, and may not write.
+    #[must_use]
+    pub fn access_policy(self, policy: AccessPolicy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// Set the timeout of reads and writes of connections, instead of
+    /// [`DEFAULT_TIMEOUT`]. A zero timeout disables timeouts.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout).filter(|timeout| !timeout.is_zero()),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of clients served concurrently by
+    /// [`Agent::serve`], instead of [`DEFAULT_MAX_CONNECTIONS`].
+    #[must_use]
+    pub fn max_connections(self, max_connections: usize) -> Self {
+        Self {
+            max_connections,
+            ..self
+        }
+    }
+
+    /// Return the address this agent listens on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener
+            .local_addr()
+            .map_err(|r| Error::from_io("TcpListener::local_addr()", r))
+    }
+
+    /// Accept one client connection, and answer all its requests.
+    pub fn serve_one(&self, backend: &mut dyn Backend) -> Result<()> {
+        let stream = self.accept()?;
+        serve_connection(backend, &self.policy, stream)
+    }
+
+    /// Accept client connections, and answer their requests concurrently,
+    /// until accepting a connection fails. Connections are then closed
+    /// after their current requests.
+    ///
+    /// Connections accepted while the maximum number of clients are served
+    /// are closed immediately. Failures of individual connections are ignored.
+    pub fn serve(&self, backend: &mut dyn Backend) -> Result<()> {
+        self.serve_streams(backend, core::iter::repeat_with(|| self.accept_stream()))
+            .map_err(|r| Error::from_io("accept()", r))
+    }
+
+    /// Answer requests received through the given streams concurrently,
+    /// until the streams end and their connections are closed, or until
+    /// one of them is an error.
+    fn serve_streams(
+        &self,
+        backend: &mut dyn Backend,
+        streams: impl Iterator<Item = io::Result<TcpStream>> + Send,
+    ) -> io::Result<()> {
+        let (sender, receiver) = mpsc::channel::<(Command, mpsc::Sender<Response>)>();
+        let stop = &AtomicBool::new(false);
+        let connections = &Connections::new(self.max_connections);
+
+        thread::scope(|scope| {
+            let acceptor = scope.spawn(move || {
+                for stream in streams {
+                    let mut stream = match stream {
+                        Ok(stream) => stream,
+                        Err(err) => {
+                            stop.store(true, Ordering::Relaxed);
+                            return Err(err);
+                        }
+                    };
+
+                    // Dropping the stream closes the connection.
+                    let Some(slot) = connections.acquire() else {
+                        continue;
+                    };
+
+                    let sender = sender.clone();
+                    let session = self.policy.session();
+                    scope.spawn(move || {
+                        let _slot = slot;
+                        let execute = |command| {
+                            let (response_sender, response_receiver) = mpsc::channel();
+                            sender.send((command, response_sender)).ok()?;
+                            response_receiver.recv().ok()
+                        };
+                        let _ignored = serve_stream(session, &mut stream, stop, execute);
+                    });
+                }
+                Ok(())
+            });
+
+            // The backend is read by this thread only, until all connections
+            // are closed.
+            for (command, response_sender) in receiver {
+                let _ignored = response_sender.send(execute(backend, command));
+            }
+
+            acceptor
+                .join()
+                .unwrap_or_else(|payload| panic::resume_unwind(payload))
+        })
+    }
+
+    fn accept(&self) -> Result<TcpStream> {
+        self.accept_stream()
+            .map_err(|r| Error::from_io("accept()", r))
+    }
+
+    fn accept_stream(&self) -> io::Result<TcpStream> {
+        let (stream, _address) = self.listener.accept()?;
+        stream.set_read_timeout(self.timeout)?;
+        stream.set_write_timeout(self.timeout)?;
+        Ok(stream)
+    }
+}
+
+/// Answer all requests received from a client through the given stream,
+/// until the client closes it, enforcing the given access policy.
+pub fn serve_connection(
+    backend: &mut dyn Backend,
+    policy: &AccessPolicy,
+    mut stream: impl Read + Write,
+) -> Result<()> {
+    let stop = AtomicBool::new(false);
+    let execute = |command| Some(execute(backend, command));
+    serve_stream(policy.session(), &mut stream, &stop, execute)
+        .map_err(|r| Error::from_io("serve_connection()", r))
+}
+
+/// Request of a client, performed with the backend.
+enum Command {
+    Read,
+    Write {
+        chip: String,
+        sub_feature: String,
+        raw_value: f64,
+    },
+}
+
+fn execute(backend: &mut dyn Backend, command: Command) -> Response {
+    match command {
+        Command::Read => Response::new(backend.read_all()),
+        Command::Write {
+            chip,
+            sub_feature,
+            raw_value,
+        } => Response::done(backend.write(&chip, &sub_feature, raw_value)),
+    }
+}
+
+/// Answer requests received through the given stream, performing commands
+/// through the given function, until the client closes the stream, or
+/// until `stop` is set.
+fn serve_stream(
+    mut session: Session,
+    stream: &mut (impl Read + Write),
+    stop: &AtomicBool,
+    mut execute: impl FnMut(Command) -> Option<Response>,
+) -> io::Result<()> {
+    // The thread reading the backend is gone.
+    let gone = || io::Error::from(io::ErrorKind::BrokenPipe);
+
+    while !stop.load(Ordering::Relaxed) {
+        // Unauthenticated clients may not make the agent allocate large frames.
+        let max_len = if session.check_read().is_ok() {
+            MAX_FRAME_LEN
+        } else {
+            MAX_UNAUTHENTICATED_FRAME_LEN
+        };
+
+        let request = match read_frame(stream, max_len) {
+            Ok(request) => request,
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        match request {
+            Request::Authenticate { token } => {
+                write_frame(stream, &Response::done(session.authenticate(&token)))?;
+            }
+
+            Request::Write {
+                chip,
+                sub_feature,
+                raw_value,
+            } => {
+                let response = match session.check_write() {
+                    Ok(()) => execute(Command::Write {
+                        chip,
+                        sub_feature,
+                        raw_value,
+                    })
+                    .ok_or_else(gone)?,
+                    Err(err) => Response::from_error(err),
+                };
+                write_frame(stream, &response)?;
+            }
+
+            Request::Snapshot => {
+                let response = match session.check_read() {
+                    Ok(()) => execute(Command::Read).ok_or_else(gone)?,
+                    Err(err) => Response::from_error(err),
+                };
+                write_frame(stream, &response)?;
+            }
+
+            Request::Subscribe { interval_ms } => {
+                if let Err(err) = session.check_read() {
+                    write_frame(stream, &Response::from_error(err))?;
+                    continue;
+                }
+                if interval_ms == 0 {
+                    return Err(io::ErrorKind::InvalidData.into());
+                }
+                let interval = Duration::from_millis(interval_ms)
+                    .clamp(MIN_SUBSCRIPTION_INTERVAL, MAX_SUBSCRIPTION_INTERVAL);
+
+                while !stop.load(Ordering::Relaxed) {
+                    let response = execute(Command::Read).ok_or_else(gone)?;
+                    // The subscription ends when the client closes the connection.
+                    if write_frame(stream, &response).is_err() {
+                        return Ok(());
+                    }
+                    sleep_unless_stopped(interval, stop);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sleep for the given duration, or until `stop` is set.
+fn sleep_unless_stopped(duration: Duration, stop: &AtomicBool) {
+    let deadline = Instant::now() + duration;
+    while !stop.load(Ordering::Relaxed) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        thread::sleep(remaining.min(STOP_CHECK_INTERVAL));
+    }
+}
+
+/// Client of an [`Agent`].
+#[derive(Debug)]
+pub struct Client<S = TcpStream> {
+    stream: S,
+}
+
+impl Client {
+    /// Connect to an agent listening at the given address.
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self> {
+        TcpStream::connect(address)
+            .map(Self::with_stream)
+            .map_err(|r| Error::from_io("TcpStream::connect()", r))
+    }
+}
+
+impl<S: Read + Write> Client<S> {
+    /// Return a client of an agent connected through the given stream,
+    /// *e.g.,* a TLS session.
+    #[must_use]
+    pub fn with_stream(stream: S) -> Self {
+        Self { stream }
+    }
+
+    /// Return readings of all sub-features of all chips, read by the agent.
+    pub fn read_all(&mut self) -> Result<Vec<Reading>> {
+        const OPERATION: &str = "Client::read_all()";
+
+        self.request(&Request::Snapshot, OPERATION)?
+            .into_readings(OPERATION)
+    }
+
+    /// Authenticate to the agent with the given token.
+    ///
+    /// See: [`AccessPolicy::auth_token`].
+    pub fn authenticate(&mut self, token: &str) -> Result<()> {
+        const OPERATION: &str = "Client::authenticate()";
+
+        let request = Request::Authenticate {
+            token: token.into(),
+        };
+        self.request(&request, OPERATION)?.into_done(OPERATION)
+    }
+
+    /// Set the raw value of a sub-feature of a chip, through the agent.
+    ///
+    /// This fails unless the agent allows writes.
+    /// See: [`AccessPolicy::allow_writes`].
+    pub fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        const OPERATION: &str = "Client::write()";
+
+        let request = Request::Write {
+            chip: chip.into(),
+            sub_feature: sub_feature.into(),
+            raw_value,
+        };
+        self.request(&request, OPERATION)?.into_done(OPERATION)
+    }
+
+    fn request(&mut self, request: &Request, operation: &'static str) -> Result<Response> {
+        write_frame(&mut self.stream, request)
+            .and_then(|()| read_frame(&mut self.stream, MAX_FRAME_LEN))
+            .map_err(|r| Error::from_io(operation, r))
+    }
+
+    /// Ask the agent to send readings periodically, with the given interval.
+    ///
+    /// The agent clamps the interval between [`MIN_SUBSCRIPTION_INTERVAL`]
+    /// and [`MAX_SUBSCRIPTION_INTERVAL`].
+    pub fn subscribe(mut self, interval: Duration) -> Result<Subscription<S>> {
+        let interval_ms = u64::try_from(interval.as_millis()).unwrap_or(u64::MAX);
+        if interval_ms == 0 {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io("Client::subscribe()", err));
+        }
+
+        write_frame(&mut self.stream, &Request::Subscribe { interval_ms })
+            .map_err(|r| Error::from_io("Client::subscribe()", r))?;
+
+        Ok(Subscription {
+            stream: self.stream,
+        })
+    }
+}
+
+impl<S: Read + Write + fmt::Debug> Backend for Client<S> {
+    fn name(&self) -> &'static str {
+        "remote"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Client::read_all(self)
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        Client::write(self, chip, sub_feature, raw_value)
+    }
+}
+
+/// Readings periodically sent by an [`Agent`].
+///
+/// Iteration ends when the agent closes the connection.
+/// Dropping this closes the connection, which ends the subscription.
+#[derive(Debug)]
+pub struct Subscription<S = TcpStream> {
+    stream: S,
+}
+
+impl<S: Read> Iterator for Subscription<S> {
+    type Item = Result<Vec<Reading>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        const OPERATION: &str = "Subscription::next()";
+
+        match read_frame::<Response>(&mut self.stream, MAX_FRAME_LEN) {
+            Ok(response) => Some(response.into_readings(OPERATION)),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => None,
+            Err(err) => Some(Err(Error::from_io(OPERATION, err))),
+        }
+    }
+}
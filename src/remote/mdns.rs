@@ -0,0 +1,182 @@
+//! Advertisement and discovery of [`Agent`]s on the local network,
+//! through multicast DNS.
+//!
+//! Agents are advertised as instances of the service type [`SERVICE_TYPE`],
+//! so that fleet dashboards can enumerate them automatically.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::time::Duration;
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Instant;
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use super::{Agent, Client};
+use crate::errors::{Error, Result};
+
+/// Service type of agents, in the `local.` domain.
+pub const SERVICE_TYPE: &str = "_lmsensors._tcp.local.";
+
+fn mdns_error(operation: &'static str, err: mdns_sd::Error) -> Error {
+    Error::from_io(operation, io::Error::other(err))
+}
+
+/// Advertisement of an agent on the local network.
+///
+/// The advertisement is withdrawn when this is dropped.
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    full_name: String,
+}
+
+impl Advertisement {
+    /// Advertise an agent listening on the given TCP port of this machine,
+    /// under the given instance name, *e.g.,* the host name.
+    pub fn new(instance_name: &str, port: u16) -> Result<Self> {
+        let daemon = ServiceDaemon::new().map_err(|r| mdns_error("ServiceDaemon::new()", r))?;
+
+        let host_name = format!("{instance_name}.local.");
+        let properties = [("protocol", "1")];
+        let service = ServiceInfo::new(
+            SERVICE_TYPE,
+            instance_name,
+            &host_name,
+            (),
+            port,
+            &properties[..],
+        )
+        .map_err(|r| mdns_error("ServiceInfo::new()", r))?
+        .enable_addr_auto();
+        let full_name = service.get_fullname().to_owned();
+
+        daemon
+            .register(service)
+            .map_err(|r| mdns_error("ServiceDaemon::register()", r))?;
+        Ok(Self { daemon, full_name })
+    }
+
+    /// Return the full name of the advertised service, *e.g.,*
+    /// `host1._lmsensors._tcp.local.`.
+    #[must_use]
+    pub fn full_name(&self) -> &str {
+        &self.full_name
+    }
+}
+
+impl fmt::Debug for Advertisement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Advertisement")
+            .field("full_name", &self.full_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        let _ignored = self.daemon.unregister(&self.full_name);
+        let _ignored = self.daemon.shutdown();
+    }
+}
+
+impl Agent {
+    /// Advertise this agent on the local network, under the given
+    /// instance name.
+    ///
+    /// See: [`Advertisement::new`].
+    pub fn advertise(&self, instance_name: &str) -> Result<Advertisement> {
+        Advertisement::new(instance_name, self.local_addr()?.port())
+    }
+}
+
+/// Agent found on the local network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discovered {
+    /// Instance name of the agent, *e.g.,* `host1`.
+    pub instance_name: String,
+    /// Host name of the machine running the agent, *e.g.,* `host1.local.`.
+    pub host_name: String,
+    /// Addresses of the agent, IPv4 first.
+    pub addresses: Vec<SocketAddr>,
+}
+
+impl Discovered {
+    fn new(
+        full_name: &str,
+        host_name: &str,
+        port: u16,
+        addresses: impl IntoIterator<Item = IpAddr>,
+    ) -> Self {
+        let instance_name = full_name
+            .strip_suffix(SERVICE_TYPE)
+            .and_then(|name| name.strip_suffix('.'))
+            .unwrap_or(full_name);
+
+        let mut addresses: Vec<_> = addresses
+            .into_iter()
+            .map(|address| SocketAddr::new(address, port))
+            .collect();
+        addresses.sort_unstable_by_key(|address| (address.is_ipv6(), *address));
+        addresses.dedup();
+
+        Self {
+            instance_name: instance_name.into(),
+            host_name: host_name.into(),
+            addresses,
+        }
+    }
+
+    /// Connect to this agent, trying its addresses in order.
+    pub fn connect(&self) -> Result<Client> {
+        Client::connect(self.addresses.as_slice())
+    }
+}
+
+/// Return agents found on the local network within the given duration,
+/// ordered by instance name.
+///
+/// A duration too long to be represented, *e.g.,* [`Duration::MAX`], has no
+/// deadline: agents are then searched until browsing fails.
+pub fn discover(timeout: Duration) -> Result<Vec<Discovered>> {
+    let daemon = ServiceDaemon::new().map_err(|r| mdns_error("ServiceDaemon::new()", r))?;
+    let events = daemon
+        .browse(SERVICE_TYPE)
+        .map_err(|r| mdns_error("ServiceDaemon::browse()", r))?;
+
+    let mut found = BTreeMap::default();
+    let deadline = Instant::now().checked_add(timeout);
+    loop {
+        let event = match deadline {
+            None => events.recv().ok(),
+            Some(deadline) => deadline
+                .checked_duration_since(Instant::now())
+                .and_then(|remaining| events.recv_timeout(remaining).ok()),
+        };
+        let Some(event) = event else {
+            break;
+        };
+
+        match event {
+            ServiceEvent::ServiceResolved(service) => {
+                let addresses = service.addresses.iter().map(|ip| ip.to_ip_addr());
+                let agent =
+                    Discovered::new(&service.fullname, &service.host, service.port, addresses);
+                found.insert(agent.instance_name.clone(), agent);
+            }
+
+            ServiceEvent::ServiceRemoved(_type, full_name) => {
+                let agent = Discovered::new(&full_name, "", 0, []);
+                found.remove(&agent.instance_name);
+            }
+
+            _ => {}
+        }
+    }
+
+    let _ignored = daemon.shutdown();
+    Ok(found.into_values().collect())
+}
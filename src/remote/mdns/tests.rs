@@ -0,0 +1,34 @@
+#![cfg(test)]
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use super::{Discovered, SERVICE_TYPE};
+
+#[test]
+fn discovered() {
+    let addresses = [
+        IpAddr::V6(Ipv6Addr::LOCALHOST),
+        IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+        IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+    ];
+    let agent = Discovered::new(
+        &format!("host1.{SERVICE_TYPE}"),
+        "host1.local.",
+        8473,
+        addresses,
+    );
+
+    assert_eq!(agent.instance_name, "host1");
+    assert_eq!(agent.host_name, "host1.local.");
+    assert_eq!(
+        agent.addresses,
+        [
+            SocketAddr::new(addresses[1], 8473),
+            SocketAddr::new(addresses[0], 8473),
+        ]
+    );
+
+    let agent = Discovered::new("other._http._tcp.local.", "", 80, []);
+    assert_eq!(agent.instance_name, "other._http._tcp.local.");
+    assert!(agent.addresses.is_empty());
+}
@@ -0,0 +1,310 @@
+#![cfg(test)]
+
+use core::time::Duration;
+use std::io::{self, Cursor};
+use std::sync::mpsc;
+use std::thread;
+
+use assert_matches::assert_matches;
+
+use super::{Agent, Client, Request, Response, MAX_FRAME_LEN, MAX_UNAUTHENTICATED_FRAME_LEN};
+use crate::access::{self, AccessPolicy};
+use crate::backend::{Backend, Reading};
+use crate::errors::{Error, Result};
+
+#[derive(Debug)]
+struct Fixed(Option<Vec<Reading>>);
+
+impl Backend for Fixed {
+    fn name(&self) -> &'static str {
+        "fixed"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        self.0.clone().ok_or(Error::UnsupportedPlatform)
+    }
+}
+
+/// Backend recording writes.
+#[derive(Debug, Default)]
+struct Recorder(Vec<(String, String, f64)>);
+
+impl Backend for Recorder {
+    fn name(&self) -> &'static str {
+        "recorder"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        Ok(readings())
+    }
+
+    fn write(&mut self, chip: &str, sub_feature: &str, raw_value: f64) -> Result<()> {
+        self.0.push((chip.into(), sub_feature.into(), raw_value));
+        Ok(())
+    }
+}
+
+fn readings() -> Vec<Reading> {
+    vec![
+        Reading {
+            chip: "coretemp-isa-0000".into(),
+            feature: "temp1".into(),
+            sub_feature: "temp1_input".into(),
+            raw_kind: 0x200,
+            raw_value: Some(47.5),
+        },
+        Reading {
+            chip: "coretemp-isa-0000".into(),
+            feature: "temp1".into(),
+            sub_feature: "temp1_crit".into(),
+            raw_kind: 0x204,
+            raw_value: None,
+        },
+    ]
+}
+
+#[test]
+fn frames() {
+    let mut buffer = Vec::default();
+    super::write_frame(&mut buffer, &Request::Subscribe { interval_ms: 250 }).unwrap();
+    super::write_frame(&mut buffer, &Response::Readings(readings())).unwrap();
+
+    let len = u32::from_be_bytes(buffer[..4].try_into().unwrap());
+    assert!(len > 0);
+
+    let mut reader = Cursor::new(buffer.as_slice());
+    let request: Request = super::read_frame(&mut reader, MAX_FRAME_LEN).unwrap();
+    assert_eq!(request, Request::Subscribe { interval_ms: 250 });
+    let response: Response = super::read_frame(&mut reader, MAX_FRAME_LEN).unwrap();
+    assert_eq!(response, Response::Readings(readings()));
+
+    let err = super::read_frame::<Request>(&mut reader, MAX_FRAME_LEN).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    // Truncated, oversized or malformed frames.
+    let truncated = &buffer[..len as usize + 3];
+    super::read_frame::<Request>(&mut Cursor::new(truncated), MAX_FRAME_LEN).unwrap_err();
+
+    let oversized = (MAX_FRAME_LEN + 1).to_be_bytes();
+    let err = super::read_frame::<Request>(&mut Cursor::new(oversized), MAX_FRAME_LEN).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let malformed = [0, 0, 0, 1, 0xff];
+    let err = super::read_frame::<Request>(&mut Cursor::new(malformed), MAX_FRAME_LEN).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn agent_client() {
+    let agent = Agent::bind("127.0.0.1:0").unwrap();
+    let address = agent.local_addr().unwrap();
+
+    let agent_thread = thread::spawn(move || {
+        agent.serve_one(&mut Fixed(Some(readings()))).unwrap();
+        agent.serve_one(&mut Fixed(None)).unwrap();
+    });
+
+    let mut client = Client::connect(address).unwrap();
+    assert_eq!(client.name(), "remote");
+    assert_eq!(client.read_all().unwrap(), readings());
+    assert_eq!(Backend::read_all(&mut client).unwrap(), readings());
+    drop(client);
+
+    let mut client = Client::connect(address).unwrap();
+    assert_matches!(
+        client.read_all(),
+        Err(Error::IO { operation: "Client::read_all()", source })
+            if source.to_string().starts_with("unsupported_platform: ")
+    );
+    drop(client);
+
+    agent_thread.join().unwrap();
+}
+
+#[test]
+fn subscription() {
+    let agent = Agent::bind("127.0.0.1:0").unwrap();
+    let address = agent.local_addr().unwrap();
+
+    let agent_thread = thread::spawn(move || {
+        agent.serve_one(&mut Fixed(Some(readings()))).unwrap();
+    });
+
+    let client = Client::with_stream(Cursor::new(Vec::default()));
+    client.subscribe(Duration::ZERO).unwrap_err();
+
+    let client = Client::connect(address).unwrap();
+    let mut subscription = client.subscribe(Duration::from_millis(1)).unwrap();
+    for _ in 0..3 {
+        assert_eq!(subscription.next().unwrap().unwrap(), readings());
+    }
+    drop(subscription);
+
+    agent_thread.join().unwrap();
+}
+
+#[test]
+fn access_policy() {
+    let policy = AccessPolicy::new().auth_token("s3cret");
+    let agent = Agent::bind("127.0.0.1:0").unwrap().access_policy(policy);
+    let address = agent.local_addr().unwrap();
+
+    let agent_thread = thread::spawn(move || {
+        let mut backend = Recorder::default();
+        agent.serve_one(&mut backend).unwrap();
+        agent.serve_one(&mut Fixed(None)).unwrap();
+
+        let policy = AccessPolicy::new().auth_token("s3cret").allow_writes(true);
+        let agent = agent.access_policy(policy);
+        agent.serve_one(&mut backend).unwrap();
+        backend.0
+    });
+
+    let mut client = Client::connect(address).unwrap();
+    assert!(access::is_denied(&client.read_all().unwrap_err()));
+    assert!(access::is_denied(
+        &client.authenticate("wrong").unwrap_err()
+    ));
+
+    client.authenticate("s3cret").unwrap();
+    assert_eq!(client.read_all().unwrap(), readings());
+
+    // The agent is read-only.
+    let err = client.write("nct6775-isa-0290", "pwm1", 128.0).unwrap_err();
+    assert!(access::is_denied(&err));
+    drop(client);
+
+    let client = Client::connect(address).unwrap();
+    let mut subscription = client.subscribe(Duration::from_millis(1)).unwrap();
+    assert!(access::is_denied(
+        &subscription.next().unwrap().unwrap_err()
+    ));
+    drop(subscription);
+
+    let mut client = Client::connect(address).unwrap();
+    client.authenticate("s3cret").unwrap();
+    client.write("nct6775-isa-0290", "pwm1", 128.0).unwrap();
+    Backend::write(&mut client, "nct6775-isa-0290", "pwm1", 255.0).unwrap();
+    drop(client);
+
+    let written = agent_thread.join().unwrap();
+    assert_eq!(
+        written,
+        [
+            ("nct6775-isa-0290".into(), "pwm1".into(), 128.0),
+            ("nct6775-isa-0290".into(), "pwm1".into(), 255.0),
+        ]
+    );
+}
+
+#[test]
+fn concurrent_clients() {
+    let agent = Agent::bind("127.0.0.1:0").unwrap();
+    let address = agent.local_addr().unwrap();
+
+    let agent_thread = thread::spawn(move || {
+        let streams = core::iter::repeat_with(|| agent.accept_stream()).take(2);
+        agent
+            .serve_streams(&mut Fixed(Some(readings())), streams)
+            .unwrap();
+    });
+
+    // A subscriber does not block other clients.
+    let client = Client::connect(address).unwrap();
+    let mut subscription = client.subscribe(Duration::from_millis(1)).unwrap();
+    assert_eq!(subscription.next().unwrap().unwrap(), readings());
+
+    let mut client = Client::connect(address).unwrap();
+    assert_eq!(client.read_all().unwrap(), readings());
+    assert_eq!(subscription.next().unwrap().unwrap(), readings());
+    drop(client);
+    drop(subscription);
+
+    agent_thread.join().unwrap();
+}
+
+#[test]
+fn max_connections() {
+    let agent = Agent::bind("127.0.0.1:0").unwrap().max_connections(1);
+    let address = agent.local_addr().unwrap();
+
+    let agent_thread = thread::spawn(move || {
+        let streams = core::iter::repeat_with(|| agent.accept_stream()).take(3);
+        agent
+            .serve_streams(&mut Fixed(Some(readings())), streams)
+            .unwrap();
+    });
+
+    let mut client = Client::connect(address).unwrap();
+    assert_eq!(client.read_all().unwrap(), readings());
+
+    // Connections beyond the maximum are closed.
+    let mut refused = Client::connect(address).unwrap();
+    refused.read_all().unwrap_err();
+    drop(refused);
+    drop(client);
+
+    // The connection of the first client is eventually released.
+    thread::sleep(Duration::from_millis(100));
+    let mut client = Client::connect(address).unwrap();
+    assert_eq!(client.read_all().unwrap(), readings());
+    drop(client);
+
+    agent_thread.join().unwrap();
+}
+
+#[test]
+fn subscription_stops() {
+    let agent = Agent::bind("127.0.0.1:0").unwrap();
+    let address = agent.local_addr().unwrap();
+    let (stop_sender, stop_receiver) = mpsc::channel::<()>();
+
+    let agent_thread = thread::spawn(move || {
+        let stopped = core::iter::once_with(move || {
+            let _ignored = stop_receiver.recv();
+            Err(io::ErrorKind::Interrupted.into())
+        });
+        let streams = core::iter::once_with(|| agent.accept_stream()).chain(stopped);
+        agent.serve_streams(&mut Fixed(Some(readings())), streams)
+    });
+
+    // Long intervals do not prevent the agent from stopping.
+    let client = Client::connect(address).unwrap();
+    let mut subscription = client.subscribe(Duration::MAX).unwrap();
+    assert_eq!(subscription.next().unwrap().unwrap(), readings());
+
+    stop_sender.send(()).unwrap();
+    let err = agent_thread.join().unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    assert!(subscription.next().is_none());
+}
+
+#[test]
+fn limits() {
+    let policy = AccessPolicy::new().auth_token("s3cret");
+    let agent = Agent::bind("127.0.0.1:0")
+        .unwrap()
+        .access_policy(policy)
+        .timeout(Duration::from_millis(50));
+    let address = agent.local_addr().unwrap();
+
+    let agent_thread = thread::spawn(move || {
+        let err = agent.serve_one(&mut Fixed(None)).unwrap_err();
+        assert_matches!(err, Error::IO { source, .. } if source.kind() == io::ErrorKind::InvalidData);
+
+        // Idle connections are closed.
+        agent.serve_one(&mut Fixed(None)).unwrap_err();
+    });
+
+    // Unauthenticated clients may not send large frames.
+    let token = "a".repeat(MAX_UNAUTHENTICATED_FRAME_LEN as usize);
+    let mut client = Client::connect(address).unwrap();
+    client.authenticate(&token).unwrap_err();
+    drop(client);
+
+    let mut client = Client::connect(address).unwrap();
+    thread::sleep(Duration::from_millis(200));
+    client.read_all().unwrap_err();
+
+    agent_thread.join().unwrap();
+}
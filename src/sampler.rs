@@ -0,0 +1,631 @@
+//! Periodic sampling of sensors, integrated with event loops.
+//!
+//! A [`Sampler`] owns a timer file descriptor that becomes readable whenever
+//! new readings are due. It can be registered with `poll()`, `epoll()`, or
+//! any event loop based on them. When the descriptor is readable,
+//! [`Sampler::drain`] returns the new readings, without blocking.
+//!
+//! The polling intervals of sub-features are measured by a
+//! [`Clock`](crate::clock::Clock), which is monotonic by default.
+//! When the clock advances much more than the timer between two drains,
+//! the system is assumed to have been suspended, which is reported by
+//! [`Sample::resumed`].
+//!
+//! Sub-features that fail to be read too many times in a row can be
+//! quarantined, so that they are no longer read.
+//! See: [`Sampler::set_quarantine_threshold`].
+//!
+//! With the `tracing` feature, readings can also be emitted as `tracing`
+//! events under the [`TRACE_TARGET`] target, so that they reach whatever
+//! subscriber the application installed, *e.g.,* a metrics layer.
+
+#[cfg(test)]
+mod tests;
+
+use core::time::Duration;
+use core::{fmt, mem, ptr};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::path::Path;
+use std::{fs, io};
+
+use crate::clock::{Clock, Monotonic};
+use crate::errors::{self, Error, Result, Warning};
+use crate::events::{self, Event};
+use crate::feature::Kind;
+use crate::{SubFeatureRef, Value};
+
+/// Default shortest suspension detected by a sampler.
+///
+/// See: [`Sampler::set_resume_threshold`].
+pub const DEFAULT_RESUME_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// Target of the `tracing` events emitted for readings.
+///
+/// See: [`Sampler::set_trace_samples`].
+#[cfg(feature = "tracing")]
+pub const TRACE_TARGET: &str = "lm_sensors::samples";
+
+/// Polling profile, trading reading freshness for wake-ups.
+///
+/// See: [`Sampler::with_profile`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Profile {
+    /// Frequent polling, *e.g.,* for desktops and servers.
+    Performance,
+    /// Moderate polling, *e.g.,* for general monitoring.
+    #[default]
+    Balanced,
+    /// Infrequent polling, *e.g.,* for laptops on battery.
+    PowerSaver,
+}
+
+impl Profile {
+    /// Return the polling interval of sub-features of the given feature kind.
+    #[must_use]
+    pub fn interval(self, kind: Option<Kind>) -> Duration {
+        let secs = match (self, kind) {
+            (Self::Performance, Some(Kind::Temperature | Kind::Fan | Kind::Power)) => 1,
+            (Self::Performance, Some(Kind::Intrusion | Kind::BeepEnable)) => 10,
+            (Self::Performance, _) => 2,
+
+            (Self::Balanced, Some(Kind::Temperature | Kind::Fan | Kind::Power)) => 2,
+            (Self::Balanced, Some(Kind::Intrusion | Kind::BeepEnable)) => 60,
+            (Self::Balanced, _) => 10,
+
+            (Self::PowerSaver, Some(Kind::Temperature | Kind::Fan | Kind::Power)) => 10,
+            (Self::PowerSaver, Some(Kind::Intrusion | Kind::BeepEnable)) => 300,
+            (Self::PowerSaver, _) => 60,
+        };
+        Duration::from_secs(secs)
+    }
+
+    /// Return the shortest polling interval of this profile.
+    #[must_use]
+    pub fn min_interval(self) -> Duration {
+        self.interval(Some(Kind::Temperature))
+    }
+}
+
+/// Return `true` if the system is powered by a battery, and not by
+/// a mains power supply.
+///
+/// This is based on `/sys/class/power_supply`, and is meant to be used as a
+/// suspension hint. See: [`Sampler::set_suspend_hint`].
+#[must_use]
+pub fn on_battery() -> bool {
+    on_battery_at(Path::new("/sys/class/power_supply"))
+}
+
+fn on_battery_at(power_supply_dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(power_supply_dir) else {
+        return false;
+    };
+
+    let mut has_battery = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |name| fs::read_to_string(path.join(name)).unwrap_or_default();
+
+        match read("type").trim() {
+            "Mains" | "USB" if read("online").trim() == "1" => return false,
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    has_battery
+}
+
+/// Reading of a sub-feature, produced by a [`Sampler`].
+#[derive(Debug)]
+pub struct Reading<'a> {
+    /// Sub-feature that was read.
+    pub sub_feature: SubFeatureRef<'a>,
+    /// Value read from the sub-feature, or the reason it could not be read.
+    pub value: Result<Value>,
+}
+
+/// Readings produced by [`Sampler::drain`].
+#[derive(Debug)]
+pub struct Sample<'a> {
+    /// Number of sampling periods that elapsed since the last drain.
+    /// This is greater than one if some periods were missed, which is also
+    /// reported as a [`Warning::SamplerOverrun`] to the error listener.
+    pub periods: u64,
+    /// Readings of sub-features whose polling interval elapsed,
+    /// in the order they were added.
+    pub readings: Vec<Reading<'a>>,
+    /// Time during which the system was suspended since the last drain,
+    /// if a suspension was detected.
+    ///
+    /// All sub-features are read after a suspension. Rates of change and
+    /// windows of readings spanning the suspension are meaningless, and
+    /// should be reset. This is also published as an [`Event::Resumed`].
+    pub resumed: Option<Duration>,
+}
+
+/**
+Periodic sampler of sub-features, signaling new readings through
+a pollable file descriptor.
+
+# Example
+
+```rust
+use std::os::fd::AsRawFd;
+
+let sensors = lm_sensors::Initializer::default().initialize()?;
+let mut sampler = lm_sensors::sampler::Sampler::new(core::time::Duration::from_millis(10))?;
+for chip in sensors.chip_iter(None) {
+    for feature in chip.feature_iter() {
+        sampler.extend(feature.sub_feature_iter());
+    }
+}
+
+let mut poll_fd = libc::pollfd { fd: sampler.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+if unsafe { libc::poll(&mut poll_fd, 1, 1000) } == 1 {
+    if let Some(sample) = sampler.drain()? {
+        for reading in &sample.readings {
+            println!("{}: {:?}", reading.sub_feature, reading.value);
+        }
+    }
+}
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub struct Sampler<'a> {
+    timer: OwnedFd,
+    interval: Duration,
+    profile: Option<Profile>,
+    entries: Vec<Entry<'a>>,
+    suspend_hint: Option<Box<dyn FnMut() -> bool + 'a>>,
+    clock: Box<dyn Clock + 'a>,
+    last_drain: Option<Duration>,
+    resume_threshold: Duration,
+    quarantine_threshold: Option<u32>,
+    #[cfg(feature = "tracing")]
+    trace_samples: bool,
+}
+
+#[derive(Debug)]
+struct Entry<'a> {
+    sub_feature: SubFeatureRef<'a>,
+    interval: Duration,
+    next_due: Option<Duration>,
+    failures: u32,
+    quarantined: bool,
+}
+
+impl<'a> Sampler<'a> {
+    /// Return a new sampler producing readings every `interval`,
+    /// starting after the first interval.
+    ///
+    /// See: `timerfd_create()`.
+    pub fn new(interval: Duration) -> Result<Self> {
+        // Safety: timerfd_create() is assumed to be safe.
+        let fd = unsafe {
+            libc::timerfd_create(
+                libc::CLOCK_MONOTONIC,
+                libc::TFD_NONBLOCK | libc::TFD_CLOEXEC,
+            )
+        };
+        if fd == -1 {
+            let err = io::Error::last_os_error();
+            return Err(Error::from_io("timerfd_create()", err));
+        }
+
+        let mut result = Self {
+            // Safety: `fd` is a newly created file descriptor.
+            timer: unsafe { OwnedFd::from_raw_fd(fd) },
+            interval: Duration::ZERO,
+            profile: None,
+            entries: Vec::default(),
+            suspend_hint: None,
+            clock: Box::new(Monotonic::new()),
+            last_drain: None,
+            resume_threshold: DEFAULT_RESUME_THRESHOLD,
+            quarantine_threshold: None,
+            #[cfg(feature = "tracing")]
+            trace_samples: false,
+        };
+        result.set_interval(interval)?;
+        Ok(result)
+    }
+
+    /// Return a new sampler polling sub-features at the intervals
+    /// of the given profile, based on the kinds of their features.
+    ///
+    /// The timer interval is the shortest interval of the profile.
+    pub fn with_profile(profile: Profile) -> Result<Self> {
+        let mut result = Self::new(profile.min_interval())?;
+        result.profile = Some(profile);
+        Ok(result)
+    }
+
+    /// Return the polling profile, if any.
+    #[must_use]
+    pub fn profile(&self) -> Option<Profile> {
+        self.profile
+    }
+
+    /// Set the polling profile, recomputing the polling intervals of all
+    /// sub-features, and restarting the sampling period.
+    pub fn set_profile(&mut self, profile: Profile) -> Result<()> {
+        self.set_interval(profile.min_interval())?;
+        self.profile = Some(profile);
+        for entry in &mut self.entries {
+            entry.interval = profile.interval(entry.sub_feature.feature().kind());
+            entry.next_due = None;
+        }
+        Ok(())
+    }
+
+    /// Set a function called before each sampling, returning `true` if
+    /// polling is suspended, *e.g.,* [`on_battery`].
+    ///
+    /// While polling is suspended, [`Sampler::drain`] returns no readings.
+    pub fn set_suspend_hint(&mut self, hint: impl FnMut() -> bool + 'a) {
+        self.suspend_hint = Some(Box::new(hint));
+    }
+
+    /// Return the clock measuring the polling intervals of sub-features.
+    #[must_use]
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Set the clock measuring the polling intervals of sub-features,
+    /// *e.g.,* a [`Mock`](crate::clock::Mock) clock in unit tests.
+    ///
+    /// All sub-features are read on the next sampling.
+    pub fn set_clock(&mut self, clock: impl Clock + 'a) {
+        self.clock = Box::new(clock);
+        self.last_drain = None;
+        for entry in &mut self.entries {
+            entry.next_due = None;
+        }
+    }
+
+    /// Return the shortest suspension detected by this sampler.
+    #[must_use]
+    pub fn resume_threshold(&self) -> Duration {
+        self.resume_threshold
+    }
+
+    /// Set the shortest suspension detected by this sampler.
+    ///
+    /// A suspension is detected when the clock advanced by more than
+    /// the elapsed sampling periods plus this threshold, between two drains.
+    /// This is [`DEFAULT_RESUME_THRESHOLD`] by default.
+    pub fn set_resume_threshold(&mut self, threshold: Duration) {
+        self.resume_threshold = threshold;
+    }
+
+    /// Return the number of consecutive failed readings after which
+    /// a sub-feature is quarantined, if any.
+    #[must_use]
+    pub fn quarantine_threshold(&self) -> Option<u32> {
+        self.quarantine_threshold
+    }
+
+    /// Set the number of consecutive failed readings after which
+    /// a sub-feature is quarantined, or `None` to never quarantine
+    /// sub-features. This is `None` by default.
+    ///
+    /// Quarantined sub-features are no longer read by [`Sampler::drain`],
+    /// and are published as an [`Event::SensorQuarantined`].
+    pub fn set_quarantine_threshold(&mut self, threshold: Option<u32>) {
+        self.quarantine_threshold = threshold;
+    }
+
+    /// Return the quarantined sub-features, in the order they were added.
+    pub fn quarantined(&self) -> impl Iterator<Item = SubFeatureRef<'a>> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| entry.quarantined)
+            .map(|entry| entry.sub_feature)
+    }
+
+    /// Read quarantined sub-features again, on the next sampling.
+    pub fn release_quarantined(&mut self) {
+        for entry in self.entries.iter_mut().filter(|entry| entry.quarantined) {
+            entry.quarantined = false;
+            entry.failures = 0;
+            entry.next_due = None;
+        }
+    }
+
+    /// Return `true` if readings are emitted as `tracing` events.
+    #[cfg(feature = "tracing")]
+    #[must_use]
+    pub fn trace_samples(&self) -> bool {
+        self.trace_samples
+    }
+
+    /// Set whether readings returned by [`Sampler::drain`] are emitted
+    /// as `tracing` events, under the [`TRACE_TARGET`] target.
+    ///
+    /// Each reading is emitted as an event of level `INFO`, with the fields
+    /// `chip`, `feature`, `sub_feature`, `kind`, `value` and `unit`.
+    /// Readings that failed are emitted as events of level `WARN`, with
+    /// the fields `chip`, `feature`, `sub_feature` and `error`.
+    /// This is disabled by default.
+    #[cfg(feature = "tracing")]
+    pub fn set_trace_samples(&mut self, enabled: bool) {
+        self.trace_samples = enabled;
+    }
+
+    /// Return the sampling interval.
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Set the sampling interval, and restart the sampling period.
+    ///
+    /// A zero interval stops sampling.
+    ///
+    /// See: `timerfd_settime()`.
+    pub fn set_interval(&mut self, interval: Duration) -> Result<()> {
+        let timespec = to_timespec(interval)?;
+        let spec = libc::itimerspec {
+            it_interval: timespec,
+            it_value: timespec,
+        };
+
+        // Safety: `spec` is properly initialized.
+        let r = unsafe { libc::timerfd_settime(self.timer.as_raw_fd(), 0, &spec, ptr::null_mut()) };
+        if r == -1 {
+            let err = io::Error::last_os_error();
+            return Err(Error::from_io("timerfd_settime()", err));
+        }
+
+        self.interval = interval;
+        Ok(())
+    }
+
+    /// Stop sampling. The file descriptor does not become readable until
+    /// [`Sampler::set_interval`] is called with a non-zero interval.
+    pub fn stop(&mut self) -> Result<()> {
+        self.set_interval(Duration::ZERO)
+    }
+
+    /// Add a sub-feature to be sampled.
+    ///
+    /// If this sampler has a profile, then the sub-feature is polled
+    /// at the interval of the profile for its feature kind.
+    /// Otherwise, it is polled at every sampling period.
+    pub fn push(&mut self, sub_feature: SubFeatureRef<'a>) {
+        let interval = self.profile.map_or(Duration::ZERO, |profile| {
+            profile.interval(sub_feature.feature().kind())
+        });
+        self.push_with_interval(sub_feature, interval);
+    }
+
+    /// Add a sub-feature to be polled at the given interval,
+    /// rounded up to a multiple of the sampling interval.
+    pub fn push_with_interval(&mut self, sub_feature: SubFeatureRef<'a>, interval: Duration) {
+        self.entries.push(Entry {
+            sub_feature,
+            interval,
+            next_due: None,
+            failures: 0,
+            quarantined: false,
+        });
+    }
+
+    /// Return the sub-features sampled, in the order they were added.
+    pub fn sub_features(&self) -> impl ExactSizeIterator<Item = SubFeatureRef<'a>> + '_ {
+        self.entries.iter().map(|entry| entry.sub_feature)
+    }
+
+    /// Return new readings, if any sampling period elapsed since the last call,
+    /// or `None` otherwise. This does not block.
+    pub fn drain(&mut self) -> Result<Option<Sample<'a>>> {
+        let mut periods = 0_u64;
+
+        // Safety: `periods` is valid for writing 8 bytes.
+        let r = unsafe {
+            libc::read(
+                self.timer.as_raw_fd(),
+                ptr::addr_of_mut!(periods).cast(),
+                mem::size_of::<u64>(),
+            )
+        };
+        if r == -1 {
+            let err = io::Error::last_os_error();
+            return if err.kind() == io::ErrorKind::WouldBlock {
+                Ok(None)
+            } else {
+                Err(Error::from_io("read()", err))
+            };
+        }
+
+        if periods > 1 {
+            errors::report_warning(&Warning::SamplerOverrun {
+                missed_periods: periods - 1,
+                interval: self.interval,
+            });
+        }
+
+        let now = self.clock.now();
+        let resumed = self.last_drain.and_then(|last_drain| {
+            let expected = self
+                .interval
+                .saturating_mul(u32::try_from(periods).unwrap_or(u32::MAX));
+            resume_gap(
+                now.saturating_sub(last_drain),
+                expected,
+                self.resume_threshold,
+            )
+        });
+        self.last_drain = Some(now);
+
+        if let Some(gap) = resumed {
+            for entry in &mut self.entries {
+                entry.next_due = None;
+            }
+            events::publish(&Event::Resumed { gap });
+        }
+
+        let suspended = self.suspend_hint.as_mut().is_some_and(|hint| hint());
+        let readings = if suspended {
+            Vec::default()
+        } else {
+            self.read_due(now)
+        };
+
+        #[cfg(feature = "tracing")]
+        if self.trace_samples {
+            readings.iter().for_each(trace_reading);
+        }
+
+        Ok(Some(Sample {
+            periods,
+            readings,
+            resumed,
+        }))
+    }
+
+    /// Read all sub-features now, regardless of their polling intervals.
+    #[must_use]
+    pub fn read_all(&self) -> Vec<Reading<'a>> {
+        self.entries
+            .iter()
+            .map(|entry| Reading {
+                sub_feature: entry.sub_feature,
+                value: entry.sub_feature.value(),
+            })
+            .collect()
+    }
+
+    fn read_due(&mut self, now: Duration) -> Vec<Reading<'a>> {
+        // Tolerate timer jitter of a fraction of the sampling period.
+        let slack = self.interval / 2;
+
+        let quarantine_threshold = self.quarantine_threshold;
+
+        self.entries
+            .iter_mut()
+            .filter(|entry| !entry.quarantined)
+            .filter(|entry| entry.next_due.is_none_or(|due| due <= now + slack))
+            .map(|entry| {
+                entry.next_due = Some(now + entry.interval);
+                let value = entry.sub_feature.value();
+                if value.is_ok() {
+                    entry.failures = 0;
+                } else {
+                    entry.failures = entry.failures.saturating_add(1);
+                    if quarantine_threshold.is_some_and(|threshold| entry.failures >= threshold) {
+                        entry.quarantine();
+                    }
+                }
+
+                Reading {
+                    sub_feature: entry.sub_feature,
+                    value,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Entry<'_> {
+    fn quarantine(&mut self) {
+        self.quarantined = true;
+
+        let sub_feature = self.sub_feature;
+        events::publish(&Event::SensorQuarantined {
+            chip: sub_feature.feature().chip().to_string(),
+            sub_feature: sub_feature.to_string(),
+            failures: self.failures,
+        });
+    }
+}
+
+impl fmt::Debug for Sampler<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Sampler");
+        debug
+            .field("timer", &self.timer)
+            .field("interval", &self.interval)
+            .field("profile", &self.profile)
+            .field("entries", &self.entries)
+            .field("suspend_hint", &self.suspend_hint.is_some())
+            .field("clock", &self.clock)
+            .field("last_drain", &self.last_drain)
+            .field("resume_threshold", &self.resume_threshold)
+            .field("quarantine_threshold", &self.quarantine_threshold);
+        #[cfg(feature = "tracing")]
+        debug.field("trace_samples", &self.trace_samples);
+        debug.finish()
+    }
+}
+
+/// Emit the given reading as a `tracing` event.
+#[cfg(feature = "tracing")]
+fn trace_reading(reading: &Reading<'_>) {
+    let sub_feature = reading.sub_feature;
+    let feature = sub_feature.feature();
+    let chip = feature.chip();
+    let feature_name = feature.name().and_then(core::result::Result::ok);
+    let sub_feature_name = sub_feature.name().and_then(core::result::Result::ok);
+
+    match &reading.value {
+        Ok(value) => tracing::info!(
+            target: TRACE_TARGET,
+            chip = %chip,
+            feature = feature_name,
+            sub_feature = sub_feature_name,
+            kind = ?value.kind(),
+            value = value.raw_value(),
+            unit = %value.unit(),
+        ),
+        Err(err) => tracing::warn!(
+            target: TRACE_TARGET,
+            chip = %chip,
+            feature = feature_name,
+            sub_feature = sub_feature_name,
+            error = %err,
+        ),
+    }
+}
+
+impl<'a> Extend<SubFeatureRef<'a>> for Sampler<'a> {
+    fn extend<T: IntoIterator<Item = SubFeatureRef<'a>>>(&mut self, iter: T) {
+        for sub_feature in iter {
+            self.push(sub_feature);
+        }
+    }
+}
+
+impl AsFd for Sampler<'_> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.timer.as_fd()
+    }
+}
+
+impl AsRawFd for Sampler<'_> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.timer.as_raw_fd()
+    }
+}
+
+/// Return the duration of the suspension of the system, if `elapsed` time
+/// exceeds the `expected` time by more than `threshold`.
+fn resume_gap(elapsed: Duration, expected: Duration, threshold: Duration) -> Option<Duration> {
+    let gap = elapsed.saturating_sub(expected);
+    (gap > threshold).then_some(gap)
+}
+
+fn to_timespec(duration: Duration) -> Result<libc::timespec> {
+    let tv_sec = libc::time_t::try_from(duration.as_secs()).map_err(|_r| {
+        let err = io::ErrorKind::InvalidInput.into();
+        Error::from_io("timerfd_settime()", err)
+    })?;
+
+    Ok(libc::timespec {
+        tv_sec,
+        // Nanoseconds are less than 10^9, so they fit in any `c_long`.
+        tv_nsec: duration.subsec_nanos() as libc::c_long,
+    })
+}
@@ -0,0 +1,357 @@
+#![cfg(test)]
+
+use core::ptr;
+use core::time::Duration;
+use std::os::fd::AsRawFd;
+
+use serial_test::serial;
+
+use super::{Profile, Sampler};
+
+fn wait_readable(sampler: &Sampler<'_>, timeout_ms: i32) -> bool {
+    let mut poll_fd = libc::pollfd {
+        fd: sampler.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) == 1 }
+}
+
+#[test]
+fn drain() {
+    let mut sampler = Sampler::new(Duration::from_millis(10)).unwrap();
+    assert_eq!(sampler.interval(), Duration::from_millis(10));
+    assert!(sampler.drain().unwrap().is_none());
+
+    assert!(wait_readable(&sampler, 1000));
+    let sample = sampler.drain().unwrap().unwrap();
+    assert!(sample.periods >= 1);
+    assert!(sample.readings.is_empty());
+
+    // Missed periods are counted.
+    std::thread::sleep(Duration::from_millis(35));
+    let sample = sampler.drain().unwrap().unwrap();
+    assert!(sample.periods >= 2);
+
+    sampler.stop().unwrap();
+    assert!(!wait_readable(&sampler, 30));
+    assert!(sampler.drain().unwrap().is_none());
+}
+
+#[test]
+#[serial]
+fn readings() {
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    let mut sampler = Sampler::new(Duration::from_millis(1)).unwrap();
+    for chip in s.chip_iter(None) {
+        for feature in chip.feature_iter() {
+            sampler.extend(feature.sub_feature_iter());
+        }
+    }
+
+    assert!(wait_readable(&sampler, 1000));
+    let sample = sampler.drain().unwrap().unwrap();
+    assert_eq!(sample.readings.len(), sampler.sub_features().len());
+    for (reading, sub_feature) in sample.readings.iter().zip(sampler.sub_features()) {
+        assert_eq!(reading.sub_feature, sub_feature);
+    }
+}
+
+#[test]
+fn profile() {
+    use crate::feature::Kind;
+
+    for profile in [Profile::Performance, Profile::Balanced, Profile::PowerSaver] {
+        let temperature = profile.interval(Some(Kind::Temperature));
+        let voltage = profile.interval(Some(Kind::Voltage));
+        let intrusion = profile.interval(Some(Kind::Intrusion));
+
+        assert_eq!(profile.min_interval(), temperature);
+        assert!(temperature < voltage);
+        assert!(voltage < intrusion);
+        assert_eq!(profile.interval(None), voltage);
+    }
+
+    assert_eq!(Profile::default(), Profile::Balanced);
+    assert_eq!(
+        Profile::Balanced.interval(Some(Kind::Temperature)),
+        Duration::from_secs(2)
+    );
+    assert_eq!(
+        Profile::Balanced.interval(Some(Kind::Voltage)),
+        Duration::from_secs(10)
+    );
+    assert_eq!(
+        Profile::Balanced.interval(Some(Kind::Intrusion)),
+        Duration::from_secs(60)
+    );
+}
+
+#[test]
+#[serial]
+fn polling_intervals() {
+    use std::cell::Cell;
+
+    use crate::clock::{Clock, Mock};
+
+    use sensors_sys::sensors_feature_type::{SENSORS_FEATURE_IN, SENSORS_FEATURE_TEMP};
+
+    use crate::feature::Kind;
+    use sensors_sys::{sensors_feature, sensors_subfeature};
+
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+
+    let raw_feature = |type_| sensors_feature {
+        name: ptr::null_mut(),
+        number: 0,
+        type_,
+        first_subfeature: 0,
+        padding1: 0,
+    };
+    let raw_sub_feature = sensors_subfeature {
+        name: ptr::null_mut(),
+        number: 0,
+        type_: 0,
+        mapping: 0,
+        flags: 0,
+    };
+    let temp_feature = raw_feature(SENSORS_FEATURE_TEMP);
+    let in_feature = raw_feature(SENSORS_FEATURE_IN);
+
+    let temp = unsafe {
+        s.new_sub_feature_ref(
+            s.new_feature_ref(chip.as_ref(), &temp_feature),
+            &raw_sub_feature,
+        )
+    };
+    let voltage = unsafe {
+        s.new_sub_feature_ref(
+            s.new_feature_ref(chip.as_ref(), &in_feature),
+            &raw_sub_feature,
+        )
+    };
+
+    let on_battery = Cell::new(true);
+    let mut sampler = Sampler::with_profile(Profile::Balanced).unwrap();
+    assert_eq!(sampler.profile(), Some(Profile::Balanced));
+    assert_eq!(sampler.interval(), Duration::from_secs(2));
+    sampler.extend([temp, voltage]);
+
+    // Everything is read on the first sampling.
+    let clock = Mock::new(Duration::from_secs(100));
+    sampler.set_clock(clock.clone());
+    assert_eq!(sampler.clock().now(), Duration::from_secs(100));
+    let readings = sampler.read_due(clock.now());
+    assert_eq!(readings.len(), 2);
+
+    let due = |sampler: &mut Sampler<'_>, secs: u64| {
+        clock.set(Duration::from_secs(100 + secs));
+        let readings = sampler.read_due(clock.now());
+        let kinds = readings.iter().map(|r| r.sub_feature.feature().kind());
+        kinds.collect::<Vec<_>>()
+    };
+    assert_eq!(due(&mut sampler, 2), [Some(Kind::Temperature)]);
+    assert_eq!(due(&mut sampler, 4), [Some(Kind::Temperature)]);
+    assert_eq!(
+        due(&mut sampler, 10),
+        [Some(Kind::Temperature), Some(Kind::Voltage)]
+    );
+
+    // Suspension hint.
+    sampler.set_suspend_hint(|| on_battery.get());
+    sampler.set_interval(Duration::from_millis(1)).unwrap();
+    std::thread::sleep(Duration::from_millis(5));
+    let sample = sampler.drain().unwrap().unwrap();
+    assert!(sample.readings.is_empty());
+
+    sampler.set_profile(Profile::PowerSaver).unwrap();
+    assert_eq!(sampler.interval(), Duration::from_secs(10));
+    assert_eq!(sampler.read_due(clock.now()).len(), 2);
+}
+
+#[test]
+fn resumed() {
+    use crate::clock::Mock;
+
+    let mut sampler = Sampler::new(Duration::from_millis(10)).unwrap();
+    assert_eq!(sampler.resume_threshold(), super::DEFAULT_RESUME_THRESHOLD);
+    let clock = Mock::default();
+    sampler.set_clock(clock.clone());
+
+    assert!(wait_readable(&sampler, 1000));
+    assert_eq!(sampler.drain().unwrap().unwrap().resumed, None);
+
+    clock.advance(Duration::from_secs(1));
+    assert!(wait_readable(&sampler, 1000));
+    assert_eq!(sampler.drain().unwrap().unwrap().resumed, None);
+
+    clock.advance(Duration::from_secs(3600));
+    assert!(wait_readable(&sampler, 1000));
+    let gap = sampler.drain().unwrap().unwrap().resumed.unwrap();
+    assert!(gap > Duration::from_secs(3599) && gap <= Duration::from_secs(3600));
+
+    sampler.set_resume_threshold(Duration::from_secs(7200));
+    clock.advance(Duration::from_secs(3600));
+    assert!(wait_readable(&sampler, 1000));
+    assert_eq!(sampler.drain().unwrap().unwrap().resumed, None);
+}
+
+#[test]
+fn resume_gap() {
+    let secs = Duration::from_secs;
+    assert_eq!(super::resume_gap(secs(5), secs(1), secs(10)), None);
+    assert_eq!(super::resume_gap(secs(11), secs(1), secs(10)), None);
+    assert_eq!(
+        super::resume_gap(secs(12), secs(1), secs(10)),
+        Some(secs(11))
+    );
+    assert_eq!(super::resume_gap(secs(1), secs(5), secs(0)), None);
+}
+
+#[test]
+fn on_battery() {
+    let dir = tempfile::tempdir().unwrap();
+    let supply = |name: &str, kind: &str, online: Option<&str>| {
+        let path = dir.path().join(name);
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("type"), format!("{kind}\n")).unwrap();
+        if let Some(online) = online {
+            std::fs::write(path.join("online"), format!("{online}\n")).unwrap();
+        }
+    };
+
+    assert!(!super::on_battery_at(&dir.path().join("inexistent")));
+    assert!(!super::on_battery_at(dir.path()));
+
+    supply("BAT0", "Battery", None);
+    assert!(super::on_battery_at(dir.path()));
+
+    supply("AC", "Mains", Some("0"));
+    assert!(super::on_battery_at(dir.path()));
+
+    std::fs::write(dir.path().join("AC/online"), "1\n").unwrap();
+    assert!(!super::on_battery_at(dir.path()));
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+#[serial]
+fn trace_samples() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tracing::span;
+
+    struct Counter(Arc<AtomicUsize>);
+
+    impl tracing::Subscriber for Counter {
+        fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+            metadata.target() == super::TRACE_TARGET
+        }
+
+        fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, _event: &tracing::Event<'_>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    let mut sampler = Sampler::new(Duration::from_millis(1)).unwrap();
+    assert!(!sampler.trace_samples());
+    sampler.set_trace_samples(true);
+    assert!(sampler.trace_samples());
+    for chip in s.chip_iter(None) {
+        for feature in chip.feature_iter() {
+            sampler.extend(feature.sub_feature_iter());
+        }
+    }
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let subscriber = Counter(Arc::clone(&count));
+    let sample = tracing::subscriber::with_default(subscriber, || {
+        assert!(wait_readable(&sampler, 1000));
+        sampler.drain().unwrap().unwrap()
+    });
+    assert_eq!(count.load(Ordering::Relaxed), sample.readings.len());
+}
+
+#[test]
+#[serial]
+fn quarantine() {
+    use crate::clock::{Clock, Mock};
+    use crate::events::Event;
+
+    use sensors_sys::sensors_feature_type::SENSORS_FEATURE_TEMP;
+    use sensors_sys::{sensors_feature, sensors_subfeature};
+
+    let s = crate::Initializer::isolated().initialize().unwrap();
+    let chip = s.new_chip("lm75-i2c-0-48").unwrap();
+    let events = s.events();
+
+    let raw_feature = sensors_feature {
+        name: ptr::null_mut(),
+        number: 0,
+        type_: SENSORS_FEATURE_TEMP,
+        first_subfeature: 0,
+        padding1: 0,
+    };
+    let raw_sub_feature = sensors_subfeature {
+        name: ptr::null_mut(),
+        number: 0,
+        type_: 0,
+        mapping: 0,
+        flags: 0,
+    };
+    // The chip is not detected, so reading the sub-feature fails.
+    let temp = unsafe {
+        s.new_sub_feature_ref(
+            s.new_feature_ref(chip.as_ref(), &raw_feature),
+            &raw_sub_feature,
+        )
+    };
+
+    let mut sampler = Sampler::new(Duration::from_secs(1)).unwrap();
+    assert_eq!(sampler.quarantine_threshold(), None);
+    sampler.set_quarantine_threshold(Some(2));
+    sampler.push(temp);
+    let clock = Mock::new(Duration::from_secs(100));
+    sampler.set_clock(clock.clone());
+
+    let readings = sampler.read_due(clock.now());
+    assert!(readings[0].value.is_err());
+    assert_eq!(sampler.quarantined().count(), 0);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(sampler.read_due(clock.now()).len(), 1);
+    assert_eq!(sampler.quarantined().collect::<Vec<_>>(), [temp]);
+    assert_eq!(
+        events.try_next(),
+        Some(Event::SensorQuarantined {
+            chip: "lm75-i2c-0-48".into(),
+            sub_feature: String::default(),
+            failures: 2,
+        })
+    );
+
+    // Quarantined sub-features are not read.
+    clock.advance(Duration::from_secs(1));
+    assert!(sampler.read_due(clock.now()).is_empty());
+
+    sampler.release_quarantined();
+    assert_eq!(sampler.quarantined().count(), 0);
+    assert_eq!(sampler.read_due(clock.now()).len(), 1);
+}
@@ -0,0 +1,198 @@
+//! Selection of sub-features by name patterns.
+//!
+//! A [`Selector`] holds rules, each made of a chip name pattern and
+//! a sub-feature name pattern, *e.g.,* `coretemp-isa-*` and `temp*_input`.
+//! Patterns match names entirely, `*` matching any sequence of characters,
+//! and `?` matching any single character.
+//!
+//! Selectors restrict snapshots to the selected sub-features, so that
+//! unselected sub-features are never read, *e.g.,* with
+//! `LMSensors::snapshot_where`.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::str::FromStr;
+use std::io;
+
+use crate::errors::{Error, Result};
+
+/// Pattern matching all names.
+const ANY: &str = "*";
+
+/// Rule of a [`Selector`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// Pattern of chip names, *e.g.,* `coretemp-isa-*`.
+    pub chip: String,
+    /// Pattern of sub-feature names, *e.g.,* `temp*_input`.
+    pub sub_feature: String,
+}
+
+impl Rule {
+    /// Return `true` if this rule selects the given sub-feature of
+    /// the given chip.
+    #[must_use]
+    pub fn matches(&self, chip: &str, sub_feature: &str) -> bool {
+        matches(&self.chip, chip) && matches(&self.sub_feature, sub_feature)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.chip, self.sub_feature)
+    }
+}
+
+/**
+Selector of sub-features, by patterns of chip and sub-feature names.
+
+A sub-feature is selected if any rule of the selector matches it.
+Selectors parse from rules separated by commas, each written as
+`chip/sub_feature`, where `/sub_feature` might be omitted to select
+all sub-features of a chip.
+
+# Example
+
+```rust
+use lm_sensors::selector::Selector;
+
+let selector = Selector::new()
+    .sensor("coretemp-isa-????", "temp1_input")
+    .sensor("nct6775-isa-0290", "fan*_input");
+assert!(selector.matches("coretemp-isa-0000", "temp1_input"));
+assert!(!selector.matches("coretemp-isa-0000", "temp1_max"));
+
+let parsed: Selector = "coretemp-isa-????/temp1_input, nct6775-isa-0290/fan*_input".parse()?;
+assert_eq!(parsed, selector);
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct Selector {
+    rules: Vec<Rule>,
+}
+
+impl Selector {
+    /// Return a selector of no sub-features.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a selector of all sub-features.
+    pub fn all() -> Self {
+        Self::new().chip(ANY)
+    }
+
+    /// Select the sub-features matching the given pattern, of the chips
+    /// matching the given pattern.
+    pub fn sensor(mut self, chip: impl Into<String>, sub_feature: impl Into<String>) -> Self {
+        self.rules.push(Rule {
+            chip: chip.into(),
+            sub_feature: sub_feature.into(),
+        });
+        self
+    }
+
+    /// Select all sub-features of the chips matching the given pattern.
+    pub fn chip(self, chip: impl Into<String>) -> Self {
+        self.sensor(chip, ANY)
+    }
+
+    /// Return the rules of this selector, in order.
+    #[must_use]
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Return `true` if this selector selects no sub-features.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Return `true` if this selector might select sub-features of
+    /// the given chip.
+    #[must_use]
+    pub fn matches_chip(&self, chip: &str) -> bool {
+        self.rules.iter().any(|rule| matches(&rule.chip, chip))
+    }
+
+    /// Return `true` if this selector selects the given sub-feature of
+    /// the given chip.
+    #[must_use]
+    pub fn matches(&self, chip: &str, sub_feature: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.matches(chip, sub_feature))
+    }
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .try_fold(Self::new(), |selector, rule| {
+                let (chip, sub_feature) = rule.rsplit_once('/').unwrap_or((rule, ANY));
+                if chip.is_empty() || sub_feature.is_empty() {
+                    let message = format!("invalid selector rule: '{rule}'");
+                    let err = io::Error::new(io::ErrorKind::InvalidInput, message);
+                    return Err(Error::from_io("Selector::from_str()", err));
+                }
+                Ok(selector.sensor(chip, sub_feature))
+            })
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{rule}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Return `true` if the given pattern matches the given name entirely.
+fn matches(pattern: &str, name: &str) -> bool {
+    if pattern == ANY {
+        return true;
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Position in the pattern following the last `*`, and position in
+    // the name matched by that `*`, to backtrack to.
+    let mut backtrack = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, n));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
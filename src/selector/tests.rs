@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use super::Selector;
+
+#[test]
+fn matches() {
+    use super::matches;
+
+    assert!(matches("*", ""));
+    assert!(matches("*", "temp1_input"));
+    assert!(matches("temp1_input", "temp1_input"));
+    assert!(!matches("temp1_input", "temp1_input2"));
+    assert!(!matches("temp1", "temp1_input"));
+    assert!(matches("temp*_input", "temp12_input"));
+    assert!(!matches("temp*_input", "temp1_max"));
+    assert!(matches("temp?_input", "temp1_input"));
+    assert!(!matches("temp?_input", "temp12_input"));
+    assert!(matches("*-isa-*", "coretemp-isa-0000"));
+    assert!(matches("a*b*c", "aXbYbZc"));
+    assert!(!matches("a*b*c", "aXbYbZ"));
+    assert!(matches("a**", "a"));
+    assert!(!matches("", "a"));
+}
+
+#[test]
+fn selector() {
+    let selector = Selector::new();
+    assert!(selector.is_empty());
+    assert!(!selector.matches_chip("coretemp-isa-0000"));
+    assert!(!selector.matches("coretemp-isa-0000", "temp1_input"));
+
+    let selector = Selector::all();
+    assert!(selector.matches("coretemp-isa-0000", "temp1_input"));
+
+    let selector = Selector::new()
+        .sensor("coretemp-isa-*", "temp1_input")
+        .chip("nct6775-*");
+    assert_eq!(selector.rules().len(), 2);
+    assert!(selector.matches_chip("coretemp-isa-0000"));
+    assert!(!selector.matches_chip("acpitz-acpi-0"));
+    assert!(selector.matches("coretemp-isa-0000", "temp1_input"));
+    assert!(!selector.matches("coretemp-isa-0000", "temp2_input"));
+    assert!(selector.matches("nct6775-isa-0290", "fan1_input"));
+}
+
+#[test]
+fn from_str() {
+    let selector: Selector = "coretemp-isa-*/temp1_input, nct6775-*,".parse().unwrap();
+    let expected = Selector::new()
+        .sensor("coretemp-isa-*", "temp1_input")
+        .chip("nct6775-*");
+    assert_eq!(selector, expected);
+    assert_eq!(
+        selector.to_string(),
+        "coretemp-isa-*/temp1_input,nct6775-*/*"
+    );
+    assert_eq!(selector.to_string().parse::<Selector>().unwrap(), selector);
+
+    // Chip names of aggregated backends hold separators.
+    let selector: Selector = "thermal/cpu_thermal-*/temp1_input".parse().unwrap();
+    assert!(selector.matches("thermal/cpu_thermal-thermal-0", "temp1_input"));
+
+    assert!("".parse::<Selector>().unwrap().is_empty());
+    for invalid in ["/temp1_input", "coretemp-*/"] {
+        let err = invalid.parse::<Selector>().unwrap_err();
+        assert_eq!(err.code(), "io");
+    }
+}
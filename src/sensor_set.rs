@@ -0,0 +1,227 @@
+//! Sets of sub-features owning the LM sensors library instance.
+//!
+//! Sub-feature references borrow the [`LMSensors`] instance they come from,
+//! so storing them next to that instance in a structure would make it
+//! self-referential. A [`SensorSet`] owns the instance instead, and the
+//! sub-features selected at construction are identified by [`Handle`]s,
+//! which are plain values that can be stored anywhere.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use std::io;
+
+use sensors_sys::{sensors_chip_name, sensors_feature, sensors_subfeature};
+
+use crate::errors::{Error, Result};
+use crate::feature::FeatureRef;
+use crate::selector::Selector;
+use crate::sub_feature::SubFeatureRef;
+use crate::{ChipRef, LMSensors, Value};
+
+/// Stable identifier of a sub-feature of a [`SensorSet`].
+///
+/// Handles are numbered in the order in which sub-features were selected,
+/// starting from zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle(usize);
+
+impl Handle {
+    /// Return the index of this handle.
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Selected sub-feature of a [`SensorSet`].
+struct Entry {
+    chip_name: String,
+    sub_feature_name: String,
+    chip: *const sensors_chip_name,
+    feature: *const sensors_feature,
+    sub_feature: *const sensors_subfeature,
+}
+
+/**
+Instance of the LM sensors library, with the sub-features selected at
+construction.
+
+# Example
+
+```rust
+use lm_sensors::sensor_set::{Handle, SensorSet};
+use lm_sensors::selector::Selector;
+
+struct Monitor {
+    sensors: SensorSet,
+    package: Option<Handle>,
+}
+
+let sensors = lm_sensors::Initializer::default().initialize()?;
+let selector: Selector = "coretemp-isa-0000/temp1_input".parse()?;
+let sensors = SensorSet::new(sensors, &selector);
+let package = sensors.handles().next();
+let monitor = Monitor { sensors, package };
+
+if let Some(package) = monitor.package {
+    println!("{:?}", monitor.sensors.read(package)?);
+}
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub struct SensorSet {
+    entries: Vec<Entry>,
+    sensors: LMSensors,
+}
+
+impl SensorSet {
+    /// Return a set of the sub-features selected by the given selector,
+    /// owning the given instance.
+    #[must_use]
+    pub fn new(sensors: LMSensors, selector: &Selector) -> Self {
+        let mut entries = Vec::default();
+        for chip in sensors.chip_iter(None) {
+            let chip_name = chip.to_string();
+            if !selector.matches_chip(&chip_name) {
+                continue;
+            }
+
+            for feature in chip.feature_iter() {
+                for sub_feature in feature.sub_feature_iter() {
+                    let name = sub_feature.raw_name().map(|name| name.to_string_lossy());
+                    let name = name.as_deref().unwrap_or_default();
+                    if !selector.matches(&chip_name, name) {
+                        continue;
+                    }
+
+                    entries.push(Entry {
+                        chip_name: chip_name.clone(),
+                        sub_feature_name: name.into(),
+                        chip: chip.as_raw_ptr(),
+                        feature: feature.as_raw_ptr(),
+                        sub_feature: sub_feature.as_raw_ptr(),
+                    });
+                }
+            }
+        }
+
+        Self { entries, sensors }
+    }
+
+    /// Return the owned instance of the LM sensors library.
+    #[must_use]
+    pub fn sensors(&self) -> &LMSensors {
+        &self.sensors
+    }
+
+    /// Return the owned instance of the LM sensors library,
+    /// forgetting the selected sub-features.
+    #[must_use]
+    pub fn into_inner(self) -> LMSensors {
+        self.sensors
+    }
+
+    /// Return the number of selected sub-features.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if no sub-features are selected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the handles of all selected sub-features, in order.
+    pub fn handles(&self) -> impl ExactSizeIterator<Item = Handle> {
+        (0..self.entries.len()).map(Handle)
+    }
+
+    /// Return the handle of the given selected sub-feature, *e.g.,*
+    /// `temp1_input`, of the given chip, *e.g.,* `coretemp-isa-0000`,
+    /// if any.
+    #[must_use]
+    pub fn handle(&self, chip: &str, sub_feature: &str) -> Option<Handle> {
+        self.entries
+            .iter()
+            .position(|e| e.chip_name == chip && e.sub_feature_name == sub_feature)
+            .map(Handle)
+    }
+
+    /// Return the names of the chip and of the sub-feature of the given
+    /// handle, if it belongs to this set.
+    #[must_use]
+    pub fn name(&self, handle: Handle) -> Option<(&str, &str)> {
+        let entry = self.entries.get(handle.0)?;
+        Some((&entry.chip_name, &entry.sub_feature_name))
+    }
+
+    /// Return the sub-feature of the given handle, if it belongs to this set.
+    #[must_use]
+    pub fn sub_feature(&self, handle: Handle) -> Option<SubFeatureRef<'_>> {
+        let entry = self.entries.get(handle.0)?;
+
+        // Safety: the pointers refer to data of the LM sensors library, which
+        // remains valid as long as the owned instance is neither dropped nor
+        // reinitialized, which requires consuming or borrowing this set
+        // exclusively.
+        let (chip, feature, sub_feature) =
+            unsafe { (&*entry.chip, &*entry.feature, &*entry.sub_feature) };
+
+        Some(SubFeatureRef {
+            feature: FeatureRef {
+                chip: ChipRef(chip),
+                raw: feature,
+            },
+            raw: sub_feature,
+        })
+    }
+
+    /// Return the value of the sub-feature of the given handle.
+    ///
+    /// This fails with [`io::ErrorKind::NotFound`] if the handle does not
+    /// belong to this set.
+    pub fn read(&self, handle: Handle) -> Result<Value> {
+        self.checked_sub_feature("SensorSet::read()", handle)?
+            .value()
+    }
+
+    /// Return the raw value of the sub-feature of the given handle.
+    ///
+    /// See: [`SensorSet::read`].
+    pub fn read_raw(&self, handle: Handle) -> Result<f64> {
+        self.checked_sub_feature("SensorSet::read_raw()", handle)?
+            .raw_value()
+    }
+
+    fn checked_sub_feature(
+        &self,
+        operation: &'static str,
+        handle: Handle,
+    ) -> Result<SubFeatureRef<'_>> {
+        self.sub_feature(handle)
+            .ok_or_else(|| Error::from_io(operation, io::ErrorKind::NotFound.into()))
+    }
+}
+
+impl fmt::Debug for SensorSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self
+            .entries
+            .iter()
+            .map(|e| (&e.chip_name, &e.sub_feature_name));
+        f.debug_struct("SensorSet")
+            .field("sub_features", &names.collect::<Vec<_>>())
+            .field("sensors", &self.sensors)
+            .finish()
+    }
+}
@@ -0,0 +1,49 @@
+#![cfg(test)]
+
+use std::io;
+
+use assert_matches::assert_matches;
+use serial_test::serial;
+
+use super::{Handle, SensorSet};
+use crate::errors::Error;
+use crate::selector::Selector;
+
+#[test]
+#[serial]
+fn all() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let readings = s.snapshot_where(&Selector::all()).readings;
+
+    let set = SensorSet::new(s, &Selector::all());
+    assert_eq!(set.len(), readings.len());
+    assert_eq!(set.handles().len(), readings.len());
+
+    for (handle, reading) in set.handles().zip(&readings) {
+        let name = (reading.chip.as_str(), reading.sub_feature.as_str());
+        assert_eq!(set.name(handle), Some(name));
+        assert_eq!(set.handle(name.0, name.1), Some(handle));
+
+        let sub_feature = set.sub_feature(handle).unwrap();
+        assert_eq!(sub_feature.raw_kind(), reading.raw_kind);
+        assert_eq!(set.read(handle).is_ok(), reading.raw_value.is_some());
+        assert_eq!(set.read_raw(handle).is_ok(), reading.raw_value.is_some());
+    }
+
+    let _sensors = set.into_inner();
+}
+
+#[test]
+#[serial]
+fn unknown_handle() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let set = SensorSet::new(s, &Selector::new());
+    assert!(set.is_empty());
+    assert_eq!(set.handle("coretemp-isa-0000", "temp1_input"), None);
+
+    let handle = Handle(0);
+    assert_eq!(handle.to_string(), "#0");
+    assert!(set.sub_feature(handle).is_none());
+    let err = set.read(handle).unwrap_err();
+    assert_matches!(err, Error::IO { source, .. } if source.kind() == io::ErrorKind::NotFound);
+}
@@ -0,0 +1,417 @@
+//! Instances of the LM sensors library shared between threads.
+//!
+//! [`ChipRef`]s, [`FeatureRef`]s and [`SubFeatureRef`]s borrow the
+//! [`LMSensors`] instance they come from, which cannot be sent to other
+//! threads. A [`SharedSensors`] instance is reference-counted instead, and
+//! its [`ArcChipRef`]s, [`ArcFeatureRef`]s and [`ArcSubFeatureRef`]s hold
+//! a strong reference to it, so that they can be sent to other threads,
+//! and stored in long-lived structures.
+//!
+//! Shared instances only read sensors. Writing requires the
+//! [`Capabilities`](crate::Capabilities) of an exclusively owned instance.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, Weak};
+
+use sensors_sys::{sensors_chip_name, sensors_feature, sensors_subfeature};
+
+use crate::errors::Result;
+use crate::feature::FeatureRef;
+use crate::snapshot::owned::ChipSnapshot;
+use crate::sub_feature::SubFeatureRef;
+use crate::{ChipRef, Initializer, LMSensors, Value};
+
+/// Longest wait for an attached instance that is being dropped in another
+/// thread, by [`SharedSensors::attach`], if the initializer does not
+/// specify any.
+const ATTACH_RELEASE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Instance of the LM sensors library that can be sent to other threads.
+///
+/// Fields are dropped in order, so the flag is set once the library
+/// instance is dropped.
+struct SendSensors(LMSensors, Released);
+
+// Safety: all calls into the LM sensors library are serialized by the API
+// access lock. The error listener of the instance is the default one, and
+// it has no audit sink, as they might not be usable from other threads.
+// See: `LMSensors::into_shared()`.
+unsafe impl Send for SendSensors {}
+// Safety: see above.
+unsafe impl Sync for SendSensors {}
+
+/// Flag set when dropped.
+#[derive(Default)]
+struct Released(Arc<AtomicBool>);
+
+impl Drop for Released {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// State of the instance shared by [`Initializer::try_initialize_or_attach`].
+struct Attached {
+    sensors: Weak<SendSensors>,
+    /// Set once the last attached instance is dropped,
+    /// or `None` if no instance was attached yet.
+    released: Option<Arc<AtomicBool>>,
+    /// Set while an instance is being initialized, outside the lock.
+    initializing: bool,
+}
+
+static ATTACHED: Mutex<Attached> = Mutex::new(Attached {
+    sensors: Weak::new(),
+    released: None,
+    initializing: false,
+});
+
+/// Notified when [`Attached::initializing`] is cleared.
+static ATTACHED_CHANGED: Condvar = Condvar::new();
+
+fn lock_attached() -> MutexGuard<'static, Attached> {
+    ATTACHED.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Clears [`Attached::initializing`] when dropped, even on panics.
+struct Initializing;
+
+impl Drop for Initializing {
+    fn drop(&mut self) {
+        lock_attached().initializing = false;
+        ATTACHED_CHANGED.notify_all();
+    }
+}
+
+/**
+Reference-counted instance of the LM sensors library, which can be shared
+between threads.
+
+See: [`LMSensors::into_shared`].
+
+# Example
+
+```rust
+use std::thread;
+
+let sensors = lm_sensors::Initializer::default().initialize()?.into_shared()?;
+
+let chips: Vec<_> = sensors.chip_iter().collect();
+let worker = thread::spawn(move || {
+    for chip in chips {
+        for feature in chip.feature_iter() {
+            for sub_feature in feature.sub_feature_iter() {
+                println!("{chip}: {feature}: {sub_feature}: {:?}", sub_feature.value());
+            }
+        }
+    }
+});
+worker.join().unwrap();
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Clone)]
+pub struct SharedSensors {
+    sensors: Arc<SendSensors>,
+}
+
+impl SharedSensors {
+    pub(crate) fn new(sensors: LMSensors) -> Self {
+        Self {
+            sensors: Arc::new(SendSensors(sensors, Released::default())),
+        }
+    }
+
+    /// Return the instance shared in this process, or share a new instance
+    /// returned by `initializer` if none exists.
+    ///
+    /// Initialization does not hold the lock of the shared instance.
+    /// Concurrent callers wait for it to complete, then attach to its result.
+    pub(crate) fn attach(initializer: Initializer) -> Result<Self> {
+        let mut attached = lock_attached();
+        while attached.initializing {
+            attached = ATTACHED_CHANGED
+                .wait(attached)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+
+        if let Some(sensors) = attached.sensors.upgrade() {
+            return Ok(Self { sensors });
+        }
+
+        // The last attached instance might still be dropping in another
+        // thread, which would make initialization fail spuriously.
+        let dropping = match &attached.released {
+            Some(released) => !released.load(Ordering::Acquire),
+            None => false,
+        };
+
+        attached.initializing = true;
+        drop(attached);
+        let initializing = Initializing;
+
+        let initializer = match initializer.wait_for_release {
+            None if dropping => initializer.wait_for_release(ATTACH_RELEASE_TIMEOUT),
+            _ => initializer,
+        };
+        let shared = initializer.initialize()?.into_shared()?;
+
+        let SendSensors(_, Released(released)) = &*shared.sensors;
+        let mut attached = lock_attached();
+        attached.sensors = Arc::downgrade(&shared.sensors);
+        attached.released = Some(Arc::clone(released));
+        drop(attached);
+
+        drop(initializing);
+        Ok(shared)
+    }
+
+    /// Returns the version of the LM sensors library,
+    /// if available and valid UTF-8.
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.sensors.0.version()
+    }
+
+    /// Return an iterator which yields all chips.
+    pub fn chip_iter(&self) -> impl Iterator<Item = ArcChipRef> + '_ {
+        self.sensors.0.chip_iter(None).map(|chip| ArcChipRef {
+            sensors: self.clone(),
+            raw: chip.as_raw_ptr(),
+        })
+    }
+
+    /// Return the chip of the given name, *e.g.,* `coretemp-isa-0000`,
+    /// if any.
+    #[must_use]
+    pub fn chip(&self, name: &str) -> Option<ArcChipRef> {
+        self.chip_iter().find(|chip| chip.to_string() == name)
+    }
+
+    /// Return owned snapshots of all chips.
+    ///
+    /// See: [`LMSensors::chip_snapshots`].
+    #[must_use]
+    pub fn chip_snapshots(&self) -> Vec<ChipSnapshot> {
+        self.sensors.0.chip_snapshots()
+    }
+
+    /// Return the number of strong references to this instance,
+    /// including references held by chips, features and sub-features.
+    #[must_use]
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.sensors)
+    }
+
+    /// Return `true` if both instances refer to the same library instance.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.sensors, &other.sensors)
+    }
+}
+
+impl fmt::Debug for SharedSensors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedSensors")
+            .field(&self.sensors.0)
+            .finish()
+    }
+}
+
+/// Chip of a [`SharedSensors`] instance, holding a strong reference to it.
+#[derive(Clone)]
+pub struct ArcChipRef {
+    sensors: SharedSensors,
+    raw: *const sensors_chip_name,
+}
+
+// Safety: the chip is owned by the LM sensors library, and remains valid and
+// unchanged as long as the shared instance is alive, as shared instances
+// cannot be reinitialized. Calls into the library are serialized by the API
+// access lock.
+unsafe impl Send for ArcChipRef {}
+// Safety: see above.
+unsafe impl Sync for ArcChipRef {}
+
+impl ArcChipRef {
+    /// Return a reference to this chip, borrowing this instance.
+    #[must_use]
+    pub fn as_ref(&self) -> ChipRef<'_> {
+        // Safety: see `impl Send for ArcChipRef`.
+        ChipRef(unsafe { &*self.raw })
+    }
+
+    /// Return the shared instance to which this chip belongs.
+    #[must_use]
+    pub fn sensors(&self) -> &SharedSensors {
+        &self.sensors
+    }
+
+    /// Return an iterator which yields all features of this chip.
+    pub fn feature_iter(&self) -> impl Iterator<Item = ArcFeatureRef> + '_ {
+        self.as_ref().feature_iter().map(|feature| ArcFeatureRef {
+            chip: self.clone(),
+            raw: feature.as_raw_ptr(),
+        })
+    }
+
+    /// Return the name of this chip, *e.g.,* `coretemp-isa-0000`.
+    pub fn name(&self) -> Result<String> {
+        self.as_ref().name()
+    }
+}
+
+impl PartialEq for ArcChipRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.sensors.ptr_eq(&other.sensors) && self.raw == other.raw
+    }
+}
+
+impl Eq for ArcChipRef {}
+
+impl fmt::Debug for ArcChipRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_ref(), f)
+    }
+}
+
+impl fmt::Display for ArcChipRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_ref(), f)
+    }
+}
+
+/// Feature of a [`SharedSensors`] instance, holding a strong reference to it.
+#[derive(Clone)]
+pub struct ArcFeatureRef {
+    chip: ArcChipRef,
+    raw: *const sensors_feature,
+}
+
+// Safety: see `impl Send for ArcChipRef`.
+unsafe impl Send for ArcFeatureRef {}
+// Safety: see `impl Send for ArcChipRef`.
+unsafe impl Sync for ArcFeatureRef {}
+
+impl ArcFeatureRef {
+    /// Return a reference to this feature, borrowing this instance.
+    #[must_use]
+    pub fn as_ref(&self) -> FeatureRef<'_> {
+        FeatureRef {
+            chip: self.chip.as_ref(),
+            // Safety: see `impl Send for ArcChipRef`.
+            raw: unsafe { &*self.raw },
+        }
+    }
+
+    /// Return the chip to which this feature belongs.
+    #[must_use]
+    pub fn chip(&self) -> &ArcChipRef {
+        &self.chip
+    }
+
+    /// Return an iterator which yields all sub-features of this feature.
+    pub fn sub_feature_iter(&self) -> impl Iterator<Item = ArcSubFeatureRef> + '_ {
+        self.as_ref()
+            .sub_feature_iter()
+            .map(|sub_feature| ArcSubFeatureRef {
+                feature: self.clone(),
+                raw: sub_feature.as_raw_ptr(),
+            })
+    }
+
+    /// Return the label of this feature.
+    pub fn label(&self) -> Result<String> {
+        self.as_ref().label()
+    }
+}
+
+impl PartialEq for ArcFeatureRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.chip == other.chip && self.raw == other.raw
+    }
+}
+
+impl Eq for ArcFeatureRef {}
+
+impl fmt::Debug for ArcFeatureRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_ref(), f)
+    }
+}
+
+impl fmt::Display for ArcFeatureRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_ref(), f)
+    }
+}
+
+/// Sub-feature of a [`SharedSensors`] instance, holding a strong reference
+/// to it.
+#[derive(Clone)]
+pub struct ArcSubFeatureRef {
+    feature: ArcFeatureRef,
+    raw: *const sensors_subfeature,
+}
+
+// Safety: see `impl Send for ArcChipRef`.
+unsafe impl Send for ArcSubFeatureRef {}
+// Safety: see `impl Send for ArcChipRef`.
+unsafe impl Sync for ArcSubFeatureRef {}
+
+impl ArcSubFeatureRef {
+    /// Return a reference to this sub-feature, borrowing this instance.
+    #[must_use]
+    pub fn as_ref(&self) -> SubFeatureRef<'_> {
+        SubFeatureRef {
+            feature: self.feature.as_ref(),
+            // Safety: see `impl Send for ArcChipRef`.
+            raw: unsafe { &*self.raw },
+        }
+    }
+
+    /// Return the feature to which this sub-feature belongs.
+    #[must_use]
+    pub fn feature(&self) -> &ArcFeatureRef {
+        &self.feature
+    }
+
+    /// Return the value of this sub-feature.
+    ///
+    /// See: [`SubFeatureRef::value`].
+    pub fn value(&self) -> Result<Value> {
+        self.as_ref().value()
+    }
+
+    /// Return the raw value of this sub-feature.
+    ///
+    /// See: [`SubFeatureRef::raw_value`].
+    pub fn raw_value(&self) -> Result<f64> {
+        self.as_ref().raw_value()
+    }
+}
+
+impl PartialEq for ArcSubFeatureRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.feature == other.feature && self.raw == other.raw
+    }
+}
+
+impl Eq for ArcSubFeatureRef {}
+
+impl fmt::Debug for ArcSubFeatureRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_ref(), f)
+    }
+}
+
+impl fmt::Display for ArcSubFeatureRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_ref(), f)
+    }
+}
@@ -0,0 +1,58 @@
+#![cfg(test)]
+
+use std::thread;
+
+use serial_test::serial;
+
+use super::{ArcSubFeatureRef, SharedSensors};
+
+fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+#[test]
+fn send_sync() {
+    assert_send_sync::<SharedSensors>();
+    assert_send_sync::<super::ArcChipRef>();
+    assert_send_sync::<super::ArcFeatureRef>();
+    assert_send_sync::<ArcSubFeatureRef>();
+}
+
+#[test]
+#[serial]
+fn shared() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let expected = s.snapshot_where(&crate::selector::Selector::all()).readings;
+
+    let s = s.into_shared().unwrap();
+    assert_eq!(s.strong_count(), 1);
+
+    let sub_features: Vec<ArcSubFeatureRef> = s
+        .chip_iter()
+        .flat_map(|chip| chip.feature_iter().collect::<Vec<_>>())
+        .flat_map(|feature| feature.sub_feature_iter().collect::<Vec<_>>())
+        .collect();
+    assert_eq!(sub_features.len(), expected.len());
+    assert_eq!(s.strong_count(), 1 + sub_features.len());
+
+    for chip in s.chip_iter() {
+        assert_eq!(s.chip(&chip.to_string()), Some(chip.clone()));
+        assert!(chip.sensors().ptr_eq(&s));
+    }
+
+    let worker = thread::spawn(move || {
+        sub_features
+            .iter()
+            .map(|sub_feature| (sub_feature.to_string(), sub_feature.raw_value().is_ok()))
+            .collect::<Vec<_>>()
+    });
+    let readings = worker.join().unwrap();
+
+    for ((name, readable), expected) in readings.iter().zip(&expected) {
+        assert_eq!(name, &expected.sub_feature);
+        assert_eq!(*readable, expected.raw_value.is_some());
+    }
+    assert_eq!(s.strong_count(), 1);
+
+    // The instance is dropped with its last reference.
+    drop(s);
+    drop(crate::Initializer::default().initialize().unwrap());
+}
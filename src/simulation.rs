@@ -0,0 +1,565 @@
+//! Offline simulation of fan control loops against recorded readings.
+//!
+//! A [`Simulation`] feeds temperatures replayed by a [`Replay`] backend,
+//! adjusted by a [`ThermalPlant`] model, to a [`ControlLoop`], and reports
+//! the PWM values that the loop would have driven. This validates fan curves
+//! and PID settings before touching hardware.
+//!
+//! A [`ThermalModel`] fitted from recorded PWM values and temperatures
+//! provides a plant model, and default PID settings.
+//!
+//! This module requires the `simulation` feature.
+
+#[cfg(test)]
+mod tests;
+
+use core::fmt;
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::io;
+
+use crate::backend::{Backend, Reading};
+use crate::errors::{Error, Result};
+
+/// Maximum PWM value, driving a fan at full speed.
+pub const PWM_MAX: f64 = 255.0;
+
+/// Controller of a fan, driven by a temperature.
+pub trait ControlLoop: fmt::Debug {
+    /// Return the PWM value to drive, from `0` to [`PWM_MAX`], given the
+    /// current temperature in degrees Celsius, and the time elapsed since
+    /// the previous update.
+    fn update(&mut self, temperature: f64, elapsed: Duration) -> f64;
+}
+
+impl<T: ControlLoop + ?Sized> ControlLoop for Box<T> {
+    fn update(&mut self, temperature: f64, elapsed: Duration) -> f64 {
+        (**self).update(temperature, elapsed)
+    }
+}
+
+/// Fan curve mapping temperatures to PWM values.
+///
+/// PWM values are linearly interpolated between points, and are constant
+/// below the first point and above the last one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanCurve {
+    points: Vec<(f64, f64)>,
+}
+
+impl FanCurve {
+    /// Return a curve going through the given points, each made of
+    /// a temperature in degrees Celsius and a PWM value.
+    ///
+    /// This fails if no points are given, if any value is not finite,
+    /// or if any PWM value is outside `0..=PWM_MAX`.
+    pub fn new(points: impl IntoIterator<Item = (f64, f64)>) -> Result<Self> {
+        let mut points: Vec<_> = points.into_iter().collect();
+
+        let valid = |&(temperature, pwm): &(f64, f64)| {
+            temperature.is_finite() && (0.0..=PWM_MAX).contains(&pwm)
+        };
+        if points.is_empty() || !points.iter().all(valid) {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io("FanCurve::new()", err));
+        }
+
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { points })
+    }
+
+    /// Return the points of this curve, sorted by temperature.
+    #[must_use]
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Return the PWM value for the given temperature.
+    #[must_use]
+    pub fn pwm(&self, temperature: f64) -> f64 {
+        let upper = self.points.partition_point(|&(t, _)| t <= temperature);
+        if upper == 0 {
+            return self.points[0].1;
+        }
+        if upper == self.points.len() {
+            return self.points[upper - 1].1;
+        }
+
+        let (t0, pwm0) = self.points[upper - 1];
+        let (t1, pwm1) = self.points[upper];
+        pwm0 + (pwm1 - pwm0) * (temperature - t0) / (t1 - t0)
+    }
+}
+
+impl ControlLoop for FanCurve {
+    fn update(&mut self, temperature: f64, _elapsed: Duration) -> f64 {
+        self.pwm(temperature)
+    }
+}
+
+/// Proportional-integral-derivative controller keeping a temperature
+/// near a target.
+///
+/// The PWM value increases when the temperature is above the target.
+/// The integral term stops accumulating while the output is saturated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pid {
+    target: f64,
+    gains: (f64, f64, f64),
+    integral: f64,
+    previous_error: Option<f64>,
+}
+
+impl Pid {
+    /// Return a controller keeping the temperature near `target` degrees
+    /// Celsius, with the given proportional, integral and derivative gains.
+    #[must_use]
+    pub fn new(target: f64, kp: f64, ki: f64, kd: f64) -> Self {
+        Self {
+            target,
+            gains: (kp, ki, kd),
+            integral: 0.0,
+            previous_error: None,
+        }
+    }
+
+    /// Return the target temperature, in degrees Celsius.
+    #[must_use]
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+}
+
+impl ControlLoop for Pid {
+    fn update(&mut self, temperature: f64, elapsed: Duration) -> f64 {
+        let (kp, ki, kd) = self.gains;
+        let error = temperature - self.target;
+        let dt = elapsed.as_secs_f64();
+
+        let derivative = match self.previous_error {
+            Some(previous) if dt > 0.0 => (error - previous) / dt,
+            _ => 0.0,
+        };
+        self.previous_error = Some(error);
+
+        let integral = self.integral + error * dt;
+        let output = kp * error + ki * integral + kd * derivative;
+        if (0.0..=PWM_MAX).contains(&output) {
+            self.integral = integral;
+        }
+        output.clamp(0.0, PWM_MAX)
+    }
+}
+
+/// Model of the effect of a fan on a temperature.
+pub trait ThermalPlant: fmt::Debug {
+    /// Return the simulated temperature, in degrees Celsius, given
+    /// the recorded temperature, the PWM value driven during the elapsed
+    /// time, and the elapsed time.
+    fn temperature(&mut self, recorded: f64, pwm: f64, elapsed: Duration) -> f64;
+}
+
+/// Plant ignoring the fan: simulated temperatures are the recorded ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OpenLoop;
+
+impl ThermalPlant for OpenLoop {
+    fn temperature(&mut self, recorded: f64, _pwm: f64, _elapsed: Duration) -> f64 {
+        recorded
+    }
+}
+
+/// First-order plant stub.
+///
+/// Driving the fan at a constant PWM value brings the temperature below
+/// the recorded one, by up to `cooling` degrees Celsius at [`PWM_MAX`],
+/// following an exponential approach of the given time constant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstOrder {
+    cooling: f64,
+    time_constant: Duration,
+    offset: f64,
+}
+
+impl FirstOrder {
+    /// Return a plant cooling by up to `cooling` degrees Celsius,
+    /// with the given time constant.
+    #[must_use]
+    pub fn new(cooling: f64, time_constant: Duration) -> Self {
+        Self {
+            cooling,
+            time_constant,
+            offset: 0.0,
+        }
+    }
+}
+
+impl ThermalPlant for FirstOrder {
+    fn temperature(&mut self, recorded: f64, pwm: f64, elapsed: Duration) -> f64 {
+        let steady_offset = -self.cooling * pwm / PWM_MAX;
+        let tau = self.time_constant.as_secs_f64();
+        let approach = if tau > 0.0 {
+            1.0 - (-elapsed.as_secs_f64() / tau).exp()
+        } else {
+            1.0
+        };
+
+        self.offset += (steady_offset - self.offset) * approach;
+        recorded + self.offset
+    }
+}
+
+/**
+First-order thermal response of a temperature to a PWM value.
+
+Driving a constant PWM value `u` brings the temperature to
+`offset + gain * u` degrees Celsius, following an exponential approach
+of time constant `time_constant`. The gain of a fan is negative.
+
+# Example
+
+```rust
+# fn f(history: &[(f64, f64)]) -> lm_sensors::errors::Result<()> {
+use core::time::Duration;
+use lm_sensors::simulation::ThermalModel;
+
+// PWM values and temperatures, recorded every second.
+let model = ThermalModel::fit(history.iter().copied(), Duration::from_secs(1))?;
+if let Some(pid) = model.pid(65.0) {
+    println!("{pid:?}");
+}
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalModel {
+    /// Change of the steady temperature per unit of PWM, in degrees Celsius.
+    pub gain: f64,
+    /// Time constant of the response.
+    pub time_constant: Duration,
+    /// Steady temperature with the fan stopped, in degrees Celsius.
+    pub offset: f64,
+}
+
+impl ThermalModel {
+    /// Fit a model to a history of PWM values and temperatures,
+    /// recorded every `interval`.
+    ///
+    /// Each sample is made of the PWM value driven from the time of the
+    /// sample until the next one, and the temperature in degrees Celsius
+    /// at the time of the sample.
+    ///
+    /// This fails if the history does not excite a first-order response,
+    /// *e.g.,* if it is too short, or if the PWM value is constant.
+    pub fn fit(samples: impl IntoIterator<Item = (f64, f64)>, interval: Duration) -> Result<Self> {
+        Self::fit_series(samples.into_iter().map(Some), interval)
+    }
+
+    /// Fit a model to the PWM values and temperatures reported by the given
+    /// sub-features, in sets of readings recorded every `interval`.
+    ///
+    /// Sub-features are identified by chip and sub-feature names.
+    /// Sets of readings lacking either value interrupt the history.
+    ///
+    /// See: [`ThermalModel::fit`].
+    pub fn fit_readings(
+        frames: &[Vec<Reading>],
+        interval: Duration,
+        pwm: (&str, &str),
+        temperature: (&str, &str),
+    ) -> Result<Self> {
+        let value = |readings: &[Reading], (chip, sub_feature): (&str, &str)| {
+            readings
+                .iter()
+                .find(|r| r.chip == chip && r.sub_feature == sub_feature)
+                .and_then(|r| r.raw_value)
+        };
+
+        let samples = frames
+            .iter()
+            .map(|readings| value(readings, pwm).zip(value(readings, temperature)));
+        Self::fit_series(samples, interval)
+    }
+
+    /// Fit `t[k+1] = a * t[k] + b * u[k] + c` by least squares.
+    fn fit_series(
+        samples: impl Iterator<Item = Option<(f64, f64)>>,
+        interval: Duration,
+    ) -> Result<Self> {
+        // Normal equations of the regression over (t[k], u[k], 1).
+        let mut m = [[0.0_f64; 3]; 3];
+        let mut v = [0.0_f64; 3];
+        let mut previous: Option<(f64, f64)> = None;
+
+        for sample in samples {
+            if let (Some((u, t)), Some((_, next_t))) = (previous, sample) {
+                let x = [t, u, 1.0];
+                for i in 0..3 {
+                    for j in 0..3 {
+                        m[i][j] += x[i] * x[j];
+                    }
+                    v[i] += x[i] * next_t;
+                }
+            }
+            previous = sample;
+        }
+
+        let invalid = || {
+            let err = io::ErrorKind::InvalidData.into();
+            Error::from_io("ThermalModel::fit()", err)
+        };
+
+        let [a, b, c] = solve_3x3(m, v).ok_or_else(invalid)?;
+        if !(a > 0.0 && a < 1.0) || interval.is_zero() {
+            return Err(invalid());
+        }
+
+        let time_constant = -interval.as_secs_f64() / a.ln();
+        Ok(Self {
+            gain: b / (1.0 - a),
+            time_constant: Duration::try_from_secs_f64(time_constant).map_err(|_| invalid())?,
+            offset: c / (1.0 - a),
+        })
+    }
+
+    /// Return the steady temperature when driving the given PWM value,
+    /// in degrees Celsius.
+    #[must_use]
+    pub fn steady_temperature(&self, pwm: f64) -> f64 {
+        self.offset + self.gain * pwm
+    }
+
+    /// Return a plant model of this response, relative to recorded
+    /// temperatures.
+    #[must_use]
+    pub fn plant(&self) -> FirstOrder {
+        FirstOrder::new(-self.gain * PWM_MAX, self.time_constant)
+    }
+
+    /// Return default PID settings keeping the temperature near `target`
+    /// degrees Celsius, with a closed loop as fast as the open loop.
+    ///
+    /// This returns `None` if the PWM value does not lower the temperature.
+    #[must_use]
+    pub fn pid(&self, target: f64) -> Option<Pid> {
+        // Lambda tuning of a first-order response, with lambda = tau.
+        let tau = self.time_constant.as_secs_f64();
+        if self.gain >= 0.0 || tau <= 0.0 {
+            return None;
+        }
+
+        let kp = -1.0 / self.gain;
+        Some(Pid::new(target, kp, kp / tau, 0.0))
+    }
+}
+
+/// Solve `m * x = v` by Cramer's rule.
+fn solve_3x3(m: [[f64; 3]; 3], v: [f64; 3]) -> Option<[f64; 3]> {
+    fn det(m: [[f64; 3]; 3]) -> f64 {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    let d = det(m);
+    let scale: f64 = m.iter().flatten().map(|x| x.abs()).sum();
+    if !d.is_normal() || d.abs() <= f64::EPSILON * scale.powi(3) {
+        return None;
+    }
+
+    let mut x = [0.0; 3];
+    for (k, x) in x.iter_mut().enumerate() {
+        let mut mk = m;
+        for (row, &value) in mk.iter_mut().zip(&v) {
+            row[k] = value;
+        }
+        *x = det(mk) / d;
+    }
+    x.iter().all(|x| x.is_finite()).then_some(x)
+}
+
+/// Backend replaying recorded readings.
+///
+/// Each call to [`Backend::read_all`] returns the next recorded set of
+/// readings, and fails with [`io::ErrorKind::UnexpectedEof`] once all sets
+/// were returned.
+#[derive(Debug, Default, Clone)]
+#[must_use]
+pub struct Replay {
+    frames: VecDeque<Vec<Reading>>,
+}
+
+impl Replay {
+    /// Return a backend replaying the given sets of readings, in order.
+    pub fn new(frames: impl IntoIterator<Item = Vec<Reading>>) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+        }
+    }
+
+    /// Return the number of sets of readings not replayed yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl Backend for Replay {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        self.frames.pop_front().ok_or_else(|| {
+            let err = io::ErrorKind::UnexpectedEof.into();
+            Error::from_io("Replay::read_all()", err)
+        })
+    }
+}
+
+/// Simulated step of a [`Simulation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step {
+    /// Time since the start of the simulation.
+    pub time: Duration,
+    /// Recorded temperature, in degrees Celsius.
+    pub recorded: f64,
+    /// Simulated temperature, in degrees Celsius.
+    pub temperature: f64,
+    /// PWM value that the control loop would have driven.
+    pub pwm: f64,
+}
+
+/// Result of a [`Simulation`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Report {
+    /// Simulated steps, in order.
+    pub steps: Vec<Step>,
+    /// Number of replayed sets of readings lacking the simulated temperature.
+    pub missing: usize,
+}
+
+impl Report {
+    /// Return the highest PWM value driven, if any.
+    #[must_use]
+    pub fn max_pwm(&self) -> Option<f64> {
+        self.steps.iter().map(|step| step.pwm).reduce(f64::max)
+    }
+
+    /// Return the average PWM value driven, if any.
+    #[must_use]
+    pub fn mean_pwm(&self) -> Option<f64> {
+        let sum: f64 = self.steps.iter().map(|step| step.pwm).sum();
+        (!self.steps.is_empty()).then(|| sum / self.steps.len() as f64)
+    }
+
+    /// Return the highest simulated temperature, if any.
+    #[must_use]
+    pub fn max_temperature(&self) -> Option<f64> {
+        self.steps
+            .iter()
+            .map(|step| step.temperature)
+            .reduce(f64::max)
+    }
+}
+
+/**
+Simulation of a control loop driven by one replayed temperature.
+
+# Example
+
+```rust
+use core::time::Duration;
+use lm_sensors::backend::Reading;
+use lm_sensors::simulation::{FanCurve, FirstOrder, Replay, Simulation};
+
+let reading = |value| Reading {
+    chip: "coretemp-isa-0000".into(),
+    feature: "temp1".into(),
+    sub_feature: "temp1_input".into(),
+    raw_kind: 0x200,
+    raw_value: Some(value),
+};
+let mut replay = Replay::new([40.0, 60.0, 80.0].map(|t| vec![reading(t)]));
+
+let mut curve = FanCurve::new([(40.0, 60.0), (80.0, 255.0)])?;
+let plant = FirstOrder::new(10.0, Duration::from_secs(30));
+let interval = Duration::from_secs(1);
+let mut simulation = Simulation::new("coretemp-isa-0000", "temp1_input", interval, plant);
+
+let report = simulation.run(&mut replay, &mut curve);
+assert_eq!(report.steps.len(), 3);
+println!("highest PWM: {:?}", report.max_pwm());
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Debug, Clone)]
+pub struct Simulation<P> {
+    chip: String,
+    sub_feature: String,
+    interval: Duration,
+    plant: P,
+}
+
+impl<P: ThermalPlant> Simulation<P> {
+    /// Return a simulation driven by the temperature reported by the given
+    /// sub-feature of the given chip, in replayed readings recorded every
+    /// `interval`.
+    pub fn new(
+        chip: impl Into<String>,
+        sub_feature: impl Into<String>,
+        interval: Duration,
+        plant: P,
+    ) -> Self {
+        Self {
+            chip: chip.into(),
+            sub_feature: sub_feature.into(),
+            interval,
+            plant,
+        }
+    }
+
+    /// Return the plant model of this simulation.
+    pub fn plant(&self) -> &P {
+        &self.plant
+    }
+
+    /// Run the control loop against all remaining readings of `replay`.
+    ///
+    /// Sets of readings lacking the temperature are counted in
+    /// [`Report::missing`], and only advance time.
+    pub fn run(&mut self, replay: &mut Replay, control: &mut dyn ControlLoop) -> Report {
+        let mut report = Report::default();
+        let mut time = Duration::ZERO;
+        let mut previous: Option<(Duration, f64)> = None;
+
+        while let Ok(readings) = replay.read_all() {
+            let recorded = readings
+                .iter()
+                .find(|r| r.chip == self.chip && r.sub_feature == self.sub_feature)
+                .and_then(|r| r.raw_value);
+
+            if let Some(recorded) = recorded {
+                let (elapsed, pwm) = previous.map_or((Duration::ZERO, 0.0), |(t, pwm)| {
+                    (time.saturating_sub(t), pwm)
+                });
+
+                let temperature = self.plant.temperature(recorded, pwm, elapsed);
+                let pwm = control.update(temperature, elapsed);
+                report.steps.push(Step {
+                    time,
+                    recorded,
+                    temperature,
+                    pwm,
+                });
+                previous = Some((time, pwm));
+            } else {
+                report.missing += 1;
+            }
+
+            time += self.interval;
+        }
+        report
+    }
+}
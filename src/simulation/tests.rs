@@ -0,0 +1,225 @@
+#![cfg(test)]
+
+use core::time::Duration;
+
+use crate::backend::{Backend, Reading};
+use crate::simulation::{
+    ControlLoop, FanCurve, FirstOrder, OpenLoop, Pid, Replay, Simulation, ThermalModel,
+    ThermalPlant, PWM_MAX,
+};
+use crate::value::Kind;
+
+const CHIP: &str = "coretemp-isa-0000";
+
+fn temperature(raw_value: Option<f64>) -> Reading {
+    Reading::for_test(CHIP, "temp1_input", Kind::TemperatureInput, raw_value)
+}
+
+#[test]
+fn fan_curve() {
+    let curve = FanCurve::new([(80.0, 255.0), (40.0, 50.0), (60.0, 100.0)]).unwrap();
+    assert_eq!(curve.points(), [(40.0, 50.0), (60.0, 100.0), (80.0, 255.0)]);
+
+    assert_eq!(curve.pwm(20.0), 50.0);
+    assert_eq!(curve.pwm(40.0), 50.0);
+    assert_eq!(curve.pwm(50.0), 75.0);
+    assert_eq!(curve.pwm(60.0), 100.0);
+    assert_eq!(curve.pwm(90.0), 255.0);
+
+    FanCurve::new([]).unwrap_err();
+    FanCurve::new([(f64::NAN, 50.0)]).unwrap_err();
+    FanCurve::new([(40.0, 300.0)]).unwrap_err();
+    FanCurve::new([(40.0, -1.0)]).unwrap_err();
+}
+
+#[test]
+fn pid() {
+    let second = Duration::from_secs(1);
+
+    let mut pid = Pid::new(60.0, 10.0, 0.0, 0.0);
+    assert_eq!(pid.target(), 60.0);
+    assert_eq!(pid.update(50.0, second), 0.0);
+    assert_eq!(pid.update(65.0, second), 50.0);
+    assert_eq!(pid.update(100.0, second), PWM_MAX);
+
+    let mut pid = Pid::new(60.0, 0.0, 1.0, 0.0);
+    assert_eq!(pid.update(70.0, Duration::ZERO), 0.0);
+    assert_eq!(pid.update(70.0, second), 10.0);
+    assert_eq!(pid.update(70.0, second), 20.0);
+
+    // The integral does not accumulate while saturated.
+    assert_eq!(pid.update(1000.0, second), PWM_MAX);
+    assert_eq!(pid.update(70.0, second), 30.0);
+
+    let mut pid = Pid::new(60.0, 0.0, 0.0, 2.0);
+    assert_eq!(pid.update(60.0, second), 0.0);
+    assert_eq!(pid.update(65.0, second), 10.0);
+}
+
+#[test]
+fn plants() {
+    let second = Duration::from_secs(1);
+    assert_eq!(OpenLoop.temperature(50.0, PWM_MAX, second), 50.0);
+
+    let mut plant = FirstOrder::new(10.0, Duration::from_secs(2));
+    assert_eq!(plant.temperature(50.0, PWM_MAX, Duration::ZERO), 50.0);
+
+    let t1 = plant.temperature(50.0, PWM_MAX, second);
+    let expected = 50.0 - 10.0 * (1.0 - (-0.5_f64).exp());
+    assert!((t1 - expected).abs() < 1e-9);
+
+    for _ in 0..100 {
+        plant.temperature(50.0, PWM_MAX, second);
+    }
+    assert!((plant.temperature(50.0, PWM_MAX, second) - 40.0).abs() < 1e-9);
+
+    let mut plant = FirstOrder::new(10.0, Duration::ZERO);
+    assert_eq!(plant.temperature(50.0, PWM_MAX / 2.0, second), 45.0);
+}
+
+#[test]
+fn replay() {
+    let mut replay = Replay::new([vec![temperature(Some(40.0))], vec![]]);
+    assert_eq!(replay.name(), "replay");
+    assert_eq!(replay.remaining(), 2);
+
+    assert_eq!(replay.read_all().unwrap().len(), 1);
+    assert!(replay.read_all().unwrap().is_empty());
+    assert_eq!(replay.remaining(), 0);
+    replay.read_all().unwrap_err();
+}
+
+#[test]
+fn simulation() {
+    let frames = [
+        vec![temperature(Some(40.0))],
+        vec![Reading::for_test(
+            CHIP,
+            "temp1_max",
+            Kind::TemperatureMaximum,
+            Some(90.0),
+        )],
+        vec![temperature(None)],
+        vec![temperature(Some(80.0))],
+        vec![temperature(Some(60.0))],
+    ];
+    let mut replay = Replay::new(frames);
+    let mut curve = FanCurve::new([(40.0, 0.0), (80.0, 255.0)]).unwrap();
+    let interval = Duration::from_secs(1);
+    let mut simulation = Simulation::new("coretemp-isa-0000", "temp1_input", interval, OpenLoop);
+
+    let report = simulation.run(&mut replay, &mut curve);
+    assert_eq!(replay.remaining(), 0);
+    assert_eq!(report.missing, 2);
+    assert_eq!(report.steps.len(), 3);
+
+    let times: Vec<_> = report
+        .steps
+        .iter()
+        .map(|step| step.time.as_secs())
+        .collect();
+    assert_eq!(times, [0, 3, 4]);
+    let pwm: Vec<_> = report.steps.iter().map(|step| step.pwm).collect();
+    assert_eq!(pwm, [0.0, 255.0, 127.5]);
+
+    assert_eq!(report.max_pwm(), Some(255.0));
+    assert_eq!(report.mean_pwm(), Some(127.5));
+    assert_eq!(report.max_temperature(), Some(80.0));
+
+    // The plant sees the PWM value driven during the elapsed time.
+    let mut replay = Replay::new([40.0, 80.0, 80.0].map(|t| vec![temperature(Some(t))]));
+    let plant = FirstOrder::new(10.0, Duration::ZERO);
+    let mut simulation = Simulation::new("coretemp-isa-0000", "temp1_input", interval, plant);
+    let report = simulation.run(&mut replay, &mut curve);
+    let temperatures: Vec<_> = report.steps.iter().map(|s| s.temperature).collect();
+    assert_eq!(temperatures, [40.0, 80.0, 70.0]);
+
+    let report = simulation.run(&mut Replay::default(), &mut curve);
+    assert!(report.steps.is_empty());
+    assert_eq!(report.max_pwm(), None);
+    assert_eq!(report.mean_pwm(), None);
+}
+
+/// Return a history of a first-order response to a varying PWM value.
+fn history(model: &ThermalModel, interval: Duration, len: usize) -> Vec<(f64, f64)> {
+    let a = (-interval.as_secs_f64() / model.time_constant.as_secs_f64()).exp();
+    let mut temperature = model.offset;
+    (0..len)
+        .map(|k| {
+            let pwm = [0.0, 255.0, 128.0, 64.0][(k / 20) % 4];
+            let sample = (pwm, temperature);
+            temperature = a * temperature + (1.0 - a) * model.steady_temperature(pwm);
+            sample
+        })
+        .collect()
+}
+
+#[test]
+fn thermal_model() {
+    let interval = Duration::from_secs(1);
+    let model = ThermalModel {
+        gain: -0.1,
+        time_constant: Duration::from_secs(10),
+        offset: 70.0,
+    };
+    assert_eq!(model.steady_temperature(100.0), 60.0);
+
+    let fitted = ThermalModel::fit(history(&model, interval, 200), interval).unwrap();
+    assert!((fitted.gain - model.gain).abs() < 1e-6);
+    assert!((fitted.offset - model.offset).abs() < 1e-6);
+    let tau = fitted.time_constant.as_secs_f64();
+    assert!((tau - 10.0).abs() < 1e-6);
+
+    let pid = fitted.pid(60.0).unwrap();
+    assert_eq!(pid.target(), 60.0);
+    assert_eq!(
+        fitted.plant(),
+        FirstOrder::new(-fitted.gain * PWM_MAX, fitted.time_constant)
+    );
+
+    let heating = ThermalModel { gain: 0.1, ..model };
+    assert_eq!(heating.pid(60.0), None);
+
+    // A constant PWM value cannot identify the gain.
+    let constant = history(&model, interval, 200).into_iter().take(20);
+    ThermalModel::fit(constant, interval).unwrap_err();
+    ThermalModel::fit([], interval).unwrap_err();
+    ThermalModel::fit(history(&model, interval, 200), Duration::ZERO).unwrap_err();
+}
+
+#[test]
+fn thermal_model_readings() {
+    let interval = Duration::from_secs(2);
+    let model = ThermalModel {
+        gain: -0.05,
+        time_constant: Duration::from_secs(30),
+        offset: 80.0,
+    };
+
+    let pwm = |value| Reading {
+        chip: "nct6775-isa-0290".into(),
+        feature: "pwm1".into(),
+        sub_feature: "pwm1".into(),
+        raw_kind: 0x10000,
+        raw_value: Some(value),
+    };
+    let mut frames: Vec<_> = history(&model, interval, 200)
+        .into_iter()
+        .map(|(u, t)| vec![pwm(u), temperature(Some(t))])
+        .collect();
+
+    // Missing values interrupt the history.
+    frames[50][1].raw_value = None;
+    frames[51].clear();
+
+    let fitted = ThermalModel::fit_readings(
+        &frames,
+        interval,
+        ("nct6775-isa-0290", "pwm1"),
+        ("coretemp-isa-0000", "temp1_input"),
+    )
+    .unwrap();
+    assert!((fitted.gain - model.gain).abs() < 1e-6);
+    assert!((fitted.offset - model.offset).abs() < 1e-6);
+    assert!((fitted.time_constant.as_secs_f64() - 30.0).abs() < 1e-6);
+}
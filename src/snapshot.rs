@@ -0,0 +1,419 @@
+//! Owned snapshots of the readings of all sub-features.
+//!
+//! [`Snapshot::read`] reads all values eagerly, while `Snapshot::lazy`
+//! reads values only when they are first accessed. [`owned`] snapshots
+//! keep the structure of chips along with their values.
+//!
+//! # Schema versions
+//!
+//! With the `serde` feature, snapshots are serialized along with
+//! [`SCHEMA_VERSION`], so that recorded snapshots remain readable across
+//! upgrades of this crate:
+//!
+//! - Fields might be added without changing the schema version.
+//!   Unknown fields are ignored when deserializing.
+//! - The schema version increases when a field is removed, renamed,
+//!   or its meaning changes.
+//! - Snapshots of all schema versions up to [`SCHEMA_VERSION`] are
+//!   deserialized. Newer snapshots are rejected with
+//!   [`Error::UnsupportedSchemaVersion`].
+//!
+//! # Fixtures
+//!
+//! [`Snapshot::to_fixture`] renders a snapshot as deterministic text,
+//! suitable for golden files. Each reading is rendered on its own line,
+//! as its chip, feature and sub-feature names, its raw type, then its raw
+//! value with [`FIXTURE_PRECISION`] decimals, or `-` if it could not be read.
+//! Lines are sorted, and the time of the snapshot is not rendered:
+//!
+//! ```text
+//! coretemp-isa-0000 temp1 temp1_input 512 45.000
+//! coretemp-isa-0000 temp1 temp1_max 513 80.000
+//! ```
+//!
+//! [`Snapshot::from_fixture`] loads readings from such a fixture, *e.g.,*
+//! to be replayed by the `simulation::Replay` backend, and
+//! [`assert_snapshot_eq!`] compares a snapshot against such a fixture.
+//!
+//! [`assert_snapshot_eq!`]: crate::assert_snapshot_eq
+
+#[cfg(target_os = "linux")]
+pub mod lazy;
+pub mod owned;
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+use crate::backend::{Backend, Reading};
+use crate::clock::{Clock, Monotonic};
+use crate::errors::{Error, Result};
+use crate::naming::NameTemplate;
+
+/// Number of decimals of values rendered in [fixtures](self#fixtures).
+pub const FIXTURE_PRECISION: usize = 3;
+
+/// Environment variable which, when set, makes [`assert_snapshot_eq!`]
+/// overwrite fixture files by the actual snapshots instead of failing.
+///
+/// [`assert_snapshot_eq!`]: crate::assert_snapshot_eq
+pub const UPDATE_FIXTURES_VAR: &str = "LM_SENSORS_UPDATE_FIXTURES";
+
+/// Version of the schema of serialized snapshots.
+///
+/// See: [Schema versions](self#schema-versions).
+#[cfg(feature = "serde")]
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Readings of all sub-features of all chips, taken at some time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "SnapshotRecord", try_from = "SnapshotRecord")
+)]
+pub struct Snapshot {
+    /// Time at which the readings were taken.
+    pub time: SystemTime,
+    /// Readings, grouped by chip and by feature.
+    pub readings: Vec<Reading>,
+}
+
+impl Snapshot {
+    /// Return a snapshot of the given readings, taken now.
+    #[must_use]
+    pub fn new(readings: Vec<Reading>) -> Self {
+        Self {
+            time: SystemTime::now(),
+            readings,
+        }
+    }
+
+    /// Return a snapshot of all readings of the given backend.
+    pub fn read(backend: &mut dyn Backend) -> Result<Self> {
+        Self::read_with_clock(backend, &Monotonic::new())
+    }
+
+    /// Return a snapshot of all readings of the given backend,
+    /// time-stamped by the given clock.
+    pub fn read_with_clock(backend: &mut dyn Backend, clock: &dyn Clock) -> Result<Self> {
+        let time = clock.system_time();
+        let readings = backend.read_all()?;
+        Ok(Self { time, readings })
+    }
+
+    /**
+    Return a snapshot of the structure of all chips known to the given
+    LM sensors library, reading values only when they are first accessed.
+
+    [`Snapshot::read`] reads all values eagerly instead.
+
+    # Example
+
+    ```rust
+    use lm_sensors::snapshot::Snapshot;
+
+    let sensors = lm_sensors::Initializer::default().initialize()?;
+    let snapshot = Snapshot::lazy(&sensors);
+    for chip in snapshot.chips() {
+        println!("{}: {} features", chip.name(), chip.features().len());
+    }
+    assert_eq!(snapshot.read_count(), 0);
+    # Ok::<(), lm_sensors::errors::Error>(())
+    ```
+    */
+    #[cfg(target_os = "linux")]
+    #[must_use]
+    pub fn lazy(sensors: &crate::LMSensors) -> lazy::LazySnapshot<'_> {
+        lazy::LazySnapshot::new(sensors)
+    }
+
+    /// Return an iterator yielding the readings of each feature,
+    /// in order.
+    ///
+    /// Consecutive readings of the same chip and feature are yielded
+    /// together.
+    pub fn features(&self) -> impl Iterator<Item = &[Reading]> {
+        self.readings
+            .chunk_by(|a, b| a.chip == b.chip && a.feature == b.feature)
+    }
+
+    /// Return an iterator yielding the readings, in order, along with
+    /// the names of their features given by the given template.
+    ///
+    /// See: [`NameTemplate::render_reading`].
+    pub fn named<'a>(
+        &'a self,
+        template: &'a NameTemplate,
+    ) -> impl Iterator<Item = (String, &'a Reading)> + 'a {
+        let readings = self.readings.iter();
+        readings.map(|reading| (template.render_reading(reading), reading))
+    }
+}
+
+impl Snapshot {
+    /// Return the [fixture](self#fixtures) rendering this snapshot.
+    #[must_use]
+    pub fn to_fixture(&self) -> String {
+        let mut lines: Vec<_> = self.readings.iter().map(fixture_line).collect();
+        lines.sort_unstable();
+
+        let mut result = String::default();
+        for line in lines {
+            let _infallible = writeln!(result, "{line}");
+        }
+        result
+    }
+
+    /// Return the snapshot rendered by the given [fixture](self#fixtures),
+    /// taken at the Unix epoch.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn from_fixture(fixture: &str) -> Result<Self> {
+        let readings = fixture
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                parse_fixture_line(line).ok_or_else(|| {
+                    let message = format!("invalid fixture line: '{line}'");
+                    let err = io::Error::new(io::ErrorKind::InvalidData, message);
+                    Error::from_io("Snapshot::from_fixture()", err)
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            time: UNIX_EPOCH,
+            readings,
+        })
+    }
+}
+
+fn fixture_line(reading: &Reading) -> String {
+    let value = match reading.raw_value {
+        None => "-".into(),
+        Some(value) => {
+            let value = format!("{value:.FIXTURE_PRECISION$}");
+            // Values rounded to zero are rendered without a sign.
+            match value.strip_prefix('-') {
+                Some(abs) if abs.bytes().all(|b| b == b'0' || b == b'.') => abs.into(),
+                _ => value,
+            }
+        }
+    };
+
+    format!(
+        "{} {} {} {} {value}",
+        reading.chip, reading.feature, reading.sub_feature, reading.raw_kind
+    )
+}
+
+fn parse_fixture_line(line: &str) -> Option<Reading> {
+    let mut fields = line.split_whitespace();
+    let reading = Reading {
+        chip: fields.next()?.into(),
+        feature: fields.next()?.into(),
+        sub_feature: fields.next()?.into(),
+        raw_kind: fields.next()?.parse().ok()?,
+        raw_value: match fields.next()? {
+            "-" => None,
+            value => Some(value.parse().ok()?),
+        },
+    };
+    fields.next().is_none().then_some(reading)
+}
+
+/// Return a description of the differences between the given snapshot
+/// and the given [fixture](self#fixtures), if any.
+///
+/// Lines of the expected fixture missing from the actual one are prefixed
+/// by `-`, and unexpected lines of the actual fixture are prefixed by `+`.
+pub fn fixture_mismatch(actual: &Snapshot, expected: &str) -> Option<String> {
+    let expected = match Snapshot::from_fixture(expected) {
+        Ok(expected) => expected.to_fixture(),
+        Err(err) => return Some(format!("invalid expected fixture: {err}")),
+    };
+
+    let actual = actual.to_fixture();
+    if actual == expected {
+        return None;
+    }
+
+    let mut result = String::from("snapshot differs from fixture:\n");
+    let mut actual = actual.lines().peekable();
+    let mut expected = expected.lines().peekable();
+    loop {
+        let (sign, line) = match (expected.peek(), actual.peek()) {
+            (None, None) => break,
+            (Some(e), Some(a)) if e == a => {
+                expected.next();
+                actual.next();
+                continue;
+            }
+            (Some(e), Some(a)) if e < a => ('-', expected.next()),
+            (Some(_), None) => ('-', expected.next()),
+            (_, Some(_)) => ('+', actual.next()),
+        };
+        let _infallible = writeln!(result, "{sign} {}", line.unwrap_or_default());
+    }
+    Some(result)
+}
+
+/// Return a description of the differences between the given snapshot
+/// and the [fixture](self#fixtures) stored in the given file, if any.
+///
+/// If the [`UPDATE_FIXTURES_VAR`] environment variable is set, then the
+/// file is overwritten by the fixture of the given snapshot instead.
+pub fn fixture_file_mismatch(actual: &Snapshot, path: impl AsRef<Path>) -> Option<String> {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_FIXTURES_VAR).is_some() {
+        return fs::write(path, actual.to_fixture())
+            .err()
+            .map(|err| format!("failed to write '{}': {err}", path.display()));
+    }
+
+    match fs::read_to_string(path) {
+        Ok(expected) => fixture_mismatch(actual, &expected)
+            .map(|mismatch| format!("{}: {mismatch}", path.display())),
+
+        Err(err) => Some(format!(
+            "failed to read '{}': {err} (set {UPDATE_FIXTURES_VAR} to create it)",
+            path.display()
+        )),
+    }
+}
+
+/**
+Assert that a snapshot matches a [fixture](snapshot#fixtures).
+
+The expected fixture is given either as text, or as the path of a file
+holding it, following `path =`. Expected fixtures are normalized before
+comparison, so their lines need not be sorted, and their values may have
+any precision.
+
+On mismatch, this panics with the differing lines.
+See: [`snapshot::fixture_mismatch`], [`snapshot::fixture_file_mismatch`].
+
+# Example
+
+```rust
+use lm_sensors::assert_snapshot_eq;
+use lm_sensors::snapshot::Snapshot;
+
+let mut snapshot = Snapshot::from_fixture(
+    "coretemp-isa-0000 temp1 temp1_input 512 45.0
+     coretemp-isa-0000 temp1 temp1_max 513 80.0",
+)?;
+snapshot.readings.retain(|reading| reading.sub_feature.ends_with("_input"));
+
+assert_snapshot_eq!(snapshot, "coretemp-isa-0000 temp1 temp1_input 512 45.000");
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+
+[`snapshot::fixture_mismatch`]: crate::snapshot::fixture_mismatch
+[`snapshot::fixture_file_mismatch`]: crate::snapshot::fixture_file_mismatch
+*/
+#[macro_export]
+macro_rules! assert_snapshot_eq {
+    ($actual:expr, path = $path:expr $(,)?) => {
+        if let Some(mismatch) = $crate::snapshot::fixture_file_mismatch(&$actual, $path) {
+            panic!("{mismatch}");
+        }
+    };
+
+    ($actual:expr, $expected:expr $(,)?) => {
+        if let Some(mismatch) = $crate::snapshot::fixture_mismatch(&$actual, $expected) {
+            panic!("{mismatch}");
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+impl Snapshot {
+    /// Return this snapshot serialized as JSON, with a schema version.
+    ///
+    /// Readings with non-finite values are serialized as unreadable.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|r| Error::from_io("serde_json::to_string()", r.into()))
+    }
+
+    /// Return the snapshot serialized as the given JSON.
+    ///
+    /// This fails with [`Error::UnsupportedSchemaVersion`] if the snapshot
+    /// was serialized by a newer version of this crate, with an incompatible
+    /// schema.
+    pub fn from_json(json: &str) -> Result<Self> {
+        #[derive(serde::Deserialize)]
+        struct Versioned {
+            schema_version: u32,
+        }
+
+        let map_err = |r: serde_json::Error| Error::from_io("serde_json::from_str()", r.into());
+
+        let versioned: Versioned = serde_json::from_str(json).map_err(map_err)?;
+        check_schema_version(versioned.schema_version)?;
+        serde_json::from_str(json).map_err(map_err)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn check_schema_version(found: u32) -> Result<()> {
+    if found > SCHEMA_VERSION {
+        Err(Error::UnsupportedSchemaVersion {
+            found,
+            supported: SCHEMA_VERSION,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Serialized form of a [`Snapshot`].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SnapshotRecord {
+    schema_version: u32,
+    time: SystemTime,
+    readings: Vec<Reading>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Snapshot> for SnapshotRecord {
+    fn from(snapshot: Snapshot) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            time: snapshot.time,
+            readings: snapshot.readings,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SnapshotRecord> for Snapshot {
+    type Error = Error;
+
+    fn try_from(record: SnapshotRecord) -> Result<Self> {
+        check_schema_version(record.schema_version)?;
+        Ok(Self {
+            time: record.time,
+            readings: record.readings,
+        })
+    }
+}
+
+/// Snapshots are taken at an arbitrary time after the Unix epoch.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Snapshot {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let since_epoch = core::time::Duration::arbitrary(u)?;
+        let time = std::time::UNIX_EPOCH
+            .checked_add(since_epoch)
+            .unwrap_or(std::time::UNIX_EPOCH);
+        let readings = u.arbitrary()?;
+        Ok(Self { time, readings })
+    }
+}
@@ -0,0 +1,231 @@
+//! Snapshots reading values on first access.
+//!
+//! A [`LazySnapshot`] captures the structure of all chips eagerly, *i.e.,*
+//! their names, features, labels and sub-features, but reads the value of
+//! each sub-feature only when it is first accessed, then remembers it.
+//! This suits user interfaces displaying a tree of sensors, of which only
+//! a few nodes are expanded.
+
+#[cfg(test)]
+mod tests;
+
+use core::cell::OnceCell;
+use core::ffi::c_uint;
+use std::time::SystemTime;
+
+use crate::backend::Reading;
+use crate::snapshot::Snapshot;
+use crate::sub_feature::SubFeatureRef;
+use crate::{LMSensors, Value};
+
+/// Snapshot of the structure of all chips, reading values on first access.
+///
+/// See: [`Snapshot::lazy`].
+#[derive(Debug)]
+pub struct LazySnapshot<'a> {
+    time: SystemTime,
+    chips: Vec<LazyChip<'a>>,
+}
+
+impl<'a> LazySnapshot<'a> {
+    /// Return a snapshot of the structure of all chips known to the given
+    /// LM sensors library, reading no values.
+    #[must_use]
+    pub fn new(sensors: &'a LMSensors) -> Self {
+        let chips = sensors.chip_iter(None).map(|chip| {
+            let features = chip.feature_iter().map(|feature| {
+                let sub_features = feature
+                    .sub_feature_iter()
+                    .map(|sub_feature| LazySubFeature {
+                        sub_feature,
+                        name: lossy_name(sub_feature.raw_name()),
+                        raw_value: OnceCell::default(),
+                    });
+
+                LazyFeature {
+                    name: lossy_name(feature.raw_name()),
+                    label: feature.label().ok(),
+                    sub_features: sub_features.collect(),
+                }
+            });
+
+            LazyChip {
+                name: chip.to_string(),
+                features: features.collect(),
+            }
+        });
+
+        Self {
+            time: SystemTime::now(),
+            chips: chips.collect(),
+        }
+    }
+
+    /// Return the time at which the structure was captured.
+    #[must_use]
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Return the chips, in order.
+    #[must_use]
+    pub fn chips(&self) -> &[LazyChip<'a>] {
+        &self.chips
+    }
+
+    /// Return the chip of the given name, *e.g.,* `coretemp-isa-0000`,
+    /// if any.
+    #[must_use]
+    pub fn chip(&self, name: &str) -> Option<&LazyChip<'a>> {
+        self.chips.iter().find(|chip| chip.name == name)
+    }
+
+    /// Return the number of sub-features whose values were read.
+    #[must_use]
+    pub fn read_count(&self) -> usize {
+        self.sub_features().filter(|(.., s)| s.is_read()).count()
+    }
+
+    /// Return an eager snapshot of all readings, reading the values that
+    /// were not read yet.
+    ///
+    /// The eager snapshot is taken at the time the structure was captured.
+    #[must_use]
+    pub fn to_snapshot(&self) -> Snapshot {
+        let readings = self
+            .sub_features()
+            .map(|(chip, feature, sub_feature)| Reading {
+                chip: chip.name.clone(),
+                feature: feature.name.clone(),
+                sub_feature: sub_feature.name.clone(),
+                raw_kind: sub_feature.raw_kind(),
+                raw_value: sub_feature.raw_value(),
+            });
+
+        Snapshot {
+            time: self.time,
+            readings: readings.collect(),
+        }
+    }
+
+    fn sub_features(
+        &self,
+    ) -> impl Iterator<Item = (&LazyChip<'a>, &LazyFeature<'a>, &LazySubFeature<'a>)> {
+        self.chips.iter().flat_map(|chip| {
+            chip.features.iter().flat_map(move |feature| {
+                let sub_features = feature.sub_features.iter();
+                sub_features.map(move |sub_feature| (chip, feature, sub_feature))
+            })
+        })
+    }
+}
+
+/// Chip of a [`LazySnapshot`].
+#[derive(Debug)]
+pub struct LazyChip<'a> {
+    name: String,
+    features: Vec<LazyFeature<'a>>,
+}
+
+impl<'a> LazyChip<'a> {
+    /// Return the name of this chip, *e.g.,* `coretemp-isa-0000`.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the features of this chip, in order.
+    #[must_use]
+    pub fn features(&self) -> &[LazyFeature<'a>] {
+        &self.features
+    }
+}
+
+/// Feature of a [`LazySnapshot`].
+#[derive(Debug)]
+pub struct LazyFeature<'a> {
+    name: String,
+    label: Option<String>,
+    sub_features: Vec<LazySubFeature<'a>>,
+}
+
+impl<'a> LazyFeature<'a> {
+    /// Return the name of this feature, *e.g.,* `temp1`.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the label of this feature, if it is valid UTF-8.
+    #[must_use]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Return the sub-features of this feature, in order.
+    #[must_use]
+    pub fn sub_features(&self) -> &[LazySubFeature<'a>] {
+        &self.sub_features
+    }
+}
+
+/// Sub-feature of a [`LazySnapshot`], whose value is read on first access.
+#[derive(Debug)]
+pub struct LazySubFeature<'a> {
+    sub_feature: SubFeatureRef<'a>,
+    name: String,
+    raw_value: OnceCell<Option<f64>>,
+}
+
+impl<'a> LazySubFeature<'a> {
+    /// Return the name of this sub-feature, *e.g.,* `temp1_input`.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the raw type of this sub-feature, one of
+    /// `SENSORS_SUBFEATURE_*`.
+    #[must_use]
+    pub fn raw_kind(&self) -> c_uint {
+        self.sub_feature.raw_kind()
+    }
+
+    /// Return the wrapped sub-feature.
+    #[must_use]
+    pub fn sub_feature(&self) -> SubFeatureRef<'a> {
+        self.sub_feature
+    }
+
+    /// Return `true` if the value of this sub-feature was read.
+    #[must_use]
+    pub fn is_read(&self) -> bool {
+        self.raw_value.get().is_some()
+    }
+
+    /// Return the raw value of this sub-feature, if it could be read.
+    ///
+    /// The value is read on the first call, and remembered afterwards,
+    /// even if it could not be read.
+    #[must_use]
+    pub fn raw_value(&self) -> Option<f64> {
+        *self
+            .raw_value
+            .get_or_init(|| self.sub_feature.raw_value().ok())
+    }
+
+    /// Return the value of this sub-feature, if it could be read,
+    /// and its type is known.
+    ///
+    /// See: [`LazySubFeature::raw_value`].
+    #[must_use]
+    pub fn value(&self) -> Option<Value> {
+        self.raw_value()
+            .and_then(|value| Value::from_raw(self.raw_kind(), value))
+    }
+}
+
+fn lossy_name(name: Option<&core::ffi::CStr>) -> String {
+    name.map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,47 @@
+#![cfg(test)]
+
+use serial_test::serial;
+
+use crate::backend::Reading;
+use crate::snapshot::Snapshot;
+
+#[test]
+#[serial]
+fn lazy() {
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    let snapshot = Snapshot::lazy(&s);
+    assert_eq!(snapshot.chips().len(), s.chip_iter(None).count());
+    assert_eq!(snapshot.read_count(), 0);
+
+    let chip0 = &snapshot.chips()[0];
+    assert_eq!(snapshot.chip(chip0.name()).unwrap().name(), chip0.name());
+    assert!(snapshot.chip("missing-isa-0000").is_none());
+
+    let sub_feature = &chip0.features()[0].sub_features()[0];
+    assert!(!sub_feature.is_read());
+    let value = sub_feature.raw_value();
+    assert!(sub_feature.is_read());
+    assert_eq!(sub_feature.raw_value(), value);
+    assert_eq!(snapshot.read_count(), 1);
+}
+
+#[test]
+#[serial]
+fn to_snapshot() {
+    let mut s = crate::Initializer::default().initialize().unwrap();
+
+    let key = |r: &Reading| (r.chip.clone(), r.sub_feature.clone(), r.raw_kind);
+
+    let structure: Vec<_> = {
+        let lazy = Snapshot::lazy(&s);
+        let snapshot = lazy.to_snapshot();
+        assert_eq!(snapshot.time, lazy.time());
+        assert_eq!(lazy.read_count(), snapshot.readings.len());
+        snapshot.readings.iter().map(key).collect()
+    };
+
+    let eager = Snapshot::read(&mut s).unwrap();
+    let expected: Vec<_> = eager.readings.iter().map(key).collect();
+    assert_eq!(structure, expected);
+}
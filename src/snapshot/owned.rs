@@ -0,0 +1,150 @@
+//! Owned snapshots of the structure and values of chips.
+//!
+//! A [`ChipSnapshot`] holds the names, labels, types and values of a chip,
+//! its features and their sub-features, without borrowing the LM sensors
+//! library, so that it can be stored in structures of applications, or sent
+//! to other threads. Values are read when the snapshot is taken.
+//!
+//! See: `LMSensors::chip_snapshots`.
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests;
+
+use core::ffi::{c_int, c_uint};
+use std::path::PathBuf;
+
+use crate::model::{feature, value};
+#[cfg(target_os = "linux")]
+use crate::sub_feature::Flags;
+use crate::Value;
+
+/// Owned snapshot of a chip, its features and their sub-features.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChipSnapshot {
+    /// Name of the chip, *e.g.,* `coretemp-isa-0000`.
+    pub name: String,
+    /// Prefix of the chip, *e.g.,* `coretemp`, if available.
+    pub prefix: Option<String>,
+    /// Address of the chip, if available.
+    pub address: Option<c_int>,
+    /// Path of the driver of the chip, if available.
+    pub path: Option<PathBuf>,
+    /// Features of the chip, in order.
+    pub features: Vec<FeatureSnapshot>,
+}
+
+impl ChipSnapshot {
+    /// Return the feature of the given name, *e.g.,* `temp1`, if any.
+    #[must_use]
+    pub fn feature(&self, name: &str) -> Option<&FeatureSnapshot> {
+        self.features.iter().find(|feature| feature.name == name)
+    }
+}
+
+/// Owned snapshot of a feature and its sub-features.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeatureSnapshot {
+    /// Name of the feature, *e.g.,* `temp1`.
+    pub name: String,
+    /// Number of the feature.
+    pub number: c_int,
+    /// Label of the feature, if it could be read.
+    pub label: Option<String>,
+    /// Raw type of the feature, one of `SENSORS_FEATURE_*`.
+    pub raw_kind: c_uint,
+    /// Sub-features of the feature, in order.
+    pub sub_features: Vec<SubFeatureSnapshot>,
+}
+
+impl FeatureSnapshot {
+    /// Return the type of this feature, if it is known.
+    #[must_use]
+    pub fn kind(&self) -> Option<feature::Kind> {
+        feature::Kind::from_raw(self.raw_kind)
+    }
+
+    /// Return the sub-feature of the given name, *e.g.,* `temp1_input`,
+    /// if any.
+    #[must_use]
+    pub fn sub_feature(&self, name: &str) -> Option<&SubFeatureSnapshot> {
+        self.sub_features.iter().find(|sub| sub.name == name)
+    }
+}
+
+/// Owned snapshot of a sub-feature and its value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubFeatureSnapshot {
+    /// Name of the sub-feature, *e.g.,* `temp1_input`.
+    pub name: String,
+    /// Number of the sub-feature.
+    pub number: c_int,
+    /// Raw type of the sub-feature, one of `SENSORS_SUBFEATURE_*`.
+    pub raw_kind: c_uint,
+    /// Raw flags of the sub-feature, *e.g.,* `SENSORS_MODE_R`.
+    pub raw_flags: c_uint,
+    /// Raw value of the sub-feature, if it is readable and could be read.
+    pub raw_value: Option<f64>,
+}
+
+impl SubFeatureSnapshot {
+    /// Return the type of this sub-feature, if it is known.
+    #[must_use]
+    pub fn kind(&self) -> Option<value::Kind> {
+        value::Kind::from_raw(self.raw_kind)
+    }
+
+    /// Return the value of this sub-feature, if it could be read,
+    /// and its type is known.
+    #[must_use]
+    pub fn value(&self) -> Option<Value> {
+        self.raw_value
+            .and_then(|value| Value::from_raw(self.raw_kind, value))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl From<crate::ChipRef<'_>> for ChipSnapshot {
+    /// Read the names, labels, types and readable values of the given chip.
+    fn from(chip: crate::ChipRef<'_>) -> Self {
+        let features = chip.feature_iter().map(|feature| {
+            let sub_features = feature.sub_feature_iter().map(|sub_feature| {
+                let readable = sub_feature
+                    .flags()
+                    .is_some_and(|flags| flags.contains(Flags::READABLE));
+
+                SubFeatureSnapshot {
+                    name: lossy_name(sub_feature.raw_name()),
+                    number: sub_feature.number(),
+                    raw_kind: sub_feature.raw_kind(),
+                    raw_flags: sub_feature.raw_flags(),
+                    raw_value: readable.then(|| sub_feature.raw_value().ok()).flatten(),
+                }
+            });
+
+            FeatureSnapshot {
+                name: lossy_name(feature.raw_name()),
+                number: feature.number(),
+                label: feature.label().ok(),
+                raw_kind: feature.raw_kind(),
+                sub_features: sub_features.collect(),
+            }
+        });
+
+        Self {
+            name: chip.to_string(),
+            prefix: chip.raw_prefix().map(|p| p.to_string_lossy().into_owned()),
+            address: chip.address(),
+            path: chip.path().map(PathBuf::from),
+            features: features.collect(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn lossy_name(name: Option<&core::ffi::CStr>) -> String {
+    name.map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
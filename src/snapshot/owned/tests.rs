@@ -0,0 +1,63 @@
+#![cfg(test)]
+
+use serial_test::serial;
+
+use crate::snapshot::Snapshot;
+use crate::sub_feature::Flags;
+
+#[test]
+#[serial]
+fn snapshot() {
+    let mut s = crate::Initializer::default().initialize().unwrap();
+    let chips = s.chip_snapshots();
+    assert_eq!(chips.len(), s.chip_iter(None).count());
+
+    for (chip, expected) in chips.iter().zip(s.chip_iter(None)) {
+        assert_eq!(chip.name, expected.to_string());
+        assert_eq!(chip.address, expected.address());
+        assert_eq!(chip.features.len(), expected.feature_iter().count());
+
+        for (feature, expected) in chip.features.iter().zip(expected.feature_iter()) {
+            assert_eq!(chip.feature(&feature.name), Some(feature));
+            assert_eq!(feature.kind(), expected.kind());
+            assert_eq!(feature.label, expected.label().ok());
+
+            for sub_feature in &feature.sub_features {
+                assert_eq!(feature.sub_feature(&sub_feature.name), Some(sub_feature));
+                if let Some(value) = sub_feature.value() {
+                    assert_eq!(Some(value.kind()), sub_feature.kind());
+                }
+                if !Flags::from_bits_truncate(sub_feature.raw_flags).contains(Flags::READABLE) {
+                    assert_eq!(sub_feature.raw_value, None);
+                }
+            }
+        }
+    }
+
+    // Snapshots hold the same readings as flat snapshots.
+    let flat = Snapshot::read(&mut s).unwrap();
+    drop(s);
+    let count: usize = chips
+        .iter()
+        .flat_map(|chip| &chip.features)
+        .map(|feature| feature.sub_features.len())
+        .sum();
+    assert_eq!(count, flat.readings.len());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+#[serial]
+fn serde() {
+    let s = crate::Initializer::default().initialize().unwrap();
+    let chips = s.chip_snapshots();
+    drop(s);
+
+    let json = serde_json::to_string(&chips).unwrap();
+    let parsed: Vec<super::ChipSnapshot> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.len(), chips.len());
+    for (chip, expected) in parsed.iter().zip(&chips) {
+        assert_eq!(chip.name, expected.name);
+        assert_eq!(chip.features.len(), expected.features.len());
+    }
+}
@@ -0,0 +1,191 @@
+#![cfg(test)]
+
+use crate::backend::Reading;
+use crate::naming::NameTemplate;
+use crate::snapshot::Snapshot;
+use crate::value::Kind;
+
+const CHIP: &str = "coretemp-isa-0000";
+
+#[test]
+fn features() {
+    let snapshot = Snapshot::new(vec![
+        Reading::for_test(CHIP, "temp1_input", Kind::TemperatureInput, Some(1.0)),
+        Reading::for_test(CHIP, "temp1_max", Kind::TemperatureMaximum, Some(1.0)),
+        Reading::for_test(CHIP, "temp2_input", Kind::TemperatureInput, Some(1.0)),
+        Reading::for_test(
+            "nct6775-isa-0290",
+            "temp1_input",
+            Kind::TemperatureInput,
+            Some(1.0),
+        ),
+    ]);
+
+    let features: Vec<_> = snapshot.features().map(<[Reading]>::len).collect();
+    assert_eq!(features, [2, 1, 1]);
+    assert_eq!(Snapshot::new(vec![]).features().count(), 0);
+}
+
+#[test]
+fn named() {
+    let snapshot = Snapshot::new(vec![
+        Reading::for_test(CHIP, "temp1_input", Kind::TemperatureInput, Some(1.0)),
+        Reading::for_test("nct6775-isa-0290", "fan2_input", Kind::FanInput, Some(1.0)),
+    ]);
+
+    let template = NameTemplate::new("{prefix}.{sub_feature}").unwrap();
+    let names: Vec<_> = snapshot.named(&template).map(|(name, _)| name).collect();
+    assert_eq!(names, ["coretemp.temp1_input", "nct6775.fan2_input"]);
+    for ((_, reading), expected) in snapshot.named(&template).zip(&snapshot.readings) {
+        assert_eq!(reading, expected);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json() {
+    use assert_matches::assert_matches;
+
+    use crate::errors::Error;
+
+    let mut snapshot = Snapshot::new(vec![
+        Reading::for_test(CHIP, "temp1_input", Kind::TemperatureInput, Some(1.0)),
+        Reading::for_test("nct6775-isa-0290", "fan1_input", Kind::FanInput, Some(1.0)),
+    ]);
+    snapshot.readings[1].raw_value = None;
+
+    let json = snapshot.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["schema_version"], super::SCHEMA_VERSION);
+    assert_eq!(Snapshot::from_json(&json).unwrap(), snapshot);
+
+    // Unknown fields are ignored.
+    let mut value = value;
+    value["comment"] = "recorded on host-1".into();
+    let json = value.to_string();
+    assert_eq!(Snapshot::from_json(&json).unwrap(), snapshot);
+
+    // Newer schemas are rejected.
+    value["schema_version"] = (super::SCHEMA_VERSION + 1).into();
+    let err = Snapshot::from_json(&value.to_string()).unwrap_err();
+    assert_matches!(err, Error::UnsupportedSchemaVersion { found, .. }
+        if found == super::SCHEMA_VERSION + 1);
+
+    // Missing schema versions are rejected.
+    let err = Snapshot::from_json(r#"{"time": null, "readings": []}"#).unwrap_err();
+    assert_matches!(err, Error::IO { .. });
+}
+
+#[test]
+fn fixture() {
+    let mut snapshot = Snapshot::new(vec![
+        Reading::for_test("nct6775-isa-0290", "fan1_input", Kind::FanInput, Some(1.0)),
+        Reading::for_test(CHIP, "temp1_input", Kind::TemperatureInput, Some(1.0)),
+        Reading::for_test(CHIP, "temp1_max", Kind::TemperatureMaximum, Some(1.0)),
+    ]);
+    snapshot.readings[0].raw_value = None;
+    snapshot.readings[1].raw_value = Some(45.0625);
+    snapshot.readings[2].raw_value = Some(-0.0001);
+
+    let fixture = snapshot.to_fixture();
+    assert_eq!(
+        fixture,
+        "coretemp-isa-0000 temp1 temp1_input 512 45.062\n\
+         coretemp-isa-0000 temp1 temp1_max 513 0.000\n\
+         nct6775-isa-0290 fan1 fan1_input 256 -\n"
+    );
+
+    let parsed = Snapshot::from_fixture(&format!("# Comment.\n\n{fixture}")).unwrap();
+    assert_eq!(parsed.time, std::time::UNIX_EPOCH);
+    assert_eq!(parsed.to_fixture(), fixture);
+
+    assert!(Snapshot::from_fixture("coretemp-isa-0000 temp1 temp1_input 0").is_err());
+    assert!(Snapshot::from_fixture("coretemp-isa-0000 temp1 temp1_input x 1.0").is_err());
+    assert!(Snapshot::from_fixture("coretemp-isa-0000 temp1 temp1_input 0 1.0 1").is_err());
+}
+
+#[test]
+fn fixture_mismatch() {
+    use crate::snapshot::fixture_mismatch;
+
+    let snapshot = Snapshot::new(vec![
+        Reading::for_test(CHIP, "temp1_input", Kind::TemperatureInput, Some(1.0)),
+        Reading::for_test(CHIP, "temp2_input", Kind::TemperatureInput, Some(1.0)),
+    ]);
+
+    crate::assert_snapshot_eq!(
+        snapshot,
+        "coretemp-isa-0000 temp2 temp2_input 512 1.0
+         coretemp-isa-0000 temp1 temp1_input 512 1"
+    );
+
+    let mismatch = fixture_mismatch(
+        &snapshot,
+        "coretemp-isa-0000 temp1 temp1_input 512 1.0\n\
+         coretemp-isa-0000 temp1 temp1_max 513 1.0",
+    );
+    assert_eq!(
+        mismatch.as_deref(),
+        Some(
+            "snapshot differs from fixture:\n\
+             - coretemp-isa-0000 temp1 temp1_max 513 1.000\n\
+             + coretemp-isa-0000 temp2 temp2_input 512 1.000\n"
+        )
+    );
+
+    let mismatch = fixture_mismatch(&snapshot, "invalid").unwrap();
+    assert!(mismatch.starts_with("invalid expected fixture"));
+}
+
+#[test]
+fn fixture_file() {
+    use crate::snapshot::fixture_file_mismatch;
+
+    let snapshot = Snapshot::new(vec![Reading::for_test(
+        CHIP,
+        "temp1_input",
+        Kind::TemperatureInput,
+        Some(1.0),
+    )]);
+
+    let path = std::env::temp_dir().join(format!("lm-sensors-fixture-{}", std::process::id()));
+    assert!(fixture_file_mismatch(&snapshot, &path).is_some());
+
+    std::fs::write(&path, snapshot.to_fixture()).unwrap();
+    crate::assert_snapshot_eq!(snapshot, path = &path);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "simulation")]
+#[test]
+fn fixture_replay() {
+    use crate::simulation::Replay;
+
+    let recorded = Snapshot::from_fixture("coretemp-isa-0000 temp1 temp1_input 512 45.0").unwrap();
+    let mut backend = Replay::new([recorded.readings]);
+
+    let snapshot = Snapshot::read(&mut backend).unwrap();
+    crate::assert_snapshot_eq!(snapshot, "coretemp-isa-0000 temp1 temp1_input 512 45.000");
+}
+
+#[cfg(feature = "simulation")]
+#[test]
+fn read_with_clock() {
+    use core::time::Duration;
+    use std::time::UNIX_EPOCH;
+
+    use crate::clock::Mock;
+    use crate::simulation::Replay;
+
+    let mut backend = Replay::new([vec![Reading::for_test(
+        "chip",
+        "temp1_input",
+        Kind::TemperatureInput,
+        Some(1.0),
+    )]]);
+    let clock = Mock::new(Duration::from_secs(42));
+
+    let snapshot = Snapshot::read_with_clock(&mut backend, &clock).unwrap();
+    assert_eq!(snapshot.time, UNIX_EPOCH + Duration::from_secs(42));
+    assert_eq!(snapshot.readings.len(), 1);
+}
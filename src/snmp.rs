@@ -0,0 +1,470 @@
+//! Read-only SNMP agent exposing readings.
+//!
+//! An [`Agent`] answers SNMP requests received over UDP with readings of
+//! a local [`Backend`], for integration with network management systems
+//! that only speak SNMP. Versions 1 and 2c are supported, with the requests
+//! `GetRequest`, `GetNextRequest` and `GetBulkRequest`. Set requests are
+//! always rejected.
+//!
+//! Communities are checked as tokens of the [`AccessPolicy`] of the agent.
+//! By default, any community is accepted. Requests with other communities
+//! are dropped without answer.
+//!
+//! # MIB
+//!
+//! Readings of input sub-features, *e.g.,* `temp1_input`, are exposed under
+//! [`LM_SENSORS_MIB`], with the layout of the `LM-SENSORS-MIB` of Net-SNMP.
+//! Each table has the columns `Index` (1), `Device` (2) and `Value` (3):
+//!
+//! | Table                      | Sub-features          | Value unit      |
+//! |----------------------------|-----------------------|-----------------|
+//! | `lmTempSensorsTable` (2)   | temperature inputs    | millidegrees C  |
+//! | `lmFanSensorsTable` (3)    | fan inputs            | RPM             |
+//! | `lmVoltSensorsTable` (4)   | voltage inputs        | millivolts      |
+//! | `lmMiscSensorsTable` (5)   | other inputs          | thousandths     |
+//!
+//! Rows are indexed from 1, in the order of readings. The device of a row
+//! is its chip and feature names, *e.g.,* `coretemp-isa-0000/temp1`.
+//! Values are rounded, and clamped to the range of `Gauge32`.
+
+mod ber;
+#[cfg(test)]
+mod tests;
+
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use crate::access::AccessPolicy;
+use crate::backend::{Backend, Reading};
+use crate::errors::{Error, Result};
+use crate::value;
+
+/// Default UDP port of an [`Agent`].
+pub const DEFAULT_PORT: u16 = 161;
+
+/// Object identifier of the `lmSensors` subtree of the `LM-SENSORS-MIB`.
+pub const LM_SENSORS_MIB: [u32; 9] = [1, 3, 6, 1, 4, 1, 2021, 13, 16];
+
+/// Maximum length of a message, in bytes, *i.e.,* the maximum payload of
+/// a UDP datagram over IPv4.
+pub const MAX_MESSAGE_LEN: usize = 65_507;
+
+/// Maximum number of variable bindings answered to a `GetBulkRequest`.
+pub const MAX_BULK_BINDINGS: usize = 256;
+
+const VERSION_1: i64 = 0;
+const VERSION_2C: i64 = 1;
+
+const GET_REQUEST: u8 = 0xA0;
+const GET_NEXT_REQUEST: u8 = 0xA1;
+const GET_RESPONSE: u8 = 0xA2;
+const SET_REQUEST: u8 = 0xA3;
+const GET_BULK_REQUEST: u8 = 0xA5;
+
+const TOO_BIG: i64 = 1;
+const NO_SUCH_NAME: i64 = 2;
+const GEN_ERR: i64 = 5;
+const NOT_WRITABLE: i64 = 17;
+
+/// Table of the `LM-SENSORS-MIB`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Table {
+    Temperature = 2,
+    Fan = 3,
+    Voltage = 4,
+    Misc = 5,
+}
+
+impl Table {
+    const ALL: [Self; 4] = [Self::Temperature, Self::Fan, Self::Voltage, Self::Misc];
+
+    fn of(kind: value::Kind) -> Option<Self> {
+        match kind {
+            value::Kind::TemperatureInput => Some(Self::Temperature),
+            value::Kind::FanInput => Some(Self::Fan),
+            value::Kind::VoltageInput => Some(Self::Voltage),
+            value::Kind::PowerInput
+            | value::Kind::EnergyInput
+            | value::Kind::CurrentInput
+            | value::Kind::HumidityInput => Some(Self::Misc),
+            _ => None,
+        }
+    }
+
+    fn scale(self) -> f64 {
+        match self {
+            Self::Fan => 1.0,
+            Self::Temperature | Self::Voltage | Self::Misc => 1000.0,
+        }
+    }
+}
+
+/// Value of a variable binding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Variable {
+    Integer(i64),
+    OctetString(String),
+    Gauge32(u32),
+    Null,
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl Variable {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Integer(n) => ber::write_integer(out, ber::INTEGER, *n),
+            Self::OctetString(s) => ber::write(out, ber::OCTET_STRING, s.as_bytes()),
+            Self::Gauge32(n) => ber::write_integer(out, ber::GAUGE32, i64::from(*n)),
+            Self::Null => ber::write(out, ber::NULL, &[]),
+            Self::NoSuchObject => ber::write(out, ber::NO_SUCH_OBJECT, &[]),
+            Self::NoSuchInstance => ber::write(out, ber::NO_SUCH_INSTANCE, &[]),
+            Self::EndOfMibView => ber::write(out, ber::END_OF_MIB_VIEW, &[]),
+        }
+    }
+}
+
+type Binding = (Vec<u32>, Variable);
+
+/// Return the variables exposing the given readings, sorted by object
+/// identifier.
+fn mib(readings: &[Reading]) -> Vec<Binding> {
+    let mut rows: [Vec<(String, u32)>; 4] = Default::default();
+    for reading in readings {
+        let Some(value) = reading.value() else {
+            continue;
+        };
+        let Some(table) = Table::of(value.kind()) else {
+            continue;
+        };
+
+        let scaled = (value.raw_value() * table.scale()).round();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped.
+        let gauge = scaled.clamp(0.0, f64::from(u32::MAX)) as u32;
+        let device = format!("{}/{}", reading.chip, reading.feature);
+        rows[table as usize - Table::Temperature as usize].push((device, gauge));
+    }
+
+    let mut variables = Vec::default();
+    for (table, rows) in Table::ALL.into_iter().zip(&rows) {
+        for column in 1..=3 {
+            for (index, (device, gauge)) in (1_u32..).zip(rows) {
+                let mut oid = LM_SENSORS_MIB.to_vec();
+                oid.extend([table as u32, 1, column, index]);
+                let variable = match column {
+                    1 => Variable::Integer(i64::from(index)),
+                    2 => Variable::OctetString(device.clone()),
+                    _ => Variable::Gauge32(*gauge),
+                };
+                variables.push((oid, variable));
+            }
+        }
+    }
+    variables
+}
+
+/// Return `true` if the given object identifier names an instance of
+/// a column of a table.
+fn is_column_instance(oid: &[u32]) -> bool {
+    match oid.strip_prefix(&LM_SENSORS_MIB[..]) {
+        Some([table, 1, column, _]) => (2..=5).contains(table) && (1..=3).contains(column),
+        _ => false,
+    }
+}
+
+/// Return the variable following the given object identifier, if any.
+fn next<'a>(mib: &'a [Binding], oid: &[u32]) -> Option<&'a Binding> {
+    mib.get(mib.partition_point(|(name, _)| name.as_slice() <= oid))
+}
+
+/// Request decoded from a message.
+#[derive(Debug)]
+struct Request<'a> {
+    version: i64,
+    community: &'a [u8],
+    pdu_type: u8,
+    request_id: i64,
+    non_repeaters: i64,
+    max_repetitions: i64,
+    oids: Vec<Vec<u32>>,
+}
+
+impl<'a> Request<'a> {
+    fn decode(message: &'a [u8]) -> Option<Self> {
+        let mut message = ber::Reader::new(ber::Reader::new(message).expect(ber::SEQUENCE)?);
+        let version = message.integer()?;
+        if version != VERSION_1 && version != VERSION_2C {
+            return None;
+        }
+        let community = message.expect(ber::OCTET_STRING)?;
+
+        let (pdu_type, pdu) = message.read()?;
+        let supported = match pdu_type {
+            GET_REQUEST | GET_NEXT_REQUEST | SET_REQUEST => true,
+            GET_BULK_REQUEST => version == VERSION_2C,
+            _ => false,
+        };
+        if !supported {
+            return None;
+        }
+
+        let mut pdu = ber::Reader::new(pdu);
+        let request_id = pdu.integer()?;
+        let non_repeaters = pdu.integer()?;
+        let max_repetitions = pdu.integer()?;
+
+        let mut bindings = ber::Reader::new(pdu.expect(ber::SEQUENCE)?);
+        let mut oids = Vec::default();
+        while !bindings.is_empty() {
+            oids.push(ber::Reader::new(bindings.expect(ber::SEQUENCE)?).oid()?);
+        }
+
+        Some(Self {
+            version,
+            community,
+            pdu_type,
+            request_id,
+            non_repeaters,
+            max_repetitions,
+            oids,
+        })
+    }
+
+    /// Return the response with the given error, and the requested
+    /// object identifiers bound to nulls.
+    fn error(&self, status: i64, index: usize) -> Response {
+        let bindings = self.oids.iter().map(|oid| (oid.clone(), Variable::Null));
+        Response {
+            status,
+            index,
+            bindings: bindings.collect(),
+        }
+    }
+
+    fn answer(&self, mib: &[Binding]) -> Response {
+        let v1 = self.version == VERSION_1;
+        let mut bindings = Vec::with_capacity(self.oids.len());
+
+        match self.pdu_type {
+            GET_REQUEST => {
+                for (index, oid) in self.oids.iter().enumerate() {
+                    let variable = match mib.binary_search_by(|(name, _)| name.cmp(oid)) {
+                        Ok(found) => mib[found].1.clone(),
+                        Err(_) if v1 => return self.error(NO_SUCH_NAME, index + 1),
+                        Err(_) if is_column_instance(oid) => Variable::NoSuchInstance,
+                        Err(_) => Variable::NoSuchObject,
+                    };
+                    bindings.push((oid.clone(), variable));
+                }
+            }
+
+            GET_NEXT_REQUEST => {
+                for (index, oid) in self.oids.iter().enumerate() {
+                    match next(mib, oid) {
+                        Some(binding) => bindings.push(binding.clone()),
+                        None if v1 => return self.error(NO_SUCH_NAME, index + 1),
+                        None => bindings.push((oid.clone(), Variable::EndOfMibView)),
+                    }
+                }
+            }
+
+            GET_BULK_REQUEST => {
+                let non_repeaters = usize::try_from(self.non_repeaters)
+                    .unwrap_or(0)
+                    .min(self.oids.len());
+                let (singles, repeated) = self.oids.split_at(non_repeaters);
+
+                for oid in singles {
+                    let binding = next(mib, oid).cloned();
+                    bindings.push(binding.unwrap_or((oid.clone(), Variable::EndOfMibView)));
+                }
+
+                let mut cursors = repeated.to_vec();
+                let repetitions = usize::try_from(self.max_repetitions).unwrap_or(0);
+                for _ in 0..repetitions {
+                    if cursors.is_empty() || bindings.len() + cursors.len() > MAX_BULK_BINDINGS {
+                        break;
+                    }
+
+                    let mut ended = true;
+                    for cursor in &mut cursors {
+                        match next(mib, cursor) {
+                            Some(binding) => {
+                                cursor.clone_from(&binding.0);
+                                bindings.push(binding.clone());
+                                ended = false;
+                            }
+                            None => bindings.push((cursor.clone(), Variable::EndOfMibView)),
+                        }
+                    }
+                    if ended {
+                        break;
+                    }
+                }
+            }
+
+            _ => {
+                // Set requests.
+                let status = if v1 { NO_SUCH_NAME } else { NOT_WRITABLE };
+                return self.error(status, 1);
+            }
+        }
+
+        Response {
+            status: 0,
+            index: 0,
+            bindings,
+        }
+    }
+
+    fn encode(&self, response: &Response) -> Vec<u8> {
+        let mut message = Vec::default();
+        ber::write_with(&mut message, ber::SEQUENCE, |out| {
+            ber::write_integer(out, ber::INTEGER, self.version);
+            ber::write(out, ber::OCTET_STRING, self.community);
+            ber::write_with(out, GET_RESPONSE, |out| {
+                ber::write_integer(out, ber::INTEGER, self.request_id);
+                ber::write_integer(out, ber::INTEGER, response.status);
+                let index = i64::try_from(response.index).unwrap_or(i64::MAX);
+                ber::write_integer(out, ber::INTEGER, index);
+                ber::write_with(out, ber::SEQUENCE, |out| {
+                    for (oid, variable) in &response.bindings {
+                        ber::write_with(out, ber::SEQUENCE, |out| {
+                            ber::write_oid(out, oid);
+                            variable.write(out);
+                        });
+                    }
+                });
+            });
+        });
+        message
+    }
+}
+
+/// Response to a request, before encoding.
+#[derive(Debug)]
+struct Response {
+    status: i64,
+    index: usize,
+    bindings: Vec<Binding>,
+}
+
+/// Answer the given SNMP request message with readings of the given backend,
+/// enforcing the given access policy.
+///
+/// Return the encoded response, or `None` if the request must be dropped,
+/// *e.g.,* because it is malformed, or its community is not accepted.
+/// This allows serving requests received through any transport.
+pub fn respond(
+    backend: &mut dyn Backend,
+    policy: &AccessPolicy,
+    message: &[u8],
+) -> Option<Vec<u8>> {
+    let request = Request::decode(message)?;
+    let community = core::str::from_utf8(request.community).ok()?;
+    if !policy.accepts(community) {
+        return None;
+    }
+
+    let response = match backend.read_all() {
+        Ok(readings) => request.answer(&mib(&readings)),
+        Err(_) => request.error(GEN_ERR, 1),
+    };
+
+    let message = request.encode(&response);
+    if message.len() <= MAX_MESSAGE_LEN {
+        Some(message)
+    } else {
+        let response = Response {
+            status: TOO_BIG,
+            index: 0,
+            bindings: Vec::default(),
+        };
+        Some(request.encode(&response))
+    }
+}
+
+/**
+Agent answering SNMP requests received over UDP.
+
+# Example
+
+```no_run
+use lm_sensors::access::AccessPolicy;
+use lm_sensors::{backend, snmp};
+
+let policy = AccessPolicy::new().auth_token("public");
+let agent = snmp::Agent::bind(("0.0.0.0", snmp::DEFAULT_PORT))?.access_policy(policy);
+agent.serve(&mut backend::native()?)?;
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+#[derive(Debug)]
+pub struct Agent {
+    socket: UdpSocket,
+    policy: AccessPolicy,
+}
+
+impl Agent {
+    /// Return an agent receiving requests on the given address, *e.g.,*
+    /// `("0.0.0.0", DEFAULT_PORT)`.
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self> {
+        UdpSocket::bind(address)
+            .map(|socket| Self {
+                socket,
+                policy: AccessPolicy::default(),
+            })
+            .map_err(|r| Error::from_io("UdpSocket::bind()", r))
+    }
+
+    /// Set the access policy enforced on requests, whose token is the
+    /// accepted community.
+    ///
+    /// By default, any community is accepted. Writes are always denied.
+    #[must_use]
+    pub fn access_policy(self, policy: AccessPolicy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// Return the address this agent receives requests on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.socket
+            .local_addr()
+            .map_err(|r| Error::from_io("UdpSocket::local_addr()", r))
+    }
+
+    /// Receive one request, and answer it, unless it is dropped.
+    pub fn serve_one(&self, backend: &mut dyn Backend) -> Result<()> {
+        let mut buffer = vec![0_u8; MAX_MESSAGE_LEN];
+        let (len, peer) = self.receive(&mut buffer)?;
+        self.answer(backend, &buffer[..len], peer)
+    }
+
+    /// Receive requests one after the other, and answer them, until
+    /// receiving a request fails.
+    ///
+    /// Failures to send responses are ignored.
+    pub fn serve(&self, backend: &mut dyn Backend) -> Result<()> {
+        let mut buffer = vec![0_u8; MAX_MESSAGE_LEN];
+        loop {
+            let (len, peer) = self.receive(&mut buffer)?;
+            let _ignored = self.answer(backend, &buffer[..len], peer);
+        }
+    }
+
+    fn receive(&self, buffer: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        self.socket
+            .recv_from(buffer)
+            .map_err(|r| Error::from_io("UdpSocket::recv_from()", r))
+    }
+
+    fn answer(&self, backend: &mut dyn Backend, message: &[u8], peer: SocketAddr) -> Result<()> {
+        let Some(response) = respond(backend, &self.policy, message) else {
+            return Ok(());
+        };
+        self.socket
+            .send_to(&response, peer)
+            .map(drop)
+            .map_err(|r| Error::from_io("UdpSocket::send_to()", r))
+    }
+}
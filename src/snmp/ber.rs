@@ -0,0 +1,162 @@
+//! Minimal encoding and decoding of SNMP messages, in the Basic Encoding
+//! Rules of ASN.1.
+
+pub(super) const INTEGER: u8 = 0x02;
+pub(super) const OCTET_STRING: u8 = 0x04;
+pub(super) const NULL: u8 = 0x05;
+pub(super) const OBJECT_IDENTIFIER: u8 = 0x06;
+pub(super) const SEQUENCE: u8 = 0x30;
+pub(super) const GAUGE32: u8 = 0x42;
+pub(super) const NO_SUCH_OBJECT: u8 = 0x80;
+pub(super) const NO_SUCH_INSTANCE: u8 = 0x81;
+pub(super) const END_OF_MIB_VIEW: u8 = 0x82;
+
+/// Reader of consecutive encoded values.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Return the tag and the content of the next value.
+    pub(super) fn read(&mut self) -> Option<(u8, &'a [u8])> {
+        let (&tag, rest) = self.0.split_first()?;
+        let (&first, mut rest) = rest.split_first()?;
+
+        let len = if first < 0x80 {
+            usize::from(first)
+        } else {
+            let count = usize::from(first & 0x7F);
+            if count == 0 || count > 4 || rest.len() < count {
+                return None;
+            }
+            let (bytes, tail) = rest.split_at(count);
+            rest = tail;
+            bytes
+                .iter()
+                .fold(0_usize, |len, &byte| (len << 8) | usize::from(byte))
+        };
+
+        if rest.len() < len {
+            return None;
+        }
+        let (content, tail) = rest.split_at(len);
+        self.0 = tail;
+        Some((tag, content))
+    }
+
+    /// Return the content of the next value, if it has the given tag.
+    pub(super) fn expect(&mut self, tag: u8) -> Option<&'a [u8]> {
+        self.read()
+            .filter(|&(actual, _)| actual == tag)
+            .map(|(_, content)| content)
+    }
+
+    pub(super) fn integer(&mut self) -> Option<i64> {
+        self.tagged_integer(INTEGER)
+    }
+
+    /// Return the next integer, if it has the given tag, *e.g.,*
+    /// [`GAUGE32`].
+    pub(super) fn tagged_integer(&mut self, tag: u8) -> Option<i64> {
+        let content = self.expect(tag)?;
+        let (&first, rest) = content.split_first()?;
+        if rest.len() >= 8 {
+            return None;
+        }
+        let first = i64::from(first.cast_signed());
+        Some(
+            rest.iter()
+                .fold(first, |n, &byte| (n << 8) | i64::from(byte)),
+        )
+    }
+
+    pub(super) fn oid(&mut self) -> Option<Vec<u32>> {
+        let content = self.expect(OBJECT_IDENTIFIER)?;
+        if content.last().is_none_or(|&byte| byte & 0x80 != 0) {
+            return None;
+        }
+
+        let mut arcs = Vec::with_capacity(content.len() + 1);
+        let mut arc = 0_u32;
+        for &byte in content {
+            arc = arc.checked_mul(0x80)? | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                if arcs.is_empty() {
+                    let first = (arc / 40).min(2);
+                    arcs.extend([first, arc - first * 40]);
+                } else {
+                    arcs.push(arc);
+                }
+                arc = 0;
+            }
+        }
+        Some(arcs)
+    }
+}
+
+/// Append a value of the given tag and content.
+pub(super) fn write(out: &mut Vec<u8>, tag: u8, content: &[u8]) {
+    out.push(tag);
+    match u8::try_from(content.len()) {
+        Ok(len) if len < 0x80 => out.push(len),
+        _ => {
+            let bytes = content.len().to_be_bytes();
+            let skipped = bytes.iter().take_while(|&&byte| byte == 0).count();
+            #[allow(clippy::cast_possible_truncation)] // At most 8 bytes.
+            out.push(0x80 | (bytes.len() - skipped) as u8);
+            out.extend_from_slice(&bytes[skipped..]);
+        }
+    }
+    out.extend_from_slice(content);
+}
+
+/// Append a value of the given tag, whose content is a sequence of values
+/// written by the given function.
+pub(super) fn write_with(out: &mut Vec<u8>, tag: u8, content: impl FnOnce(&mut Vec<u8>)) {
+    let mut buffer = Vec::default();
+    content(&mut buffer);
+    write(out, tag, &buffer);
+}
+
+/// Append an integer of the given tag, *e.g.,* [`INTEGER`] or [`GAUGE32`].
+pub(super) fn write_integer(out: &mut Vec<u8>, tag: u8, n: i64) {
+    let bytes = n.to_be_bytes();
+    // Skip leading bytes that only extend the sign of the next byte.
+    let skipped = bytes
+        .windows(2)
+        .take_while(|pair| {
+            matches!(pair, [0x00, next] if next & 0x80 == 0)
+                || matches!(pair, [0xFF, next] if next & 0x80 != 0)
+        })
+        .count();
+    write(out, tag, &bytes[skipped..]);
+}
+
+pub(super) fn write_oid(out: &mut Vec<u8>, arcs: &[u32]) {
+    let (first, rest) = match arcs {
+        [a, b, rest @ ..] => (a.saturating_mul(40).saturating_add(*b), rest),
+        [a] => (a.saturating_mul(40), &[][..]),
+        [] => (0, &[][..]),
+    };
+
+    let mut content = Vec::with_capacity(arcs.len() + 4);
+    for arc in core::iter::once(first).chain(rest.iter().copied()) {
+        let mut shift = 28;
+        while shift > 0 && arc >> shift == 0 {
+            shift -= 7;
+        }
+        while shift > 0 {
+            content.push(0x80 | ((arc >> shift) & 0x7F) as u8);
+            shift -= 7;
+        }
+        content.push((arc & 0x7F) as u8);
+    }
+    write(out, OBJECT_IDENTIFIER, &content);
+}
@@ -0,0 +1,330 @@
+#![cfg(test)]
+
+use std::net::UdpSocket;
+use std::thread;
+
+use super::{
+    ber, mib, respond, Agent, Variable, GET_BULK_REQUEST, GET_NEXT_REQUEST, GET_REQUEST,
+    LM_SENSORS_MIB, SET_REQUEST,
+};
+use crate::access::AccessPolicy;
+use crate::backend::{Backend, Reading};
+use crate::errors::{Error, Result};
+use crate::value;
+
+#[derive(Debug)]
+struct Fixed(Option<Vec<Reading>>);
+
+impl Backend for Fixed {
+    fn name(&self) -> &'static str {
+        "fixed"
+    }
+
+    fn read_all(&mut self) -> Result<Vec<Reading>> {
+        self.0.clone().ok_or(Error::UnsupportedPlatform)
+    }
+}
+
+fn readings() -> Vec<Reading> {
+    vec![
+        Reading::for_test(
+            "coretemp-isa-0000",
+            "temp1_input",
+            value::Kind::TemperatureInput,
+            Some(47.5),
+        ),
+        Reading::for_test(
+            "coretemp-isa-0000",
+            "temp1_max",
+            value::Kind::TemperatureMaximum,
+            Some(80.0),
+        ),
+        Reading::for_test(
+            "coretemp-isa-0000",
+            "temp2_input",
+            value::Kind::TemperatureInput,
+            Some(45.0),
+        ),
+        Reading::for_test(
+            "nct6775-isa-0290",
+            "fan1_input",
+            value::Kind::FanInput,
+            Some(1200.0),
+        ),
+        Reading::for_test(
+            "nct6775-isa-0290",
+            "in0_input",
+            value::Kind::VoltageInput,
+            Some(1.104),
+        ),
+    ]
+}
+
+fn oid(suffix: &[u32]) -> Vec<u32> {
+    [&LM_SENSORS_MIB[..], suffix].concat()
+}
+
+fn request(
+    version: i64,
+    community: &str,
+    pdu_type: u8,
+    fields: [i64; 2],
+    oids: &[Vec<u32>],
+) -> Vec<u8> {
+    let mut message = Vec::default();
+    ber::write_with(&mut message, ber::SEQUENCE, |out| {
+        ber::write_integer(out, ber::INTEGER, version);
+        ber::write(out, ber::OCTET_STRING, community.as_bytes());
+        ber::write_with(out, pdu_type, |out| {
+            ber::write_integer(out, ber::INTEGER, 42);
+            ber::write_integer(out, ber::INTEGER, fields[0]);
+            ber::write_integer(out, ber::INTEGER, fields[1]);
+            ber::write_with(out, ber::SEQUENCE, |out| {
+                for oid in oids {
+                    ber::write_with(out, ber::SEQUENCE, |out| {
+                        ber::write_oid(out, oid);
+                        ber::write(out, ber::NULL, &[]);
+                    });
+                }
+            });
+        });
+    });
+    message
+}
+
+/// Decoded response: error status, error index, and variable bindings.
+type Response = (i64, i64, Vec<(Vec<u32>, Variable)>);
+
+fn decode_response(message: &[u8]) -> Response {
+    let mut message = ber::Reader::new(ber::Reader::new(message).expect(ber::SEQUENCE).unwrap());
+    message.integer().unwrap();
+    message.expect(ber::OCTET_STRING).unwrap();
+
+    let mut pdu = ber::Reader::new(message.expect(super::GET_RESPONSE).unwrap());
+    assert_eq!(pdu.integer(), Some(42));
+    let status = pdu.integer().unwrap();
+    let index = pdu.integer().unwrap();
+
+    let mut bindings = ber::Reader::new(pdu.expect(ber::SEQUENCE).unwrap());
+    let mut result = Vec::default();
+    while !bindings.is_empty() {
+        let mut binding = ber::Reader::new(bindings.expect(ber::SEQUENCE).unwrap());
+        let oid = binding.oid().unwrap();
+        let (tag, content) = binding.clone().read().unwrap();
+        let variable = match tag {
+            ber::INTEGER => Variable::Integer(binding.integer().unwrap()),
+            ber::GAUGE32 => {
+                let n = binding.tagged_integer(ber::GAUGE32).unwrap();
+                Variable::Gauge32(u32::try_from(n).unwrap())
+            }
+            ber::OCTET_STRING => {
+                Variable::OctetString(String::from_utf8(content.to_vec()).unwrap())
+            }
+            ber::NULL => Variable::Null,
+            ber::NO_SUCH_OBJECT => Variable::NoSuchObject,
+            ber::NO_SUCH_INSTANCE => Variable::NoSuchInstance,
+            ber::END_OF_MIB_VIEW => Variable::EndOfMibView,
+            _ => panic!("unexpected tag {tag:#x}: {content:?}"),
+        };
+        result.push((oid, variable));
+    }
+    (status, index, result)
+}
+
+fn query(version: i64, pdu_type: u8, fields: [i64; 2], oids: &[Vec<u32>]) -> Response {
+    let mut backend = Fixed(Some(readings()));
+    let message = request(version, "public", pdu_type, fields, oids);
+    decode_response(&respond(&mut backend, &AccessPolicy::new(), &message).unwrap())
+}
+
+#[test]
+fn encoding() {
+    let mut out = Vec::default();
+    ber::write_oid(&mut out, &[1, 3, 6, 1, 4, 1, 2021, 13, 16]);
+    assert_eq!(out, [0x06, 0x09, 0x2B, 6, 1, 4, 1, 0x8F, 0x65, 13, 16]);
+    assert_eq!(ber::Reader::new(&out).oid().unwrap(), LM_SENSORS_MIB);
+
+    for n in [0, 1, 127, 128, 255, 256, -1, -128, -129, i64::MIN, i64::MAX] {
+        let mut out = Vec::default();
+        ber::write_integer(&mut out, ber::INTEGER, n);
+        assert_eq!(ber::Reader::new(&out).integer(), Some(n), "{n}");
+    }
+
+    let mut out = Vec::default();
+    ber::write_integer(&mut out, ber::GAUGE32, i64::from(u32::MAX));
+    assert_eq!(out, [0x42, 5, 0, 0xFF, 0xFF, 0xFF, 0xFF]);
+
+    let mut out = Vec::default();
+    ber::write(&mut out, ber::OCTET_STRING, &[b'x'; 300]);
+    assert_eq!(out[..4], [0x04, 0x82, 0x01, 0x2C]);
+    let (tag, content) = ber::Reader::new(&out).read().unwrap();
+    assert_eq!((tag, content.len()), (ber::OCTET_STRING, 300));
+
+    // Truncated values.
+    assert_eq!(ber::Reader::new(&out[..100]).read(), None);
+    assert_eq!(ber::Reader::new(&[0x06, 0x01, 0x81]).oid(), None);
+}
+
+#[test]
+fn layout() {
+    let mib = mib(&readings());
+    let names: Vec<_> = mib
+        .iter()
+        .map(|(oid, _)| oid[LM_SENSORS_MIB.len()..].to_vec())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            [2, 1, 1, 1],
+            [2, 1, 1, 2],
+            [2, 1, 2, 1],
+            [2, 1, 2, 2],
+            [2, 1, 3, 1],
+            [2, 1, 3, 2],
+            [3, 1, 1, 1],
+            [3, 1, 2, 1],
+            [3, 1, 3, 1],
+            [4, 1, 1, 1],
+            [4, 1, 2, 1],
+            [4, 1, 3, 1],
+        ]
+    );
+    assert!(mib.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    assert_eq!(
+        mib[2].1,
+        Variable::OctetString("coretemp-isa-0000/temp1".into())
+    );
+    assert_eq!(mib[4].1, Variable::Gauge32(47_500));
+    assert_eq!(mib[8].1, Variable::Gauge32(1200));
+    assert_eq!(mib[11].1, Variable::Gauge32(1104));
+
+    let negative = Reading::for_test(
+        "acpitz-acpi-0",
+        "temp1_input",
+        value::Kind::TemperatureInput,
+        Some(-5.0),
+    );
+    assert_eq!(super::mib(&[negative])[2].1, Variable::Gauge32(0));
+}
+
+#[test]
+fn get() {
+    let (status, _, bindings) = query(
+        1,
+        GET_REQUEST,
+        [0, 0],
+        &[oid(&[2, 1, 3, 2]), oid(&[2, 1, 3, 9]), oid(&[7])],
+    );
+    assert_eq!(status, 0);
+    assert_eq!(
+        bindings,
+        [
+            (oid(&[2, 1, 3, 2]), Variable::Gauge32(45_000)),
+            (oid(&[2, 1, 3, 9]), Variable::NoSuchInstance),
+            (oid(&[7]), Variable::NoSuchObject),
+        ]
+    );
+
+    // SNMPv1 reports missing variables as errors.
+    let (status, index, _) = query(0, GET_REQUEST, [0, 0], &[oid(&[2, 1, 3, 2]), oid(&[7])]);
+    assert_eq!((status, index), (super::NO_SUCH_NAME, 2));
+}
+
+#[test]
+fn get_next() {
+    let (status, _, bindings) = query(
+        1,
+        GET_NEXT_REQUEST,
+        [0, 0],
+        &[
+            LM_SENSORS_MIB.to_vec(),
+            oid(&[2, 1, 3, 2]),
+            oid(&[4, 1, 3, 1]),
+        ],
+    );
+    assert_eq!(status, 0);
+    assert_eq!(
+        bindings,
+        [
+            (oid(&[2, 1, 1, 1]), Variable::Integer(1)),
+            (oid(&[3, 1, 1, 1]), Variable::Integer(1)),
+            (oid(&[4, 1, 3, 1]), Variable::EndOfMibView),
+        ]
+    );
+
+    let (status, index, _) = query(0, GET_NEXT_REQUEST, [0, 0], &[oid(&[4, 1, 3, 1])]);
+    assert_eq!((status, index), (super::NO_SUCH_NAME, 1));
+}
+
+#[test]
+fn get_bulk() {
+    let (status, _, bindings) = query(
+        1,
+        GET_BULK_REQUEST,
+        [1, 3],
+        &[LM_SENSORS_MIB.to_vec(), oid(&[2, 1, 3]), oid(&[4, 1, 2])],
+    );
+    assert_eq!(status, 0);
+    let names: Vec<_> = bindings.iter().map(|(oid, _)| oid.clone()).collect();
+    assert_eq!(
+        names,
+        [
+            oid(&[2, 1, 1, 1]),
+            oid(&[2, 1, 3, 1]),
+            oid(&[4, 1, 2, 1]),
+            oid(&[2, 1, 3, 2]),
+            oid(&[4, 1, 3, 1]),
+            oid(&[3, 1, 1, 1]),
+            oid(&[4, 1, 3, 1]),
+        ]
+    );
+    assert_eq!(bindings[6].1, Variable::EndOfMibView);
+
+    // Bulk requests are not part of SNMPv1.
+    let mut backend = Fixed(Some(readings()));
+    let message = request(0, "public", GET_BULK_REQUEST, [0, 3], &[oid(&[2])]);
+    assert_eq!(respond(&mut backend, &AccessPolicy::new(), &message), None);
+}
+
+#[test]
+fn denied() {
+    let (status, index, bindings) = query(1, SET_REQUEST, [0, 0], &[oid(&[2, 1, 3, 1])]);
+    assert_eq!((status, index), (super::NOT_WRITABLE, 1));
+    assert_eq!(bindings, [(oid(&[2, 1, 3, 1]), Variable::Null)]);
+
+    let policy = AccessPolicy::new().auth_token("secret");
+    let mut backend = Fixed(Some(readings()));
+    let message = request(1, "public", GET_REQUEST, [0, 0], &[oid(&[2, 1, 3, 1])]);
+    assert_eq!(respond(&mut backend, &policy, &message), None);
+    assert_eq!(respond(&mut backend, &policy, b"garbage"), None);
+
+    let message = request(1, "secret", GET_REQUEST, [0, 0], &[oid(&[2, 1, 3, 1])]);
+    assert!(respond(&mut backend, &policy, &message).is_some());
+
+    // Failures of the backend are reported as general errors.
+    let mut backend = Fixed(None);
+    let response = respond(&mut backend, &policy, &message).unwrap();
+    assert_eq!(decode_response(&response).0, super::GEN_ERR);
+}
+
+#[test]
+fn agent() {
+    let agent = Agent::bind("127.0.0.1:0").unwrap();
+    let address = agent.local_addr().unwrap();
+    let agent_thread = thread::spawn(move || {
+        agent.serve_one(&mut Fixed(Some(readings()))).unwrap();
+    });
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let message = request(1, "public", GET_REQUEST, [0, 0], &[oid(&[3, 1, 3, 1])]);
+    socket.send_to(&message, address).unwrap();
+
+    let mut buffer = [0_u8; 1024];
+    let len = socket.recv(&mut buffer).unwrap();
+    agent_thread.join().unwrap();
+
+    let (status, _, bindings) = decode_response(&buffer[..len]);
+    assert_eq!(status, 0);
+    assert_eq!(bindings, [(oid(&[3, 1, 3, 1]), Variable::Gauge32(1200))]);
+}
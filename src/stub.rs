@@ -0,0 +1,136 @@
+//! Stub of the LM sensors library, for platforms where it is unavailable.
+//!
+//! This keeps the data model and errors usable, so that applications can
+//! depend on this crate unconditionally. Initialization always fails with
+//! [`Error::UnsupportedPlatform`].
+
+#[cfg(test)]
+mod tests;
+
+use core::convert::Infallible;
+use core::time::Duration;
+use std::fs::File;
+use std::path::PathBuf;
+
+use crate::audit::AuditSink;
+use crate::config::ConfigLayer;
+use crate::errors::{Diagnostic, Error, InitFailure, Listener, Result};
+
+/// LM sensors library initializer.
+///
+/// On this platform, initialization always fails with
+/// [`Error::UnsupportedPlatform`].
+#[derive(Debug, Default)]
+pub struct Initializer {
+    _private: (),
+}
+
+/// LM sensors library instance.
+///
+/// On this platform, no instance can be created.
+#[derive(Debug)]
+pub struct LMSensors {
+    _never: Infallible,
+}
+
+/// LM sensors library instance shared between threads.
+///
+/// On this platform, no instance can be created.
+#[derive(Debug, Clone)]
+pub struct SharedSensors {
+    _never: Infallible,
+}
+
+/// Result of [`Initializer::initialize_with_report`].
+#[derive(Debug)]
+pub struct InitReport {
+    /// Loaded and initialized LM sensors library.
+    pub sensors: LMSensors,
+    /// Configuration errors reported during initialization, in order.
+    pub config_diagnostics: Vec<Diagnostic>,
+}
+
+impl Initializer {
+    /// Return an initializer loading no configuration at all.
+    pub fn isolated() -> Self {
+        Self::default()
+    }
+
+    /// Return an initializer loading the default configuration files.
+    pub fn default_paths() -> Self {
+        Self::default()
+    }
+
+    /// Return an initializer loading the default configuration files,
+    /// but not executing set statements.
+    pub fn minimal() -> Self {
+        Self::default()
+    }
+
+    /// Set whether set statements of the configuration may be executed.
+    #[must_use]
+    pub fn allow_chip_sets(self, _allowed: bool) -> Self {
+        self
+    }
+
+    /// Set the path of the configuration file to be read.
+    #[must_use]
+    pub fn config_path(self, _path: impl Into<PathBuf>) -> Self {
+        self
+    }
+
+    /// Set the configuration contents to be used.
+    #[must_use]
+    pub fn config_file(self, _file: File) -> Self {
+        self
+    }
+
+    /// Set the configuration layers to be concatenated.
+    #[must_use]
+    pub fn config_layers(self, _layers: impl IntoIterator<Item = ConfigLayer>) -> Self {
+        self
+    }
+
+    /// Set the error listener.
+    #[must_use]
+    pub fn error_listener(self, _listener: Box<dyn Listener>) -> Self {
+        self
+    }
+
+    /// Set the tag of the initialized instance.
+    #[must_use]
+    pub fn tag(self, _tag: impl Into<String>) -> Self {
+        self
+    }
+
+    /// Set the sink notified of writes.
+    #[must_use]
+    pub fn audit_sink(self, _sink: Box<dyn AuditSink>) -> Self {
+        self
+    }
+
+    /// Set how long initialization waits for an existing instance
+    /// to be dropped.
+    #[must_use]
+    pub fn wait_for_release(self, _timeout: Duration) -> Self {
+        self
+    }
+
+    /// Fail with [`Error::UnsupportedPlatform`].
+    pub fn initialize(self) -> Result<LMSensors> {
+        Err(Error::UnsupportedPlatform)
+    }
+
+    /// Fail with [`Error::UnsupportedPlatform`].
+    pub fn initialize_with_report(self) -> core::result::Result<InitReport, InitFailure> {
+        Err(InitFailure {
+            error: Error::UnsupportedPlatform,
+            config_diagnostics: Vec::default(),
+        })
+    }
+
+    /// Fail with [`Error::UnsupportedPlatform`].
+    pub fn try_initialize_or_attach(self) -> Result<SharedSensors> {
+        Err(Error::UnsupportedPlatform)
+    }
+}
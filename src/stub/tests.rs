@@ -0,0 +1,26 @@
+#![cfg(test)]
+
+use assert_matches::assert_matches;
+
+use crate::errors::Error;
+use crate::Initializer;
+
+#[test]
+fn initialize() {
+    let err = Initializer::default().initialize().unwrap_err();
+    assert_matches!(err, Error::UnsupportedPlatform);
+
+    let failure = Initializer::isolated()
+        .allow_chip_sets(false)
+        .initialize_with_report()
+        .unwrap_err();
+    assert_eq!(failure.error.code(), "unsupported_platform");
+    assert!(failure.config_diagnostics.is_empty());
+}
+
+#[test]
+fn diagnostic() {
+    let message = Error::UnsupportedPlatform.to_string();
+    assert!(message.contains(std::env::consts::OS));
+    assert!(message.contains("backend::native()"));
+}
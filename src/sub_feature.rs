@@ -6,14 +6,22 @@ mod tests;
 
 use core::ffi::CStr;
 use core::fmt;
+use core::future::Future;
+use core::ops::ControlFlow;
+use core::time::Duration;
 use std::io;
 use std::os::raw::{c_int, c_uint};
+use std::thread;
+use std::time::{Instant, SystemTime};
 
 use bitflags::bitflags;
 use sensors_sys::*;
 
+use crate::audit::{self, AuditRecord};
+use crate::capabilities::Capabilities;
 use crate::errors::{Error, Result};
 use crate::feature::FeatureRef;
+use crate::redact::Redacted;
 use crate::utils::api_access_lock;
 use crate::value::{Kind, Value};
 
@@ -21,7 +29,7 @@ use crate::value::{Kind, Value};
 /// provided by a [`Chip`].
 ///
 /// [`Kind`]: crate::value::Kind
-#[derive(Debug, Clone, Copy, Eq)]
+#[derive(Clone, Copy, Eq)]
 pub struct SubFeatureRef<'a> {
     pub(crate) feature: FeatureRef<'a>,
     pub(crate) raw: &'a sensors_subfeature,
@@ -34,6 +42,15 @@ impl<'a> SubFeatureRef<'a> {
         self.raw
     }
 
+    /// Returns a pointer to the raw data structure [`sensors_subfeature`].
+    ///
+    /// The pointer is valid for the lifetime `'a`, and must not be used
+    /// to change the raw data structure.
+    #[must_use]
+    pub fn as_raw_ptr(self) -> *const sensors_subfeature {
+        self.raw
+    }
+
     /// Return the feature to which this sub-feature belongs.
     #[must_use]
     pub fn feature(self) -> FeatureRef<'a> {
@@ -50,6 +67,14 @@ impl<'a> SubFeatureRef<'a> {
             .map(|name| name.to_str().map_err(Into::into))
     }
 
+    /// Return this sub-feature, formatted with serial-number-like strings
+    /// redacted, *e.g.,* for public bug reports.
+    ///
+    /// See: [`Redacted`].
+    pub fn redact(self) -> Redacted<Self> {
+        Redacted::new(self)
+    }
+
     /// Return the number of this sub-feature.
     #[must_use]
     pub fn number(self) -> c_int {
@@ -77,13 +102,163 @@ impl<'a> SubFeatureRef<'a> {
     /// Return the value reported by this sub-feature, *e.g.,* sensor.
     pub fn value(self) -> Result<Value> {
         let value = self.raw_value()?;
+        self.value_from_raw(value)
+    }
+
+    /// Return the value reported by this sub-feature, along with the time
+    /// at which it was read.
+    ///
+    /// The time is captured while the library is locked, right after
+    /// reading the value, so it is not delayed by waiting for other threads
+    /// using the library. This makes it suitable for computing rates of
+    /// change, *e.g.,* of energy inputs.
+    pub fn timed_value(self) -> Result<Timed<Value>> {
+        let timed = self.timed_raw_value()?;
+        let value = self.value_from_raw(timed.value)?;
+        Ok(Timed {
+            value,
+            time: timed.time,
+        })
+    }
+
+    /// Return the raw value reported by this sub-feature, along with
+    /// the time at which it was read.
+    ///
+    /// See: [`SubFeatureRef::timed_value`].
+    pub fn timed_raw_value(self) -> Result<Timed<f64>> {
+        let mut result = 0.0_f64;
+
+        let (r, time) = api_access_lock()
+            .lock()
+            // Safety: `result` was properly initialized.
+            .map(|_guard| unsafe {
+                let r = sensors_get_value(self.feature.chip.raw_ref(), self.number(), &mut result);
+                (r, Instant::now())
+            })?;
+        if r == 0 {
+            Ok(Timed {
+                value: result,
+                time,
+            })
+        } else {
+            Err(Error::from_lm_sensors("sensors_get_value()", r))
+        }
+    }
+
+    /**
+    Return the median of `samples` values reported by this sub-feature,
+    read `delay` apart, *e.g.,* to discard an occasional spurious value.
+
+    All values are read within a single lock of the library, so other
+    threads using the library wait until all samples are read. Values that
+    cannot be read are ignored, unless none can be read.
+
+    This fails with [`io::ErrorKind::InvalidInput`] if `samples` is zero.
+
+    # Example
+
+    ```no_run
+    # use core::time::Duration;
+    # fn f(temp: lm_sensors::SubFeatureRef) -> lm_sensors::errors::Result<()> {
+    let temperature = temp.value_stable(5, Duration::from_millis(10))?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn value_stable(self, samples: usize, delay: Duration) -> Result<Value> {
+        let value = self.raw_value_stable(samples, delay)?;
+        self.value_from_raw(value)
+    }
+
+    /// Return the median of `samples` raw values reported by this
+    /// sub-feature, read `delay` apart.
+    ///
+    /// See: [`SubFeatureRef::value_stable`].
+    pub fn raw_value_stable(self, samples: usize, delay: Duration) -> Result<f64> {
+        let chip = self.feature.chip.raw_ref();
+        let number = self.number();
+
+        let _guard = api_access_lock().lock()?;
+        let read = || {
+            let mut result = 0.0_f64;
+            // Safety: `result` was properly initialized, and the API lock is held.
+            let r = unsafe { sensors_get_value(chip, number, &mut result) };
+            if r == 0 {
+                Ok(result)
+            } else {
+                Err(Error::from_lm_sensors("sensors_get_value()", r))
+            }
+        };
+        median_of_samples(samples, read, || thread::sleep(delay))
+    }
+
+    /**
+    Wait until the value reported by this sub-feature differs from its
+    current value by more than `epsilon`, polling it every `poll_interval`.
+
+    This returns the new value, or `None` if `timeout` elapsed.
+    `epsilon` is expressed in the unit of the raw value, *e.g.,* RPM.
+
+    # Example
+
+    ```no_run
+    # use core::time::Duration;
+    # fn f(sensors: &lm_sensors::LMSensors, pwm: lm_sensors::SubFeatureRef,
+    #     fan: lm_sensors::SubFeatureRef) -> lm_sensors::errors::Result<()> {
+    pwm.set_raw_value(sensors.capabilities(), 255.0)?;
+    let fan_speed = fan.wait_for_change(
+        Duration::from_millis(250),
+        100.0,
+        Duration::from_secs(5),
+    )?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn wait_for_change(
+        self,
+        poll_interval: Duration,
+        epsilon: f64,
+        timeout: Duration,
+    ) -> Result<Option<Value>> {
+        let waiter = ChangeWaiter::new(poll_interval, epsilon, timeout);
+        let value = waiter.wait(|| self.raw_value(), thread::sleep)?;
+        value.map(|value| self.value_from_raw(value)).transpose()
+    }
+
+    /**
+    Asynchronous version of [`SubFeatureRef::wait_for_change`].
+
+    `sleep` is called to wait for a given duration without blocking,
+    *e.g.,* `tokio::time::sleep`. This makes this function independent from
+    any particular asynchronous runtime.
+
+    Reading the value still blocks, but only briefly.
+    */
+    pub async fn wait_for_change_async<S, F>(
+        self,
+        poll_interval: Duration,
+        epsilon: f64,
+        timeout: Duration,
+        sleep: S,
+    ) -> Result<Option<Value>>
+    where
+        S: FnMut(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        let waiter = ChangeWaiter::new(poll_interval, epsilon, timeout);
+        let value = waiter.wait_async(|| self.raw_value(), sleep).await?;
+        value.map(|value| self.value_from_raw(value)).transpose()
+    }
+
+    fn value_from_raw(self, value: f64) -> Result<Value> {
         Value::from_raw(self.raw_kind(), value)
             .ok_or_else(|| Error::from_io("Value::from_raw", io::ErrorKind::InvalidData.into()))
     }
 
     /// Set the value associated with this sub-feature, *e.g.,* actuator.
-    pub fn set_value(self, new_value: &Value) -> Result<()> {
-        self.set_raw_value(new_value.raw_value())
+    pub fn set_value(self, capabilities: Capabilities<'_>, new_value: &Value) -> Result<()> {
+        self.set_raw_value(capabilities, new_value.raw_value())
     }
 
     /// Return the raw name of this sub-feature, if available.
@@ -128,10 +303,108 @@ impl<'a> SubFeatureRef<'a> {
         }
     }
 
+    /**
+    Gradually move the value associated with this sub-feature (*e.g.,* a pump
+    or fan speed) to `target`, changing it by at most `step` every `interval`.
+
+    `progress` is called with each raw value written. Returning
+    [`ControlFlow::Break`] from it cancels the ramp, leaving the actuator at
+    the last written value.
+
+    This returns `true` if the target value was reached,
+    or `false` if the ramp was cancelled.
+
+    # Example
+
+    ```no_run
+    # use core::ops::ControlFlow;
+    # use core::time::Duration;
+    # fn f(sensors: &lm_sensors::LMSensors, fan_min: lm_sensors::SubFeatureRef)
+    #     -> lm_sensors::errors::Result<()> {
+    let capabilities = sensors.capabilities();
+    let target = lm_sensors::Value::FanMinimum(1200.0);
+    let interval = Duration::from_millis(100);
+    fan_min.set_value_ramped(capabilities, &target, 100.0, interval, |value| {
+        println!("Minimum fan speed: {value}");
+        ControlFlow::Continue(())
+    })?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_value_ramped(
+        self,
+        capabilities: Capabilities<'_>,
+        target: &Value,
+        step: f64,
+        interval: Duration,
+        progress: impl FnMut(f64) -> ControlFlow<()>,
+    ) -> Result<bool> {
+        self.set_raw_value_ramped(capabilities, target.raw_value(), step, interval, progress)
+    }
+
+    /// Gradually move the raw value associated with this sub-feature
+    /// to `target`.
+    ///
+    /// See: [`SubFeatureRef::set_value_ramped`].
+    pub fn set_raw_value_ramped(
+        self,
+        capabilities: Capabilities<'_>,
+        target: f64,
+        step: f64,
+        interval: Duration,
+        progress: impl FnMut(f64) -> ControlFlow<()>,
+    ) -> Result<bool> {
+        if !(target.is_finite() && step.is_finite() && step > 0.0) {
+            let err = io::ErrorKind::InvalidInput.into();
+            return Err(Error::from_io("SubFeatureRef::set_raw_value_ramped()", err));
+        }
+
+        let start = self.raw_value()?;
+        ramp(
+            start,
+            target,
+            step,
+            |value| self.set_raw_value(capabilities, value),
+            || thread::sleep(interval),
+            progress,
+        )
+    }
+
     /// Set the raw value associated with this sub-feature, *e.g.,* actuator.
     ///
-    /// See: [`sensors_set_value`].
-    pub fn set_raw_value(self, new_value: f64) -> Result<()> {
+    /// The write is recorded by the audit sink of the library instance,
+    /// if any, and published as an [`Event::WriteAudited`].
+    /// See: [`sensors_set_value`], [`crate::audit`].
+    ///
+    /// [`Event::WriteAudited`]: crate::events::Event::WriteAudited
+    pub fn set_raw_value(self, capabilities: Capabilities<'_>, new_value: f64) -> Result<()> {
+        if !capabilities.audited() {
+            return self.write_raw_value(new_value);
+        }
+
+        let old_value = self.raw_value().ok();
+        let time = SystemTime::now();
+        let result = self.write_raw_value(new_value);
+
+        let chip = self.feature.chip;
+        let name = self
+            .raw_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        capabilities.audit(AuditRecord {
+            time,
+            operation: "sensors_set_value()",
+            chip: chip.to_string(),
+            path: chip.path().zip(name.as_ref()).map(|(p, n)| p.join(n)),
+            sub_feature: name,
+            old_value,
+            new_value: Some(new_value),
+            error: result.as_ref().err().map(audit::describe),
+        });
+        result
+    }
+
+    fn write_raw_value(self, new_value: f64) -> Result<()> {
         let chip = self.feature.chip.raw_ref();
         let number = self.number();
         let r = api_access_lock()
@@ -146,6 +419,167 @@ impl<'a> SubFeatureRef<'a> {
     }
 }
 
+/// Value read at some time.
+///
+/// See: [`SubFeatureRef::timed_value`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timed<T> {
+    /// Value read.
+    pub value: T,
+    /// Monotonic time at which the value was read.
+    pub time: Instant,
+}
+
+impl<T> Timed<T> {
+    /// Return the time elapsed between `earlier` and this value.
+    ///
+    /// This is zero if `earlier` was read later than this value.
+    #[must_use]
+    pub fn since<U>(&self, earlier: &Timed<U>) -> Duration {
+        self.time.saturating_duration_since(earlier.time)
+    }
+}
+
+/// Write values from `start` to `target`, by increments of at most `step`.
+///
+/// See: [`SubFeatureRef::set_value_ramped`].
+fn ramp(
+    start: f64,
+    target: f64,
+    step: f64,
+    mut write: impl FnMut(f64) -> Result<()>,
+    mut sleep: impl FnMut(),
+    mut progress: impl FnMut(f64) -> ControlFlow<()>,
+) -> Result<bool> {
+    let mut value = start;
+    while value != target {
+        value = if target > value {
+            f64::min(value + step, target)
+        } else {
+            f64::max(value - step, target)
+        };
+
+        write(value)?;
+        if progress(value).is_break() {
+            return Ok(false);
+        }
+
+        if value != target {
+            sleep();
+        }
+    }
+    Ok(true)
+}
+
+/// Return the median of `samples` values returned by `read`,
+/// calling `sleep` between reads.
+///
+/// See: [`SubFeatureRef::value_stable`].
+fn median_of_samples(
+    samples: usize,
+    mut read: impl FnMut() -> Result<f64>,
+    mut sleep: impl FnMut(),
+) -> Result<f64> {
+    if samples == 0 {
+        let err = io::ErrorKind::InvalidInput.into();
+        return Err(Error::from_io("SubFeatureRef::raw_value_stable()", err));
+    }
+
+    let mut values = Vec::with_capacity(samples);
+    let mut last_error = None;
+    for index in 0..samples {
+        if index > 0 {
+            sleep();
+        }
+        match read() {
+            Ok(value) if value.is_nan() => {}
+            Ok(value) => values.push(value),
+            Err(err) => last_error = Some(err),
+        }
+    }
+
+    if values.is_empty() {
+        return Err(last_error.unwrap_or_else(|| {
+            let err = io::ErrorKind::InvalidData.into();
+            Error::from_io("SubFeatureRef::raw_value_stable()", err)
+        }));
+    }
+
+    values.sort_unstable_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        Ok(values[middle])
+    } else {
+        Ok((values[middle - 1] + values[middle]) / 2.0)
+    }
+}
+
+/// Polling policy of [`SubFeatureRef::wait_for_change`].
+#[derive(Debug, Clone, Copy)]
+struct ChangeWaiter {
+    poll_interval: Duration,
+    epsilon: f64,
+    timeout: Duration,
+}
+
+impl ChangeWaiter {
+    fn new(poll_interval: Duration, epsilon: f64, timeout: Duration) -> Self {
+        Self {
+            poll_interval,
+            epsilon: epsilon.abs(),
+            timeout,
+        }
+    }
+
+    /// Return how long to sleep before the next poll, or `None` if timed out.
+    fn next_sleep(&self, start: Instant) -> Option<Duration> {
+        let remaining = self.timeout.checked_sub(start.elapsed())?;
+        (!remaining.is_zero()).then(|| remaining.min(self.poll_interval))
+    }
+
+    fn is_change(&self, initial: f64, value: f64) -> bool {
+        (value - initial).abs() > self.epsilon
+    }
+
+    fn wait(
+        &self,
+        mut read: impl FnMut() -> Result<f64>,
+        mut sleep: impl FnMut(Duration),
+    ) -> Result<Option<f64>> {
+        let start = Instant::now();
+        let initial = read()?;
+
+        while let Some(duration) = self.next_sleep(start) {
+            sleep(duration);
+
+            let value = read()?;
+            if self.is_change(initial, value) {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn wait_async<F: Future<Output = ()>>(
+        &self,
+        mut read: impl FnMut() -> Result<f64>,
+        mut sleep: impl FnMut(Duration) -> F,
+    ) -> Result<Option<f64>> {
+        let start = Instant::now();
+        let initial = read()?;
+
+        while let Some(duration) = self.next_sleep(start) {
+            sleep(duration).await;
+
+            let value = read()?;
+            if self.is_change(initial, value) {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+}
+
 impl<'a> PartialEq for SubFeatureRef<'a> {
     fn eq(&self, other: &Self) -> bool {
         self.feature == other.feature
@@ -157,6 +591,20 @@ impl<'a> PartialEq for SubFeatureRef<'a> {
     }
 }
 
+/// Formats the fields of the sub-feature that are readable without locking,
+/// *i.e.,* not its value.
+impl<'a> fmt::Debug for SubFeatureRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubFeatureRef")
+            .field("feature", &self.feature)
+            .field("name", &self.raw_name())
+            .field("number", &self.number())
+            .field("kind", &self.kind())
+            .field("flags", &self.flags())
+            .finish()
+    }
+}
+
 impl<'a> fmt::Display for SubFeatureRef<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(name) = self.raw_name() {
@@ -169,6 +617,7 @@ impl<'a> fmt::Display for SubFeatureRef<'a> {
 
 bitflags! {
     /// Flags of a sub-feature of a chip.
+    #[derive(Debug)]
     #[repr(transparent)]
     pub struct Flags: c_uint {
         /// Sub-feature is readable, *e.g.,* sensor data.
@@ -23,6 +23,7 @@ fn new_sub_feature_ref() {
             .unwrap()
     };
     let sub_feature_ref = unsafe { s.new_sub_feature_ref(feature_ref, sub_feature) };
+    assert_eq!(sub_feature_ref.as_raw_ptr(), sub_feature as *const _);
 
     assert!(!sub_feature_ref.to_string().is_empty());
 }
@@ -42,7 +43,208 @@ fn feature_iter() {
         let _flags = sub_feature.flags().unwrap();
 
         if let Ok(value) = sub_feature.raw_value() {
-            drop(sub_feature.set_raw_value(value));
+            drop(sub_feature.set_raw_value(s.capabilities(), value));
         }
     }
 }
+
+#[test]
+fn wait_for_change() {
+    use core::time::Duration;
+
+    use super::ChangeWaiter;
+
+    let waiter = ChangeWaiter::new(Duration::from_millis(1), 10.0, Duration::from_secs(5));
+
+    let mut values = [1000.0, 1005.0, 990.0, 1011.0, 2000.0].into_iter();
+    let mut sleeps = 0;
+    let r = waiter.wait(|| Ok(values.next().unwrap()), |_d| sleeps += 1);
+    assert_eq!(r.unwrap(), Some(1011.0));
+    assert_eq!(sleeps, 3);
+
+    let waiter = ChangeWaiter::new(Duration::from_millis(1), 10.0, Duration::from_millis(20));
+    let r = waiter.wait(|| Ok(1000.0), std::thread::sleep);
+    assert_eq!(r.unwrap(), None);
+
+    let r = waiter.wait(
+        || {
+            let err = std::io::ErrorKind::Other.into();
+            Err(crate::errors::Error::from_io("read", err))
+        },
+        |_d| (),
+    );
+    r.unwrap_err();
+}
+
+#[test]
+fn wait_for_change_async() {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use core::time::Duration;
+
+    use super::ChangeWaiter;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    let waiter = ChangeWaiter::new(Duration::from_millis(1), 10.0, Duration::from_secs(5));
+
+    let mut values = [1000.0, 995.0, 1020.0].into_iter();
+    let mut sleeps = Vec::new();
+    let r = block_on(waiter.wait_async(
+        || Ok(values.next().unwrap()),
+        |d| {
+            sleeps.push(d);
+            async {}
+        },
+    ));
+    assert_eq!(r.unwrap(), Some(1020.0));
+    assert_eq!(sleeps, [Duration::from_millis(1); 2]);
+
+    let waiter = ChangeWaiter::new(Duration::from_millis(1), 10.0, Duration::from_millis(20));
+    let r = block_on(waiter.wait_async(|| Ok(1000.0), |d| async move { std::thread::sleep(d) }));
+    assert_eq!(r.unwrap(), None);
+}
+
+#[test]
+fn ramp() {
+    use core::ops::ControlFlow;
+
+    let mut written = Vec::new();
+    let mut sleeps = 0;
+    let r = super::ramp(
+        10.0,
+        35.0,
+        10.0,
+        |v| {
+            written.push(v);
+            Ok(())
+        },
+        || sleeps += 1,
+        |_v| ControlFlow::Continue(()),
+    );
+    assert!(r.unwrap());
+    assert_eq!(written, [20.0, 30.0, 35.0]);
+    assert_eq!(sleeps, 2);
+
+    written.clear();
+    let r = super::ramp(
+        35.0,
+        10.0,
+        10.0,
+        |v| {
+            written.push(v);
+            Ok(())
+        },
+        || (),
+        |v| {
+            if v < 20.0 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        },
+    );
+    assert!(!r.unwrap());
+    assert_eq!(written, [25.0, 15.0]);
+
+    let r = super::ramp(
+        5.0,
+        5.0,
+        1.0,
+        |_v| unreachable!(),
+        || (),
+        |_v| unreachable!(),
+    );
+    assert!(r.unwrap());
+
+    let r = super::ramp(
+        0.0,
+        5.0,
+        1.0,
+        |_v| {
+            let err = std::io::ErrorKind::Other.into();
+            Err(crate::errors::Error::from_io("write", err))
+        },
+        || (),
+        |_v| ControlFlow::Continue(()),
+    );
+    r.unwrap_err();
+}
+
+#[test]
+fn median_of_samples() {
+    use crate::errors::Error;
+
+    let mut values = [3.0, 100.0, 1.0, 2.0, -50.0].into_iter();
+    let mut sleeps = 0;
+    let r = super::median_of_samples(5, || Ok(values.next().unwrap()), || sleeps += 1);
+    assert_eq!(r.unwrap(), 2.0);
+    assert_eq!(sleeps, 4);
+
+    let mut values = [4.0, 1.0, f64::NAN, 2.0, 10.0].into_iter();
+    let r = super::median_of_samples(5, || Ok(values.next().unwrap()), || ());
+    assert_eq!(r.unwrap(), 3.0);
+
+    let mut values = [
+        Ok(5.0),
+        Err(Error::from_lm_sensors("sensors_get_value()", 3)),
+    ]
+    .into_iter();
+    let r = super::median_of_samples(2, || values.next().unwrap(), || ());
+    assert_eq!(r.unwrap(), 5.0);
+
+    let r = super::median_of_samples(
+        2,
+        || Err(Error::from_lm_sensors("sensors_get_value()", 3)),
+        || (),
+    );
+    assert_eq!(r.unwrap_err().code(), "lm_sensors");
+
+    let r = super::median_of_samples(0, || Ok(1.0), || ());
+    assert_eq!(r.unwrap_err().code(), "io");
+}
+
+#[test]
+#[serial]
+fn value_stable() {
+    use core::time::Duration;
+
+    let s = crate::Initializer::default().initialize().unwrap();
+    let chip0 = s.chip_iter(None).next().unwrap();
+    let feature0 = chip0.feature_iter().next().unwrap();
+    let sub_feature = feature0.sub_feature_iter().next().unwrap();
+
+    let value = sub_feature
+        .value_stable(3, Duration::from_millis(1))
+        .unwrap();
+    assert_eq!(value.kind(), sub_feature.kind().unwrap());
+    assert!(sub_feature.value_stable(0, Duration::ZERO).is_err());
+}
+
+#[test]
+#[serial]
+fn timed_value() {
+    use std::time::Instant;
+
+    let s = crate::Initializer::default().initialize().unwrap();
+    let chip0 = s.chip_iter(None).next().unwrap();
+    let feature0 = chip0.feature_iter().next().unwrap();
+    let sub_feature = feature0.sub_feature_iter().next().unwrap();
+
+    let start = Instant::now();
+    let first = sub_feature.timed_value().unwrap();
+    let second = sub_feature.timed_raw_value().unwrap();
+    assert!(start <= first.time && first.time <= second.time);
+    assert_eq!(first.value.kind(), sub_feature.kind().unwrap());
+    assert_eq!(first.since(&second), core::time::Duration::ZERO);
+    assert_eq!(second.since(&first), second.time - first.time);
+}
@@ -0,0 +1,111 @@
+//! Identification of the system running this process.
+//!
+//! [`board_info`] reads the DMI identification of the board, the product
+//! and the BIOS from `/sys/class/dmi/id`, *e.g.,* to select the
+//! [quirks](crate::quirks) of the board.
+
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::path::Path;
+
+use crate::quirks::Board;
+
+/// Default directory of DMI identification attributes.
+pub const DEFAULT_DMI_ROOT: &str = "/sys/class/dmi/id";
+
+/// Values reported by firmwares that were not configured by the vendor.
+const PLACEHOLDERS: &[&str] = &[
+    "Default string",
+    "Not Applicable",
+    "Not Specified",
+    "O.E.M.",
+    "System manufacturer",
+    "System Product Name",
+    "System Version",
+    "To Be Filled By O.E.M.",
+];
+
+/// DMI identification of the board, the product and the BIOS.
+///
+/// Attributes that cannot be read, or that hold placeholder values
+/// (*e.g.,* `To Be Filled By O.E.M.`), are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BoardInfo {
+    /// Vendor of the board, *i.e.,* `board_vendor`.
+    pub board_vendor: Option<String>,
+    /// Name of the board, *i.e.,* `board_name`.
+    pub board_name: Option<String>,
+    /// Version of the board, *i.e.,* `board_version`.
+    pub board_version: Option<String>,
+    /// Vendor of the product, *i.e.,* `sys_vendor`.
+    pub product_vendor: Option<String>,
+    /// Name of the product, *i.e.,* `product_name`.
+    pub product_name: Option<String>,
+    /// Version of the product, *i.e.,* `product_version`.
+    pub product_version: Option<String>,
+    /// Vendor of the BIOS, *i.e.,* `bios_vendor`.
+    pub bios_vendor: Option<String>,
+    /// Version of the BIOS, *i.e.,* `bios_version`.
+    pub bios_version: Option<String>,
+    /// Release date of the BIOS, *i.e.,* `bios_date`.
+    pub bios_date: Option<String>,
+}
+
+impl BoardInfo {
+    /// Return the identity of the board, if its vendor and name are known.
+    #[must_use]
+    pub fn board(&self) -> Option<Board> {
+        let vendor = self.board_vendor.as_deref()?;
+        let name = self.board_name.as_deref()?;
+        Some(Board::new(vendor, name))
+    }
+}
+
+/**
+Return the DMI identification of the system, read from [`DEFAULT_DMI_ROOT`].
+
+All attributes are `None` on systems lacking DMI, *e.g.,* most ARM boards.
+
+# Example
+
+```rust
+let info = lm_sensors::system::board_info();
+if let Some(board) = info.board() {
+    println!("{} {}", board.vendor, board.name);
+}
+```
+*/
+#[must_use]
+pub fn board_info() -> BoardInfo {
+    board_info_from(DEFAULT_DMI_ROOT)
+}
+
+/// Return the DMI identification of the system, read from the given
+/// directory instead of [`DEFAULT_DMI_ROOT`].
+#[must_use]
+pub fn board_info_from(root: impl AsRef<Path>) -> BoardInfo {
+    let root = root.as_ref();
+    let read = |name: &str| read_attribute(&root.join(name));
+
+    BoardInfo {
+        board_vendor: read("board_vendor"),
+        board_name: read("board_name"),
+        board_version: read("board_version"),
+        product_vendor: read("sys_vendor"),
+        product_name: read("product_name"),
+        product_version: read("product_version"),
+        bios_vendor: read("bios_vendor"),
+        bios_version: read("bios_version"),
+        bios_date: read("bios_date"),
+    }
+}
+
+fn read_attribute(path: &Path) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?;
+    let value = value.trim();
+    let placeholder = PLACEHOLDERS.iter().any(|p| p.eq_ignore_ascii_case(value));
+    (!value.is_empty() && !placeholder).then(|| value.into())
+}
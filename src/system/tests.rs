@@ -0,0 +1,40 @@
+#![cfg(test)]
+
+use std::fs;
+
+use crate::quirks::Board;
+use crate::system::{board_info_from, BoardInfo};
+
+#[test]
+fn board_info() {
+    let root = tempfile::tempdir().unwrap();
+    let write = |name: &str, contents: &str| fs::write(root.path().join(name), contents).unwrap();
+    write("board_vendor", "ASUSTeK COMPUTER INC.\n");
+    write("board_name", "PRIME X570-PRO\n");
+    write("board_version", "Rev X.0x\n");
+    write("sys_vendor", "System manufacturer\n");
+    write("product_name", "To Be Filled By O.E.M.\n");
+    write("bios_version", "4021\n");
+    write("bios_date", "\n");
+
+    let info = board_info_from(root.path());
+    assert_eq!(info.board_vendor.as_deref(), Some("ASUSTeK COMPUTER INC."));
+    assert_eq!(info.board_version.as_deref(), Some("Rev X.0x"));
+    assert_eq!(info.bios_version.as_deref(), Some("4021"));
+    // Placeholders, empty and missing attributes.
+    assert_eq!(info.product_vendor, None);
+    assert_eq!(info.product_name, None);
+    assert_eq!(info.bios_date, None);
+    assert_eq!(info.bios_vendor, None);
+
+    let board = Board::new("ASUSTeK COMPUTER INC.", "PRIME X570-PRO");
+    assert_eq!(info.board(), Some(board));
+}
+
+#[test]
+fn no_dmi() {
+    let root = tempfile::tempdir().unwrap();
+    let info = board_info_from(root.path().join("missing"));
+    assert_eq!(info, BoardInfo::default());
+    assert_eq!(info.board(), None);
+}
@@ -0,0 +1,101 @@
+//! Helpers for test suites using the LM sensors library.
+//!
+//! The LM sensors library is a process-wide singleton, so tests using it
+//! cannot run concurrently. The helpers of this module serialize such tests,
+//! and guarantee that the library is cleaned up after each test, even if
+//! the test panics.
+//!
+//! This module requires the `testing` feature.
+
+#[cfg(test)]
+mod tests;
+
+use core::sync::atomic;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, PoisonError};
+
+use crate::errors::{Reporter, Result};
+use crate::utils::{api_access_lock, CallBacks};
+use crate::{Initializer, LMSensors};
+
+static FRESH_LIBRARY_LOCK: Mutex<()> = Mutex::new(());
+
+/**
+Call the given function with a freshly initialized instance of
+the LM sensors library, then clean it up.
+
+The library is initialized with [`Initializer::default`].
+
+See: [`with_fresh_library_from`].
+
+# Example
+
+```rust
+let chip_count = lm_sensors::testing::with_fresh_library(|sensors| {
+    sensors.chip_iter(None).count()
+})?;
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub fn with_fresh_library<R>(f: impl FnOnce(&LMSensors) -> R) -> Result<R> {
+    with_fresh_library_from(Initializer::default(), f)
+}
+
+/**
+Call the given function with an instance of the LM sensors library
+freshly initialized by the given initializer, then clean it up.
+
+Calls are serialized across all threads of the process.
+When the function returns or panics, the library is cleaned up, and the
+error call backs of the LM sensors library are restored to what they were
+before initialization, unless another thread initialized the library since.
+A panic of the function is then propagated.
+
+This fails if the library was already initialized outside of this function.
+
+# Example
+
+```rust
+let chip_count = lm_sensors::testing::with_fresh_library_from(
+    lm_sensors::Initializer::isolated(),
+    |sensors| sensors.chip_iter(None).count(),
+)?;
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub fn with_fresh_library_from<R>(
+    initializer: Initializer,
+    f: impl FnOnce(&LMSensors) -> R,
+) -> Result<R> {
+    // A panic in a previous call does not leave the library initialized.
+    let _guard = FRESH_LIBRARY_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    // Other threads might initialize the library outside of this function,
+    // so the call backs are only accessed while holding the API access lock.
+    let call_backs = {
+        let api_guard = api_access_lock().lock()?;
+        // Safety: reading the call backs is assumed to be safe.
+        unsafe { CallBacks::current(&api_guard) }
+    };
+
+    let sensors = initializer.initialize()?;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&sensors)));
+    drop(sensors);
+
+    if let Ok(api_guard) = api_access_lock().lock() {
+        // Another thread might have initialized the library since,
+        // with its own call backs.
+        if !crate::INITIALIZED.load(atomic::Ordering::Acquire) {
+            // Safety: the library is not initialized anymore.
+            unsafe { call_backs.set(&api_guard) };
+        }
+        let _ignored = Reporter::take_listener_panic();
+    }
+
+    match result {
+        Ok(r) => Ok(r),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
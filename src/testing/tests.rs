@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use std::panic;
+
+use assert_matches::assert_matches;
+use serial_test::serial;
+
+use super::{with_fresh_library, with_fresh_library_from};
+use crate::errors::Error;
+
+#[test]
+#[serial]
+fn fresh_library() {
+    let version = with_fresh_library(|s| s.version().map(String::from)).unwrap();
+    assert!(version.is_some());
+
+    // The library was cleaned up.
+    let _s = crate::Initializer::isolated().initialize().unwrap();
+
+    // Initialization fails if the library is already initialized.
+    let err = with_fresh_library(|_s| ()).unwrap_err();
+    assert_matches!(err, Error::IO { .. });
+}
+
+#[test]
+#[serial]
+fn fresh_library_panic() {
+    let r = panic::catch_unwind(|| {
+        with_fresh_library_from(crate::Initializer::isolated(), |_s| {
+            panic!("panicking test function")
+        })
+    });
+    assert!(r.is_err());
+
+    // The library was cleaned up.
+    with_fresh_library_from(crate::Initializer::isolated(), |_s| ()).unwrap();
+}
+
+#[test]
+#[serial]
+fn fresh_library_race() {
+    use std::thread;
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                for _ in 0..50 {
+                    // Direct initializations of other threads make this fail.
+                    let r = with_fresh_library_from(crate::Initializer::isolated(), |_s| ());
+                    if let Err(err) = r {
+                        assert_matches!(err, Error::IO { .. });
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for _ in 0..50 {
+        drop(crate::Initializer::isolated().initialize());
+    }
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    // The library was cleaned up.
+    with_fresh_library_from(crate::Initializer::isolated(), |_s| ()).unwrap();
+}
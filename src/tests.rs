@@ -2,6 +2,7 @@
 
 use std::fs::File;
 
+use assert_matches::assert_matches;
 use serial_test::serial;
 
 #[test]
@@ -125,3 +126,677 @@ fn list_all() {
         }
     }
 }
+
+#[test]
+#[serial]
+fn init_panicking_error_listener() {
+    use std::io::Write;
+
+    #[derive(Debug)]
+    struct EL;
+
+    impl super::errors::Listener for EL {
+        fn on_lm_sensors_config_error(
+            &self,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+            panic!("panicking error listener")
+        }
+
+        fn on_lm_sensors_fatal_error(&self, _error: &str, _procedure: &str) {
+            unreachable!()
+        }
+    }
+
+    let mut config = tempfile::NamedTempFile::new().unwrap();
+    writeln!(config, "this is not a valid configuration statement").unwrap();
+
+    let err = super::Initializer::default()
+        .config_path(config.path())
+        .error_listener(Box::new(EL))
+        .initialize()
+        .unwrap_err();
+    assert_matches!(err, super::errors::Error::ListenerPanicked { .. });
+
+    // Global state was restored.
+    let _s = super::Initializer::default()
+        .config_path("/dev/null")
+        .initialize()
+        .unwrap();
+}
+
+#[test]
+#[serial]
+fn init_reentrant_error_listener() {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct EL {
+        bus: super::Bus,
+        reentrant: Arc<AtomicBool>,
+    }
+
+    impl super::errors::Listener for EL {
+        fn on_lm_sensors_config_error(
+            &self,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+            let r = self.bus.raw_name();
+            let reentrant = matches!(r, Err(super::errors::Error::Reentrant));
+            self.reentrant.store(reentrant, Ordering::Release);
+        }
+
+        fn on_lm_sensors_fatal_error(&self, _error: &str, _procedure: &str) {
+            unreachable!()
+        }
+    }
+
+    let bus = {
+        let s = super::Initializer::default().initialize().unwrap();
+        s.new_bus(super::bus::Kind::PCI, super::bus::Number::Number(42))
+    };
+
+    let mut config = tempfile::NamedTempFile::new().unwrap();
+    writeln!(config, "this is not a valid configuration statement").unwrap();
+
+    let reentrant = Arc::new(AtomicBool::new(false));
+    let listener = EL {
+        bus,
+        reentrant: Arc::clone(&reentrant),
+    };
+
+    let _ignored = super::Initializer::default()
+        .config_path(config.path())
+        .error_listener(Box::new(listener))
+        .initialize();
+    assert!(reentrant.load(Ordering::Acquire));
+}
+
+#[test]
+#[serial]
+fn shutdown() {
+    use std::cell::RefCell;
+    use std::fs;
+    use std::rc::Rc;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pwm1_enable");
+    fs::write(&path, "1").unwrap();
+
+    let steps = Rc::new(RefCell::new(Vec::new()));
+    let hook_steps = Rc::clone(&steps);
+    let hook_path = path.clone();
+
+    let sensors = crate::Initializer::default().initialize().unwrap();
+    let options = crate::ShutdownOptions::default()
+        .hook(move || {
+            // Hooks run before restore guards.
+            hook_steps
+                .borrow_mut()
+                .push(fs::read_to_string(&hook_path).unwrap());
+            Err(super::errors::Error::from_io(
+                "hook",
+                std::io::ErrorKind::Other.into(),
+            ))
+        })
+        .restore(crate::control::RestoreGuard::new(&path, "2").unwrap());
+
+    let err = sensors.shutdown(options).unwrap_err();
+    assert_matches!(
+        err,
+        super::errors::Error::IO {
+            operation: "hook",
+            ..
+        }
+    );
+    assert_eq!(*steps.borrow(), ["1"]);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "2");
+
+    // The library instance was dropped.
+    let sensors = crate::Initializer::default().initialize().unwrap();
+    sensors.shutdown(crate::ShutdownOptions::default()).unwrap();
+}
+
+#[test]
+#[serial]
+fn init_with_report() {
+    use std::io::Write;
+
+    let mut config = tempfile::NamedTempFile::new().unwrap();
+    writeln!(config, "chip \"*-isa-*\"").unwrap();
+    writeln!(config, "this is not a valid configuration statement").unwrap();
+
+    let report = super::Initializer::default()
+        .config_path(config.path())
+        .initialize_with_report()
+        .unwrap();
+    assert!(!report.config_diagnostics.is_empty());
+    assert!(report.config_diagnostics.iter().all(|d| d.line_number >= 1));
+    drop(report);
+
+    let report = super::Initializer::default()
+        .config_path("/dev/null")
+        .initialize_with_report()
+        .unwrap();
+    assert!(report.config_diagnostics.is_empty());
+}
+
+#[test]
+#[serial]
+fn init_config_layers() {
+    use super::config::ConfigLayer;
+
+    let report = super::Initializer::default()
+        .config_layers([
+            ConfigLayer::text("package", "chip \"*-isa-*\"\n"),
+            ConfigLayer::text("user", "\nthis is not a valid configuration statement\n"),
+        ])
+        .initialize_with_report()
+        .unwrap();
+
+    assert!(!report.config_diagnostics.is_empty());
+    for diagnostic in &report.config_diagnostics {
+        assert_eq!(diagnostic.file_name.as_deref(), Some("user".as_ref()));
+        assert_eq!(diagnostic.line_number, 2);
+    }
+    drop(report);
+
+    let failure = super::Initializer::default()
+        .config_layers([ConfigLayer::text("user", "chip \"unterminated\n")])
+        .initialize_with_report()
+        .unwrap_err();
+    assert_eq!(failure.error.code(), "lm_sensors");
+    assert!(!failure.config_diagnostics.is_empty());
+    for diagnostic in &failure.config_diagnostics {
+        assert_eq!(diagnostic.file_name.as_deref(), Some("user".as_ref()));
+        assert!(!diagnostic.message.is_empty());
+    }
+    assert_eq!(failure.to_string(), failure.error.to_string());
+}
+
+#[test]
+#[serial]
+fn init_drop_race() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    let live = Arc::new(AtomicUsize::new(0));
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let live = Arc::clone(&live);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    match super::Initializer::isolated().initialize() {
+                        Ok(s) => {
+                            // Only one instance exists at a time.
+                            assert_eq!(live.fetch_add(1, Ordering::AcqRel), 0);
+                            thread::yield_now();
+                            live.fetch_sub(1, Ordering::AcqRel);
+                            drop(s);
+                        }
+                        Err(err) => {
+                            assert_matches!(err, super::errors::Error::IO { source, .. }
+                                if source.kind() == std::io::ErrorKind::AlreadyExists);
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    // Global state was restored.
+    let _s = super::Initializer::isolated().initialize().unwrap();
+}
+
+#[test]
+#[serial]
+fn init_wait_for_release() {
+    use std::thread;
+    use std::time::Duration;
+
+    let s0 = super::Initializer::isolated().initialize().unwrap();
+
+    let err = super::Initializer::isolated()
+        .wait_for_release(Duration::from_millis(20))
+        .initialize()
+        .unwrap_err();
+    assert_matches!(err, super::errors::Error::IO { source, .. }
+        if source.kind() == std::io::ErrorKind::AlreadyExists);
+
+    let waiter = thread::spawn(|| {
+        super::Initializer::isolated()
+            .wait_for_release(Duration::from_secs(60))
+            .initialize()
+            .unwrap();
+    });
+    thread::sleep(Duration::from_millis(50));
+    drop(s0);
+    waiter.join().unwrap();
+}
+
+#[test]
+#[serial]
+fn init_or_attach() {
+    use std::thread;
+
+    let s0 = super::Initializer::isolated()
+        .try_initialize_or_attach()
+        .unwrap();
+    let s1 = super::Initializer::default()
+        .try_initialize_or_attach()
+        .unwrap();
+    assert!(s0.ptr_eq(&s1));
+
+    let attacher = thread::spawn(|| {
+        super::Initializer::default()
+            .try_initialize_or_attach()
+            .map_err(|err| err.to_string())
+    });
+    assert!(s0.ptr_eq(&attacher.join().unwrap().unwrap()));
+
+    let err = super::Initializer::isolated().initialize().unwrap_err();
+    assert_matches!(err, super::errors::Error::IO { source, .. }
+        if source.kind() == std::io::ErrorKind::AlreadyExists);
+
+    drop(s0);
+    drop(s1);
+    let s2 = super::Initializer::isolated().initialize().unwrap();
+    let err = super::Initializer::isolated()
+        .try_initialize_or_attach()
+        .unwrap_err();
+    assert_matches!(err, super::errors::Error::IO { source, .. }
+        if source.kind() == std::io::ErrorKind::AlreadyExists);
+    drop(s2);
+
+    // Shared instances have no error listener and no audit sink.
+    let err = super::Initializer::isolated()
+        .error_listener(Box::new(super::errors::DefaultListener))
+        .try_initialize_or_attach()
+        .unwrap_err();
+    assert_matches!(err, super::errors::Error::IO { source, .. }
+        if source.kind() == std::io::ErrorKind::InvalidInput);
+    let err = super::Initializer::isolated()
+        .audit_sink(Box::new(super::audit::Callback(|_: &_| ())))
+        .try_initialize_or_attach()
+        .unwrap_err();
+    assert_matches!(err, super::errors::Error::IO { source, .. }
+        if source.kind() == std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+#[serial]
+fn init_or_attach_race() {
+    use std::thread;
+
+    // Attaching while the last attached instance is dropped in another thread
+    // does not fail.
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                for _ in 0..100 {
+                    let sensors = super::Initializer::isolated().try_initialize_or_attach();
+                    drop(sensors.map_err(|err| err.to_string()).unwrap());
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+#[serial]
+fn config_overlay() {
+    let mut s = super::Initializer::isolated().initialize().unwrap();
+    let chip_count = s.chip_iter(None).count();
+
+    let overlay = "chip \"*-isa-*\"\n    ignore temp1\n";
+    let count = s
+        .with_config_overlay(overlay, |s| s.chip_iter(None).count())
+        .unwrap();
+    assert_eq!(count, chip_count);
+
+    let err = s
+        .with_config_overlay("chip \"unterminated\n", |_| ())
+        .unwrap_err();
+    assert_eq!(err.code(), "lm_sensors");
+
+    // The previous configuration was restored.
+    assert_eq!(s.chip_iter(None).count(), chip_count);
+    drop(s);
+
+    let config_file = File::open("/dev/null").unwrap();
+    let mut s = super::Initializer::default()
+        .config_file(config_file)
+        .initialize()
+        .unwrap();
+    let err = s.with_config_overlay("", |_| ()).unwrap_err();
+    assert_matches!(err, super::errors::Error::IO { source, .. }
+        if source.kind() == std::io::ErrorKind::Unsupported);
+}
+
+#[test]
+#[serial]
+fn config_overlay_not_restored() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let config = tempfile::NamedTempFile::new().unwrap();
+    let valid = "chip \"*-isa-*\"\n";
+    let invalid = "chip \"unterminated\n";
+    std::fs::write(config.path(), valid).unwrap();
+
+    let mut s = super::Initializer::default()
+        .config_path(config.path())
+        .initialize()
+        .unwrap();
+
+    // The previous configuration becomes invalid while the overlay is active.
+    let err = s
+        .with_config_overlay("", |_| std::fs::write(config.path(), invalid).unwrap())
+        .unwrap_err();
+    assert_matches!(err, super::errors::Error::ConfigNotRestored { source }
+        if source.code() == "lm_sensors");
+
+    // Panics take precedence.
+    std::fs::write(config.path(), valid).unwrap();
+    let panicked = panic::catch_unwind(AssertUnwindSafe(|| {
+        s.with_config_overlay("", |_| {
+            std::fs::write(config.path(), invalid).unwrap();
+            panic!("overlay function panicked");
+        })
+    }));
+    assert!(panicked.is_err());
+}
+
+#[test]
+#[serial]
+fn init_error_listener_race() {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[derive(Debug)]
+    struct EL(Arc<AtomicUsize>);
+
+    impl super::errors::Listener for EL {
+        fn on_lm_sensors_config_error(
+            &self,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+            self.0.fetch_add(1, Ordering::AcqRel);
+        }
+
+        fn on_lm_sensors_fatal_error(&self, _error: &str, _procedure: &str) {
+            unreachable!()
+        }
+    }
+
+    let mut config = tempfile::NamedTempFile::new().unwrap();
+    writeln!(config, "chip \"*-isa-*\"").unwrap();
+    writeln!(config, "this is not a valid configuration statement").unwrap();
+    let config_path = Arc::new(config.path().to_path_buf());
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let config_path = Arc::clone(&config_path);
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    let errors = Arc::new(AtomicUsize::new(0));
+                    let r = super::Initializer::default()
+                        .config_path(config_path.as_path())
+                        .error_listener(Box::new(EL(Arc::clone(&errors))))
+                        .initialize();
+
+                    // Each listener is only notified of errors of its own initialization.
+                    if r.is_ok() {
+                        assert_ne!(errors.load(Ordering::Acquire), 0);
+                    } else {
+                        assert_eq!(errors.load(Ordering::Acquire), 0);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+#[serial]
+fn swap_error_listener() {
+    #[derive(Debug)]
+    struct EL(u8);
+
+    impl super::errors::Listener for EL {
+        fn on_lm_sensors_config_error(
+            &self,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+        }
+
+        fn on_lm_sensors_fatal_error(&self, _error: &str, _procedure: &str) {
+            unreachable!()
+        }
+    }
+
+    let s = super::Initializer::isolated()
+        .error_listener(Box::new(EL(1)))
+        .initialize()
+        .unwrap();
+
+    let previous = s.swap_error_listener(Some(Box::new(EL(2)))).unwrap();
+    assert_eq!(format!("{previous:?}"), "Some(EL(1))");
+
+    let previous = s.swap_error_listener(None).unwrap();
+    assert_eq!(format!("{previous:?}"), "Some(EL(2))");
+
+    assert!(s.swap_error_listener(None).unwrap().is_none());
+
+    let _previous = s.swap_error_listener(Some(Box::new(EL(3)))).unwrap();
+    drop(s);
+
+    // Global state was restored.
+    let _s = super::Initializer::isolated().initialize().unwrap();
+}
+
+#[test]
+#[serial]
+fn set_error_listener() {
+    #[derive(Debug)]
+    struct EL(u8);
+
+    impl super::errors::Listener for EL {
+        fn on_lm_sensors_config_error(
+            &self,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+        }
+
+        fn on_lm_sensors_fatal_error(&self, _error: &str, _procedure: &str) {
+            unreachable!()
+        }
+    }
+
+    let s = super::Initializer::isolated().initialize().unwrap();
+    assert!(s.set_error_listener(Box::new(EL(1))).unwrap().is_none());
+
+    let previous = s.set_error_listener(Box::new(EL(2))).unwrap();
+    assert_eq!(format!("{previous:?}"), "Some(EL(1))");
+}
+
+#[test]
+#[serial]
+fn init_error_listener_context() {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use super::errors::{ConfigSource, ListenerContext};
+
+    #[derive(Debug)]
+    struct EL(Arc<Mutex<Vec<ListenerContext>>>);
+
+    impl super::errors::Listener for EL {
+        fn on_lm_sensors_config_error(
+            &self,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+            unreachable!()
+        }
+
+        fn on_lm_sensors_fatal_error(&self, _error: &str, _procedure: &str) {
+            unreachable!()
+        }
+
+        fn on_config_error(
+            &self,
+            context: &ListenerContext,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+            self.0.lock().unwrap().push(context.clone());
+        }
+    }
+
+    let mut config = tempfile::NamedTempFile::new().unwrap();
+    writeln!(config, "chip \"*-isa-*\"").unwrap();
+    writeln!(config, "this is not a valid configuration statement").unwrap();
+
+    let contexts = Arc::new(Mutex::new(Vec::new()));
+    let s = super::Initializer::default()
+        .config_path(config.path())
+        .tag("test")
+        .error_listener(Box::new(EL(Arc::clone(&contexts))))
+        .initialize_with_report()
+        .unwrap();
+    drop(s);
+
+    let contexts = contexts.lock().unwrap();
+    assert!(!contexts.is_empty());
+    for context in contexts.iter() {
+        assert_eq!(
+            context.config_source,
+            ConfigSource::Path(config.path().to_path_buf())
+        );
+        assert_eq!(context.tag.as_deref(), Some("test"));
+    }
+    drop(contexts);
+
+    let contexts = Arc::new(Mutex::new(Vec::new()));
+    let _s = super::Initializer::default()
+        .config_file(std::fs::File::open(config.path()).unwrap())
+        .error_listener(Box::new(EL(Arc::clone(&contexts))))
+        .initialize()
+        .unwrap();
+
+    let contexts = contexts.lock().unwrap();
+    assert!(!contexts.is_empty());
+    assert!(contexts
+        .iter()
+        .all(|c| c.config_source == ConfigSource::File && c.tag.is_none()));
+}
+
+#[test]
+#[serial]
+fn error_listener_warnings() {
+    use std::sync::{Arc, Mutex};
+
+    use super::errors::{ListenerContext, Warning};
+
+    type TaggedWarnings = Arc<Mutex<Vec<(Option<String>, Warning)>>>;
+
+    #[derive(Debug)]
+    struct EL(TaggedWarnings);
+
+    impl super::errors::Listener for EL {
+        fn on_lm_sensors_config_error(
+            &self,
+            _error: &str,
+            _file_name: Option<&std::path::Path>,
+            _line_number: usize,
+        ) {
+        }
+
+        fn on_lm_sensors_fatal_error(&self, _error: &str, _procedure: &str) {
+            unreachable!()
+        }
+
+        fn on_warning(&self, context: &ListenerContext, warning: &Warning) {
+            let entry = (context.tag.clone(), warning.clone());
+            self.0.lock().unwrap().push(entry);
+        }
+    }
+
+    let warning = Warning::AuditRecordLost {
+        path: "/var/log/audit.log".into(),
+        message: "disk full".into(),
+    };
+
+    // Without a library instance, warnings are reported to stderr.
+    super::errors::report_warning(&warning);
+
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let s = super::Initializer::isolated()
+        .tag("test")
+        .error_listener(Box::new(EL(Arc::clone(&warnings))))
+        .initialize()
+        .unwrap();
+
+    super::errors::report_warning(&warning);
+    drop(s);
+    super::errors::report_warning(&warning);
+
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [(Some(String::from("test")), warning)]
+    );
+}
+
+#[test]
+#[serial]
+fn snapshot_where() {
+    use crate::selector::Selector;
+
+    let mut s = crate::Initializer::default().initialize().unwrap();
+    let all = crate::snapshot::Snapshot::read(&mut s).unwrap();
+    let first = &all.readings[0];
+
+    let selector = Selector::new().sensor(first.chip.as_str(), first.sub_feature.as_str());
+    let snapshot = s.snapshot_where(&selector);
+    assert!(!snapshot.readings.is_empty());
+    assert!(snapshot
+        .readings
+        .iter()
+        .all(|r| r.chip == first.chip && r.sub_feature == first.sub_feature));
+
+    assert_eq!(
+        s.snapshot_where(&Selector::all()).readings.len(),
+        all.readings.len()
+    );
+    assert!(s.snapshot_where(&Selector::new()).readings.is_empty());
+}
@@ -0,0 +1,218 @@
+//! Features filtered by kind at compile time.
+//!
+//! Each [`FeatureKind`] is a marker type, *e.g.,* [`Temperature`], holding
+//! the raw type of its features and of their input sub-features as
+//! constants. [`features`] yields the features of one kind as
+//! [`TypedFeature`]s, so that generic code is compiled once per kind,
+//! instead of matching on [`Kind`] at runtime.
+
+#[cfg(test)]
+mod tests;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use std::os::raw::c_uint;
+
+use crate::chip::ChipRef;
+use crate::errors::Result;
+use crate::feature::{FeatureRef, Kind};
+use crate::sub_feature::SubFeatureRef;
+
+/**
+Kind of features, known at compile time.
+
+This trait is sealed. It is implemented by the marker types of this module,
+one per [`Kind`] other than [`Kind::Unknown`].
+
+# Example
+
+```rust
+use lm_sensors::typed::{self, FeatureKind, Temperature};
+
+fn sum<K: FeatureKind>(chip: lm_sensors::ChipRef) -> f64 {
+    typed::features::<K>(chip)
+        .filter_map(|feature| feature.raw_input().ok())
+        .sum()
+}
+
+let sensors = lm_sensors::Initializer::default().initialize()?;
+for chip in sensors.chip_iter(None) {
+    println!("{}: {}", chip, sum::<Temperature>(chip));
+}
+# Ok::<(), lm_sensors::errors::Error>(())
+```
+*/
+pub trait FeatureKind: sealed::Sealed + fmt::Debug + Copy + Send + Sync + 'static {
+    /// Kind of the features.
+    const KIND: Kind;
+
+    /// Input sub-feature of the features, *e.g.,*
+    /// [`TemperatureInput`](crate::value::Kind::TemperatureInput).
+    const INPUT: crate::value::Kind;
+
+    /// Raw type of the features, which is one of `SENSORS_FEATURE_*`.
+    const RAW_KIND: c_uint = Self::KIND as c_uint;
+
+    /// Raw type of the input sub-features of the features, which is one of
+    /// `SENSORS_SUBFEATURE_*`.
+    const RAW_INPUT: c_uint = Self::INPUT as c_uint;
+}
+
+macro_rules! feature_kinds {
+    ($($(#[$attr:meta])* $name:ident => $input:ident,)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum $name {}
+
+            impl sealed::Sealed for $name {}
+
+            impl FeatureKind for $name {
+                const KIND: Kind = Kind::$name;
+                const INPUT: crate::value::Kind = crate::value::Kind::$input;
+            }
+        )*
+    };
+}
+
+feature_kinds! {
+    /// Voltage features, *e.g.,* `in0`.
+    Voltage => VoltageInput,
+    /// Fan features, *e.g.,* `fan1`.
+    Fan => FanInput,
+    /// Temperature features, *e.g.,* `temp1`.
+    Temperature => TemperatureInput,
+    /// Power features, *e.g.,* `power1`.
+    Power => PowerInput,
+    /// Energy features, *e.g.,* `energy1`.
+    Energy => EnergyInput,
+    /// Current features, *e.g.,* `curr1`.
+    Current => CurrentInput,
+    /// Humidity features, *e.g.,* `humidity1`.
+    Humidity => HumidityInput,
+    /// Voltage identification features, *e.g.,* `cpu0_vid`.
+    VoltageID => VoltageID,
+    /// Chassis intrusion features, *e.g.,* `intrusion0`.
+    Intrusion => IntrusionAlarm,
+    /// Beep enable features, *i.e.,* `beep_enable`.
+    BeepEnable => BeepEnable,
+}
+
+/// Feature of the kind `K`.
+///
+/// It dereferences to the wrapped [`FeatureRef`].
+pub struct TypedFeature<'a, K: FeatureKind> {
+    feature: FeatureRef<'a>,
+    _kind: PhantomData<K>,
+}
+
+impl<'a, K: FeatureKind> TypedFeature<'a, K> {
+    /// Return the given feature, if it is of the kind `K`.
+    #[must_use]
+    pub fn new(feature: FeatureRef<'a>) -> Option<Self> {
+        (feature.raw_kind() == K::RAW_KIND).then_some(Self {
+            feature,
+            _kind: PhantomData,
+        })
+    }
+
+    /// Return the wrapped feature.
+    #[must_use]
+    pub fn feature(self) -> FeatureRef<'a> {
+        self.feature
+    }
+
+    /// Return the input sub-feature of this feature, *e.g.,* `temp1_input`,
+    /// if it exists, or an error otherwise.
+    pub fn input(self) -> Result<SubFeatureRef<'a>> {
+        self.feature.sub_feature_by_raw_kind(K::RAW_INPUT)
+    }
+
+    /// Return the raw value of the input sub-feature of this feature.
+    pub fn raw_input(self) -> Result<f64> {
+        self.input()?.raw_value()
+    }
+}
+
+impl<K: FeatureKind> Clone for TypedFeature<'_, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: FeatureKind> Copy for TypedFeature<'_, K> {}
+
+impl<K: FeatureKind> PartialEq for TypedFeature<'_, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.feature == other.feature
+    }
+}
+
+impl<K: FeatureKind> Eq for TypedFeature<'_, K> {}
+
+impl<K: FeatureKind> fmt::Debug for TypedFeature<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedFeature")
+            .field("kind", &K::KIND)
+            .field("feature", &self.feature)
+            .finish()
+    }
+}
+
+impl<K: FeatureKind> fmt::Display for TypedFeature<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.feature, f)
+    }
+}
+
+impl<'a, K: FeatureKind> Deref for TypedFeature<'a, K> {
+    type Target = FeatureRef<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.feature
+    }
+}
+
+impl<'a, K: FeatureKind> From<TypedFeature<'a, K>> for FeatureRef<'a> {
+    fn from(feature: TypedFeature<'a, K>) -> Self {
+        feature.feature
+    }
+}
+
+/// Return an iterator over the features of the kind `K` of the given chip.
+pub fn features<K: FeatureKind>(chip: ChipRef<'_>) -> Features<'_, K> {
+    Features {
+        iter: chip.feature_iter(),
+        _kind: PhantomData,
+    }
+}
+
+/// Iterator over the features of the kind `K` of a chip.
+/// Yields [`TypedFeature`]s.
+#[must_use]
+pub struct Features<'a, K: FeatureKind> {
+    iter: crate::feature::Iter<'a>,
+    _kind: PhantomData<K>,
+}
+
+impl<'a, K: FeatureKind> Iterator for Features<'a, K> {
+    type Item = TypedFeature<'a, K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find_map(TypedFeature::new)
+    }
+}
+
+impl<K: FeatureKind> fmt::Debug for Features<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Features")
+            .field("kind", &K::KIND)
+            .field("iter", &self.iter)
+            .finish()
+    }
+}
@@ -0,0 +1,61 @@
+#![cfg(test)]
+
+use serial_test::serial;
+
+use super::{FeatureKind, Temperature, TypedFeature, Voltage};
+
+#[test]
+fn raw_kinds() {
+    use sensors_sys::sensors_feature_type::{SENSORS_FEATURE_IN, SENSORS_FEATURE_TEMP};
+    use sensors_sys::sensors_subfeature_type::SENSORS_SUBFEATURE_TEMP_INPUT;
+
+    assert_eq!(Temperature::RAW_KIND, SENSORS_FEATURE_TEMP);
+    assert_eq!(Temperature::RAW_INPUT, SENSORS_SUBFEATURE_TEMP_INPUT);
+    assert_eq!(Voltage::RAW_KIND, SENSORS_FEATURE_IN);
+}
+
+#[test]
+#[serial]
+fn features() {
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    for chip in s.chip_iter(None) {
+        let expected: Vec<_> = chip
+            .feature_iter()
+            .filter(|feature| feature.kind() == Some(Temperature::KIND))
+            .collect();
+
+        let features: Vec<_> = super::features::<Temperature>(chip)
+            .map(|feature| {
+                assert!(!format!("{feature:?}").is_empty());
+                if let Ok(input) = feature.input() {
+                    assert_eq!(input.raw_kind(), Temperature::RAW_INPUT);
+                }
+                feature.feature()
+            })
+            .collect();
+        assert_eq!(features, expected);
+    }
+}
+
+#[test]
+#[serial]
+fn new() {
+    let s = crate::Initializer::default().initialize().unwrap();
+
+    let chip0 = s.chip_iter(None).next().unwrap();
+    let feature0 = chip0.feature_iter().next().unwrap();
+
+    let temperature = TypedFeature::<Temperature>::new(feature0);
+    let voltage = TypedFeature::<Voltage>::new(feature0);
+    assert_eq!(
+        temperature.is_some(),
+        feature0.kind() == Some(Temperature::KIND)
+    );
+    assert_eq!(voltage.is_some(), feature0.kind() == Some(Voltage::KIND));
+
+    if let Some(temperature) = temperature {
+        assert_eq!(*temperature, feature0);
+        assert_eq!(temperature.to_string(), feature0.to_string());
+    }
+}
@@ -1,31 +1,104 @@
 #[cfg(test)]
 mod tests;
 
+use core::cell::Cell;
 use core::ffi::CStr;
 use core::mem::MaybeUninit;
+use core::ptr;
 use core::ptr::NonNull;
-use core::sync::atomic;
+use core::time::Duration;
 use std::borrow::Cow;
 use std::ffi::{CString, OsStr};
 use std::fs::File;
 use std::io;
 use std::os::raw::{c_char, c_int};
 use std::path::Path;
-use std::sync::{Mutex, Once};
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
 
 use sensors_sys::*;
 
 use crate::errors::{Error, Result};
 
-pub(crate) fn api_access_lock() -> &'static Mutex<()> {
-    static INIT: Once = Once::new();
-    static mut LOCK: MaybeUninit<Mutex<()>> = MaybeUninit::uninit();
+pub(crate) fn api_access_lock() -> &'static ApiAccessLock {
+    static LOCK: OnceLock<ApiAccessLock> = OnceLock::new();
 
-    INIT.call_once(|| unsafe {
-        LOCK.write(Mutex::new(()));
-    });
+    LOCK.get_or_init(|| ApiAccessLock {
+        mutex: Mutex::new(()),
+        released: Condvar::new(),
+    })
+}
+
+thread_local! {
+    // Whether the current thread holds the API access lock.
+    static API_ACCESS_LOCK_HELD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Lock serializing all calls into the LM sensors library.
+///
+/// The LM sensors library calls error listeners while this lock is held,
+/// so locking it again from the same thread is detected and reported
+/// as [`Error::Reentrant`], instead of dead-locking.
+///
+/// This lock also guards the error call backs of the LM sensors library:
+/// [`CallBacks`] are only accessed through an [`ApiAccessGuard`].
+#[derive(Debug)]
+pub(crate) struct ApiAccessLock {
+    mutex: Mutex<()>,
+    /// Notified when the instance of the LM sensors library is dropped.
+    released: Condvar,
+}
 
-    unsafe { LOCK.assume_init_ref() }
+impl ApiAccessLock {
+    pub(crate) fn lock(&'static self) -> Result<ApiAccessGuard> {
+        if API_ACCESS_LOCK_HELD.with(Cell::get) {
+            return Err(Error::Reentrant);
+        }
+
+        let guard = self.mutex.lock()?;
+        API_ACCESS_LOCK_HELD.with(|held| held.set(true));
+        Ok(ApiAccessGuard { guard: Some(guard) })
+    }
+
+    /// Wake up all threads waiting in [`ApiAccessGuard::wait_while`].
+    pub(crate) fn notify_released(&self) {
+        self.released.notify_all();
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ApiAccessGuard {
+    /// This is only `None` while waiting.
+    guard: Option<MutexGuard<'static, ()>>,
+}
+
+impl ApiAccessGuard {
+    /// Release the lock while the given condition holds, until the timeout
+    /// elapses, and return whether the condition still holds.
+    ///
+    /// The condition is evaluated while holding the lock, and re-evaluated
+    /// whenever [`ApiAccessLock::notify_released`] is called.
+    pub(crate) fn wait_while(
+        &mut self,
+        timeout: Duration,
+        mut condition: impl FnMut() -> bool,
+    ) -> bool {
+        let Some(guard) = self.guard.take() else {
+            return condition();
+        };
+
+        let (guard, result) = api_access_lock()
+            .released
+            .wait_timeout_while(guard, timeout, |_| condition())
+            .unwrap_or_else(PoisonError::into_inner);
+        self.guard = Some(guard);
+        result.timed_out()
+    }
+}
+
+impl Drop for ApiAccessGuard {
+    fn drop(&mut self) {
+        API_ACCESS_LOCK_HELD.with(|held| held.set(false));
+    }
 }
 
 type ParseErrorProc = unsafe extern "C" fn(err: *const c_char, line_no: c_int);
@@ -33,6 +106,11 @@ type ParseErrorWFnProc =
     unsafe extern "C" fn(err: *const c_char, file_name: *const c_char, line_no: c_int);
 type FatalErrorProc = unsafe extern "C" fn(procedure: *const c_char, err: *const c_char);
 
+/// Error call backs of the LM sensors library.
+///
+/// These are plain global variables of the LM sensors library, so they are
+/// only accessed while holding the API access lock, which orders all
+/// accesses. See: [`crate::errors::Reporter`].
 #[derive(Debug)]
 pub(crate) struct CallBacks {
     parse_error: Option<ParseErrorProc>,
@@ -53,21 +131,30 @@ impl CallBacks {
         }
     }
 
-    pub(crate) unsafe fn set(&self) {
-        atomic::fence(atomic::Ordering::Acquire);
+    /// Return the call backs currently set in the LM sensors library.
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) unsafe fn current(_guard: &ApiAccessGuard) -> Self {
+        unsafe {
+            Self {
+                parse_error: sensors_parse_error,
+                parse_error_wfn: sensors_parse_error_wfn,
+                fatal_error: sensors_fatal_error,
+            }
+        }
+    }
 
+    /// Set the call backs of the LM sensors library.
+    pub(crate) unsafe fn set(&self, _guard: &ApiAccessGuard) {
         unsafe {
             sensors_parse_error = self.parse_error;
             sensors_parse_error_wfn = self.parse_error_wfn;
             sensors_fatal_error = self.fatal_error;
         }
-
-        atomic::fence(atomic::Ordering::Release);
     }
 
-    pub(crate) unsafe fn replace(self) -> Self {
-        atomic::fence(atomic::Ordering::Acquire);
-
+    /// Set the call backs of the LM sensors library, and return the
+    /// call backs set before.
+    pub(crate) unsafe fn replace(self, _guard: &ApiAccessGuard) -> Self {
         let previous;
         unsafe {
             previous = Self {
@@ -81,8 +168,6 @@ impl CallBacks {
             sensors_fatal_error = self.fatal_error;
         }
 
-        atomic::fence(atomic::Ordering::Release);
-
         previous
     }
 }
@@ -131,6 +216,27 @@ impl LibCFileStream {
             .ok_or_else(|| Error::from_io("fdopen()", io::Error::last_os_error()))
     }
 
+    /// Return a stream reading the given bytes, from an anonymous file.
+    ///
+    /// See: `memfd_create()`.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        use std::io::{Seek, Write};
+        use std::os::unix::io::FromRawFd;
+
+        // Safety: memfd_create() is assumed to be safe.
+        let fd = unsafe { libc::memfd_create(c"lm-sensors-config".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd == -1 {
+            return Err(Error::from_io("memfd_create()", io::Error::last_os_error()));
+        }
+
+        // Safety: `fd` is a newly created file descriptor.
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(bytes)
+            .and_then(|()| file.rewind())
+            .map_err(|r| Error::from_io("std::fs::File::write_all()", r))?;
+        Self::from_file(file)
+    }
+
     fn refers_to_dir(&self, path: &Path) -> Result<bool> {
         let mut st = MaybeUninit::zeroed();
         // Safety: fileno() is assumed to be safe.
@@ -166,6 +272,23 @@ pub(crate) fn lossy_string_from_c_str(s: *const c_char, default: &str) -> Cow<st
     }
 }
 
+/// Return a copy of the given string, allocated by `malloc()`,
+/// or null if `s` is null.
+pub(crate) fn c_str_dup(operation: &'static str, s: *const c_char) -> Result<*mut c_char> {
+    if s.is_null() {
+        return Ok(ptr::null_mut());
+    }
+
+    // Safety: if `s` is not null, then it is assumed to be a null-terminated string.
+    let result = unsafe { libc::strdup(s) };
+    if result.is_null() {
+        let err = io::ErrorKind::OutOfMemory.into();
+        Err(Error::from_io(operation, err))
+    } else {
+        Ok(result)
+    }
+}
+
 pub(crate) fn str_from_c_str<'t>(s: *const c_char) -> Option<&'t str> {
     if s.is_null() {
         None